@@ -1,33 +1,36 @@
-use crate::database::{ConnectionConfig, DatabaseType};
-use crate::models::{ConnectionTestResult, StoredConnection};
-use crate::utils::crypto;
+use crate::database::registry::DriverCapabilities;
+use crate::database::{ConnectionConfig, DatabaseType, DbError, ErrorCode, ServerInfo};
+use crate::models::{ConnectionHealth, ConnectionStatus, ConnectionTestResult, StoredConnection};
+use crate::utils::crypto::{self, CredentialBackend, CredentialStorage, KeyringBackend, SecretKind};
+use crate::utils::i18n::Msg;
 use crate::AppState;
 use serde_json::{json, Value};
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, State};
 use tauri_plugin_store::StoreExt;
 
+/// 连接状态变化事件
+pub const CONNECTION_STATUS_EVENT: &str = "connection-status-changed";
+
 /// 将 StoredConnection 转换为 ConnectionConfig
-fn stored_to_config(stored: &StoredConnection) -> Result<ConnectionConfig, String> {
-    // 解密密码
-    let password = if let Some(ref encrypted) = stored.encrypted_password {
-        crypto::decrypt_password(encrypted)?
-    } else {
-        String::new()
-    };
-    
+fn stored_to_config(stored: &StoredConnection) -> Result<ConnectionConfig, DbError> {
+    // 解密密码（或从系统密钥环读取）
+    let password = crypto::load_secret(
+        &KeyringBackend,
+        stored.credential_storage,
+        &stored.id,
+        SecretKind::Password,
+        stored.encrypted_password.as_deref(),
+    )
+    .map_err(|e| format!("{}: {}", Msg::PasswordUndecryptable.with(&[&stored.name]), e))?
+    .unwrap_or_default();
+
     Ok(stored_to_config_with_password(stored, &password))
 }
 
 /// 将 StoredConnection 转换为 ConnectionConfig（带明文密码）
 fn stored_to_config_with_password(stored: &StoredConnection, password: &str) -> ConnectionConfig {
-    let db_type = match stored.db_type.as_str() {
-        "mysql" => DatabaseType::MySQL,
-        "postgresql" => DatabaseType::PostgreSQL,
-        "sqlite" => DatabaseType::SQLite,
-        "mongodb" => DatabaseType::MongoDB,
-        "redis" => DatabaseType::Redis,
-        _ => DatabaseType::MySQL,
-    };
+    // 无法识别的类型按 MySQL 处理
+    let db_type = stored.db_type.parse().unwrap_or(DatabaseType::MySQL);
 
     ConnectionConfig {
         id: stored.id.clone(),
@@ -39,38 +42,153 @@ fn stored_to_config_with_password(stored: &StoredConnection, password: &str) ->
         password: password.to_string(),
         database: stored.database.clone(),
         ssl: stored.ssl,
+        trust_server_certificate: stored.trust_server_certificate,
         connection_timeout: stored.connection_timeout,
         pool_size: stored.pool_size,
+        init_sql: stored.init_sql.clone(),
+        charset: stored.charset.clone(),
+        time_zone: stored.time_zone.clone(),
+        application_name: stored.application_name.clone(),
+    }
+}
+
+/// 获取连接配置中保存的凭据密文
+fn encrypted_secret(conn: &StoredConnection, kind: SecretKind) -> Option<&str> {
+    match kind {
+        SecretKind::Password => conn.encrypted_password.as_deref(),
+        SecretKind::SshPassphrase => conn.encrypted_ssh_passphrase.as_deref(),
+    }
+}
+
+/// 设置连接配置中的凭据密文
+fn set_encrypted_secret(conn: &mut StoredConnection, kind: SecretKind, value: Option<String>) {
+    match kind {
+        SecretKind::Password => conn.encrypted_password = value,
+        SecretKind::SshPassphrase => conn.encrypted_ssh_passphrase = value,
     }
 }
 
+/// 按连接的存储方式保存一项凭据
+///
+/// 新凭据为空时保留原有凭据；存储方式变化时将原有凭据迁移到新的存储位置。
+/// 密钥环不可用时回退到加密存储，并把连接的存储方式改为实际使用的方式
+fn apply_secret(
+    connection: &mut StoredConnection,
+    existing: Option<&StoredConnection>,
+    kind: SecretKind,
+    secret: Option<String>,
+) -> Result<(), DbError> {
+    let backend = KeyringBackend;
+    let secret = secret.filter(|s| !s.is_empty());
+
+    let secret = match (secret, existing) {
+        (Some(secret), _) => Some(secret),
+        (None, Some(existing)) if existing.credential_storage != connection.credential_storage => {
+            crypto::load_secret(
+                &backend,
+                existing.credential_storage,
+                &existing.id,
+                kind,
+                encrypted_secret(existing, kind),
+            )?
+        }
+        (None, Some(existing)) => {
+            set_encrypted_secret(connection, kind, encrypted_secret(existing, kind).map(String::from));
+            return Ok(());
+        }
+        (None, None) => None,
+    };
+    let Some(secret) = secret else {
+        set_encrypted_secret(connection, kind, None);
+        return Ok(());
+    };
+
+    let stored = crypto::store_secret(&backend, connection.credential_storage, &connection.id, kind, &secret)?;
+
+    // 不再使用密钥环时，清理旧的密钥环条目（条目不存在或密钥环不可用时忽略）
+    let was_keyring = existing
+        .map(|e| e.credential_storage == CredentialStorage::Keyring)
+        .unwrap_or(false);
+    if stored.encrypted.is_some() && was_keyring {
+        let _ = backend.delete_secret(&connection.id, kind);
+    }
+
+    connection.credential_storage = stored.storage;
+    set_encrypted_secret(connection, kind, stored.encrypted);
+    Ok(())
+}
+
+/// 按连接的存储方式保存密码和 SSH 私钥口令
+///
+/// 后一项凭据回退到加密存储时，前一项可能已写入密钥环，此时把它也转为加密存储，
+/// 保证同一连接的凭据使用同一种存储方式
+pub(crate) fn apply_secrets(
+    connection: &mut StoredConnection,
+    existing: Option<&StoredConnection>,
+    password: Option<String>,
+    ssh_passphrase: Option<String>,
+) -> Result<(), DbError> {
+    let requested = connection.credential_storage;
+    apply_secret(connection, existing, SecretKind::Password, password)?;
+    apply_secret(connection, existing, SecretKind::SshPassphrase, ssh_passphrase)?;
+
+    if requested == CredentialStorage::Keyring && connection.credential_storage == CredentialStorage::Encrypted {
+        for kind in SecretKind::ALL {
+            if encrypted_secret(connection, kind).is_some() {
+                continue;
+            }
+            if let Ok(Some(secret)) = KeyringBackend.get_secret(&connection.id, kind) {
+                set_encrypted_secret(connection, kind, Some(crypto::encrypt_password(&secret)?));
+                let _ = KeyringBackend.delete_secret(&connection.id, kind);
+            }
+        }
+    }
+    Ok(())
+}
+
 /// 测试数据库连接
 #[tauri::command]
 pub async fn test_connection(
     config: Value,
     state: State<'_, AppState>,
-) -> Result<ConnectionTestResult, String> {
+) -> Result<ConnectionTestResult, DbError> {
     let start = std::time::Instant::now();
 
     // 解析配置
-    let conn_config: ConnectionConfig =
-        serde_json::from_value(config).map_err(|e| e.to_string())?;
+    let conn_config: ConnectionConfig = serde_json::from_value(config)
+        .map_err(|e| DbError::ConfigError(format!("{}: {}", Msg::ParseConnectionConfigFailed, e)))?;
 
     // 使用连接管理器测试连接
-    let manager = state.connection_manager.lock().await;
+    let manager = &state.connection_manager;
     let result = manager.test_connection(&conn_config).await;
 
     match result {
-        Ok(_) => Ok(ConnectionTestResult {
+        Ok(Ok(info)) => Ok(ConnectionTestResult {
             success: true,
-            message: "连接成功".to_string(),
-            version: Some("8.0.35".to_string()), // TODO: 获取实际版本
+            message: Msg::ConnectionSucceeded.into(),
+            error_code: None,
+            version: Some(info.version.clone()),
+            server_info: Some(info),
+            server_info_error: None,
+            ping_time_ms: start.elapsed().as_millis(),
+        }),
+        // 连接和认证已成功，仅服务器信息不可用
+        Ok(Err(e)) => Ok(ConnectionTestResult {
+            success: true,
+            message: Msg::ConnectionSucceeded.into(),
+            error_code: None,
+            version: None,
+            server_info: None,
+            server_info_error: Some(e.to_string()),
             ping_time_ms: start.elapsed().as_millis(),
         }),
         Err(e) => Ok(ConnectionTestResult {
             success: false,
             message: e.to_string(),
+            error_code: Some(e.code()),
             version: None,
+            server_info: None,
+            server_info_error: None,
             ping_time_ms: start.elapsed().as_millis(),
         }),
     }
@@ -82,17 +200,14 @@ pub async fn save_connection(
     app: AppHandle,
     mut connection: StoredConnection,
     password: Option<String>,
-) -> Result<StoredConnection, String> {
+    ssh_passphrase: Option<String>,
+) -> Result<StoredConnection, DbError> {
     let store = app
         .store("connections.json")
         .map_err(|e| format!("Failed to get store: {}", e))?;
 
-    // 如果提供了密码，加密并保存
-    if let Some(pwd) = password {
-        if !pwd.is_empty() {
-            connection.encrypted_password = Some(crypto::encrypt_password(&pwd)?);
-        }
-    }
+    // 如果提供了密码或 SSH 口令，按存储方式加密保存或写入系统密钥环
+    apply_secrets(&mut connection, None, password, ssh_passphrase)?;
 
     // 保存连接信息
     store.set(connection.id.clone(), json!(connection));
@@ -108,29 +223,20 @@ pub async fn update_connection(
     app: AppHandle,
     mut connection: StoredConnection,
     password: Option<String>,
-) -> Result<StoredConnection, String> {
+    ssh_passphrase: Option<String>,
+) -> Result<StoredConnection, DbError> {
     let store = app
         .store("connections.json")
         .map_err(|e| format!("Failed to get store: {}", e))?;
 
     // 检查连接是否存在
-    if !store.has(connection.id.clone()) {
-        return Err("连接配置不存在".to_string());
-    }
+    let existing_value = store
+        .get(connection.id.clone())
+        .ok_or(Msg::ConnectionConfigNotFound)?;
+    let existing = serde_json::from_value::<StoredConnection>(existing_value).ok();
 
-    // 如果提供了密码，加密并保存
-    if let Some(pwd) = password {
-        if !pwd.is_empty() {
-            connection.encrypted_password = Some(crypto::encrypt_password(&pwd)?);
-        }
-    } else {
-        // 如果没有提供新密码，保留原有密码
-        if let Some(existing_value) = store.get(connection.id.clone()) {
-            if let Ok(existing_conn) = serde_json::from_value::<StoredConnection>(existing_value) {
-                connection.encrypted_password = existing_conn.encrypted_password;
-            }
-        }
-    }
+    // 如果提供了新凭据则保存，否则保留原有凭据（存储方式变化时迁移）
+    apply_secrets(&mut connection, existing.as_ref(), password, ssh_passphrase)?;
 
     // 更新连接信息
     store.set(connection.id.clone(), json!(connection));
@@ -142,7 +248,7 @@ pub async fn update_connection(
 
 /// 获取所有连接配置
 #[tauri::command]
-pub async fn get_connections(app: AppHandle) -> Result<Vec<StoredConnection>, String> {
+pub async fn get_connections(app: AppHandle) -> Result<Vec<StoredConnection>, DbError> {
     let store = app
         .store("connections.json")
         .map_err(|e| format!("Failed to get store: {}", e))?;
@@ -161,11 +267,23 @@ pub async fn get_connections(app: AppHandle) -> Result<Vec<StoredConnection>, St
 
 /// 删除连接配置
 #[tauri::command]
-pub async fn delete_connection(app: AppHandle, id: String) -> Result<bool, String> {
+pub async fn delete_connection(app: AppHandle, id: String) -> Result<bool, DbError> {
     let store = app
         .store("connections.json")
         .map_err(|e| format!("Failed to get store: {}", e))?;
 
+    // 清理系统密钥环中的凭据（条目不存在或密钥环不可用时忽略）
+    let uses_keyring = store
+        .get(id.clone())
+        .and_then(|value| serde_json::from_value::<StoredConnection>(value).ok())
+        .map(|conn| conn.credential_storage == CredentialStorage::Keyring)
+        .unwrap_or(false);
+    if uses_keyring {
+        for kind in SecretKind::ALL {
+            let _ = KeyringBackend.delete_secret(&id, kind);
+        }
+    }
+
     store.delete(id);
     store.save().map_err(|e| e.to_string())?;
 
@@ -178,7 +296,7 @@ pub async fn create_connection(
     app: AppHandle,
     connection_id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<(), DbError> {
     // 从存储中获取连接配置
     let store = app
         .store("connections.json")
@@ -186,17 +304,19 @@ pub async fn create_connection(
     
     let stored_value = store
         .get(connection_id.clone())
-        .ok_or("连接配置不存在")?;
+        .ok_or(Msg::ConnectionConfigNotFound)?;
     
     let stored_conn: StoredConnection = serde_json::from_value(stored_value)
-        .map_err(|e| format!("解析连接配置失败: {}", e))?;
+        .map_err(|e| format!("{}: {}", Msg::ParseConnectionConfigFailed, e))?;
     
     // 转换为 ConnectionConfig
     let config = stored_to_config(&stored_conn)?;
     
     // 使用连接管理器创建连接
-    let manager = state.connection_manager.lock().await;
-    manager.create_connection(config).await.map_err(|e| e.to_string())?;
+    let manager = &state.connection_manager;
+    manager.create_connection(config).await?;
+    
+    let _ = app.emit(CONNECTION_STATUS_EVENT, manager.get_health(&connection_id).await);
     
     Ok(())
 }
@@ -204,13 +324,79 @@ pub async fn create_connection(
 /// 断开数据库连接
 #[tauri::command]
 pub async fn disconnect_database(
+    app: AppHandle,
     connection_id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<(), DbError> {
     // 使用连接管理器断开连接
-    let manager = state.connection_manager.lock().await;
-    manager.disconnect(&connection_id).await.map_err(|e| e.to_string())?;
+    let manager = &state.connection_manager;
+    manager.disconnect(&connection_id).await?;
+    
+    let _ = app.emit(CONNECTION_STATUS_EVENT, manager.get_health(&connection_id).await);
     
     Ok(())
 }
 
+/// 获取已连接数据库的服务器信息
+#[tauri::command]
+pub async fn get_server_info(
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<ServerInfo, DbError> {
+    let manager = &state.connection_manager;
+    manager.get_server_info(&connection_id).await
+}
+
+/// 获取连接支持的对象类型、编辑操作、执行计划和导出格式
+#[tauri::command]
+pub async fn get_capabilities(
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<DriverCapabilities, DbError> {
+    let manager = &state.connection_manager;
+    manager.get_capabilities(&connection_id).await
+}
+
+/// 获取连接状态
+#[tauri::command]
+pub async fn get_connection_status(
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<ConnectionHealth, DbError> {
+    let manager = &state.connection_manager;
+    Ok(manager.get_health(&connection_id).await)
+}
+
+/// 获取所有已建立连接的状态
+#[tauri::command]
+pub async fn get_connection_statuses(
+    state: State<'_, AppState>,
+) -> Result<Vec<ConnectionHealth>, DbError> {
+    let manager = &state.connection_manager;
+    Ok(manager.all_health().await)
+}
+
+/// 立即重连（重置自动重连的退避计数）
+#[tauri::command]
+pub async fn reconnect_database(
+    app: AppHandle,
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<ConnectionHealth, DbError> {
+    let manager = state.connection_manager.clone();
+
+    let changed = manager.reconnect(&connection_id).await?;
+    for health in &changed {
+        let _ = app.emit(CONNECTION_STATUS_EVENT, health);
+    }
+
+    let health = manager.get_health(&connection_id).await;
+    match health.status {
+        ConnectionStatus::Error => Err(DbError::database(
+            health.error_code.unwrap_or(ErrorCode::ConnectionFailed),
+            health.error.unwrap_or_else(|| Msg::ReconnectFailed.into()),
+            Default::default(),
+        )),
+        _ => Ok(health),
+    }
+}
@@ -0,0 +1,586 @@
+use crate::database::{
+    truncate_sql, ColumnInfo, ConnectionManager, DatabaseType, DbError, DbResult, ErrorCode, RowSink, SqlSplitter,
+    TableInfo, Transaction,
+};
+use crate::dump::schema::{DumpObject, SchemaReader};
+use crate::dump::{self, DumpOptions, DumpOutput, DumpProgress, DumpStage, RestoreProgress};
+use crate::export::sql::{SqlExportOptions, SqlWriter};
+use crate::export::{self, ExportJob, RowWriter};
+use crate::utils::i18n::Msg;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+/// 备份进度事件
+pub const DUMP_PROGRESS_EVENT: &str = "dump-progress";
+
+/// 恢复进度事件
+pub const RESTORE_PROGRESS_EVENT: &str = "restore-progress";
+
+/// 进度事件的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 恢复时逐条语句使用的保存点
+const RESTORE_SAVEPOINT: &str = "datasmith_restore";
+
+/// 备份任务
+#[derive(Debug, Clone, Deserialize)]
+pub struct DumpTask {
+    /// 前端生成的任务 ID，用于进度事件和取消
+    pub dump_id: String,
+    pub connection_id: String,
+    pub database: String,
+    pub file_path: String,
+    #[serde(default)]
+    pub options: DumpOptions,
+}
+
+/// 备份结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DumpSummary {
+    pub dump_id: String,
+    pub tables: usize,
+    pub views: usize,
+    pub routines: usize,
+    pub triggers: usize,
+    pub rows: u64,
+    pub elapsed_ms: u128,
+    /// 被取消时已删除未完成的文件
+    pub cancelled: bool,
+}
+
+/// 节流发送备份进度
+struct DumpReporter<'a> {
+    emit: Box<dyn Fn(&DumpProgress) + Send + 'a>,
+    progress: DumpProgress,
+    started: Instant,
+    last_report: Instant,
+}
+
+impl DumpReporter<'_> {
+    fn report(&mut self, force: bool) {
+        if force || self.last_report.elapsed() >= PROGRESS_INTERVAL {
+            self.last_report = Instant::now();
+            self.progress.elapsed_ms = self.started.elapsed().as_millis();
+            (self.emit)(&self.progress);
+        }
+    }
+
+    fn stage(&mut self, stage: DumpStage, object: &str) {
+        self.progress.stage = stage;
+        self.progress.object = object.to_string();
+        self.report(true);
+    }
+}
+
+/// 把一张表的查询结果写成 INSERT 语句
+struct TableSink<'a, 'r, W: Write> {
+    writer: SqlWriter<W>,
+    job: &'a ExportJob,
+    reporter: &'a mut DumpReporter<'r>,
+}
+
+impl<W: Write + Send> RowSink for TableSink<'_, '_, W> {
+    fn columns(&mut self, columns: &[String]) -> DbResult<()> {
+        self.writer.begin(columns).map_err(export::write_error)
+    }
+
+    fn row(&mut self, values: Vec<Value>) -> DbResult<()> {
+        if self.job.is_cancelled() {
+            return Err(DbError::Other(Msg::DumpCancelled.into()));
+        }
+        self.writer.write_row(&values).map_err(export::write_error)?;
+        self.reporter.progress.rows += 1;
+        self.reporter.report(false);
+        Ok(())
+    }
+}
+
+/// 按方言写入脚本语句
+struct Script<'a> {
+    out: &'a mut (dyn Write + Send),
+    db_type: DatabaseType,
+}
+
+impl Script<'_> {
+    fn comment(&mut self, text: impl std::fmt::Display) -> DbResult<()> {
+        writeln!(self.out, "\n-- {}\n", text).map_err(export::write_error)
+    }
+
+    fn statement(&mut self, sql: &str, block: bool) -> DbResult<()> {
+        self.out
+            .write_all(dump::statement_text(&self.db_type, sql, block).as_bytes())
+            .map_err(export::write_error)
+    }
+
+    fn create(&mut self, object: &DumpObject) -> DbResult<()> {
+        for sql in &object.create {
+            self.statement(sql, object.block)?;
+        }
+        Ok(())
+    }
+
+    /// 写入对象，需要时先删除已存在的同名对象
+    fn object(&mut self, object: &DumpObject, drop_existing: bool) -> DbResult<()> {
+        if let Some(drop) = object.drop.as_deref().filter(|_| drop_existing) {
+            self.statement(drop, false)?;
+        }
+        self.create(object)
+    }
+}
+
+/// 备份数据库：结构、数据（按外键依赖排序）、存储过程和触发器写入一个 SQL 文件
+///
+/// 备份过程中发送 `dump-progress` 事件，可通过 `cancel_export` 取消
+#[tauri::command]
+pub async fn dump_database(
+    app: AppHandle,
+    task: DumpTask,
+    state: State<'_, AppState>,
+) -> Result<DumpSummary, DbError> {
+    let manager = state.connection_manager.clone();
+    let job = ExportJob::start(&task.dump_id);
+    let mut reporter = DumpReporter {
+        emit: Box::new(|progress: &DumpProgress| {
+            let _ = app.emit(DUMP_PROGRESS_EVENT, progress);
+        }),
+        progress: DumpProgress {
+            dump_id: task.dump_id.clone(),
+            stage: DumpStage::Schema,
+            object: String::new(),
+            tables_done: 0,
+            tables_total: 0,
+            rows: 0,
+            elapsed_ms: 0,
+        },
+        started: Instant::now(),
+        last_report: Instant::now(),
+    };
+
+    let file = File::create(&task.file_path).map_err(export::write_error)?;
+    let mut output = DumpOutput::new(BufWriter::new(file), task.options.gzip);
+    let result = write_dump(&manager, &task, &job, &mut reporter, &mut output).await;
+    let result = result.and_then(|summary| {
+        output.finish().map_err(export::write_error)?;
+        Ok(summary)
+    });
+
+    let elapsed_ms = reporter.started.elapsed().as_millis();
+    match result {
+        Ok(mut summary) => {
+            summary.rows = reporter.progress.rows;
+            summary.elapsed_ms = elapsed_ms;
+            reporter.report(true);
+            Ok(summary)
+        }
+        Err(e) => {
+            // 不保留不完整的文件
+            let _ = std::fs::remove_file(&task.file_path);
+            if job.is_cancelled() {
+                Ok(DumpSummary {
+                    dump_id: task.dump_id.clone(),
+                    tables: 0,
+                    views: 0,
+                    routines: 0,
+                    triggers: 0,
+                    rows: 0,
+                    elapsed_ms,
+                    cancelled: true,
+                })
+            } else {
+                Err(e)
+            }
+        }
+    }
+}
+
+async fn write_dump(
+    manager: &ConnectionManager,
+    task: &DumpTask,
+    job: &ExportJob,
+    reporter: &mut DumpReporter<'_>,
+    out: &mut (dyn Write + Send),
+) -> DbResult<DumpSummary> {
+    let options = &task.options;
+    let connection_id = task.connection_id.as_str();
+    let database = Some(task.database.as_str());
+    let reader = SchemaReader::new(manager, connection_id, &task.database).await?;
+    let db_type = reader.db_type.clone();
+    let driver = manager.get_driver(connection_id).await?;
+    let cancelled = || -> DbResult<()> {
+        if job.is_cancelled() {
+            Err(DbError::Other(Msg::DumpCancelled.into()))
+        } else {
+            Ok(())
+        }
+    };
+
+    // 表按外键依赖排序，被引用的表先创建、先写入数据
+    let tables: Vec<TableInfo> = manager
+        .get_tables(connection_id, database)
+        .await?
+        .into_iter()
+        .filter(|t| !t.table_type.to_uppercase().contains("VIEW"))
+        .filter(|t| !driver.is_system_table(&t.name))
+        .filter(|t| {
+            options.tables.is_empty()
+                || options.tables.contains(&t.name)
+                || options.tables.contains(&reader.table_key(t))
+        })
+        .collect();
+    let keys: Vec<String> = tables.iter().map(|t| reader.table_key(t)).collect();
+    let order = dump::dependency_order(&keys, &reader.foreign_keys().await.unwrap_or_default());
+    let mut by_key: HashMap<String, TableInfo> = keys.into_iter().zip(tables).collect();
+    let tables: Vec<TableInfo> = order.iter().filter_map(|key| by_key.remove(key)).collect();
+
+    // 只备份部分表时不包含视图、存储过程和触发器
+    let whole_database = options.tables.is_empty();
+    let views = if options.schema && whole_database {
+        manager.get_views(connection_id, database).await.unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    reporter.progress.tables_total = tables.len();
+    let mut summary = DumpSummary {
+        dump_id: task.dump_id.clone(),
+        tables: tables.len(),
+        views: 0,
+        routines: 0,
+        triggers: 0,
+        rows: 0,
+        elapsed_ms: 0,
+        cancelled: false,
+    };
+
+    let mut script = Script {
+        out,
+        db_type: db_type.clone(),
+    };
+    writeln!(
+        script.out,
+        "-- DataSmith dump\n-- Database: {}\n-- Type: {:?}\n-- Date: {}",
+        task.database,
+        db_type,
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    )
+    .map_err(export::write_error)?;
+
+    let (setup, teardown) = dump::session_statements(&db_type);
+    if !setup.is_empty() {
+        script.comment(Msg::DumpSessionSettings)?;
+        for sql in setup {
+            script.statement(sql, false)?;
+        }
+    }
+
+    // 表结构
+    let mut columns: HashMap<String, Vec<ColumnInfo>> = HashMap::new();
+    for table in &tables {
+        let table_columns = manager
+            .get_table_structure(connection_id, &table.name, table.schema.as_deref(), database)
+            .await?;
+        columns.insert(reader.table_key(table), table_columns);
+    }
+
+    let mut constraints = Vec::new();
+    if options.schema {
+        let mut table_objects = Vec::with_capacity(tables.len());
+        for table in &tables {
+            cancelled()?;
+            reporter.stage(DumpStage::Schema, &table.name);
+            table_objects.push(reader.table(table, &columns[&reader.table_key(table)]).await?);
+        }
+        let mut view_objects = Vec::with_capacity(views.len());
+        for view in &views {
+            cancelled()?;
+            reporter.stage(DumpStage::Schema, &view.name);
+            view_objects.extend(reader.view(view).await?);
+        }
+        summary.views = view_objects.len();
+
+        // 先删除依赖方：视图在表之前，表按依赖的逆序
+        if options.drop_existing {
+            script.comment(Msg::DumpDropObjects)?;
+            for object in view_objects.iter().chain(table_objects.iter().rev()) {
+                if let Some(drop) = &object.drop {
+                    script.statement(drop, false)?;
+                }
+            }
+        }
+
+        let schemas = reader.schema_statements(&tables);
+        if !schemas.is_empty() {
+            script.comment(Msg::DumpSchemas)?;
+            for sql in &schemas {
+                script.statement(sql, false)?;
+            }
+        }
+        for object in &table_objects {
+            script.comment(Msg::DumpTable.with(&[&object.name]))?;
+            script.create(object)?;
+        }
+        constraints.extend(table_objects.into_iter().flat_map(|object| object.constraints));
+        for object in &view_objects {
+            script.comment(Msg::DumpView.with(&[&object.name]))?;
+            script.create(object)?;
+        }
+    }
+
+    // 表数据
+    if options.data {
+        for table in &tables {
+            cancelled()?;
+            reporter.stage(DumpStage::Data, &table.name);
+            let table_columns = columns.remove(&reader.table_key(table)).unwrap_or_default();
+            let qualified = reader.qualified(table.schema.as_deref(), &table.name);
+            script.comment(Msg::DumpTableData.with(&[&reader.table_key(table)]))?;
+
+            // 例如 SQL Server 写入自增列需要打开 IDENTITY_INSERT
+            let identity = table_columns.iter().any(|c| c.is_auto_increment);
+            if let Some(sql) = driver.identity_insert_sql(&qualified, true).filter(|_| identity) {
+                script.statement(&sql, false)?;
+            }
+
+            let sql_options = SqlExportOptions {
+                dialect: Some(db_type.clone()),
+                batch_size: options.batch_size,
+                table_columns,
+                binary_base64: driver.binary_as_base64(),
+                schema: table.schema.clone().filter(|_| reader.uses_schema()),
+                batch_separator: driver.batch_separator(),
+                ..Default::default()
+            };
+            let writer = SqlWriter::with_options(&mut *script.out, &table.name, &sql_options)?;
+            let mut sink = TableSink {
+                writer,
+                job,
+                reporter: &mut *reporter,
+            };
+            manager
+                .stream_query(connection_id, &format!("SELECT * FROM {}", qualified), database, &mut sink)
+                .await?;
+            sink.writer.finish().map_err(export::write_error)?;
+
+            if let Some(sql) = driver.identity_insert_sql(&qualified, false).filter(|_| identity) {
+                script.statement(&sql, false)?;
+            }
+            reporter.progress.tables_done += 1;
+        }
+    }
+
+    // 外键在数据之后添加，导入数据时不受表的顺序和循环引用影响
+    if !constraints.is_empty() {
+        script.comment(Msg::DumpForeignKeys)?;
+        for sql in &constraints {
+            script.statement(sql, false)?;
+        }
+    }
+
+    // 存储过程和触发器放在数据之后，避免导入数据时触发
+    if options.routines && whole_database {
+        cancelled()?;
+        reporter.stage(DumpStage::Routines, "");
+        let routines = reader.routines().await?;
+        for object in &routines {
+            script.comment(Msg::DumpRoutine.with(&[&object.name]))?;
+            script.object(object, options.drop_existing)?;
+        }
+        summary.routines = routines.len();
+    }
+    if options.triggers && whole_database {
+        cancelled()?;
+        reporter.stage(DumpStage::Triggers, "");
+        let triggers = reader.triggers().await?;
+        for object in &triggers {
+            script.comment(Msg::DumpTrigger.with(&[&object.name]))?;
+            script.object(object, options.drop_existing)?;
+        }
+        summary.triggers = triggers.len();
+    }
+
+    if !teardown.is_empty() {
+        script.comment(Msg::DumpRestoreSession)?;
+        for sql in teardown {
+            script.statement(sql, false)?;
+        }
+    }
+    Ok(summary)
+}
+
+/// 恢复任务
+#[derive(Debug, Clone, Deserialize)]
+pub struct RestoreTask {
+    /// 前端生成的任务 ID，用于进度事件和取消
+    pub restore_id: String,
+    pub connection_id: String,
+    #[serde(default)]
+    pub database: Option<String>,
+    pub file_path: String,
+    /// 出错后继续执行后续语句（默认遇到错误即停止并回滚）
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+/// 执行失败的语句
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreError {
+    /// 语句序号（从 1 开始）
+    pub index: usize,
+    pub sql: String,
+    pub error: String,
+}
+
+/// 恢复结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreSummary {
+    pub restore_id: String,
+    pub executed: usize,
+    pub total: usize,
+    pub errors: Vec<RestoreError>,
+    pub elapsed_ms: u128,
+    pub cancelled: bool,
+}
+
+/// 从备份文件恢复（自动识别 gzip），按连接的方言拆分后逐条执行
+///
+/// 支持事务的数据库在一个事务中执行；遇到错误时回滚并返回错误，
+/// 设置 `continue_on_error` 时记录错误后继续。
+/// MySQL 的 DDL 会隐式提交，无法回滚，因此不使用事务，出错时在错误中说明已生效的语句数。
+/// 恢复过程中发送 `restore-progress` 事件，可通过 `cancel_export` 取消
+#[tauri::command]
+pub async fn restore_database(
+    app: AppHandle,
+    task: RestoreTask,
+    state: State<'_, AppState>,
+) -> Result<RestoreSummary, DbError> {
+    let start = Instant::now();
+    let manager = state.connection_manager.clone();
+    let driver = manager.get_driver(&task.connection_id).await?;
+    let Some(dialect) = driver.dialect() else {
+        return Err(DbError::database(
+            ErrorCode::Unsupported,
+            Msg::DumpUnsupported.with(&[&format!("{:?}", driver.db_type())]),
+            Default::default(),
+        ));
+    };
+
+    let path = task.file_path.clone();
+    let script = tokio::task::spawn_blocking(move || {
+        let bytes = std::fs::read(&path)
+            .map_err(|e| DbError::Other(format!("{}: {}", Msg::OpenFileFailed.with(&[&path]), e)))?;
+        dump::decode_script(bytes).map_err(|e| DbError::Other(format!("{}: {}", Msg::ReadFileFailed, e)))
+    })
+    .await
+    .map_err(|e| DbError::Other(e.to_string()))??;
+    let statements = SqlSplitter::new(dialect).split(&script);
+    drop(script);
+
+    let database = task.database.as_deref();
+    let mut tx: Option<Box<dyn Transaction>> = if !driver.transactional_ddl() {
+        None
+    } else {
+        match manager.begin_transaction(&task.connection_id, database).await {
+            Ok(tx) => Some(tx),
+            Err(e) if e.code() == ErrorCode::Unsupported => None,
+            Err(e) => return Err(e),
+        }
+    };
+    // 例如 PostgreSQL 的语句出错后整个事务失效，继续执行需要回滚到保存点
+    let use_savepoints = task.continue_on_error && driver.error_aborts_transaction();
+
+    let job = ExportJob::start(&task.restore_id);
+    let total = statements.len();
+    let mut executed = 0;
+    let mut errors = Vec::new();
+    let mut failure = None;
+    let mut last_report = Instant::now();
+    let emit = |executed, failed| {
+        let _ = app.emit(
+            RESTORE_PROGRESS_EVENT,
+            RestoreProgress {
+                restore_id: task.restore_id.clone(),
+                executed,
+                total,
+                failed,
+                elapsed_ms: start.elapsed().as_millis(),
+            },
+        );
+    };
+
+    for (idx, sql) in statements.iter().enumerate() {
+        if job.is_cancelled() {
+            break;
+        }
+
+        let result = match tx.as_mut() {
+            Some(tx) if use_savepoints => {
+                tx.savepoint(RESTORE_SAVEPOINT).await?;
+                match tx.execute(sql).await {
+                    Ok(rows) => tx.release_savepoint(RESTORE_SAVEPOINT).await.map(|_| rows),
+                    Err(e) => {
+                        tx.rollback_to_savepoint(RESTORE_SAVEPOINT).await?;
+                        Err(e)
+                    }
+                }
+            }
+            Some(tx) => tx.execute(sql).await,
+            None => manager
+                .execute_query(&task.connection_id, sql, database)
+                .await
+                .map(|r| r.affected_rows),
+        };
+
+        match result {
+            Ok(_) => executed += 1,
+            Err(e) => {
+                errors.push(RestoreError {
+                    index: idx + 1,
+                    sql: truncate_sql(sql, 200),
+                    error: e.to_string(),
+                });
+                if !task.continue_on_error {
+                    let mut e = e.context(Msg::RestoreStatementFailed.with(&[&(idx + 1)]));
+                    // 没有事务时已执行的语句保留在数据库中
+                    if tx.is_none() && executed > 0 {
+                        e = e.context(Msg::RestorePartiallyApplied.with(&[&executed]));
+                    }
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            last_report = Instant::now();
+            emit(executed, errors.len());
+        }
+    }
+    emit(executed, errors.len());
+
+    let cancelled = job.is_cancelled();
+    if let Some(tx) = tx.take() {
+        if failure.is_some() || cancelled {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+    }
+    if let Some(e) = failure {
+        return Err(e);
+    }
+
+    Ok(RestoreSummary {
+        restore_id: task.restore_id.clone(),
+        executed,
+        total,
+        errors,
+        elapsed_ms: start.elapsed().as_millis(),
+        cancelled,
+    })
+}
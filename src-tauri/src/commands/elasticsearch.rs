@@ -0,0 +1,20 @@
+use crate::database::DbError;
+use crate::utils::i18n::Msg;
+use crate::AppState;
+use tauri::State;
+
+/// 获取集群健康状态
+#[tauri::command]
+pub async fn get_elasticsearch_cluster_health(
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
+
+    let es_db = db
+        .as_search()
+        .ok_or(Msg::NotSearchConnection)?;
+
+    es_db.cluster_health().await
+}
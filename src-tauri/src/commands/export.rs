@@ -1,40 +1,49 @@
-use crate::database::QueryResult;
+use crate::commands::metadata::create_table_ddl;
+use crate::database::registry::ExportFormat;
+use crate::database::{is_query_statement, DatabaseType, DbError, QueryResult, SqlSplitter};
+use crate::export::clipboard::{self, CopySelection};
+use crate::export::xlsx::XlsxWriter;
+use crate::export::csv::CsvDialect;
+use crate::export::sql::SqlExportOptions;
+use crate::export::{self, ExportJob, ExportOptions, ExportProgress, ExportSink, RowWriter};
+use crate::utils::i18n::Msg;
 use crate::AppState;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::Write;
-use tauri::State;
+use std::io::BufWriter;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 
-/// 导出为 CSV
+/// 导出进度事件
+pub const EXPORT_PROGRESS_EVENT: &str = "export-progress";
+
+/// 将前端传入的查询结果写入文件
+fn export_result(
+    data: &QueryResult,
+    format: ExportFormat,
+    table_name: &str,
+    file_path: &str,
+    options: &ExportOptions,
+) -> Result<bool, DbError> {
+    let file = File::create(file_path).map_err(export::write_error)?;
+    let mut writer = export::create_writer(format, file, table_name, options)?;
+    export::write_result(writer.as_mut(), data).map_err(export::write_error)?;
+    Ok(true)
+}
+
+/// 导出为 CSV（未指定格式选项时使用逗号分隔、UTF-8 编码）
 #[tauri::command]
 pub async fn export_to_csv(
     data: QueryResult,
     file_path: String,
-) -> Result<bool, String> {
-    let mut file = File::create(&file_path).map_err(|e| e.to_string())?;
-    
-    // 写入表头
-    let header = data.columns.join(",");
-    writeln!(file, "{}", header).map_err(|e| e.to_string())?;
-    
-    // 写入数据行
-    for row in &data.rows {
-        let values: Vec<String> = data
-            .columns
-            .iter()
-            .map(|col| {
-                let val = row.get(col).unwrap_or(&Value::Null);
-                match val {
-                    Value::String(s) => format!("\"{}\"", s.replace("\"", "\"\"")),
-                    Value::Null => String::new(),
-                    _ => val.to_string(),
-                }
-            })
-            .collect();
-        writeln!(file, "{}", values.join(",")).map_err(|e| e.to_string())?;
-    }
-    
-    Ok(true)
+    options: Option<CsvDialect>,
+) -> Result<bool, DbError> {
+    let options = ExportOptions {
+        csv: options.unwrap_or_default(),
+        ..Default::default()
+    };
+    export_result(&data, ExportFormat::Csv, "", &file_path, &options)
 }
 
 /// 导出为 JSON
@@ -42,54 +51,240 @@ pub async fn export_to_csv(
 pub async fn export_to_json(
     data: QueryResult,
     file_path: String,
-) -> Result<bool, String> {
-    let json = serde_json::to_string_pretty(&data.rows).map_err(|e| e.to_string())?;
-    
-    let mut file = File::create(&file_path).map_err(|e| e.to_string())?;
-    file.write_all(json.as_bytes()).map_err(|e| e.to_string())?;
-    
-    Ok(true)
+) -> Result<bool, DbError> {
+    export_result(&data, ExportFormat::Json, "", &file_path, &ExportOptions::default())
 }
 
-/// 导出为 SQL INSERT 语句
+/// 导出为 SQL INSERT 语句（未指定选项时按 MySQL 语法逐行写入）
 #[tauri::command]
 pub async fn export_to_sql(
     data: QueryResult,
     table_name: String,
     file_path: String,
-) -> Result<bool, String> {
-    let mut file = File::create(&file_path).map_err(|e| e.to_string())?;
-    
-    for row in &data.rows {
-        let columns = data.columns.join("`, `");
-        let values: Vec<String> = data
-            .columns
-            .iter()
-            .map(|col| {
-                let val = row.get(col).unwrap_or(&Value::Null);
-                match val {
-                    Value::String(s) => format!("'{}'", s.replace("'", "''")),
-                    Value::Null => "NULL".to_string(),
-                    Value::Number(n) => n.to_string(),
-                    Value::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
-                    _ => format!("'{}'", val.to_string()),
-                }
-            })
-            .collect();
-        
-        let insert_stmt = format!(
-            "INSERT INTO `{}` (`{}`) VALUES ({});\n",
-            table_name,
-            columns,
-            values.join(", ")
-        );
-        
-        file.write_all(insert_stmt.as_bytes()).map_err(|e| e.to_string())?;
+    options: Option<SqlExportOptions>,
+) -> Result<bool, DbError> {
+    let options = ExportOptions {
+        sql: options.unwrap_or_default(),
+        ..Default::default()
+    };
+    export_result(&data, ExportFormat::Sql, &table_name, &file_path, &options)
+}
+
+/// 按指定格式导出前端传入的查询结果
+///
+/// `table_name` 用作 SQL 的目标表、XML 的结果集名和 HTML 的标题
+#[tauri::command]
+pub async fn export_result_to_file(
+    data: QueryResult,
+    format: ExportFormat,
+    file_path: String,
+    table_name: Option<String>,
+    options: Option<ExportOptions>,
+) -> Result<bool, DbError> {
+    let table_name = table_name.unwrap_or_default();
+    export_result(&data, format, &table_name, &file_path, &options.unwrap_or_default())
+}
+
+/// 写入 Excel 的一个结果集
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResultSheet {
+    /// 工作表名称
+    pub name: String,
+    pub data: QueryResult,
+}
+
+/// 导出为 Excel，每个结果集一个工作表
+#[tauri::command]
+pub async fn export_to_xlsx(
+    sheets: Vec<ResultSheet>,
+    file_path: String,
+) -> Result<bool, DbError> {
+    let file = File::create(&file_path).map_err(export::write_error)?;
+    let mut writer = XlsxWriter::new(BufWriter::new(file), "Sheet1");
+
+    for sheet in &sheets {
+        writer
+            .start_sheet(&sheet.name, &sheet.data.columns)
+            .map_err(export::write_error)?;
+        export::write_rows(&mut writer, &sheet.data).map_err(export::write_error)?;
     }
-    
+    writer.finish().map_err(export::write_error)?;
+
     Ok(true)
 }
 
+/// 流式导出的数据来源
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportSource {
+    /// 查询语句；导出为 XLSX 时可以是多条语句的脚本，每个结果集一个工作表
+    Query { sql: String },
+    /// 整张表
+    Table {
+        table: String,
+        #[serde(default)]
+        schema: Option<String>,
+    },
+}
+
+/// 流式导出任务
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreamExportTask {
+    /// 前端生成的任务 ID，用于进度事件和取消
+    pub export_id: String,
+    pub connection_id: String,
+    #[serde(default)]
+    pub database: Option<String>,
+    pub source: ExportSource,
+    pub format: ExportFormat,
+    pub file_path: String,
+    /// SQL 格式的目标表名（默认使用来源表名）
+    #[serde(default)]
+    pub table_name: Option<String>,
+    /// 格式选项
+    #[serde(default)]
+    pub options: ExportOptions,
+}
+
+/// 流式导出结果
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamExportSummary {
+    pub export_id: String,
+    pub rows: u64,
+    pub elapsed_ms: u128,
+    /// 被取消时已删除未完成的文件
+    pub cancelled: bool,
+}
+
+/// 直接从数据库游标流式导出到文件
+///
+/// 查询结果不经过前端，也不会整体保存在内存中；
+/// 导出过程中发送 `export-progress` 事件，可通过 `cancel_export` 取消
+#[tauri::command]
+pub async fn export_query_to_file(
+    app: AppHandle,
+    task: StreamExportTask,
+    state: State<'_, AppState>,
+) -> Result<StreamExportSummary, DbError> {
+    let start = Instant::now();
+    let manager = state.connection_manager.clone();
+    let driver = manager.get_driver(&task.connection_id).await?;
+
+    let (statements, default_table) = match &task.source {
+        ExportSource::Query { sql } => {
+            let statements = match driver.dialect() {
+                Some(dialect) => SqlSplitter::new(dialect).split(sql),
+                None => vec![sql.trim().to_string()],
+            };
+            // 除多结果格式外，游标只能读取一条语句的结果
+            if statements.is_empty() || (statements.len() > 1 && !task.format.multi_result()) {
+                return Err(Msg::SingleStatementRequired.into());
+            }
+            (statements, "export".to_string())
+        }
+        ExportSource::Table { table, schema } => {
+            let qualified = match schema {
+                Some(schema) => format!(
+                    "{}.{}",
+                    driver.quote_identifier(schema),
+                    driver.quote_identifier(table)
+                ),
+                None => driver.quote_identifier(table),
+            };
+            (vec![format!("SELECT * FROM {}", qualified)], table.clone())
+        }
+    };
+    let table_name = task.table_name.clone().unwrap_or(default_table);
+
+    let mut options = task.options.clone();
+    if task.format == ExportFormat::Sql {
+        let source_type = driver.db_type();
+        let sql = &mut options.sql;
+        sql.binary_base64 = driver.binary_as_base64();
+        // 导出整表时按表结构识别二进制列；目标数据库相同时建表语句沿用原始列类型
+        if let ExportSource::Table { table, schema } = &task.source {
+            let columns = manager
+                .get_table_structure(&task.connection_id, table, schema.as_deref(), task.database.as_deref())
+                .await
+                .unwrap_or_default();
+            sql.binary_columns.extend(
+                columns
+                    .iter()
+                    .filter(|c| export::sql::is_binary_type(&c.data_type))
+                    .map(|c| c.name.clone()),
+            );
+            if sql.dialect.as_ref().is_none_or(|dialect| *dialect == source_type) {
+                sql.table_columns = columns;
+            }
+        }
+        sql.dialect.get_or_insert(source_type);
+    } else if matches!(task.format, ExportFormat::Parquet | ExportFormat::Arrow) {
+        let columnar = &mut options.columnar;
+        columnar.binary_base64 = driver.binary_as_base64();
+        // 导出整表时按表结构确定列类型，查询结果按数据推断
+        if let ExportSource::Table { table, schema } = &task.source {
+            columnar.table_columns = manager
+                .get_table_structure(&task.connection_id, table, schema.as_deref(), task.database.as_deref())
+                .await
+                .unwrap_or_default();
+        }
+        columnar.source_type = Some(driver.db_type());
+    }
+
+    let file = File::create(&task.file_path).map_err(export::write_error)?;
+    let writer = export::create_writer(task.format, file, &table_name, &options)?;
+
+    let job = ExportJob::start(&task.export_id);
+    let mut sink = ExportSink::new(writer, &job, |progress: ExportProgress| {
+        let _ = app.emit(EXPORT_PROGRESS_EVENT, progress);
+    });
+
+    let database = task.database.as_deref();
+    let mut result = Ok(0);
+    for sql in &statements {
+        result = if statements.len() == 1 || is_query_statement(sql) {
+            manager.stream_query(&task.connection_id, sql, database, &mut sink).await
+        } else {
+            // 脚本中的非查询语句只执行，不生成工作表
+            manager
+                .execute_query(&task.connection_id, sql, database)
+                .await
+                .map(|r| r.affected_rows)
+        };
+        if result.is_err() {
+            break;
+        }
+    }
+
+    let summary = |rows, cancelled| StreamExportSummary {
+        export_id: task.export_id.clone(),
+        rows,
+        elapsed_ms: start.elapsed().as_millis(),
+        cancelled,
+    };
+
+    match result {
+        Ok(_) => Ok(summary(sink.finish()?, false)),
+        Err(e) => {
+            let cancelled = job.is_cancelled();
+            drop(sink);
+            // 不保留不完整的文件
+            let _ = std::fs::remove_file(&task.file_path);
+            if cancelled {
+                Ok(summary(0, true))
+            } else {
+                Err(e)
+            }
+        }
+    }
+}
+
+/// 取消流式导出，任务已结束时返回 false
+#[tauri::command]
+pub async fn cancel_export(export_id: String) -> Result<bool, DbError> {
+    Ok(export::cancel_export(&export_id))
+}
+
 /// 导出表结构为 DDL
 #[tauri::command]
 pub async fn export_table_ddl(
@@ -97,25 +292,39 @@ pub async fn export_table_ddl(
     database: String,
     table: String,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<String, DbError> {
+    let manager = &state.connection_manager;
 
-    // 获取表的 CREATE TABLE 语句
-    let sql = format!("SHOW CREATE TABLE `{}`.`{}`", database, table);
-    let result = manager
-        .execute_query(&connection_id, &sql, Some(&database))
+    create_table_ddl(manager, &connection_id, &database, None, &table)
         .await
-        .map_err(|e| format!("获取表结构失败: {}", e))?;
+        .map_err(|e| e.context(Msg::TableStructureFailed))?
+        .ok_or_else(|| Msg::TableStructureUnavailable.with(&[&database, &table]).into())
+}
 
-    // SHOW CREATE TABLE 返回的结果中，第二列是 CREATE TABLE 语句
-    if let Some(row) = result.rows.first() {
-        if let Some(create_stmt) = row.get("Create Table") {
-            if let Some(ddl) = create_stmt.as_str() {
-                return Ok(ddl.to_string());
-            }
+/// 按指定格式把结果中选中的区域复制到剪贴板，返回复制的文本
+///
+/// SQL 格式按 `dialect` 引用标识符和字面量，未指定时使用 `connection_id` 对应的数据库类型，
+/// 两者都没有时按 MySQL 语法
+#[tauri::command]
+pub async fn copy_selection(
+    app: AppHandle,
+    selection: CopySelection,
+    connection_id: Option<String>,
+    dialect: Option<DatabaseType>,
+    state: State<'_, AppState>,
+) -> Result<String, DbError> {
+    let dialect = match (dialect, connection_id) {
+        (Some(dialect), _) => dialect,
+        (None, Some(connection_id)) => {
+            let manager = state.connection_manager.clone();
+            manager.get_database_type(&connection_id).await?
         }
-    }
+        (None, None) => DatabaseType::MySQL,
+    };
 
-    Err(format!("无法获取表 {}.{} 的结构", database, table))
+    let text = clipboard::format_selection(&selection, &dialect)?;
+    app.clipboard()
+        .write_text(text.as_str())
+        .map_err(|e| DbError::Other(format!("{}: {}", Msg::ClipboardWriteFailed, e)))?;
+    Ok(text)
 }
-
@@ -0,0 +1,241 @@
+use crate::database::{ConnectionManager, DatabaseType, DbError, DbResult, ErrorCode, Transaction};
+use crate::import::{
+    self, BatchInserter, ColumnMapping, ImportColumn, ImportPreview, ImportProgress, ReadOptions,
+    RejectedRow, RowSource,
+};
+use crate::utils::sql_formatter::SqlFormatter;
+use crate::AppState;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+/// 导入进度事件
+pub const IMPORT_PROGRESS_EVENT: &str = "import-progress";
+
+/// 进度事件的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 预览时默认返回的行数
+const DEFAULT_PREVIEW_ROWS: usize = 100;
+
+/// 预览导入文件：工作表、推断的列类型和前若干行
+#[tauri::command]
+pub async fn preview_import_file(
+    file_path: String,
+    options: ReadOptions,
+    limit: Option<usize>,
+) -> Result<ImportPreview, DbError> {
+    // 电子表格需要整体解析，放到阻塞线程中执行
+    tokio::task::spawn_blocking(move || {
+        import::preview_file(&file_path, &options, limit.unwrap_or(DEFAULT_PREVIEW_ROWS))
+    })
+    .await
+    .map_err(|e| DbError::Other(e.to_string()))?
+}
+
+/// 按推断的列生成目标数据库的建表语句
+#[tauri::command]
+pub async fn generate_import_ddl(
+    connection_id: String,
+    database: Option<String>,
+    schema: Option<String>,
+    table: String,
+    columns: Vec<ImportColumn>,
+    state: State<'_, AppState>,
+) -> Result<String, DbError> {
+    let manager = &state.connection_manager;
+    let driver = manager.get_driver(&connection_id).await?;
+
+    Ok(import::sql::create_table_sql(
+        &driver.db_type(),
+        database.as_deref().unwrap_or_default(),
+        schema.as_deref(),
+        &table,
+        &columns,
+    ))
+}
+
+/// 导入任务
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportTask {
+    /// 前端生成的任务 ID，用于进度事件
+    pub import_id: String,
+    pub connection_id: String,
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub table: String,
+    pub file_path: String,
+    pub options: ReadOptions,
+    pub mappings: Vec<ColumnMapping>,
+    /// 导入前执行的建表语句（支持事务时在同一事务中执行）
+    #[serde(default)]
+    pub create_table_sql: Option<String>,
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    /// 允许被拒绝的行数，超过后回滚整个导入
+    #[serde(default)]
+    pub max_errors: Option<usize>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSummary {
+    pub import_id: String,
+    /// 读取的数据行数
+    pub rows: u64,
+    pub inserted: u64,
+    pub rejected: Vec<RejectedRow>,
+    pub elapsed_ms: u128,
+}
+
+/// 不支持事务的连接（例如 ClickHouse）上逐条直接执行语句
+///
+/// 每条语句单独生效，失败的语句不影响其他行，因此不需要真正创建保存点；
+/// 出错时已插入的行不会回滚
+struct DirectSession {
+    manager: ConnectionManager,
+    connection_id: String,
+    database: Option<String>,
+}
+
+#[async_trait]
+impl Transaction for DirectSession {
+    async fn execute(&mut self, sql: &str) -> DbResult<u64> {
+        self.manager
+            .execute_query(&self.connection_id, sql, self.database.as_deref())
+            .await
+            .map(|result| result.affected_rows)
+    }
+
+    async fn savepoint(&mut self, _name: &str) -> DbResult<()> {
+        Ok(())
+    }
+
+    async fn rollback_to_savepoint(&mut self, _name: &str) -> DbResult<()> {
+        Ok(())
+    }
+
+    async fn release_savepoint(&mut self, _name: &str) -> DbResult<()> {
+        Ok(())
+    }
+
+    async fn commit(self: Box<Self>) -> DbResult<()> {
+        Ok(())
+    }
+
+    async fn rollback(self: Box<Self>) -> DbResult<()> {
+        Ok(())
+    }
+}
+
+/// 将文件导入到表
+///
+/// 支持事务时所有数据在一个事务中插入，出错时整体回滚；不支持事务的数据库逐批直接插入。
+/// 被数据库拒绝的行记录在结果中，导入过程中发送 `import-progress` 事件
+#[tauri::command]
+pub async fn import_file_to_table(
+    app: AppHandle,
+    task: ImportTask,
+    state: State<'_, AppState>,
+) -> Result<ImportSummary, DbError> {
+    let start = Instant::now();
+    let manager = state.connection_manager.clone();
+    let driver = manager.get_driver(&task.connection_id).await?;
+    let source = RowSource::open(&task.file_path, &task.options)?;
+
+    let mut tx: Box<dyn Transaction> = match manager
+        .begin_transaction(&task.connection_id, task.database.as_deref())
+        .await
+    {
+        Ok(tx) => tx,
+        Err(e) if e.code() == ErrorCode::Unsupported => Box::new(DirectSession {
+            manager: manager.clone(),
+            connection_id: task.connection_id.clone(),
+            database: task.database.clone(),
+        }),
+        Err(e) => return Err(e),
+    };
+
+    let emit = |rows, inserted, rejected| {
+        let _ = app.emit(
+            IMPORT_PROGRESS_EVENT,
+            ImportProgress {
+                import_id: task.import_id.clone(),
+                rows,
+                inserted,
+                rejected,
+                elapsed_ms: start.elapsed().as_millis(),
+            },
+        );
+    };
+
+    let table_ref = SqlFormatter::format_table_ref(
+        &driver.db_type(),
+        task.database.as_deref().unwrap_or_default(),
+        &task.table,
+        task.schema.as_deref(),
+    );
+    let result = import_rows(tx.as_mut(), &task, driver.db_type(), table_ref, source, &emit).await;
+
+    match result {
+        Ok((rows, inserted, rejected)) => {
+            tx.commit().await?;
+            emit(rows, inserted, rejected.len() as u64);
+            Ok(ImportSummary {
+                import_id: task.import_id.clone(),
+                rows,
+                inserted,
+                rejected,
+                elapsed_ms: start.elapsed().as_millis(),
+            })
+        }
+        Err(e) => {
+            let _ = tx.rollback().await;
+            Err(e)
+        }
+    }
+}
+
+/// 在事务（或直接执行的会话）中建表并插入所有行，返回 (读取行数, 插入行数, 被拒绝的行)
+async fn import_rows(
+    tx: &mut dyn Transaction,
+    task: &ImportTask,
+    db_type: DatabaseType,
+    table_ref: String,
+    source: RowSource,
+    emit: &(dyn Fn(u64, u64, u64) + Sync),
+) -> DbResult<(u64, u64, Vec<RejectedRow>)> {
+    if let Some(sql) = task.create_table_sql.as_deref().filter(|s| !s.trim().is_empty()) {
+        tx.execute(sql).await?;
+    }
+
+    let width = source.columns.len();
+    let mut inserter = BatchInserter::new(
+        tx,
+        db_type,
+        table_ref,
+        &task.mappings,
+        width,
+        task.batch_size.unwrap_or(import::DEFAULT_BATCH_SIZE),
+        task.max_errors.unwrap_or(import::DEFAULT_MAX_ERRORS),
+    )?;
+
+    let mut rows = 0u64;
+    let mut last_report = Instant::now();
+    for item in source {
+        let (row, values) = item?;
+        inserter.push(row, values).await?;
+        rows += 1;
+
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            last_report = Instant::now();
+            emit(rows, inserter.inserted, inserter.rejected.len() as u64);
+        }
+    }
+    inserter.flush().await?;
+
+    Ok((rows, inserter.inserted, inserter.rejected))
+}
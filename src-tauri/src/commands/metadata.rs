@@ -1,24 +1,28 @@
-use crate::database::{ColumnInfo, DatabaseInfo, TableInfo, QueryResult, DatabaseType, TableOptions};
+use crate::database::registry::ObjectType;
+use crate::database::{
+    ColumnInfo, ConnectionManager, DatabaseInfo, DbError, QueryResult, ServerFlavor, ServerInfo,
+    TableInfo, TableOptions,
+};
+use crate::utils::i18n::Msg;
 use crate::AppState;
 use tauri::State;
 
-/// 根据数据库类型生成表引用 SQL
-fn format_table_reference(db_type: &DatabaseType, database: &str, table: &str, schema: Option<&str>) -> String {
-    match db_type {
-        DatabaseType::PostgreSQL => {
-            let schema_name = schema.unwrap_or("public");
-            format!("\"{}\".\"{}\"", schema_name, table)
-        }
-        DatabaseType::MySQL => {
-            format!("`{}`.`{}`", database, table)
-        }
-        DatabaseType::SQLite => {
-            format!("\"{}\"", table)
-        }
-        _ => {
-            format!("\"{}\".\"{}\"", database, table)
-        }
-    }
+/// 获取连接的服务器信息（获取失败时返回 None，按默认方言处理）
+async fn server_info(manager: &ConnectionManager, connection_id: &str) -> Option<ServerInfo> {
+    manager.get_server_info(connection_id).await.ok()
+}
+
+/// 连接是否支持某类对象（按驱动能力和服务器特性判断）
+async fn supports_object(
+    manager: &ConnectionManager,
+    connection_id: &str,
+    object_type: ObjectType,
+) -> bool {
+    manager
+        .get_capabilities(connection_id)
+        .await
+        .map(|capabilities| capabilities.supports_object(object_type))
+        .unwrap_or(false)
 }
 
 /// 获取数据库列表
@@ -26,13 +30,12 @@ fn format_table_reference(db_type: &DatabaseType, database: &str, table: &str, s
 pub async fn get_databases(
     connection_id: String,
     state: State<'_, AppState>,
-) -> Result<Vec<DatabaseInfo>, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<Vec<DatabaseInfo>, DbError> {
+    let manager = &state.connection_manager;
     
     manager
         .get_databases(&connection_id)
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 获取表列表
@@ -41,13 +44,12 @@ pub async fn get_tables(
     connection_id: String,
     database: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Vec<TableInfo>, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<Vec<TableInfo>, DbError> {
+    let manager = &state.connection_manager;
     
     manager
         .get_tables(&connection_id, database.as_deref())
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 获取表结构
@@ -58,13 +60,12 @@ pub async fn get_table_structure(
     schema: Option<String>,
     database: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Vec<ColumnInfo>, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<Vec<ColumnInfo>, DbError> {
+    let manager = &state.connection_manager;
     
     manager
         .get_table_structure(&connection_id, &table, schema.as_deref(), database.as_deref())
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 查看表数据
@@ -76,45 +77,16 @@ pub async fn view_table_data(
     schema: Option<String>,
     limit: Option<u32>,
     state: State<'_, AppState>,
-) -> Result<QueryResult, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<QueryResult, DbError> {
+    let manager = &state.connection_manager;
     
-    let limit_clause = match limit {
-        Some(l) => format!(" LIMIT {}", l),
-        None => String::new(),
-    };
-    
-    // 获取数据库类型
-    let db_type = manager
-        .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    // 根据数据库类型生成不同的 SQL
-    let sql = match db_type {
-        crate::database::DatabaseType::PostgreSQL => {
-            // PostgreSQL 使用 schema.table 格式
-            let schema_name = schema.unwrap_or_else(|| "public".to_string());
-            format!("SELECT * FROM \"{}\".\"{}\"{}", schema_name, table, limit_clause)
-        }
-        crate::database::DatabaseType::MySQL => {
-            // MySQL 使用 `database`.`table` 格式
-            format!("SELECT * FROM `{}`.`{}`{}", database, table, limit_clause)
-        }
-        crate::database::DatabaseType::SQLite => {
-            // SQLite 直接使用表名
-            format!("SELECT * FROM \"{}\"{}", table, limit_clause)
-        }
-        _ => {
-            // 默认使用标准 SQL
-            format!("SELECT * FROM \"{}\".\"{}\"{}", database, table, limit_clause)
-        }
-    };
+    let driver = manager.get_driver(&connection_id).await?;
+    let table_ref = driver.table_ref(&database, schema.as_deref(), &table);
+    let sql = driver.select_rows_sql(&table_ref, limit);
     
     manager
         .execute_query(&connection_id, &sql, Some(&database))
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 清空表数据
@@ -125,43 +97,18 @@ pub async fn truncate_table(
     database: String,
     schema: Option<String>,
     state: State<'_, AppState>,
-) -> Result<QueryResult, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<QueryResult, DbError> {
+    let manager = &state.connection_manager;
     
-    let db_type = manager
-        .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    let table_ref = format_table_reference(&db_type, &database, &table, schema.as_deref());
-    
-    // 根据数据库类型构建 SQL 脚本
-    // 重要：MySQL 需要将所有语句作为一次 execute_query 调用传入，
-    // 这样才能在同一个连接上执行，SET FOREIGN_KEY_CHECKS 的效果才能延续
-    let sql_script = match db_type {
-        DatabaseType::MySQL => {
-            // MySQL: 在一个脚本中禁用外键检查、执行 TRUNCATE、重新启用外键检查
-            format!(
-                "SET FOREIGN_KEY_CHECKS = 0;\nTRUNCATE TABLE {};\nSET FOREIGN_KEY_CHECKS = 1;",
-                table_ref
-            )
-        }
-        DatabaseType::PostgreSQL => {
-            // PostgreSQL: 使用 TRUNCATE ... CASCADE 自动处理外键约束
-            format!("TRUNCATE TABLE {} CASCADE;", table_ref)
-        }
-        _ => {
-            // 其他数据库: 直接执行 TRUNCATE
-            format!("TRUNCATE TABLE {};", table_ref)
-        }
-    };
+    let driver = manager.get_driver(&connection_id).await?;
+    let table_ref = driver.table_ref(&database, schema.as_deref(), &table);
+    
+    // 例如 MySQL 需要在同一个连接上关闭外键检查，脚本中的语句作为一次 execute_query 调用执行
+    let sql_script = driver.truncate_table_sql(&table_ref);
     
-    // 直接将整个脚本传给 execute_query，让 MySQL 内部处理多条语句
-    // MySQL 的 execute_query 实现会在同一个连接上依次执行所有语句
     manager
         .execute_query(&connection_id, &sql_script, Some(&database))
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 删除表
@@ -172,21 +119,15 @@ pub async fn drop_table(
     database: String,
     schema: Option<String>,
     state: State<'_, AppState>,
-) -> Result<QueryResult, String> {
-    let manager = state.connection_manager.lock().await;
-    
-    let db_type = manager
-        .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
+) -> Result<QueryResult, DbError> {
+    let manager = &state.connection_manager;
     
-    let table_ref = format_table_reference(&db_type, &database, &table, schema.as_deref());
-    let sql = format!("DROP TABLE {}", table_ref);
+    let driver = manager.get_driver(&connection_id).await?;
+    let sql = format!("DROP TABLE {}", driver.table_ref(&database, schema.as_deref(), &table));
     
     manager
         .execute_query(&connection_id, &sql, Some(&database))
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 获取视图列表
@@ -195,13 +136,12 @@ pub async fn get_views(
     connection_id: String,
     database: String,
     state: State<'_, AppState>,
-) -> Result<Vec<TableInfo>, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<Vec<TableInfo>, DbError> {
+    let manager = &state.connection_manager;
     
     manager
         .get_views(&connection_id, Some(&database))
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 获取存储过程列表
@@ -210,21 +150,22 @@ pub async fn get_procedures(
     connection_id: String,
     database: String,
     state: State<'_, AppState>,
-) -> Result<Vec<serde_json::Value>, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<Vec<serde_json::Value>, DbError> {
+    let manager = &state.connection_manager;
     
-    let sql = format!(
-        "SELECT ROUTINE_NAME, ROUTINE_TYPE, CREATED, LAST_ALTERED, ROUTINE_COMMENT
-         FROM information_schema.ROUTINES 
-         WHERE ROUTINE_SCHEMA = '{}' AND ROUTINE_TYPE = 'PROCEDURE'
-         ORDER BY ROUTINE_NAME",
-        database.replace("'", "''")
-    );
+    // 例如 SQLite、TiDB 不支持存储过程
+    if !supports_object(manager, &connection_id, ObjectType::Procedure).await {
+        return Ok(Vec::new());
+    }
+    
+    let driver = manager.get_driver(&connection_id).await?;
+    let Some(sql) = driver.list_routines_sql(&database, "PROCEDURE") else {
+        return Ok(Vec::new());
+    };
     
     let result = manager
         .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     Ok(result.rows.into_iter().map(|row| serde_json::Value::Object(
         row.into_iter().collect()
@@ -237,21 +178,22 @@ pub async fn get_functions(
     connection_id: String,
     database: String,
     state: State<'_, AppState>,
-) -> Result<Vec<serde_json::Value>, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<Vec<serde_json::Value>, DbError> {
+    let manager = &state.connection_manager;
     
-    let sql = format!(
-        "SELECT ROUTINE_NAME, ROUTINE_TYPE, CREATED, LAST_ALTERED, ROUTINE_COMMENT
-         FROM information_schema.ROUTINES 
-         WHERE ROUTINE_SCHEMA = '{}' AND ROUTINE_TYPE = 'FUNCTION'
-         ORDER BY ROUTINE_NAME",
-        database.replace("'", "''")
-    );
+    // 例如 SQLite、TiDB 不支持存储函数
+    if !supports_object(manager, &connection_id, ObjectType::Function).await {
+        return Ok(Vec::new());
+    }
+    
+    let driver = manager.get_driver(&connection_id).await?;
+    let Some(sql) = driver.list_routines_sql(&database, "FUNCTION") else {
+        return Ok(Vec::new());
+    };
     
     let result = manager
         .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     Ok(result.rows.into_iter().map(|row| serde_json::Value::Object(
         row.into_iter().collect()
@@ -264,22 +206,22 @@ pub async fn get_triggers(
     connection_id: String,
     database: String,
     state: State<'_, AppState>,
-) -> Result<Vec<serde_json::Value>, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<Vec<serde_json::Value>, DbError> {
+    let manager = &state.connection_manager;
     
-    let sql = format!(
-        "SELECT TRIGGER_NAME, EVENT_MANIPULATION, EVENT_OBJECT_TABLE, 
-                ACTION_TIMING, CREATED
-         FROM information_schema.TRIGGERS 
-         WHERE TRIGGER_SCHEMA = '{}'
-         ORDER BY TRIGGER_NAME",
-        database.replace("'", "''")
-    );
+    // 例如 TiDB、ClickHouse 不支持触发器
+    if !supports_object(manager, &connection_id, ObjectType::Trigger).await {
+        return Ok(Vec::new());
+    }
+    
+    let driver = manager.get_driver(&connection_id).await?;
+    let Some(sql) = driver.list_triggers_sql(&database) else {
+        return Ok(Vec::new());
+    };
     
     let result = manager
         .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     Ok(result.rows.into_iter().map(|row| serde_json::Value::Object(
         row.into_iter().collect()
@@ -292,8 +234,13 @@ pub async fn get_events(
     connection_id: String,
     database: String,
     state: State<'_, AppState>,
-) -> Result<Vec<serde_json::Value>, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<Vec<serde_json::Value>, DbError> {
+    let manager = &state.connection_manager;
+    
+    // 只有 MySQL / MariaDB 有事件
+    if !supports_object(manager, &connection_id, ObjectType::Event).await {
+        return Ok(Vec::new());
+    }
     
     let sql = format!(
         "SELECT EVENT_NAME, STATUS, EVENT_TYPE, EXECUTE_AT, 
@@ -306,8 +253,87 @@ pub async fn get_events(
     
     let result = manager
         .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
+    
+    Ok(result.rows.into_iter().map(|row| serde_json::Value::Object(
+        row.into_iter().collect()
+    )).collect())
+}
+
+/// 获取序列列表（MariaDB 10.3+、TiDB、PostgreSQL）
+#[tauri::command]
+pub async fn get_sequences(
+    connection_id: String,
+    database: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<serde_json::Value>, DbError> {
+    let manager = &state.connection_manager;
+    
+    if !supports_object(manager, &connection_id, ObjectType::Sequence).await {
+        return Ok(Vec::new());
+    }
+    let Some(info) = server_info(manager, &connection_id).await else {
+        return Ok(Vec::new());
+    };
+    
+    let escaped = database.replace("'", "''");
+    let sql = match info.flavor {
+        // MariaDB 的序列是一种特殊的表，没有 information_schema.SEQUENCES
+        ServerFlavor::MariaDB => format!(
+            "SELECT TABLE_NAME AS SEQUENCE_NAME, CREATE_TIME AS CREATED, TABLE_COMMENT AS COMMENT
+             FROM information_schema.TABLES
+             WHERE TABLE_SCHEMA = '{}' AND TABLE_TYPE = 'SEQUENCE'
+             ORDER BY TABLE_NAME",
+            escaped
+        ),
+        ServerFlavor::TiDB => format!(
+            "SELECT SEQUENCE_NAME, START, MIN_VALUE, MAX_VALUE, INCREMENT, CYCLE, CACHE, COMMENT
+             FROM information_schema.SEQUENCES
+             WHERE SEQUENCE_SCHEMA = '{}'
+             ORDER BY SEQUENCE_NAME",
+            escaped
+        ),
+        ServerFlavor::PostgreSQL | ServerFlavor::CockroachDB => "SELECT sequence_schema AS \"SEQUENCE_SCHEMA\", sequence_name AS \"SEQUENCE_NAME\",
+                    start_value AS \"START\", minimum_value AS \"MIN_VALUE\", maximum_value AS \"MAX_VALUE\",
+                    increment AS \"INCREMENT\", cycle_option AS \"CYCLE\"
+             FROM information_schema.sequences
+             WHERE sequence_schema NOT IN ('pg_catalog', 'information_schema')
+             ORDER BY sequence_schema, sequence_name"
+            .to_string(),
+        _ => return Ok(Vec::new()),
+    };
+    
+    let result = manager
+        .execute_query(&connection_id, &sql, Some(&database))
+        .await?;
+    
+    Ok(result.rows.into_iter().map(|row| serde_json::Value::Object(
+        row.into_iter().collect()
+    )).collect())
+}
+
+/// 获取 TiDB 放置策略列表（集群级对象，其他数据库返回空列表）
+#[tauri::command]
+pub async fn get_placement_policies(
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<serde_json::Value>, DbError> {
+    let manager = &state.connection_manager;
+    
+    // Placement Rules in SQL 从 TiDB 6.0 开始提供
+    match server_info(manager, &connection_id).await {
+        Some(info) if info.flavor == ServerFlavor::TiDB && info.version_at_least(6, 0) => {}
+        _ => return Ok(Vec::new()),
+    }
+    
+    let sql = "SELECT POLICY_NAME, PRIMARY_REGION, REGIONS, CONSTRAINTS, LEADER_CONSTRAINTS,
+                      FOLLOWERS, FOLLOWER_CONSTRAINTS, LEARNERS, LEARNER_CONSTRAINTS, SCHEDULE
+               FROM information_schema.PLACEMENT_POLICIES
+               ORDER BY POLICY_NAME";
+    
+    let result = manager
+        .execute_query(&connection_id, sql, None)
+        .await?;
     
     Ok(result.rows.into_iter().map(|row| serde_json::Value::Object(
         row.into_iter().collect()
@@ -322,21 +348,15 @@ pub async fn drop_view(
     database: String,
     schema: Option<String>,
     state: State<'_, AppState>,
-) -> Result<QueryResult, String> {
-    let manager = state.connection_manager.lock().await;
-    
-    let db_type = manager
-        .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
+) -> Result<QueryResult, DbError> {
+    let manager = &state.connection_manager;
     
-    let view_ref = format_table_reference(&db_type, &database, &view, schema.as_deref());
-    let sql = format!("DROP VIEW {}", view_ref);
+    let driver = manager.get_driver(&connection_id).await?;
+    let sql = format!("DROP VIEW {}", driver.table_ref(&database, schema.as_deref(), &view));
     
     manager
         .execute_query(&connection_id, &sql, Some(&database))
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 获取视图定义
@@ -346,30 +366,21 @@ pub async fn get_view_definition(
     view: String,
     database: String,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<String, DbError> {
+    let manager = &state.connection_manager;
     
-    let sql = format!(
-        "SELECT VIEW_DEFINITION FROM information_schema.VIEWS 
-         WHERE TABLE_SCHEMA = '{}' AND TABLE_NAME = '{}'",
-        database.replace("'", "''"),
-        view.replace("'", "''")
-    );
+    let driver = manager.get_driver(&connection_id).await?;
+    let sql = driver.view_definition_sql(&database, &view);
     
     let result = manager
         .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
-    if let Some(row) = result.rows.first() {
-        if let Some(definition) = row.get("VIEW_DEFINITION") {
-            if let serde_json::Value::String(def) = definition {
-                return Ok(def.clone());
-            }
-        }
+    if let Some(serde_json::Value::String(def)) = result.rows.first().and_then(|row| row.get("VIEW_DEFINITION")) {
+        return Ok(def.clone());
     }
     
-    Err("未找到视图定义".to_string())
+    Err(Msg::ViewDefinitionNotFound.into())
 }
 
 /// 删除存储过程
@@ -380,31 +391,15 @@ pub async fn drop_procedure(
     database: String,
     schema: Option<String>,
     state: State<'_, AppState>,
-) -> Result<QueryResult, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<QueryResult, DbError> {
+    let manager = &state.connection_manager;
     
-    let db_type = manager
-        .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    let sql = match db_type {
-        DatabaseType::PostgreSQL => {
-            let schema_name = schema.unwrap_or_else(|| "public".to_string());
-            format!("DROP PROCEDURE \"{}\".\"{}\"", schema_name, procedure)
-        }
-        DatabaseType::MySQL => {
-            format!("DROP PROCEDURE `{}`.`{}`", database, procedure)
-        }
-        _ => {
-            format!("DROP PROCEDURE \"{}\"", procedure)
-        }
-    };
+    let driver = manager.get_driver(&connection_id).await?;
+    let sql = format!("DROP PROCEDURE {}", driver.routine_ref(&database, schema.as_deref(), &procedure));
     
     manager
         .execute_query(&connection_id, &sql, Some(&database))
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 删除函数
@@ -415,31 +410,15 @@ pub async fn drop_function(
     database: String,
     schema: Option<String>,
     state: State<'_, AppState>,
-) -> Result<QueryResult, String> {
-    let manager = state.connection_manager.lock().await;
-    
-    let db_type = manager
-        .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
+) -> Result<QueryResult, DbError> {
+    let manager = &state.connection_manager;
     
-    let sql = match db_type {
-        DatabaseType::PostgreSQL => {
-            let schema_name = schema.unwrap_or_else(|| "public".to_string());
-            format!("DROP FUNCTION \"{}\".\"{}\"", schema_name, function)
-        }
-        DatabaseType::MySQL => {
-            format!("DROP FUNCTION `{}`.`{}`", database, function)
-        }
-        _ => {
-            format!("DROP FUNCTION \"{}\"", function)
-        }
-    };
+    let driver = manager.get_driver(&connection_id).await?;
+    let sql = format!("DROP FUNCTION {}", driver.routine_ref(&database, schema.as_deref(), &function));
     
     manager
         .execute_query(&connection_id, &sql, Some(&database))
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 删除触发器
@@ -450,31 +429,15 @@ pub async fn drop_trigger(
     database: String,
     schema: Option<String>,
     state: State<'_, AppState>,
-) -> Result<QueryResult, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<QueryResult, DbError> {
+    let manager = &state.connection_manager;
     
-    let db_type = manager
-        .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    let sql = match db_type {
-        DatabaseType::PostgreSQL => {
-            let schema_name = schema.unwrap_or_else(|| "public".to_string());
-            format!("DROP TRIGGER \"{}\".\"{}\"", schema_name, trigger)
-        }
-        DatabaseType::MySQL => {
-            format!("DROP TRIGGER `{}`.`{}`", database, trigger)
-        }
-        _ => {
-            format!("DROP TRIGGER \"{}\"", trigger)
-        }
-    };
+    let driver = manager.get_driver(&connection_id).await?;
+    let sql = format!("DROP TRIGGER {}", driver.routine_ref(&database, schema.as_deref(), &trigger));
     
     manager
         .execute_query(&connection_id, &sql, Some(&database))
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 删除事件
@@ -483,30 +446,21 @@ pub async fn drop_event(
     connection_id: String,
     event: String,
     database: String,
-    _schema: Option<String>,
+    schema: Option<String>,
     state: State<'_, AppState>,
-) -> Result<QueryResult, String> {
-    let manager = state.connection_manager.lock().await;
-    
-    let db_type = manager
-        .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
+) -> Result<QueryResult, DbError> {
+    let manager = &state.connection_manager;
     
-    // 注意：PostgreSQL 不支持 EVENT，这是 MySQL 特有的功能
-    let sql = match db_type {
-        DatabaseType::MySQL => {
-            format!("DROP EVENT `{}`.`{}`", database, event)
-        }
-        _ => {
-            return Err("该数据库类型不支持 EVENT".to_string());
-        }
-    };
+    // 只有 MySQL / MariaDB 有事件
+    if !supports_object(manager, &connection_id, ObjectType::Event).await {
+        return Err(Msg::EventNotSupported.into());
+    }
+    let driver = manager.get_driver(&connection_id).await?;
+    let sql = format!("DROP EVENT {}", driver.routine_ref(&database, schema.as_deref(), &event));
     
     manager
         .execute_query(&connection_id, &sql, Some(&database))
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 获取表索引
@@ -516,8 +470,8 @@ pub async fn get_table_indexes(
     database: String,
     table: String,
     state: State<'_, AppState>,
-) -> Result<Vec<serde_json::Value>, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<Vec<serde_json::Value>, DbError> {
+    let manager = &state.connection_manager;
     
     let sql = format!(
         "SELECT INDEX_NAME as index_name, COLUMN_NAME as column_name,
@@ -531,8 +485,7 @@ pub async fn get_table_indexes(
     
     let result = manager
         .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     Ok(result.rows.into_iter().map(|row| serde_json::Value::Object(
         row.into_iter().collect()
@@ -546,8 +499,8 @@ pub async fn get_table_foreign_keys(
     database: String,
     table: String,
     state: State<'_, AppState>,
-) -> Result<Vec<serde_json::Value>, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<Vec<serde_json::Value>, DbError> {
+    let manager = &state.connection_manager;
     
     let sql = format!(
         "SELECT CONSTRAINT_NAME as constraint_name, COLUMN_NAME as column_name,
@@ -563,8 +516,7 @@ pub async fn get_table_foreign_keys(
     
     let result = manager
         .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     Ok(result.rows.into_iter().map(|row| serde_json::Value::Object(
         row.into_iter().collect()
@@ -578,13 +530,12 @@ pub async fn get_table_options(
     table: String,
     schema: Option<String>,
     state: State<'_, AppState>,
-) -> Result<TableOptions, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<TableOptions, DbError> {
+    let manager = &state.connection_manager;
     
     manager
         .get_table_options(&connection_id, &table, schema.as_deref())
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 获取创建表的DDL语句
@@ -595,44 +546,42 @@ pub async fn get_create_table_ddl(
     table: String,
     schema: Option<String>,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let manager = state.connection_manager.lock().await;
-    
-    let db_type = manager
-        .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
+) -> Result<String, DbError> {
+    let manager = &state.connection_manager;
     
-    let sql = match db_type {
-        DatabaseType::PostgreSQL => {
-            let schema_name = schema.unwrap_or_else(|| "public".to_string());
-            format!("SELECT 'CREATE TABLE ' || schemaname || '.' || tablename || ' (' || array_to_string(array_agg(column_name || ' ' || data_type), ', ') || ')' as \"Create Table\" FROM pg_tables t JOIN information_schema.columns c ON c.table_name = t.tablename AND c.table_schema = t.schemaname WHERE t.schemaname = '{}' AND t.tablename = '{}' GROUP BY schemaname, tablename", schema_name, table)
-        }
-        DatabaseType::MySQL => {
-            format!("SHOW CREATE TABLE `{}`.`{}`", database, table)
-        }
-        _ => {
-            format!("SHOW CREATE TABLE \"{}\"", table)
-        }
-    };
+    create_table_ddl(manager, &connection_id, &database, schema.as_deref(), &table)
+        .await?
+        .ok_or_else(|| Msg::DdlNotFound.into())
+}
+
+/// 按驱动的建表语句查询读取 DDL，结果中没有 DDL 时返回 None
+pub(crate) async fn create_table_ddl(
+    manager: &ConnectionManager,
+    connection_id: &str,
+    database: &str,
+    schema: Option<&str>,
+    table: &str,
+) -> Result<Option<String>, DbError> {
+    let driver = manager.get_driver(connection_id).await?;
+    let flavor = server_info(manager, connection_id).await.map(|info| info.flavor);
+    let sql = driver.create_table_sql(database, schema, table, flavor);
     
     let result = manager
-        .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    if let Some(row) = result.rows.first() {
-        // MySQL 返回的列名通常是 "Create Table"
-        for (key, value) in row {
-            if key.to_lowercase().contains("create") {
-                if let serde_json::Value::String(ddl) = value {
-                    return Ok(ddl.clone());
+        .execute_query(connection_id, &sql, Some(database))
+        .await?;
+    
+    // MySQL 返回的列名通常是 "Create Table"，ClickHouse 是 "statement"
+    Ok(result.rows.first().and_then(|row| {
+        row.iter().find_map(|(key, value)| {
+            let key = key.to_lowercase();
+            match value {
+                serde_json::Value::String(ddl) if key.contains("create") || key == "statement" => {
+                    Some(ddl.clone())
                 }
+                _ => None,
             }
-        }
-    }
-    
-    Err("未找到DDL语句".to_string())
+        })
+    }))
 }
 
 /// 自动补全数据结构
@@ -664,14 +613,13 @@ pub async fn get_autocomplete_data(
     connection_id: String,
     database: Option<String>,
     state: State<'_, AppState>,
-) -> Result<AutoCompleteData, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<AutoCompleteData, DbError> {
+    let manager = &state.connection_manager;
     
     // 获取数据库列表
     let databases_info = manager
         .get_databases(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     let databases: Vec<String> = databases_info.iter().map(|db| db.name.clone()).collect();
     
@@ -717,7 +665,7 @@ pub async fn get_autocomplete_data(
     }
     
     // SQL 关键字列表
-    let keywords = vec![
+    let mut keywords: Vec<String> = vec![
         "SELECT", "FROM", "WHERE", "INSERT", "UPDATE", "DELETE", "CREATE", "ALTER", "DROP",
         "TABLE", "DATABASE", "INDEX", "VIEW", "PROCEDURE", "FUNCTION", "TRIGGER",
         "JOIN", "INNER", "LEFT", "RIGHT", "OUTER", "ON", "AS", "AND", "OR", "NOT",
@@ -739,6 +687,23 @@ pub async fn get_autocomplete_data(
         "BEGIN", "USE", "SHOW", "DESCRIBE", "DESC", "EXPLAIN",
     ].iter().map(|s| s.to_string()).collect();
     
+    // 按服务器特性补充关键字
+    if let Some(info) = server_info(manager, &connection_id).await {
+        let features = &info.features;
+        let extra: &[(bool, &[&str])] = &[
+            (features.cte, &["WITH", "RECURSIVE"]),
+            (features.window_functions, &["OVER", "PARTITION", "ROW_NUMBER", "RANK", "DENSE_RANK", "LAG", "LEAD"]),
+            (features.returning, &["RETURNING"]),
+            (features.sequences, &["SEQUENCE", "NEXTVAL"]),
+            (features.json, &["JSON"]),
+        ];
+        for (enabled, words) in extra {
+            if *enabled {
+                keywords.extend(words.iter().map(|s| s.to_string()));
+            }
+        }
+    }
+    
     Ok(AutoCompleteData {
         databases,
         tables,
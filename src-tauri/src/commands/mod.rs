@@ -2,6 +2,11 @@ pub mod connection;
 pub mod metadata;
 pub mod query;
 pub mod export;
+pub mod import;
+pub mod dump;
+pub mod transfer;
 pub mod utils;
 pub mod redis;
-
+pub mod elasticsearch;
+pub mod security;
+pub mod settings;
@@ -1,5 +1,5 @@
-use crate::database::{QueryResult, BatchQueryResult, StatementResult};
-use crate::database::{SqlSplitter, SqlDialect, is_query_statement, truncate_sql};
+use crate::database::{QueryResult, BatchQueryResult, DbError, StatementResult};
+use crate::database::{SqlSplitter, is_query_statement, truncate_sql};
 use crate::utils::sql_formatter::SqlFormatter;
 use crate::AppState;
 use tauri::State;
@@ -12,13 +12,12 @@ pub async fn execute_query(
     sql: String,
     database: Option<String>,
     state: State<'_, AppState>,
-) -> Result<QueryResult, String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<QueryResult, DbError> {
+    let manager = &state.connection_manager;
     
     manager
         .execute_query(&connection_id, &sql, database.as_deref())
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 执行 SQL 脚本（返回每条语句的详细执行结果）
@@ -29,12 +28,21 @@ pub async fn execute_sql_script(
     sql: String,
     database: Option<String>,
     state: State<'_, AppState>,
-) -> Result<BatchQueryResult, String> {
+) -> Result<BatchQueryResult, DbError> {
     let total_start = Instant::now();
     
-    // 使用SQL分割器分割脚本
-    let mut splitter = SqlSplitter::new(SqlDialect::mysql());
-    let statements = splitter.split(&sql);
+    let manager = &state.connection_manager;
+    
+    // 使用连接驱动的方言分割脚本；不支持 SQL 的数据库整体作为一条命令执行
+    let dialect = manager
+        .get_driver(&connection_id)
+        .await?
+        .dialect();
+    let statements = match dialect {
+        Some(dialect) => SqlSplitter::new(dialect).split(&sql),
+        None if sql.trim().is_empty() => Vec::new(),
+        None => vec![sql.trim().to_string()],
+    };
     
     // 调试日志：打印分割后的语句数量
     println!("SQL分割完成: 共 {} 条语句", statements.len());
@@ -49,7 +57,6 @@ pub async fn execute_sql_script(
         });
     }
     
-    let manager = state.connection_manager.lock().await;
     let mut results: Vec<StatementResult> = Vec::new();
     let mut success_count = 0usize;
     let mut failed_count = 0usize;
@@ -77,6 +84,7 @@ pub async fn execute_sql_script(
                     sql: sql_preview,
                     success: true,
                     error: None,
+                    error_code: None,
                     affected_rows: query_result.affected_rows,
                     execution_time_ms: stmt_time,
                     is_query,
@@ -91,6 +99,7 @@ pub async fn execute_sql_script(
                     sql: sql_preview,
                     success: false,
                     error: Some(e.to_string()),
+                    error_code: Some(e.code()),
                     affected_rows: 0,
                     execution_time_ms: stmt_time,
                     is_query: false,
@@ -122,7 +131,7 @@ pub async fn execute_query_batch(
     sqls: Vec<String>,
     database: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Vec<QueryResult>, String> {
+) -> Result<Vec<QueryResult>, DbError> {
     let mut results = Vec::new();
     
     for sql in sqls {
@@ -135,6 +144,7 @@ pub async fn execute_query_batch(
 
 /// 更新表数据
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_table_data(
     connection_id: String,
     database: String,
@@ -144,14 +154,13 @@ pub async fn update_table_data(
     value: Option<String>,
     where_clause: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<(), DbError> {
+    let manager = &state.connection_manager;
     
     // 获取数据库类型
     let db_type = manager
         .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     // 使用 SqlFormatter 构建 UPDATE SQL 语句
     let sql = SqlFormatter::format_update(
@@ -166,8 +175,7 @@ pub async fn update_table_data(
     
     manager
         .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     Ok(())
 }
@@ -181,14 +189,13 @@ pub async fn insert_table_data(
     schema: Option<String>,
     data: std::collections::HashMap<String, Option<String>>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<(), DbError> {
+    let manager = &state.connection_manager;
     
     // 获取数据库类型
     let db_type = manager
         .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     let columns: Vec<String> = data.keys().cloned().collect();
     let values: Vec<String> = data.values().map(|v| {
@@ -211,8 +218,7 @@ pub async fn insert_table_data(
     
     manager
         .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     Ok(())
 }
@@ -226,14 +232,13 @@ pub async fn delete_table_data(
     schema: Option<String>,
     where_clause: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
+) -> Result<(), DbError> {
+    let manager = &state.connection_manager;
     
     // 获取数据库类型
     let db_type = manager
         .get_database_type(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     // 使用 SqlFormatter 构建 DELETE SQL 语句
     let sql = SqlFormatter::format_delete(
@@ -246,8 +251,7 @@ pub async fn delete_table_data(
     
     manager
         .execute_query(&connection_id, &sql, Some(&database))
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     Ok(())
 }
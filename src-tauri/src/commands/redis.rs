@@ -1,6 +1,6 @@
-use crate::database::redis::RedisDatabase;
+use crate::database::{DbError, ErrorCode};
+use crate::utils::i18n::Msg;
 use crate::AppState;
-use base64::{engine::general_purpose, Engine as _};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tauri::State;
@@ -11,6 +11,7 @@ pub struct RedisCommandResult {
     pub success: bool,
     pub result: Option<serde_json::Value>,
     pub error: Option<String>,
+    pub error_code: Option<ErrorCode>,
     pub execution_time_ms: u128,
 }
 
@@ -39,18 +40,19 @@ pub async fn execute_redis_command(
     connection_id: String,
     command: String,
     state: State<'_, AppState>,
-) -> Result<RedisCommandResult, String> {
+) -> Result<RedisCommandResult, DbError> {
     let start = std::time::Instant::now();
 
     // 解析命令和参数
     let parts: Vec<String> =
-        shell_words::split(&command).map_err(|e| format!("解析命令失败: {}", e))?;
+        shell_words::split(&command).map_err(|e| format!("{}: {}", Msg::ParseCommandFailed, e))?;
 
     if parts.is_empty() {
         return Ok(RedisCommandResult {
             success: false,
             result: None,
-            error: Some("命令不能为空".to_string()),
+            error: Some(Msg::EmptyCommand.into()),
+            error_code: None,
             execution_time_ms: start.elapsed().as_millis(),
         });
     }
@@ -58,37 +60,27 @@ pub async fn execute_redis_command(
     let cmd = parts[0].to_uppercase();
     let args: Vec<String> = parts[1..].to_vec();
 
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
-    // 向下转型为 RedisDatabase
+    // 通过键值扩展接口访问
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
     match redis_db.execute_command(&cmd, args).await {
-        Ok(value) => {
-            let json_value = redis_value_to_json(value);
-            Ok(RedisCommandResult {
-                success: true,
-                result: Some(json_value),
-                error: None,
-                execution_time_ms: start.elapsed().as_millis(),
-            })
-        }
+        Ok(value) => Ok(RedisCommandResult {
+            success: true,
+            result: Some(value),
+            error: None,
+            error_code: None,
+            execution_time_ms: start.elapsed().as_millis(),
+        }),
         Err(e) => Ok(RedisCommandResult {
             success: false,
             result: None,
             error: Some(e.to_string()),
+            error_code: Some(e.code()),
             execution_time_ms: start.elapsed().as_millis(),
         }),
     }
@@ -99,24 +91,15 @@ pub async fn execute_redis_command(
 pub async fn get_redis_info(
     connection_id: String,
     state: State<'_, AppState>,
-) -> Result<HashMap<String, String>, String> {
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+) -> Result<HashMap<String, String>, DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
-    redis_db.get_server_info().await.map_err(|e| e.to_string())
+    redis_db.get_server_info().await
 }
 
 /// 获取键值
@@ -125,51 +108,38 @@ pub async fn get_redis_key_value(
     connection_id: String,
     key: String,
     state: State<'_, AppState>,
-) -> Result<RedisKeyDetail, String> {
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+) -> Result<RedisKeyDetail, DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
     // 获取键类型
     let key_type_value = redis_db
         .execute_command("TYPE", vec![key.clone()])
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     let key_type = match key_type_value {
-        redis::Value::SimpleString(s) => s,
+        serde_json::Value::String(s) => s,
         _ => "unknown".to_string(),
     };
 
     // 获取 TTL
     let ttl = redis_db
         .get_key_ttl(&key)
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     // 获取值
     let value = redis_db
         .get_key_value(&key)
-        .await
-        .map_err(|e| e.to_string())?;
-    let json_value = redis_value_to_json(value);
+        .await?;
 
     Ok(RedisKeyDetail {
         key,
         key_type,
         ttl,
-        value: json_value,
+        value,
     })
 }
 
@@ -181,27 +151,17 @@ pub async fn set_redis_key_value(
     value: String,
     ttl: Option<u64>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+) -> Result<(), DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
     redis_db
         .set_key_value(&key, &value, ttl)
         .await
-        .map_err(|e| e.to_string())
 }
 
 /// 删除键
@@ -210,24 +170,15 @@ pub async fn delete_redis_key(
     connection_id: String,
     key: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+) -> Result<(), DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
-    redis_db.delete_key(&key).await.map_err(|e| e.to_string())
+    redis_db.delete_key(&key).await
 }
 
 /// 设置 List 类型的值
@@ -237,24 +188,15 @@ pub async fn set_redis_list_value(
     key: String,
     values: Vec<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+) -> Result<(), DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
-    redis_db.set_list_value(&key, values).await.map_err(|e| e.to_string())
+    redis_db.set_list_value(&key, values).await
 }
 
 /// 设置 Set 类型的值
@@ -264,24 +206,15 @@ pub async fn set_redis_set_value(
     key: String,
     members: Vec<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+) -> Result<(), DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
-    redis_db.set_set_value(&key, members).await.map_err(|e| e.to_string())
+    redis_db.set_set_value(&key, members).await
 }
 
 /// ZSet 成员
@@ -298,25 +231,16 @@ pub async fn set_redis_zset_value(
     key: String,
     members: Vec<ZSetMember>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+) -> Result<(), DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
     let members: Vec<(String, f64)> = members.into_iter().map(|m| (m.member, m.score)).collect();
-    redis_db.set_zset_value(&key, members).await.map_err(|e| e.to_string())
+    redis_db.set_zset_value(&key, members).await
 }
 
 /// Hash 字段
@@ -333,25 +257,16 @@ pub async fn set_redis_hash_value(
     key: String,
     fields: Vec<HashField>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+) -> Result<(), DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
     let fields: Vec<(String, String)> = fields.into_iter().map(|f| (f.field, f.value)).collect();
-    redis_db.set_hash_value(&key, fields).await.map_err(|e| e.to_string())
+    redis_db.set_hash_value(&key, fields).await
 }
 
 /// 设置键的 TTL
@@ -361,24 +276,15 @@ pub async fn set_redis_key_ttl(
     key: String,
     ttl: i64,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+) -> Result<(), DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
-    redis_db.set_key_ttl(&key, ttl).await.map_err(|e| e.to_string())
+    redis_db.set_key_ttl(&key, ttl).await
 }
 
 /// 重命名键
@@ -388,93 +294,13 @@ pub async fn rename_redis_key(
     old_key: String,
     new_key: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let manager = state.connection_manager.lock().await;
-    let connections = manager
-        .get_connection(&connection_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let connections_guard = connections.read().await;
-    let db = connections_guard
-        .get(&connection_id)
-        .ok_or_else(|| "连接不存在".to_string())?;
+) -> Result<(), DbError> {
+    let manager = state.connection_manager.clone();
+    let db = manager.get_connection(&connection_id).await?;
 
     let redis_db = db
-        .as_any()
-        .downcast_ref::<RedisDatabase>()
-        .ok_or_else(|| "不是 Redis 连接".to_string())?;
+        .as_key_value()
+        .ok_or(Msg::NotKeyValueConnection)?;
 
-    redis_db.rename_key(&old_key, &new_key).await.map_err(|e| e.to_string())
-}
-
-/// 将 Redis Value 转换为 JSON
-fn redis_value_to_json(value: redis::Value) -> serde_json::Value {
-    match value {
-        redis::Value::Nil => serde_json::Value::Null,
-        redis::Value::Int(i) => serde_json::json!(i),
-        redis::Value::BulkString(data) => {
-            // 尝试将字节转换为字符串
-            match String::from_utf8(data.clone()) {
-                Ok(s) => serde_json::Value::String(s),
-                Err(_) => {
-                    // 如果不是有效的 UTF-8，返回 base64 编码
-                    serde_json::Value::String(general_purpose::STANDARD.encode(&data))
-                }
-            }
-        }
-        redis::Value::Array(values) => {
-            let arr: Vec<serde_json::Value> = values.into_iter().map(redis_value_to_json).collect();
-            serde_json::Value::Array(arr)
-        }
-        redis::Value::SimpleString(s) => serde_json::Value::String(s),
-        redis::Value::Okay => serde_json::Value::String("OK".to_string()),
-        redis::Value::Map(map) => {
-            let obj: serde_json::Map<String, serde_json::Value> = map
-                .into_iter()
-                .map(|(k, v)| {
-                    let key = match k {
-                        redis::Value::BulkString(bytes) => {
-                            String::from_utf8_lossy(&bytes).to_string()
-                        }
-                        redis::Value::SimpleString(s) => s,
-                        _ => format!("{:?}", k),
-                    };
-                    (key, redis_value_to_json(v))
-                })
-                .collect();
-            serde_json::Value::Object(obj)
-        }
-        redis::Value::Attribute {
-            data,
-            attributes: _,
-        } => {
-            // For attributes, just return the data part
-            redis_value_to_json(*data)
-        }
-        redis::Value::Set(values) => {
-            let arr: Vec<serde_json::Value> = values.into_iter().map(redis_value_to_json).collect();
-            serde_json::Value::Array(arr)
-        }
-        redis::Value::Double(f) => serde_json::json!(f),
-        redis::Value::Boolean(b) => serde_json::json!(b),
-        redis::Value::VerbatimString { format: _, text } => serde_json::Value::String(text),
-        redis::Value::BigNumber(n) => serde_json::Value::String(format!("{}", n)),
-        redis::Value::Push { kind: _, data } => {
-            let arr: Vec<serde_json::Value> = data.into_iter().map(redis_value_to_json).collect();
-            serde_json::Value::Array(arr)
-        }
-        redis::Value::ServerError(err) => {
-            serde_json::json!({
-                "error": true,
-                "message": err.details().unwrap_or("Unknown error"),
-                "kind": format!("{:?}", err.kind())
-            })
-        }
-        _ => serde_json::json!({
-            "error": true,
-            "message": "Unsupported redis value type",
-            "debug": format!("{:?}", value)
-        }),
-    }
+    redis_db.rename_key(&old_key, &new_key).await
 }
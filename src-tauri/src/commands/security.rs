@@ -0,0 +1,461 @@
+use crate::commands::connection::apply_secrets;
+use crate::database::DbError;
+use crate::models::{CredentialMigrationResult, StoredConnection, UndecryptableConnection};
+use crate::utils::crypto::{
+    self, MasterPasswordConfig, MasterPasswordStatus,
+};
+use crate::utils::i18n::Msg;
+use serde_json::json;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// 安全设置存储文件
+const SECURITY_STORE: &str = "security.json";
+/// 主密码配置在存储中的键
+const MASTER_PASSWORD_KEY: &str = "master_password";
+/// 机器密钥 salt 在存储中的键（轮换过密钥后才存在）
+const MACHINE_KEY_SALT_KEY: &str = "machine_key_salt";
+/// 轮换前的机器密钥 salt，连接写回完成前保留，用于中断后恢复
+const PREVIOUS_MACHINE_KEY_SALT_KEY: &str = "previous_machine_key_salt";
+
+/// 启动时加载主密码配置和机器密钥 salt
+pub fn load_security_settings(app: &AppHandle) -> Result<(), DbError> {
+    let store = app
+        .store(SECURITY_STORE)
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let config = store
+        .get(MASTER_PASSWORD_KEY)
+        .and_then(|value| serde_json::from_value::<MasterPasswordConfig>(value).ok());
+
+    crypto::load_master_password_config(config);
+    crypto::load_machine_key_salt(read_string(app, MACHINE_KEY_SALT_KEY)?.as_deref());
+    Ok(())
+}
+
+/// 读取安全设置中的字符串值
+fn read_string(app: &AppHandle, key: &str) -> Result<Option<String>, DbError> {
+    let store = app
+        .store(SECURITY_STORE)
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    Ok(store
+        .get(key)
+        .and_then(|value| value.as_str().map(String::from)))
+}
+
+/// 保存机器密钥 salt，同时记录轮换前的 salt
+fn save_machine_key_salt(
+    app: &AppHandle,
+    salt: Option<&str>,
+    previous: Option<&str>,
+) -> Result<(), DbError> {
+    let store = app
+        .store(SECURITY_STORE)
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    for (key, value) in [(MACHINE_KEY_SALT_KEY, salt), (PREVIOUS_MACHINE_KEY_SALT_KEY, previous)] {
+        match value {
+            Some(value) => store.set(key, json!(value)),
+            None => {
+                store.delete(key);
+            }
+        }
+    }
+
+    store.save().map_err(|e| DbError::Other(e.to_string()))
+}
+
+/// 保存（或清除）主密码配置
+fn save_master_password_config(
+    app: &AppHandle,
+    config: Option<&MasterPasswordConfig>,
+) -> Result<(), DbError> {
+    let store = app
+        .store(SECURITY_STORE)
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    match config {
+        Some(config) => store.set(MASTER_PASSWORD_KEY, json!(config)),
+        None => {
+            store.delete(MASTER_PASSWORD_KEY);
+        }
+    }
+
+    store.save().map_err(|e| DbError::Other(e.to_string()))
+}
+
+/// 重新加密后待写回的连接：`(连接 ID, 原值, 新值)`
+type ReencryptedConnections = Vec<(String, serde_json::Value, serde_json::Value)>;
+
+/// 将 connections.json 中所有已保存密码（含 SSH 口令）从旧密钥转换到新密钥
+///
+/// 只在内存中完成转换，不写回存储；全部转换成功才返回，避免部分连接使用新密钥、部分使用旧密钥。
+/// 保存在系统密钥环中的凭据不受影响
+fn reencrypt_connections(
+    app: &AppHandle,
+    from_key: &[u8; 32],
+    to_key: &[u8; 32],
+) -> Result<ReencryptedConnections, DbError> {
+    let store = app
+        .store("connections.json")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let mut changes = Vec::new();
+    for (_, value) in store.entries() {
+        let Ok(mut conn) = serde_json::from_value::<StoredConnection>(value.clone()) else {
+            continue;
+        };
+
+        if conn.encrypted_password.is_none() && conn.encrypted_ssh_passphrase.is_none() {
+            continue;
+        }
+
+        if let Some(ref encrypted) = conn.encrypted_password {
+            let reencrypted = crypto::reencrypt_with_key(encrypted, from_key, to_key)
+                .map_err(|e| format!("{}: {}", Msg::MigratePasswordFailed.with(&[&conn.name]), e))?;
+            conn.encrypted_password = Some(reencrypted);
+        }
+        if let Some(ref encrypted) = conn.encrypted_ssh_passphrase {
+            let reencrypted = crypto::reencrypt_with_key(encrypted, from_key, to_key)
+                .map_err(|e| format!("{}: {}", Msg::MigrateSshPassphraseFailed.with(&[&conn.name]), e))?;
+            conn.encrypted_ssh_passphrase = Some(reencrypted);
+        }
+        changes.push((conn.id.clone(), value, json!(conn)));
+    }
+
+    Ok(changes)
+}
+
+/// 写回连接（`restore` 为 true 时写回原值）
+fn write_connections(
+    app: &AppHandle,
+    changes: &ReencryptedConnections,
+    restore: bool,
+) -> Result<(), DbError> {
+    let store = app
+        .store("connections.json")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    for (id, original, updated) in changes {
+        let value = if restore { original } else { updated };
+        store.set(id.clone(), value.clone());
+    }
+    store.save().map_err(|e| DbError::Other(e.to_string()))
+}
+
+/// 依次写入新的密钥配置和重新加密的连接
+///
+/// 先写配置：任一步失败都会恢复旧配置和连接原值，不会留下只能用丢失密钥解密的密文
+fn commit_reencryption(
+    app: &AppHandle,
+    changes: &ReencryptedConnections,
+    save_config: impl FnOnce() -> Result<(), DbError>,
+    restore_config: impl Fn(),
+) -> Result<(), DbError> {
+    if let Err(e) = save_config() {
+        restore_config();
+        return Err(e);
+    }
+
+    if let Err(e) = write_connections(app, changes, false) {
+        let _ = write_connections(app, changes, true);
+        restore_config();
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// 依次写入新的主密码配置和重新加密的连接，失败时恢复旧配置
+fn commit_master_password(
+    app: &AppHandle,
+    old_config: Option<&MasterPasswordConfig>,
+    new_config: Option<&MasterPasswordConfig>,
+    changes: &ReencryptedConnections,
+) -> Result<(), DbError> {
+    commit_reencryption(
+        app,
+        changes,
+        || save_master_password_config(app, new_config),
+        || {
+            let _ = save_master_password_config(app, old_config);
+        },
+    )
+}
+
+/// 读取 connections.json 中的所有连接
+fn load_stored_connections(app: &AppHandle) -> Result<Vec<StoredConnection>, DbError> {
+    let store = app
+        .store("connections.json")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    Ok(store
+        .entries()
+        .into_iter()
+        .filter_map(|(_, value)| serde_json::from_value::<StoredConnection>(value).ok())
+        .collect())
+}
+
+/// 检查连接中无法用当前密钥解密的凭据
+fn check_undecryptable(conn: &StoredConnection, key: &[u8; 32]) -> Option<UndecryptableConnection> {
+    let failed = |encrypted: &Option<String>| {
+        encrypted
+            .as_deref()
+            .is_some_and(|e| crypto::decrypt_with_key(key, e).is_err())
+    };
+
+    let password = failed(&conn.encrypted_password);
+    let ssh_passphrase = failed(&conn.encrypted_ssh_passphrase);
+    (password || ssh_passphrase).then(|| UndecryptableConnection {
+        id: conn.id.clone(),
+        name: conn.name.clone(),
+        password,
+        ssh_passphrase,
+    })
+}
+
+/// 获取无法解密凭据的连接列表（例如机器标识变化后）
+#[tauri::command]
+pub async fn get_undecryptable_connections(
+    app: AppHandle,
+) -> Result<Vec<UndecryptableConnection>, DbError> {
+    let key = crypto::active_key()?;
+    Ok(load_stored_connections(&app)?
+        .iter()
+        .filter_map(|conn| check_undecryptable(conn, &key))
+        .collect())
+}
+
+/// 迁移凭据密文：使用当前密钥重新加密所有已保存的凭据，并升级为新版密文格式
+///
+/// 不会生成新密钥；更换密钥请使用 `rotate_encryption_key`。会同时尝试使用中断的密钥轮换前的密钥解密；
+/// 提供 `previous_machine_id` 时，还会尝试使用旧机器标识派生的密钥，用于虚拟机克隆、重装系统等机器标识变化的场景。
+#[tauri::command]
+pub async fn migrate_encrypted_credentials(
+    app: AppHandle,
+    previous_machine_id: Option<String>,
+) -> Result<CredentialMigrationResult, DbError> {
+    let key = crypto::active_key()?;
+
+    let mut candidates = vec![key];
+    let previous_salt = read_string(&app, PREVIOUS_MACHINE_KEY_SALT_KEY)?;
+    candidates.push(crypto::derive_machine_key(previous_salt.as_deref()));
+    if let Some(id) = previous_machine_id.filter(|id| !id.trim().is_empty()) {
+        let salt = read_string(&app, MACHINE_KEY_SALT_KEY)?;
+        candidates.push(crypto::derive_machine_key_from_id(id.trim(), salt.as_deref()));
+        candidates.push(crypto::derive_machine_key_from_id(id.trim(), None));
+    }
+
+    let migrate = |encrypted: &mut Option<String>, count: &mut usize| -> Result<bool, DbError> {
+        let Some(ref current) = encrypted else {
+            return Ok(true);
+        };
+        match crypto::decrypt_with_any_key(&candidates, current) {
+            Some(plaintext) => {
+                *encrypted = Some(crypto::encrypt_with_key(&key, &plaintext)?);
+                *count += 1;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    };
+
+    let mut reencrypted = 0;
+    let mut updated = Vec::new();
+    let mut undecryptable = Vec::new();
+    for mut conn in load_stored_connections(&app)? {
+        let before = reencrypted;
+        let password = migrate(&mut conn.encrypted_password, &mut reencrypted)?;
+        let ssh_passphrase = migrate(&mut conn.encrypted_ssh_passphrase, &mut reencrypted)?;
+
+        if !password || !ssh_passphrase {
+            undecryptable.push(UndecryptableConnection {
+                id: conn.id.clone(),
+                name: conn.name.clone(),
+                password: !password,
+                ssh_passphrase: !ssh_passphrase,
+            });
+        }
+        if reencrypted > before {
+            updated.push(conn);
+        }
+    }
+
+    // 全部转换完成后统一写回
+    if !updated.is_empty() {
+        let store = app
+            .store("connections.json")
+            .map_err(|e| format!("Failed to get store: {}", e))?;
+        for conn in updated {
+            store.set(conn.id.clone(), json!(conn));
+        }
+        store.save().map_err(|e| e.to_string())?;
+    }
+
+    Ok(CredentialMigrationResult {
+        reencrypted,
+        undecryptable,
+    })
+}
+
+/// 轮换加密密钥：生成新密钥并用它重新加密 connections.json 中的所有凭据
+///
+/// 启用主密码时需要提供主密码，以新的随机 salt 重新派生密钥；
+/// 否则生成新的随机机器密钥 salt。返回重新加密的连接数量，保存在系统密钥环中的凭据不受影响
+#[tauri::command]
+pub async fn rotate_encryption_key(
+    app: AppHandle,
+    password: Option<String>,
+) -> Result<usize, DbError> {
+    if let Some(old_config) = crypto::master_password_config() {
+        let password = password.unwrap_or_default();
+        let old_key = crypto::verify_master_password(&old_config, &password)?;
+        let (config, key) =
+            crypto::create_master_password_config(&password, old_config.auto_lock_secs)?;
+
+        let changes = reencrypt_connections(&app, &old_key, &key)?;
+        commit_master_password(&app, Some(&old_config), Some(&config), &changes)?;
+        crypto::activate_master_password(config, key);
+        return Ok(changes.len());
+    }
+
+    let old_salt = read_string(&app, MACHINE_KEY_SALT_KEY)?;
+    let old_key = crypto::machine_key()?;
+    let (salt, key) = crypto::generate_machine_key();
+
+    let changes = reencrypt_connections(&app, &old_key, &key)?;
+    commit_reencryption(
+        &app,
+        &changes,
+        || save_machine_key_salt(&app, Some(&salt), old_salt.as_deref()),
+        || {
+            let _ = save_machine_key_salt(&app, old_salt.as_deref(), None);
+        },
+    )?;
+    crypto::activate_machine_key(key);
+
+    // 连接已全部写回，不再需要轮换前的 salt
+    let _ = save_machine_key_salt(&app, Some(&salt), None);
+
+    Ok(changes.len())
+}
+
+/// 为无法解密的连接重新输入凭据
+#[tauri::command]
+pub async fn recover_connection_credentials(
+    app: AppHandle,
+    connection_id: String,
+    password: Option<String>,
+    ssh_passphrase: Option<String>,
+) -> Result<StoredConnection, DbError> {
+    let store = app
+        .store("connections.json")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let value = store
+        .get(connection_id.clone())
+        .ok_or(Msg::ConnectionConfigNotFound)?;
+    let mut conn: StoredConnection =
+        serde_json::from_value(value).map_err(|e| format!("{}: {}", Msg::ParseConnectionConfigFailed, e))?;
+
+    let existing = conn.clone();
+    apply_secrets(&mut conn, Some(&existing), password, ssh_passphrase)?;
+
+    store.set(conn.id.clone(), json!(conn));
+    store.save().map_err(|e| e.to_string())?;
+
+    Ok(conn)
+}
+
+/// 获取主密码状态
+#[tauri::command]
+pub async fn get_master_password_status() -> Result<MasterPasswordStatus, DbError> {
+    Ok(crypto::master_password_status())
+}
+
+/// 启用主密码，并将已保存的密码迁移到主密码派生的密钥
+#[tauri::command]
+pub async fn enable_master_password(
+    app: AppHandle,
+    password: String,
+    auto_lock_secs: Option<u64>,
+) -> Result<MasterPasswordStatus, DbError> {
+    if crypto::master_password_config().is_some() {
+        return Err(Msg::MasterPasswordEnabled.into());
+    }
+
+    let (config, key) = crypto::create_master_password_config(
+        &password,
+        auto_lock_secs.unwrap_or(crypto::DEFAULT_AUTO_LOCK_SECS),
+    )?;
+
+    let machine_key = crypto::machine_key()?;
+    let changes = reencrypt_connections(&app, &machine_key, &key)?;
+    commit_master_password(&app, None, Some(&config), &changes)?;
+    crypto::activate_master_password(config, key);
+
+    Ok(crypto::master_password_status())
+}
+
+/// 修改主密码（重新生成 salt 并迁移已保存的密码）
+#[tauri::command]
+pub async fn change_master_password(
+    app: AppHandle,
+    old_password: String,
+    new_password: String,
+) -> Result<MasterPasswordStatus, DbError> {
+    let old_config = crypto::master_password_config().ok_or(Msg::MasterPasswordDisabled)?;
+    let old_key = crypto::verify_master_password(&old_config, &old_password)?;
+
+    let (config, key) =
+        crypto::create_master_password_config(&new_password, old_config.auto_lock_secs)?;
+
+    let changes = reencrypt_connections(&app, &old_key, &key)?;
+    commit_master_password(&app, Some(&old_config), Some(&config), &changes)?;
+    crypto::activate_master_password(config, key);
+
+    Ok(crypto::master_password_status())
+}
+
+/// 停用主密码，已保存的密码迁移回机器派生密钥
+#[tauri::command]
+pub async fn disable_master_password(
+    app: AppHandle,
+    password: String,
+) -> Result<MasterPasswordStatus, DbError> {
+    let config = crypto::master_password_config().ok_or(Msg::MasterPasswordDisabled)?;
+    let key = crypto::verify_master_password(&config, &password)?;
+
+    let machine_key = crypto::machine_key()?;
+    let changes = reencrypt_connections(&app, &key, &machine_key)?;
+    commit_master_password(&app, Some(&config), None, &changes)?;
+    crypto::deactivate_master_password();
+
+    Ok(crypto::master_password_status())
+}
+
+/// 使用主密码解锁
+#[tauri::command]
+pub async fn unlock_master_password(password: String) -> Result<MasterPasswordStatus, DbError> {
+    crypto::unlock_master_password(&password)?;
+    Ok(crypto::master_password_status())
+}
+
+/// 立即锁定
+#[tauri::command]
+pub async fn lock_master_password() -> Result<MasterPasswordStatus, DbError> {
+    crypto::lock_master_password();
+    Ok(crypto::master_password_status())
+}
+
+/// 设置空闲自动锁定时间（秒），0 表示不自动锁定
+#[tauri::command]
+pub async fn set_master_password_auto_lock(
+    app: AppHandle,
+    auto_lock_secs: u64,
+) -> Result<MasterPasswordStatus, DbError> {
+    let config = crypto::set_auto_lock_secs(auto_lock_secs)?;
+    save_master_password_config(&app, Some(&config))?;
+    Ok(crypto::master_password_status())
+}
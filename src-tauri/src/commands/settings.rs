@@ -0,0 +1,45 @@
+use crate::database::DbError;
+use crate::utils::i18n::{self, Locale};
+use serde_json::json;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// 应用设置存储文件
+const SETTINGS_STORE: &str = "settings.json";
+/// 界面语言在存储中的键
+const LOCALE_KEY: &str = "locale";
+
+/// 启动时加载应用设置
+pub fn load_app_settings(app: &AppHandle) -> Result<(), DbError> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    if let Some(locale) = store
+        .get(LOCALE_KEY)
+        .and_then(|value| serde_json::from_value::<Locale>(value).ok())
+    {
+        i18n::set_locale(locale);
+    }
+    Ok(())
+}
+
+/// 获取后端消息使用的语言
+#[tauri::command]
+pub async fn get_locale() -> Result<Locale, DbError> {
+    Ok(i18n::locale())
+}
+
+/// 设置后端消息使用的语言并持久化
+#[tauri::command]
+pub async fn set_locale(app: AppHandle, locale: Locale) -> Result<(), DbError> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    store.set(LOCALE_KEY, json!(locale));
+    store.save().map_err(|e| e.to_string())?;
+
+    i18n::set_locale(locale);
+    Ok(())
+}
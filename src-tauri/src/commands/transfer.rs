@@ -0,0 +1,388 @@
+use crate::database::registry::DatabaseDriver;
+use crate::database::{ColumnInfo, ConnectionManager, DbError, DbResult, ErrorCode, RowSink, Transaction};
+use crate::export::sql::{SqlExportOptions, SqlInsertMode, SqlWriter};
+use crate::export::{ExportJob, RowWriter};
+use crate::transfer::{self, SqlType, TransferMode, TransferOptions, TransferProgress};
+use crate::utils::i18n::Msg;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::mpsc;
+
+/// 传输进度事件
+pub const TRANSFER_PROGRESS_EVENT: &str = "transfer-progress";
+
+/// 进度事件的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 读取端最多领先写入端的批数
+const PENDING_BATCHES: usize = 4;
+
+/// 传输任务
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferTask {
+    /// 前端生成的任务 ID，用于进度事件和取消
+    pub transfer_id: String,
+    pub source_connection_id: String,
+    #[serde(default)]
+    pub source_database: Option<String>,
+    #[serde(default)]
+    pub source_schema: Option<String>,
+    pub source_table: String,
+    pub target_connection_id: String,
+    #[serde(default)]
+    pub target_database: Option<String>,
+    #[serde(default)]
+    pub target_schema: Option<String>,
+    /// 目标表名（默认与来源表相同）
+    #[serde(default)]
+    pub target_table: Option<String>,
+    #[serde(default)]
+    pub options: TransferOptions,
+}
+
+/// 传输结果
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferSummary {
+    pub transfer_id: String,
+    pub rows: u64,
+    /// 是否新建了目标表
+    pub created_table: bool,
+    pub elapsed_ms: u128,
+    /// 被取消时已回滚（目标数据库不支持事务时保留已写入的行）
+    pub cancelled: bool,
+}
+
+/// 把来源查询的结果按批发送给写入端
+struct ChannelSink {
+    sender: mpsc::Sender<Vec<Vec<Value>>>,
+    batch: Vec<Vec<Value>>,
+    batch_size: usize,
+}
+
+impl RowSink for ChannelSink {
+    fn columns(&mut self, _columns: &[String]) -> DbResult<()> {
+        Ok(())
+    }
+
+    fn row(&mut self, values: Vec<Value>) -> DbResult<()> {
+        self.batch.push(values);
+        if self.batch.len() >= self.batch_size {
+            let batch = std::mem::take(&mut self.batch);
+            // 写入端较慢时在此等待，避免把整张表读入内存
+            tokio::task::block_in_place(|| self.sender.blocking_send(batch))
+                .map_err(|_| DbError::Other(Msg::TransferCancelled.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// 目标连接上的写入会话：支持事务时所有语句在一个事务中执行，否则逐条直接执行
+enum TargetSession<'a> {
+    Transaction(Box<dyn Transaction>),
+    Direct {
+        manager: &'a ConnectionManager,
+        connection_id: &'a str,
+        database: Option<&'a str>,
+    },
+}
+
+impl TargetSession<'_> {
+    async fn execute(&mut self, sql: &str) -> DbResult<()> {
+        match self {
+            Self::Transaction(tx) => tx.execute(sql).await.map(|_| ()),
+            Self::Direct {
+                manager,
+                connection_id,
+                database,
+            } => manager.execute_query(connection_id, sql, *database).await.map(|_| ()),
+        }
+    }
+
+    async fn finish(self, commit: bool) -> DbResult<()> {
+        match self {
+            Self::Transaction(tx) if commit => tx.commit().await,
+            Self::Transaction(tx) => tx.rollback().await,
+            Self::Direct { .. } => Ok(()),
+        }
+    }
+}
+
+/// 写入目标表的列
+struct TargetColumn {
+    name: String,
+    sql_type: SqlType,
+    is_primary_key: bool,
+    is_auto_increment: bool,
+}
+
+/// 带架构的表引用
+fn qualified(driver: &dyn DatabaseDriver, schema: Option<&str>, table: &str) -> String {
+    match schema {
+        Some(schema) => format!("{}.{}", driver.quote_identifier(schema), driver.quote_identifier(table)),
+        None => driver.quote_identifier(table),
+    }
+}
+
+/// 在两个连接之间复制表数据
+///
+/// 目标表不存在时按映射后的类型创建；数据分批写入，支持事务的数据库在一个事务中完成，
+/// 出错或取消时回滚。传输过程中发送 `transfer-progress` 事件，可通过 `cancel_export` 取消
+#[tauri::command]
+pub async fn transfer_table(
+    app: AppHandle,
+    task: TransferTask,
+    state: State<'_, AppState>,
+) -> Result<TransferSummary, DbError> {
+    let start = Instant::now();
+    let manager = state.connection_manager.clone();
+    let source_driver = manager.get_driver(&task.source_connection_id).await?;
+    let target_driver = manager.get_driver(&task.target_connection_id).await?;
+    for driver in [&source_driver, &target_driver] {
+        if driver.dialect().is_none() {
+            return Err(DbError::database(
+                ErrorCode::Unsupported,
+                Msg::TransferUnsupported.with(&[&format!("{:?}", driver.db_type())]),
+                Default::default(),
+            ));
+        }
+    }
+    let source_type = source_driver.db_type();
+    let target_type = target_driver.db_type();
+
+    let options = &task.options;
+    let source_database = task.source_database.as_deref();
+    let target_database = task.target_database.as_deref();
+    let target_name = task.target_table.clone().unwrap_or_else(|| task.source_table.clone());
+    let target_schema = task.target_schema.as_deref().or(target_driver.default_schema());
+
+    let source_columns = manager
+        .get_table_structure(
+            &task.source_connection_id,
+            &task.source_table,
+            task.source_schema.as_deref(),
+            source_database,
+        )
+        .await?;
+
+    // 目标表已存在时按列名（不区分大小写）对应，只复制两边都有的列
+    let exists = manager
+        .get_tables(&task.target_connection_id, target_database)
+        .await?
+        .iter()
+        .any(|t| {
+            t.name.eq_ignore_ascii_case(&target_name)
+                && (target_driver.default_schema().is_none()
+                    || t.schema.as_deref().zip(target_schema).is_none_or(|(a, b)| a.eq_ignore_ascii_case(b)))
+        });
+    let target_columns: Vec<ColumnInfo> = if exists {
+        manager
+            .get_table_structure(&task.target_connection_id, &target_name, target_schema, target_database)
+            .await?
+    } else if options.create_table {
+        Vec::new()
+    } else {
+        return Err(DbError::Other(Msg::TransferTargetMissing.with(&[&target_name])));
+    };
+
+    let mut source_names = Vec::new();
+    let mut columns = Vec::new();
+    for column in &source_columns {
+        let target = if exists {
+            target_columns
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(&column.name))
+                .map(|c| (c, &target_type))
+        } else {
+            Some((column, &source_type))
+        };
+        if let Some((target, db_type)) = target {
+            source_names.push(column.name.clone());
+            columns.push(TargetColumn {
+                name: target.name.clone(),
+                sql_type: SqlType::parse(db_type, target),
+                is_primary_key: target.is_primary_key,
+                is_auto_increment: exists && target.is_auto_increment,
+            });
+        }
+    }
+    if columns.is_empty() {
+        return Err(DbError::Other(Msg::TransferNoColumns.into()));
+    }
+
+    // 未指定冲突键时按主键合并
+    let conflict_columns = if options.conflict_columns.is_empty() {
+        columns.iter().filter(|c| c.is_primary_key).map(|c| c.name.clone()).collect()
+    } else {
+        options.conflict_columns.clone()
+    };
+    let schema = target_driver.default_schema().and(target_schema).map(str::to_string);
+    let sql_options = SqlExportOptions {
+        dialect: Some(target_type.clone()),
+        batch_size: options.batch_size,
+        mode: match options.mode {
+            TransferMode::Upsert => SqlInsertMode::Upsert,
+            _ => SqlInsertMode::Insert,
+        },
+        conflict_columns,
+        binary_columns: columns
+            .iter()
+            .filter(|c| c.sql_type == SqlType::Binary)
+            .map(|c| c.name.clone())
+            .collect(),
+        binary_base64: source_driver.binary_as_base64(),
+        schema: schema.clone(),
+        ..Default::default()
+    };
+    let mut writer = SqlWriter::with_options(io::sink(), &target_name, &sql_options)?;
+    let target_names: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+    writer.begin(&target_names).map_err(crate::export::write_error)?;
+    let target_ref = qualified(target_driver.as_ref(), schema.as_deref(), &target_name);
+
+    let mut session = match manager.begin_transaction(&task.target_connection_id, target_database).await {
+        Ok(tx) => TargetSession::Transaction(tx),
+        Err(e) if e.code() == ErrorCode::Unsupported => TargetSession::Direct {
+            manager: &manager,
+            connection_id: &task.target_connection_id,
+            database: target_database,
+        },
+        Err(e) => return Err(e),
+    };
+
+    let mut prologue = Vec::new();
+    if !exists {
+        let created: Vec<ColumnInfo> = source_columns
+            .iter()
+            .filter(|c| source_names.contains(&c.name))
+            .cloned()
+            .collect();
+        prologue.push(transfer::create_table_sql(&source_type, &target_type, &target_ref, &created));
+    } else if options.mode == TransferMode::Truncate {
+        prologue.push(transfer::truncate_sql(&target_type, &target_ref));
+    }
+    // 例如 SQL Server 写入自增列需要打开 IDENTITY_INSERT
+    let identity = columns.iter().any(|c| c.is_auto_increment);
+    prologue.extend(target_driver.identity_insert_sql(&target_ref, true).filter(|_| identity));
+
+    let source_ref = match source_database {
+        Some(database) => source_driver.table_ref(database, task.source_schema.as_deref(), &task.source_table),
+        None => qualified(source_driver.as_ref(), task.source_schema.as_deref(), &task.source_table),
+    };
+    let select_list: Vec<String> = source_names
+        .iter()
+        .map(|name| source_driver.quote_identifier(name))
+        .collect();
+    let total = manager
+        .execute_query(
+            &task.source_connection_id,
+            &format!("SELECT COUNT(*) AS total FROM {}", source_ref),
+            source_database,
+        )
+        .await
+        .ok()
+        .and_then(|result| result.rows.into_iter().next())
+        .and_then(|row| row.into_values().next())
+        .and_then(|value| value.as_u64().or_else(|| value.as_str().and_then(|s| s.parse().ok())));
+
+    // 读取端在单独的任务中流式查询，通过有界通道把批次交给写入端
+    let (sender, mut receiver) = mpsc::channel(PENDING_BATCHES);
+    let reader = {
+        let manager = manager.clone();
+        let connection_id = task.source_connection_id.clone();
+        let database = task.source_database.clone();
+        let sql = format!("SELECT {} FROM {}", select_list.join(", "), source_ref);
+        let batch_size = writer_batch_size(target_driver.as_ref(), options.batch_size);
+        tokio::spawn(async move {
+            let mut sink = ChannelSink {
+                sender,
+                batch: Vec::new(),
+                batch_size,
+            };
+            manager
+                .stream_query(&connection_id, &sql, database.as_deref(), &mut sink)
+                .await?;
+            if !sink.batch.is_empty() {
+                let batch = std::mem::take(&mut sink.batch);
+                sink.sender
+                    .send(batch)
+                    .await
+                    .map_err(|_| DbError::Other(Msg::TransferCancelled.into()))?;
+            }
+            Ok::<(), DbError>(())
+        })
+    };
+
+    let job = ExportJob::start(&task.transfer_id);
+    let emit = |rows| {
+        let _ = app.emit(
+            TRANSFER_PROGRESS_EVENT,
+            TransferProgress {
+                transfer_id: task.transfer_id.clone(),
+                rows,
+                total,
+                elapsed_ms: start.elapsed().as_millis(),
+            },
+        );
+    };
+
+    let mut rows = 0u64;
+    let mut last_report = Instant::now();
+    let copied: DbResult<()> = async {
+        for sql in &prologue {
+            session.execute(sql).await?;
+        }
+        while let Some(batch) = receiver.recv().await {
+            if job.is_cancelled() {
+                break;
+            }
+            let batch: Vec<Vec<Value>> = batch
+                .into_iter()
+                .map(|row| row.into_iter().zip(&columns).map(|(v, c)| c.sql_type.coerce(v)).collect())
+                .collect();
+            session.execute(&writer.insert_statement(&batch)).await?;
+            rows += batch.len() as u64;
+
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                last_report = Instant::now();
+                emit(rows);
+            }
+        }
+        if let Some(sql) = target_driver.identity_insert_sql(&target_ref, false).filter(|_| identity) {
+            session.execute(&sql).await?;
+        }
+        Ok(())
+    }
+    .await;
+
+    // 关闭通道让读取端结束，再取读取端的错误
+    drop(receiver);
+    let cancelled = job.is_cancelled();
+    let read = reader.await.map_err(|e| DbError::Other(e.to_string()))?;
+    let result = copied.and(if cancelled { Ok(()) } else { read });
+
+    match result {
+        Ok(()) => {
+            session.finish(!cancelled).await?;
+            emit(rows);
+            Ok(TransferSummary {
+                transfer_id: task.transfer_id.clone(),
+                rows,
+                created_table: !exists,
+                elapsed_ms: start.elapsed().as_millis(),
+                cancelled,
+            })
+        }
+        Err(e) => {
+            let _ = session.finish(false).await;
+            Err(e)
+        }
+    }
+}
+
+/// 与写入器一致的批大小（例如 SQL Server 的 VALUES 最多 1000 行）
+fn writer_batch_size(driver: &dyn DatabaseDriver, batch_size: usize) -> usize {
+    batch_size.clamp(1, driver.max_insert_rows().unwrap_or(usize::MAX))
+}
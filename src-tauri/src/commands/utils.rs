@@ -1,14 +1,16 @@
+use crate::database::DbError;
+use crate::utils::i18n::Msg;
 use std::fs;
 
 #[tauri::command]
-pub async fn read_file(path: String) -> Result<String, String> {
+pub async fn read_file(path: String) -> Result<String, DbError> {
     fs::read_to_string(&path)
-        .map_err(|e| format!("读取文件失败: {}", e))
+        .map_err(|e| DbError::Other(format!("{}: {}", Msg::ReadFileFailed, e)))
 }
 
 #[tauri::command]
-pub async fn write_file(path: String, content: String) -> Result<(), String> {
+pub async fn write_file(path: String, content: String) -> Result<(), DbError> {
     fs::write(&path, content)
-        .map_err(|e| format!("写入文件失败: {}", e))
+        .map_err(|e| DbError::Other(format!("{}: {}", Msg::WriteFileFailed, e)))
 }
 
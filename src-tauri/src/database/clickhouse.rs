@@ -0,0 +1,852 @@
+use super::sql_splitter::{SqlDialect, SqlSplitter};
+use super::registry::{
+    DatabaseDriver, DriverCapabilities, DriverRegistration, EditingCapabilities, ExplainFormat,
+    ExportFormat, ObjectType,
+};
+use super::traits::*;
+use crate::utils::i18n::Msg;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// ClickHouse 连接（基于 HTTP 接口）
+///
+/// HTTP 接口是无状态的：初始化 SQL 中的 `SET name = value` 会转换为每个请求的设置参数，
+/// 其他语句只在连接建立时执行一次。
+pub struct ClickHouseDatabase {
+    client: Option<Client>,
+    base_url: Option<String>,
+    config: Option<ConnectionConfig>,
+    current_database: Option<String>,
+    /// 随每个请求发送的会话设置
+    settings: Vec<(String, String)>,
+}
+
+impl ClickHouseDatabase {
+    pub fn new() -> Self {
+        Self {
+            client: None,
+            base_url: None,
+            config: None,
+            current_database: None,
+            settings: Vec::new(),
+        }
+    }
+
+    /// 构建基础 URL
+    fn build_base_url(config: &ConnectionConfig) -> String {
+        let protocol = if config.ssl { "https" } else { "http" };
+        format!("{}://{}:{}/", protocol, config.host, config.port)
+    }
+
+    /// 创建 HTTP 客户端（应用名称作为 User-Agent，出现在 system.query_log 中）
+    fn build_client(config: &ConnectionConfig) -> DbResult<Client> {
+        let user_agent = session_setting(&config.application_name).unwrap_or("DataSmith");
+        Client::builder()
+            .connect_timeout(Duration::from_secs(config.connection_timeout))
+            .user_agent(user_agent)
+            .build()
+            .map_err(|e| DbError::ConnectionFailed(format!("{}: {}", Msg::CreateHttpClientFailed, e)))
+    }
+
+    /// 解析 `SET name = value` 语句，返回设置名和去掉引号的值
+    fn parse_set_statement(sql: &str) -> Option<(String, String)> {
+        let trimmed = sql.trim().trim_end_matches(';');
+        let rest = trimmed.get(..4)?.eq_ignore_ascii_case("SET ").then(|| &trimmed[4..])?;
+        // 一条 SET 可能包含多个设置，这种情况按普通语句处理
+        if rest.contains(',') {
+            return None;
+        }
+        let (name, value) = rest.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('\'')
+            .and_then(|v| v.strip_suffix('\''))
+            .map(|v| v.replace("''", "'").replace("\\'", "'"))
+            .unwrap_or_else(|| value.to_string());
+        Some((name.to_string(), value))
+    }
+
+    /// 会话设置：时区 + 初始化 SQL 中的 SET 语句；其余初始化语句单独返回
+    ///
+    /// `session_timezone` 从 23.6 开始支持，更早的服务器不发送时区设置，使用服务器时区
+    fn session_settings(config: &ConnectionConfig, server_version: &str) -> (Vec<(String, String)>, Vec<String>) {
+        let mut settings = Vec::new();
+        let mut statements = Vec::new();
+
+        if let Some(tz) = session_setting(&config.time_zone) {
+            let (major, minor, _) = parse_version(server_version);
+            if (major, minor) >= (23, 6) {
+                settings.push(("session_timezone".to_string(), tz.to_string()));
+            }
+        }
+
+        for sql in config.init_sql_statements(SqlDialect::clickhouse()) {
+            match Self::parse_set_statement(&sql) {
+                Some(setting) => settings.push(setting),
+                None => statements.push(sql),
+            }
+        }
+
+        (settings, statements)
+    }
+
+    /// 查询服务器版本，同时用于检查连接是否可用
+    async fn server_version(
+        client: &Client,
+        base_url: &str,
+        config: &ConnectionConfig,
+        database: Option<&str>,
+    ) -> DbResult<String> {
+        let (body, _) = Self::send(client, base_url, config, &[], database, "SELECT version()").await?;
+        let response: Value = serde_json::from_str(&body)
+            .map_err(|e| DbError::QueryFailed(format!("{}: {}", Msg::ParseResponseFailed, e)))?;
+        Ok(response
+            .pointer("/data/0/0")
+            .and_then(Self::as_string)
+            .unwrap_or_default())
+    }
+
+    /// 发送一条语句，返回响应体和写入行数
+    async fn send(
+        client: &Client,
+        base_url: &str,
+        config: &ConnectionConfig,
+        settings: &[(String, String)],
+        database: Option<&str>,
+        sql: &str,
+    ) -> DbResult<(String, u64)> {
+        // 等待查询结束后再返回响应，执行中的异常也会体现在状态码和异常头中
+        let settings: Vec<(String, String)> = std::iter::once(("wait_end_of_query".to_string(), "1".to_string()))
+            .chain(settings.iter().cloned())
+            .collect();
+        let response = Self::post(client, base_url, config, &settings, database, sql, "JSONCompact").await?;
+
+        // 写入行数在 X-ClickHouse-Summary 头中，例如 {"written_rows":"10",...}
+        let written_rows = response
+            .headers()
+            .get("X-ClickHouse-Summary")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| serde_json::from_str::<Value>(h).ok())
+            .and_then(|summary| {
+                summary
+                    .get("written_rows")
+                    .and_then(|v| v.as_str().and_then(|s| s.parse().ok()).or_else(|| v.as_u64()))
+            })
+            .unwrap_or(0);
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| DbError::QueryFailed(format!("{}: {}", Msg::ReadResponseFailed, e)))?;
+
+        Ok((body, written_rows))
+    }
+
+    /// 发送一条语句，返回未读取响应体的响应；`format` 为未指定 FORMAT 子句时的输出格式
+    ///
+    /// 服务器返回错误状态码或 `X-ClickHouse-Exception-Code` 头时读取响应体作为错误信息
+    async fn post(
+        client: &Client,
+        base_url: &str,
+        config: &ConnectionConfig,
+        settings: &[(String, String)],
+        database: Option<&str>,
+        sql: &str,
+        format: &str,
+    ) -> DbResult<reqwest::Response> {
+        let mut params: Vec<(&str, &str)> = vec![("default_format", format)];
+        if let Some(db) = database.filter(|d| !d.is_empty()) {
+            params.push(("database", db));
+        }
+        for (name, value) in settings {
+            params.push((name.as_str(), value.as_str()));
+        }
+
+        let mut request = client.post(base_url).query(&params).body(sql.to_string());
+        if !config.username.is_empty() {
+            request = request
+                .header("X-ClickHouse-User", &config.username)
+                .header("X-ClickHouse-Key", &config.password);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                DbError::Timeout(e.to_string())
+            } else {
+                DbError::ConnectionFailed(e.to_string())
+            }
+        })?;
+
+        let exception_code = response
+            .headers()
+            .get("X-ClickHouse-Exception-Code")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.trim().parse::<i64>().ok());
+        if response.status().is_success() && exception_code.is_none() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(Self::exception_error(exception_code, body.trim()))
+    }
+
+    /// 根据异常码构造错误；没有异常头时从响应（形如 "Code: 60. DB::Exception: ..."）中解析
+    fn exception_error(code: Option<i64>, body: &str) -> DbError {
+        let number = code.or_else(|| {
+            body.strip_prefix("Code: ")
+                .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+                .and_then(|n| n.parse::<i64>().ok())
+        });
+        let Some(number) = number else {
+            return DbError::QueryFailed(body.to_string());
+        };
+
+        let code = match number {
+            62 => ErrorCode::SyntaxError,
+            192 | 193 | 194 | 516 => ErrorCode::AuthFailed,
+            164 | 497 => ErrorCode::PermissionDenied,
+            16 | 47 | 60 | 81 | 46 => ErrorCode::NotFound,
+            159 | 209 | 210 => ErrorCode::Timeout,
+            _ => ErrorCode::QueryFailed,
+        };
+        let native = NativeError {
+            errno: Some(number),
+            ..Default::default()
+        };
+        DbError::database(code, body, native)
+    }
+
+    /// 使用当前连接执行一条语句
+    async fn request(&self, sql: &str, database: Option<&str>) -> DbResult<(String, u64)> {
+        let (client, base_url, config) = match (&self.client, &self.base_url, &self.config) {
+            (Some(client), Some(base_url), Some(config)) => (client, base_url, config),
+            _ => return Err(DbError::ConnectionFailed(Msg::NotConnected.into())),
+        };
+
+        let database = database.or(self.current_database.as_deref());
+        Self::send(client, base_url, config, &self.settings, database, sql).await
+    }
+
+    /// 解析 JSONCompactEachRowWithNames 的一行；首行为列名，其余为值数组。
+    /// 用户指定了其他 FORMAT 时返回 None，按原始文本处理
+    fn parse_each_row_line(line: &str) -> Option<Vec<Value>> {
+        match serde_json::from_str(line) {
+            Ok(Value::Array(values)) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// 查询并返回 JSONCompact 格式的行
+    async fn query_rows(&self, sql: &str) -> DbResult<Vec<Vec<Value>>> {
+        let (body, _) = self.request(sql, None).await?;
+        let response: Value = serde_json::from_str(&body)
+            .map_err(|e| DbError::QueryFailed(format!("{}: {}", Msg::ParseResponseFailed, e)))?;
+        Ok(response
+            .get("data")
+            .and_then(|d| d.as_array())
+            .map(|rows| rows.iter().filter_map(|r| r.as_array().cloned()).collect())
+            .unwrap_or_default())
+    }
+
+    /// 将 JSONCompact 响应转换为结果集；DDL/INSERT 等语句没有响应体
+    fn response_to_result(body: &str, written_rows: u64, start: Instant) -> DbResult<QueryResult> {
+        if body.trim().is_empty() {
+            return Ok(QueryResult {
+                columns: vec![],
+                rows: vec![],
+                affected_rows: written_rows,
+                execution_time_ms: start.elapsed().as_millis(),
+            });
+        }
+
+        let response: Value = match serde_json::from_str(body) {
+            Ok(value) => value,
+            // 用户指定了 FORMAT TabSeparated 等非 JSON 格式，按行返回原始文本
+            Err(_) => {
+                let rows: Vec<HashMap<String, Value>> = body
+                    .lines()
+                    .map(|line| HashMap::from([("result".to_string(), Value::String(line.to_string()))]))
+                    .collect();
+                return Ok(QueryResult {
+                    columns: vec!["result".to_string()],
+                    affected_rows: rows.len() as u64,
+                    rows,
+                    execution_time_ms: start.elapsed().as_millis(),
+                });
+            }
+        };
+
+        let columns: Vec<String> = response
+            .get("meta")
+            .and_then(|m| m.as_array())
+            .map(|meta| {
+                meta.iter()
+                    .filter_map(|c| c.get("name").and_then(|n| n.as_str()).map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let rows: Vec<HashMap<String, Value>> = response
+            .get("data")
+            .and_then(|d| d.as_array())
+            .map(|data| {
+                data.iter()
+                    .filter_map(|row| row.as_array())
+                    .map(|values| columns.iter().cloned().zip(values.iter().cloned()).collect())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(QueryResult {
+            columns,
+            affected_rows: rows.len() as u64,
+            rows,
+            execution_time_ms: start.elapsed().as_millis(),
+        })
+    }
+
+    fn as_string(value: &Value) -> Option<String> {
+        match value {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    /// 数值列在 JSONCompact 中可能以字符串返回（64 位整数）
+    fn as_u64(value: &Value) -> Option<u64> {
+        value.as_u64().or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+    }
+
+    fn as_i64(value: &Value) -> Option<i64> {
+        value.as_i64().or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+    }
+
+    /// 查询当前数据库条件
+    fn database_filter(&self, database: Option<&str>) -> String {
+        match database.or(self.current_database.as_deref()).filter(|d| !d.is_empty()) {
+            Some(db) => format!("database = '{}'", escape_sql_literal(db)),
+            None => "database = currentDatabase()".to_string(),
+        }
+    }
+
+    /// 列出表或视图
+    async fn list_tables(&self, database: Option<&str>, views: bool) -> DbResult<Vec<TableInfo>> {
+        let sql = format!(
+            "SELECT name, engine, total_rows, total_bytes, comment FROM system.tables \
+             WHERE {} AND engine {} ('View', 'MaterializedView', 'LiveView', 'WindowView') \
+             AND NOT is_temporary ORDER BY name",
+            self.database_filter(database),
+            if views { "IN" } else { "NOT IN" }
+        );
+
+        Ok(self
+            .query_rows(&sql)
+            .await?
+            .iter()
+            .map(|row| TableInfo {
+                name: Self::as_string(&row[0]).unwrap_or_default(),
+                schema: None,
+                table_type: if views { "VIEW".to_string() } else { "TABLE".to_string() },
+                engine: Self::as_string(&row[1]),
+                rows: Self::as_u64(&row[2]),
+                size_mb: Self::as_u64(&row[3]).map(|bytes| bytes as f64 / 1024.0 / 1024.0),
+                comment: Self::as_string(&row[4]).filter(|c| !c.is_empty()),
+            })
+            .collect())
+    }
+}
+
+impl Default for ClickHouseDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ClickHouse 驱动
+pub struct ClickHouseDriver;
+
+inventory::submit! {
+    DriverRegistration { factory: || Arc::new(ClickHouseDriver) }
+}
+
+impl DatabaseDriver for ClickHouseDriver {
+    fn db_type(&self) -> DatabaseType {
+        DatabaseType::ClickHouse
+    }
+
+    fn display_name(&self) -> &'static str {
+        "ClickHouse"
+    }
+
+    fn create(&self) -> DbResult<Box<dyn DatabaseOperations>> {
+        Ok(Box::new(ClickHouseDatabase::new()))
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            sql: true,
+            object_types: vec![ObjectType::Table, ObjectType::View, ObjectType::Index],
+            // UPDATE / DELETE 通过 mutation 实现
+            editing: EditingCapabilities::all(),
+            transactions: false,
+            explain_formats: vec![ExplainFormat::Text, ExplainFormat::Json],
+            export_formats: ExportFormat::for_sql(),
+            ..Default::default()
+        }
+    }
+
+    fn dialect(&self) -> Option<SqlDialect> {
+        Some(SqlDialect::clickhouse())
+    }
+
+    /// 反引号，内部的反引号和反斜杠需要转义
+    fn quote_identifier(&self, identifier: &str) -> String {
+        format!("`{}`", identifier.replace('\\', "\\\\").replace('`', "\\`"))
+    }
+
+    /// 默认把反斜杠当作转义符
+    fn string_literal(&self, value: &str) -> String {
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+    }
+
+    fn binary_literal(&self, hex: &str) -> String {
+        format!("unhex('{}')", hex)
+    }
+
+    /// 通过 mutation 更新数据
+    fn update_sql(&self, table_ref: &str, assignments: &str, where_clause: &str) -> String {
+        format!("ALTER TABLE {} UPDATE {} WHERE {}", table_ref, assignments, where_clause)
+    }
+}
+
+#[async_trait]
+impl DatabaseOperations for ClickHouseDatabase {
+    async fn test_connection(&self, config: &ConnectionConfig) -> DbResult<bool> {
+        let client = Self::build_client(config)?;
+        let base_url = Self::build_base_url(config);
+        let database = config.database.as_deref();
+        let version = Self::server_version(&client, &base_url, config, database)
+            .await
+            .map_err(DbError::connecting)?;
+        let (settings, _) = Self::session_settings(config, &version);
+        Self::send(&client, &base_url, config, &settings, database, "SELECT 1")
+            .await
+            .map_err(DbError::connecting)?;
+        Ok(true)
+    }
+
+    async fn connect(&mut self, config: ConnectionConfig) -> DbResult<()> {
+        let client = Self::build_client(&config)?;
+        let base_url = Self::build_base_url(&config);
+        let database = config.database.clone().filter(|d| !d.is_empty());
+
+        let version = Self::server_version(&client, &base_url, &config, database.as_deref())
+            .await
+            .map_err(DbError::connecting)?;
+        let (settings, statements) = Self::session_settings(&config, &version);
+        // 确认初始化 SQL 中的设置可以被服务器接受
+        Self::send(&client, &base_url, &config, &settings, database.as_deref(), "SELECT 1")
+            .await
+            .map_err(DbError::connecting)?;
+        for sql in &statements {
+            Self::send(&client, &base_url, &config, &settings, database.as_deref(), sql)
+                .await
+                .map_err(|e| e.context(Msg::InitSqlFailed))?;
+        }
+
+        self.client = Some(client);
+        self.base_url = Some(base_url);
+        self.current_database = database;
+        self.settings = settings;
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> DbResult<()> {
+        self.client = None;
+        self.base_url = None;
+        self.config = None;
+        self.current_database = None;
+        self.settings.clear();
+        Ok(())
+    }
+
+    async fn execute_query(&self, sql: &str, database: Option<&str>) -> DbResult<QueryResult> {
+        let start = Instant::now();
+        let statements = SqlSplitter::new(SqlDialect::clickhouse()).split(sql);
+
+        let mut total_written_rows: u64 = 0;
+        let mut last_query_result: Option<QueryResult> = None;
+
+        for (idx, stmt) in statements.iter().enumerate() {
+            let (body, written_rows) = self.request(stmt, database).await.map_err(|e| {
+                if statements.len() > 1 {
+                    e.context(Msg::StatementFailed.with(&[&(idx + 1)]))
+                } else {
+                    e
+                }
+            })?;
+
+            let result = Self::response_to_result(&body, written_rows, start)?;
+            if result.columns.is_empty() {
+                total_written_rows += result.affected_rows;
+            } else {
+                last_query_result = Some(result);
+            }
+        }
+
+        // 有查询结果则返回最后一个，否则返回累积的写入行数
+        Ok(match last_query_result {
+            Some(mut result) => {
+                result.affected_rows += total_written_rows;
+                result.execution_time_ms = start.elapsed().as_millis();
+                result
+            }
+            None => QueryResult {
+                columns: vec![],
+                rows: vec![],
+                affected_rows: total_written_rows,
+                execution_time_ms: start.elapsed().as_millis(),
+            },
+        })
+    }
+
+    async fn stream_query(
+        &self,
+        sql: &str,
+        database: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> DbResult<u64> {
+        let (client, base_url, config) = match (&self.client, &self.base_url, &self.config) {
+            (Some(client), Some(base_url), Some(config)) => (client, base_url, config),
+            _ => return Err(DbError::ConnectionFailed(Msg::NotConnected.into())),
+        };
+        let database = database.or(self.current_database.as_deref());
+
+        // 前面的语句直接执行，只流式读取最后一条语句的结果
+        let mut statements = SqlSplitter::new(SqlDialect::clickhouse()).split(sql);
+        let Some(last) = statements.pop() else {
+            sink.columns(&[])?;
+            return Ok(0);
+        };
+        for stmt in &statements {
+            Self::send(client, base_url, config, &self.settings, database, stmt).await?;
+        }
+
+        let mut response = Self::post(
+            client,
+            base_url,
+            config,
+            &self.settings,
+            database,
+            &last,
+            "JSONCompactEachRowWithNames",
+        )
+        .await?;
+
+        // 按块读取响应，每个完整的行解析一次；列名在第一行
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut columns: Option<bool> = None;
+        let mut count = 0u64;
+        let mut handle_line = |line: &[u8]| -> DbResult<()> {
+            let line = String::from_utf8_lossy(line);
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() {
+                return Ok(());
+            }
+            // 响应头已发出后才出错时状态码仍是 200，错误信息以文本行追加在末尾
+            if line.starts_with("Code: ") && line.contains("DB::Exception") {
+                return Err(Self::exception_error(None, line));
+            }
+            match columns {
+                None => match Self::parse_each_row_line(line) {
+                    Some(names) => {
+                        let names: Vec<String> =
+                            names.iter().map(|n| Self::as_string(n).unwrap_or_default()).collect();
+                        sink.columns(&names)?;
+                        columns = Some(true);
+                    }
+                    None => {
+                        sink.columns(&["result".to_string()])?;
+                        columns = Some(false);
+                        sink.row(vec![Value::String(line.to_string())])?;
+                        count += 1;
+                    }
+                },
+                Some(true) => {
+                    let values = Self::parse_each_row_line(line).ok_or_else(|| {
+                        DbError::QueryFailed(format!("{}: {}", Msg::ParseResponseFailed, line))
+                    })?;
+                    sink.row(values)?;
+                    count += 1;
+                }
+                Some(false) => {
+                    sink.row(vec![Value::String(line.to_string())])?;
+                    count += 1;
+                }
+            }
+            Ok(())
+        };
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| DbError::QueryFailed(format!("{}: {}", Msg::ReadResponseFailed, e)))?
+        {
+            buffer.extend_from_slice(&chunk);
+            while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                handle_line(&line[..pos])?;
+            }
+        }
+        handle_line(&buffer)?;
+
+        // DDL/INSERT 等语句没有响应体
+        if columns.is_none() {
+            sink.columns(&[])?;
+        }
+        Ok(count)
+    }
+
+    async fn get_databases(&self) -> DbResult<Vec<DatabaseInfo>> {
+        Ok(self
+            .query_rows("SELECT name FROM system.databases ORDER BY name")
+            .await?
+            .iter()
+            .filter_map(|row| Self::as_string(&row[0]))
+            .map(|name| DatabaseInfo {
+                name,
+                charset: None,
+                collation: None,
+            })
+            .collect())
+    }
+
+    async fn get_tables(&self, database: Option<&str>) -> DbResult<Vec<TableInfo>> {
+        self.list_tables(database, false).await
+    }
+
+    async fn get_views(&self, database: Option<&str>) -> DbResult<Vec<TableInfo>> {
+        self.list_tables(database, true).await
+    }
+
+    async fn get_table_structure(&self, table: &str, _schema: Option<&str>, database: Option<&str>) -> DbResult<Vec<ColumnInfo>> {
+        let sql = format!(
+            "SELECT name, type, default_kind, default_expression, comment, is_in_primary_key, \
+             character_octet_length, numeric_precision, numeric_scale \
+             FROM system.columns WHERE {} AND table = '{}' ORDER BY position",
+            self.database_filter(database),
+            escape_sql_literal(table)
+        );
+
+        Ok(self
+            .query_rows(&sql)
+            .await?
+            .iter()
+            .map(|row| {
+                let data_type = Self::as_string(&row[1]).unwrap_or_default();
+                let default_kind = Self::as_string(&row[2]).unwrap_or_default();
+                let default_expression = Self::as_string(&row[3]).filter(|d| !d.is_empty());
+                ColumnInfo {
+                    name: Self::as_string(&row[0]).unwrap_or_default(),
+                    nullable: data_type.starts_with("Nullable("),
+                    data_type,
+                    // MATERIALIZED / ALIAS 列保留种类前缀，便于区分
+                    default_value: default_expression.map(|expr| match default_kind.as_str() {
+                        "" | "DEFAULT" => expr,
+                        kind => format!("{} {}", kind, expr),
+                    }),
+                    is_primary_key: Self::as_u64(&row[5]).unwrap_or(0) > 0,
+                    is_auto_increment: false,
+                    comment: Self::as_string(&row[4]).filter(|c| !c.is_empty()),
+                    character_maximum_length: Self::as_i64(&row[6]),
+                    numeric_precision: Self::as_i64(&row[7]),
+                    numeric_scale: Self::as_i64(&row[8]),
+                }
+            })
+            .collect())
+    }
+
+    async fn get_indexes(&self, table: &str, _schema: Option<&str>) -> DbResult<Vec<IndexInfo>> {
+        let table = escape_sql_literal(table);
+        let mut indexes = Vec::new();
+
+        // MergeTree 的主键（稀疏索引）
+        let key_sql = format!(
+            "SELECT primary_key FROM system.tables WHERE {} AND name = '{}'",
+            self.database_filter(None),
+            table
+        );
+        for row in self.query_rows(&key_sql).await? {
+            let columns: Vec<String> = Self::as_string(&row[0])
+                .unwrap_or_default()
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect();
+            if !columns.is_empty() {
+                indexes.push(IndexInfo {
+                    name: "PRIMARY".to_string(),
+                    columns,
+                    is_unique: false,
+                    is_primary: true,
+                    index_type: "SPARSE".to_string(),
+                });
+            }
+        }
+
+        // 数据跳数索引
+        let skip_sql = format!(
+            "SELECT name, type, expr FROM system.data_skipping_indices WHERE {} AND table = '{}' ORDER BY name",
+            self.database_filter(None),
+            table
+        );
+        for row in self.query_rows(&skip_sql).await? {
+            indexes.push(IndexInfo {
+                name: Self::as_string(&row[0]).unwrap_or_default(),
+                columns: Self::as_string(&row[2]).into_iter().collect(),
+                is_unique: false,
+                is_primary: false,
+                index_type: Self::as_string(&row[1]).unwrap_or_default().to_uppercase(),
+            });
+        }
+
+        Ok(indexes)
+    }
+
+    async fn get_table_options(&self, table: &str, _schema: Option<&str>) -> DbResult<TableOptions> {
+        let sql = format!(
+            "SELECT engine_full, comment FROM system.tables WHERE {} AND name = '{}'",
+            self.database_filter(None),
+            escape_sql_literal(table)
+        );
+        let rows = self.query_rows(&sql).await?;
+        let row = rows.first();
+
+        Ok(TableOptions {
+            engine: row.and_then(|r| Self::as_string(&r[0])),
+            charset: None,
+            collation: None,
+            comment: row.and_then(|r| Self::as_string(&r[1])).filter(|c| !c.is_empty()),
+            auto_increment: None,
+            extra: Default::default(),
+        })
+    }
+
+    async fn switch_database(&mut self, database: &str) -> DbResult<()> {
+        // HTTP 接口无会话，只需更新默认数据库参数
+        self.current_database = Some(database.to_string()).filter(|d| !d.is_empty());
+        Ok(())
+    }
+
+    async fn ping(&self) -> DbResult<()> {
+        self.request("SELECT 1", None)
+            .await
+            .map(|_| ())
+    }
+
+    async fn server_info(&self) -> DbResult<ServerInfo> {
+        let rows = self
+            .query_rows("SELECT version(), currentUser(), timezone()")
+            .await
+            .map_err(|e| e.context(Msg::ServerInfoFailed))?;
+        let row = rows.first().cloned().unwrap_or_default();
+        let field = |idx: usize| row.get(idx).and_then(Self::as_string);
+
+        let version = field(0).unwrap_or_default();
+        let mut info = ServerInfo {
+            flavor: ServerFlavor::ClickHouse,
+            version_string: format!("ClickHouse {}", version),
+            version,
+            current_user: field(1),
+            time_zone: field(2),
+            features: ServerFeatures::default(),
+        };
+
+        info.features = ServerFeatures {
+            window_functions: info.version_at_least(21, 3),
+            cte: true,
+            json: true,
+            returning: false,
+            upsert: false,
+            sequences: false,
+            check_constraints: true,
+            stored_procedures: false,
+            triggers: false,
+            events: false,
+            transactions: false,
+        };
+
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_set_statement() {
+        assert_eq!(
+            ClickHouseDatabase::parse_set_statement("SET max_threads = 4;"),
+            Some(("max_threads".to_string(), "4".to_string()))
+        );
+        assert_eq!(
+            ClickHouseDatabase::parse_set_statement("set session_timezone = 'Asia/Shanghai'"),
+            Some(("session_timezone".to_string(), "Asia/Shanghai".to_string()))
+        );
+        assert_eq!(ClickHouseDatabase::parse_set_statement("SET a = 1, b = 2"), None);
+        assert_eq!(ClickHouseDatabase::parse_set_statement("SELECT 1"), None);
+    }
+
+    #[test]
+    fn test_response_to_result() {
+        let body = r#"{"meta":[{"name":"id","type":"UInt64"},{"name":"name","type":"String"}],
+            "data":[["1","a"],["2","b"]],"rows":2}"#;
+        let result = ClickHouseDatabase::response_to_result(body, 0, Instant::now()).unwrap();
+        assert_eq!(result.columns, vec!["id", "name"]);
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[1]["name"], Value::String("b".to_string()));
+
+        let result = ClickHouseDatabase::response_to_result("", 5, Instant::now()).unwrap();
+        assert!(result.columns.is_empty());
+        assert_eq!(result.affected_rows, 5);
+    }
+
+    #[test]
+    fn test_parse_each_row_line() {
+        assert_eq!(
+            ClickHouseDatabase::parse_each_row_line(r#"["id","name"]"#),
+            Some(vec![Value::String("id".to_string()), Value::String("name".to_string())])
+        );
+        assert_eq!(ClickHouseDatabase::parse_each_row_line("1\ta"), None);
+    }
+
+    #[test]
+    fn test_exception_error() {
+        let err = ClickHouseDatabase::exception_error(
+            None,
+            "Code: 60. DB::Exception: Table default.t does not exist. (UNKNOWN_TABLE)",
+        );
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        assert_eq!(err.native_error().and_then(|n| n.errno), Some(60));
+
+        // 异常头中的错误码优先于响应内容
+        let err = ClickHouseDatabase::exception_error(Some(516), "Authentication failed");
+        assert_eq!(err.code(), ErrorCode::AuthFailed);
+        assert_eq!(err.native_error().and_then(|n| n.errno), Some(516));
+
+        let err = ClickHouseDatabase::exception_error(None, "Bad Gateway");
+        assert_eq!(err.code(), ErrorCode::QueryFailed);
+        assert!(err.native_error().is_none());
+    }
+}
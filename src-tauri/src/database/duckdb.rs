@@ -0,0 +1,963 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use duckdb::types::Value;
+use duckdb::Connection;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use super::sql_splitter::{is_query_statement, SqlDialect, SqlSplitter};
+use super::registry::{
+    DatabaseDriver, DriverCapabilities, DriverRegistration, EditingCapabilities, ExplainFormat,
+    ExportFormat, ObjectType,
+};
+use super::traits::*;
+use crate::utils::i18n::Msg;
+
+/// 可直接作为连接目标打开的数据文件扩展名及对应的读取函数
+const FILE_READERS: &[(&str, &str)] = &[
+    ("parquet", "read_parquet"),
+    ("csv", "read_csv_auto"),
+    ("tsv", "read_csv_auto"),
+    ("json", "read_json_auto"),
+    ("ndjson", "read_json_auto"),
+    ("jsonl", "read_json_auto"),
+];
+
+/// 流式读取时阻塞线程与异步任务之间的通道容量（行）
+const STREAM_CHANNEL_SIZE: usize = 256;
+
+/// 流式读取时从阻塞线程发出的数据
+enum StreamItem {
+    Columns(Vec<String>),
+    Row(Vec<serde_json::Value>),
+}
+
+/// DuckDB 嵌入式数据库连接
+///
+/// 连接目标可以是 `.duckdb` 数据库文件、`:memory:`，也可以直接是 Parquet/CSV/JSON 文件
+/// （支持通配符），此时在内存库中为其创建同名视图。查询中也可以直接引用文件，
+/// 例如 `SELECT * FROM 'data/*.parquet'`。
+pub struct DuckDbDatabase {
+    conn: Option<Arc<Mutex<Connection>>>,
+    config: Option<ConnectionConfig>,
+}
+
+/// 单条语句的执行结果
+struct StatementOutput {
+    columns: Vec<String>,
+    rows: Vec<HashMap<String, serde_json::Value>>,
+    affected_rows: u64,
+    is_query: bool,
+}
+
+impl DuckDbDatabase {
+    pub fn new() -> Self {
+        Self {
+            conn: None,
+            config: None,
+        }
+    }
+
+    /// 取连接目标路径（与 SQLite 一致，优先使用 database 字段）
+    fn target_path(config: &ConnectionConfig) -> String {
+        config
+            .database
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or(&config.host)
+            .trim()
+            .to_string()
+    }
+
+    /// 如果目标是数据文件，返回（视图名, 创建视图的 SQL）
+    fn file_view_statement(path: &str) -> Option<(String, String)> {
+        let lower = path.to_lowercase();
+        let (_, reader) = FILE_READERS
+            .iter()
+            .find(|(ext, _)| lower.ends_with(&format!(".{}", ext)))?;
+
+        let stem = Path::new(path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("data")
+            .replace(['*', '?'], "");
+        let view_name = if stem.is_empty() { "data".to_string() } else { stem };
+
+        let sql = format!(
+            "CREATE OR REPLACE VIEW \"{}\" AS SELECT * FROM {}('{}')",
+            view_name.replace('"', "\"\""),
+            reader,
+            escape_sql_literal(path)
+        );
+        Some((view_name, sql))
+    }
+
+    /// 打开连接并执行初始化 SQL
+    ///
+    /// DuckDB 没有字符集和应用名称等会话设置
+    fn open_connection(config: &ConnectionConfig) -> DbResult<Connection> {
+        let path = Self::target_path(config);
+
+        // 直接打开 CSV/Parquet 等文件时在内存库中创建视图
+        let conn = if path.is_empty()
+            || path == ":memory:"
+            || Self::file_view_statement(&path).is_some()
+        {
+            Connection::open_in_memory()
+        } else {
+            Connection::open(&path)
+        }
+        .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+
+        if let Some((view_name, sql)) = Self::file_view_statement(&path) {
+            conn.execute_batch(&sql).map_err(|e| {
+                DbError::ConnectionFailed(format!("{}: {}", Msg::AttachFileFailed.with(&[&path, &view_name]), e))
+            })?;
+        }
+
+        for sql in config.init_sql_statements(SqlDialect::postgresql()) {
+            conn.execute_batch(&sql)
+                .map_err(|e| DbError::ConnectionFailed(format!("{}: {}", Msg::InitSqlFailed, e)))?;
+        }
+
+        Ok(conn)
+    }
+
+    /// 在阻塞线程中使用连接（DuckDB 的 API 是同步的）
+    async fn with_conn<T, F>(&self, f: F) -> DbResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> DbResult<T> + Send + 'static,
+    {
+        let conn = self
+            .conn
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?
+            .clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|_| DbError::QueryFailed(Msg::ConnectionBroken.into()))?;
+            f(&conn)
+        })
+        .await
+        .map_err(|e| DbError::QueryFailed(e.to_string()))?
+    }
+
+    /// 切换默认数据库（主库或 ATTACH 的数据库），未指定时保持不变
+    fn use_database(conn: &Connection, database: Option<&str>) -> DbResult<()> {
+        match database.filter(|d| !d.is_empty()) {
+            Some(db) => conn
+                .execute_batch(&format!("USE \"{}\"", db.replace('"', "\"\"")))
+                .map_err(|e| DbError::QueryFailed(e.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// DuckDB 特有的返回结果集的语句
+    fn returns_rows(sql: &str) -> bool {
+        let upper = sql.trim_start().to_uppercase();
+        is_query_statement(sql)
+            || ["FROM", "SUMMARIZE", "PRAGMA", "PIVOT", "UNPIVOT", "VALUES", "CALL", "TABLE"]
+                .iter()
+                .any(|kw| upper.starts_with(kw))
+            || upper.contains(" RETURNING ")
+    }
+
+    /// 执行查询并返回列名和原始值
+    fn query_values(conn: &Connection, sql: &str) -> DbResult<(Vec<String>, Vec<Vec<Value>>)> {
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        let mut rows = stmt
+            .query([])
+            .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+
+        // 列信息在语句执行后才可用
+        let columns = rows
+            .as_ref()
+            .map(|s| s.column_names())
+            .unwrap_or_default();
+
+        let mut values = Vec::new();
+        while let Some(row) = rows.next().map_err(|e| DbError::QueryFailed(e.to_string()))? {
+            let mut record = Vec::with_capacity(columns.len());
+            for idx in 0..columns.len() {
+                record.push(row.get::<_, Value>(idx).unwrap_or(Value::Null));
+            }
+            values.push(record);
+        }
+
+        Ok((columns, values))
+    }
+
+    /// 执行单条语句
+    fn execute_statement(conn: &Connection, sql: &str) -> DbResult<StatementOutput> {
+        if Self::returns_rows(sql) {
+            let (columns, values) = Self::query_values(conn, sql)?;
+            let rows: Vec<HashMap<String, serde_json::Value>> = values
+                .into_iter()
+                .map(|record| {
+                    columns
+                        .iter()
+                        .cloned()
+                        .zip(record.into_iter().map(Self::value_to_json))
+                        .collect()
+                })
+                .collect();
+
+            Ok(StatementOutput {
+                affected_rows: rows.len() as u64,
+                columns,
+                rows,
+                is_query: true,
+            })
+        } else {
+            let affected = conn
+                .execute(sql, [])
+                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+
+            Ok(StatementOutput {
+                columns: vec![],
+                rows: vec![],
+                affected_rows: affected as u64,
+                is_query: false,
+            })
+        }
+    }
+
+    /// 将 DuckDB 值转换为 JSON
+    fn value_to_json(value: Value) -> serde_json::Value {
+        use serde_json::json;
+
+        match value {
+            Value::Null => serde_json::Value::Null,
+            Value::Boolean(b) => json!(b),
+            Value::TinyInt(v) => json!(v),
+            Value::SmallInt(v) => json!(v),
+            Value::Int(v) => json!(v),
+            Value::BigInt(v) => json!(v),
+            Value::UTinyInt(v) => json!(v),
+            Value::USmallInt(v) => json!(v),
+            Value::UInt(v) => json!(v),
+            Value::UBigInt(v) => json!(v),
+            // 超出 JSON 数值精度的类型使用字符串
+            Value::HugeInt(v) => json!(v.to_string()),
+            Value::Decimal(d) => json!(d.to_string()),
+            Value::Float(v) => json!(v),
+            Value::Double(v) => json!(v),
+            Value::Text(s) | Value::Enum(s) => json!(s),
+            Value::Blob(bytes) => json!(general_purpose::STANDARD.encode(bytes)),
+            Value::Date32(days) => chrono::DateTime::from_timestamp(i64::from(days) * 86_400, 0)
+                .map(|dt| json!(dt.date_naive().to_string()))
+                .unwrap_or(serde_json::Value::Null),
+            Value::Timestamp(unit, v) => {
+                chrono::DateTime::from_timestamp_micros(unit.to_micros(v))
+                    .map(|dt| json!(dt.naive_utc().to_string()))
+                    .unwrap_or(serde_json::Value::Null)
+            }
+            Value::Time64(unit, v) => {
+                let micros = unit.to_micros(v);
+                chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+                    (micros / 1_000_000) as u32,
+                    ((micros % 1_000_000) * 1_000) as u32,
+                )
+                .map(|t| json!(t.to_string()))
+                .unwrap_or(serde_json::Value::Null)
+            }
+            Value::List(items) | Value::Array(items) => {
+                serde_json::Value::Array(items.into_iter().map(Self::value_to_json).collect())
+            }
+            other => json!(format!("{:?}", other)),
+        }
+    }
+
+    fn value_to_string(value: &Value) -> Option<String> {
+        match Self::value_to_json(value.clone()) {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) => Some(s),
+            other => Some(other.to_string()),
+        }
+    }
+
+    fn value_to_u64(value: &Value) -> Option<u64> {
+        Self::value_to_string(value).and_then(|s| s.parse().ok())
+    }
+
+    fn value_to_i64(value: &Value) -> Option<i64> {
+        Self::value_to_string(value).and_then(|s| s.parse().ok())
+    }
+
+    /// 元数据查询中的数据库（catalog）过滤条件
+    fn database_filter(database: Option<&str>) -> String {
+        match database.filter(|d| !d.is_empty()) {
+            Some(db) => format!("database_name = '{}'", escape_sql_literal(db)),
+            None => "database_name = current_database()".to_string(),
+        }
+    }
+
+    fn schema_filter(schema: Option<&str>) -> String {
+        match schema.filter(|s| !s.is_empty()) {
+            Some(schema) => format!("schema_name = '{}'", escape_sql_literal(schema)),
+            None => "schema_name = current_schema()".to_string(),
+        }
+    }
+
+    /// 从 CREATE INDEX 语句中取出索引列
+    fn index_columns_from_sql(sql: &str) -> Vec<String> {
+        let (Some(start), Some(end)) = (sql.find('('), sql.rfind(')')) else {
+            return Vec::new();
+        };
+        if end <= start {
+            return Vec::new();
+        }
+        sql[start + 1..end]
+            .split(',')
+            .map(|c| c.trim().trim_matches('"').to_string())
+            .filter(|c| !c.is_empty())
+            .collect()
+    }
+
+    /// 将列表值转换为字符串数组（例如约束的列名）
+    fn value_to_strings(value: &Value) -> Vec<String> {
+        match value {
+            Value::List(items) | Value::Array(items) => {
+                items.iter().filter_map(Self::value_to_string).collect()
+            }
+            other => Self::value_to_string(other).into_iter().collect(),
+        }
+    }
+}
+
+impl Default for DuckDbDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// DuckDB 驱动
+pub struct DuckDbDriver;
+
+inventory::submit! {
+    DriverRegistration { factory: || Arc::new(DuckDbDriver) }
+}
+
+impl DatabaseDriver for DuckDbDriver {
+    fn db_type(&self) -> DatabaseType {
+        DatabaseType::DuckDB
+    }
+
+    fn display_name(&self) -> &'static str {
+        "DuckDB"
+    }
+
+    fn create(&self) -> DbResult<Box<dyn DatabaseOperations>> {
+        Ok(Box::new(DuckDbDatabase::new()))
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            sql: true,
+            schemas: true,
+            file_based: true,
+            object_types: vec![
+                ObjectType::Table,
+                ObjectType::View,
+                ObjectType::Sequence,
+                ObjectType::Index,
+            ],
+            editing: EditingCapabilities::all(),
+            transactions: true,
+            explain_formats: vec![ExplainFormat::Text, ExplainFormat::Json],
+            export_formats: ExportFormat::for_sql(),
+            ..Default::default()
+        }
+    }
+
+    fn dialect(&self) -> Option<SqlDialect> {
+        Some(SqlDialect::postgresql())
+    }
+
+    fn default_schema(&self) -> Option<&'static str> {
+        Some("main")
+    }
+
+    fn binary_literal(&self, hex: &str) -> String {
+        format!("from_hex('{}')", hex)
+    }
+
+    fn binary_as_base64(&self) -> bool {
+        true
+    }
+}
+
+#[async_trait]
+impl DatabaseOperations for DuckDbDatabase {
+    async fn test_connection(&self, config: &ConnectionConfig) -> DbResult<bool> {
+        let config = config.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = Self::open_connection(&config)?;
+            conn.execute_batch("SELECT 1")
+                .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+            Ok(true)
+        })
+        .await
+        .map_err(|e| DbError::ConnectionFailed(e.to_string()))?
+    }
+
+    async fn connect(&mut self, config: ConnectionConfig) -> DbResult<()> {
+        let open_config = config.clone();
+        let conn = tokio::task::spawn_blocking(move || Self::open_connection(&open_config))
+            .await
+            .map_err(|e| DbError::ConnectionFailed(e.to_string()))??;
+
+        self.conn = Some(Arc::new(Mutex::new(conn)));
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> DbResult<()> {
+        // 最后一个引用释放时连接自动关闭
+        self.conn = None;
+        self.config = None;
+        Ok(())
+    }
+
+    async fn execute_query(&self, sql: &str, database: Option<&str>) -> DbResult<QueryResult> {
+        let start = Instant::now();
+        let statements = SqlSplitter::new(SqlDialect::postgresql()).split(sql);
+
+        if statements.is_empty() {
+            return Ok(QueryResult {
+                columns: vec![],
+                rows: vec![],
+                affected_rows: 0,
+                execution_time_ms: 0,
+            });
+        }
+
+        let database = database.map(str::to_string);
+        self.with_conn(move |conn| {
+            Self::use_database(conn, database.as_deref())?;
+
+            let mut total_affected_rows: u64 = 0;
+            let mut last_query_result: Option<StatementOutput> = None;
+
+            for (idx, stmt) in statements.iter().enumerate() {
+                let output = Self::execute_statement(conn, stmt).map_err(|e| {
+                    if statements.len() > 1 {
+                        e.context(Msg::StatementFailed.with(&[&(idx + 1)]))
+                    } else {
+                        e
+                    }
+                })?;
+
+                if output.is_query {
+                    last_query_result = Some(output);
+                } else {
+                    total_affected_rows += output.affected_rows;
+                }
+            }
+
+            // 有查询结果则返回最后一个，否则返回累积的影响行数
+            Ok(match last_query_result {
+                Some(output) => QueryResult {
+                    columns: output.columns,
+                    rows: output.rows,
+                    affected_rows: output.affected_rows + total_affected_rows,
+                    execution_time_ms: start.elapsed().as_millis(),
+                },
+                None => QueryResult {
+                    columns: vec![],
+                    rows: vec![],
+                    affected_rows: total_affected_rows,
+                    execution_time_ms: start.elapsed().as_millis(),
+                },
+            })
+        })
+        .await
+    }
+
+    async fn stream_query(
+        &self,
+        sql: &str,
+        database: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> DbResult<u64> {
+        let database = database.map(str::to_string);
+        let conn = self
+            .with_conn(move |conn| {
+                // 读取期间使用独立连接，不阻塞其他查询
+                let stream_conn = conn.try_clone().map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                Self::use_database(&stream_conn, database.as_deref())?;
+                Ok(stream_conn)
+            })
+            .await?;
+
+        // 阻塞线程按行迭代，通过有界通道交给 sink；sink 出错时通道关闭，迭代随之停止
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<StreamItem>(STREAM_CHANNEL_SIZE);
+        let sql = sql.to_string();
+        let reader = tokio::task::spawn_blocking(move || -> DbResult<()> {
+            let mut stmt = conn
+                .prepare(&sql)
+                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+            let mut rows = stmt
+                .query([])
+                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+
+            let columns = rows
+                .as_ref()
+                .map(|s| s.column_names())
+                .unwrap_or_default();
+            let width = columns.len();
+            if tx.blocking_send(StreamItem::Columns(columns)).is_err() {
+                return Ok(());
+            }
+
+            while let Some(row) = rows.next().map_err(|e| DbError::QueryFailed(e.to_string()))? {
+                let values = (0..width)
+                    .map(|idx| Self::value_to_json(row.get::<_, Value>(idx).unwrap_or(Value::Null)))
+                    .collect();
+                if tx.blocking_send(StreamItem::Row(values)).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        });
+
+        let mut count = 0u64;
+        let mut sink_result = Ok(());
+        while let Some(item) = rx.recv().await {
+            sink_result = match item {
+                StreamItem::Columns(columns) => sink.columns(&columns),
+                StreamItem::Row(values) => sink.row(values).map(|_| count += 1),
+            };
+            if sink_result.is_err() {
+                break;
+            }
+        }
+        drop(rx);
+
+        reader.await.map_err(|e| DbError::QueryFailed(e.to_string()))??;
+        sink_result?;
+        Ok(count)
+    }
+
+    async fn begin_transaction(&self, database: Option<&str>) -> DbResult<Box<dyn Transaction>> {
+        let database = database.map(str::to_string);
+        let conn = self
+            .with_conn(move |conn| {
+                // 事务使用同一数据库实例上的独立连接，不影响其他查询
+                let tx_conn = conn
+                    .try_clone()
+                    .map_err(|e| DbError::QueryFailed(e.to_string()).context(Msg::BeginTransactionFailed))?;
+                Self::use_database(&tx_conn, database.as_deref())?;
+                tx_conn
+                    .execute_batch("BEGIN TRANSACTION")
+                    .map_err(|e| DbError::QueryFailed(e.to_string()).context(Msg::BeginTransactionFailed))?;
+                Ok(tx_conn)
+            })
+            .await?;
+
+        Ok(Box::new(DuckDbTransaction {
+            conn: Arc::new(Mutex::new(conn)),
+        }))
+    }
+
+    async fn get_databases(&self) -> DbResult<Vec<DatabaseInfo>> {
+        // 主库及通过 ATTACH 附加的数据库
+        self.with_conn(|conn| {
+            let (_, rows) = Self::query_values(
+                conn,
+                "SELECT database_name FROM duckdb_databases() WHERE NOT internal ORDER BY database_name",
+            )?;
+
+            Ok(rows
+                .iter()
+                .filter_map(|row| Self::value_to_string(&row[0]))
+                .map(|name| DatabaseInfo {
+                    name,
+                    charset: Some("UTF-8".to_string()),
+                    collation: None,
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_tables(&self, database: Option<&str>) -> DbResult<Vec<TableInfo>> {
+        let sql = format!(
+            "SELECT table_name, schema_name, estimated_size, comment
+             FROM duckdb_tables()
+             WHERE NOT internal AND {}
+             ORDER BY schema_name, table_name",
+            Self::database_filter(database)
+        );
+
+        self.with_conn(move |conn| {
+            let (_, rows) = Self::query_values(conn, &sql)?;
+            Ok(rows
+                .iter()
+                .map(|row| TableInfo {
+                    name: Self::value_to_string(&row[0]).unwrap_or_default(),
+                    schema: Self::value_to_string(&row[1]),
+                    table_type: "TABLE".to_string(),
+                    engine: Some("DuckDB".to_string()),
+                    rows: Self::value_to_u64(&row[2]),
+                    size_mb: None,
+                    comment: Self::value_to_string(&row[3]),
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_views(&self, database: Option<&str>) -> DbResult<Vec<TableInfo>> {
+        let sql = format!(
+            "SELECT view_name, schema_name, comment
+             FROM duckdb_views()
+             WHERE NOT internal AND {}
+             ORDER BY schema_name, view_name",
+            Self::database_filter(database)
+        );
+
+        self.with_conn(move |conn| {
+            let (_, rows) = Self::query_values(conn, &sql)?;
+            Ok(rows
+                .iter()
+                .map(|row| TableInfo {
+                    name: Self::value_to_string(&row[0]).unwrap_or_default(),
+                    schema: Self::value_to_string(&row[1]),
+                    table_type: "VIEW".to_string(),
+                    engine: Some("DuckDB".to_string()),
+                    rows: None,
+                    size_mb: None,
+                    comment: Self::value_to_string(&row[2]),
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_table_structure(&self, table: &str, schema: Option<&str>, database: Option<&str>) -> DbResult<Vec<ColumnInfo>> {
+        let filter = format!(
+            "{} AND {} AND table_name = '{}'",
+            Self::database_filter(database),
+            Self::schema_filter(schema),
+            escape_sql_literal(table)
+        );
+        let columns_sql = format!(
+            "SELECT column_name, data_type, is_nullable, column_default, comment,
+                    character_maximum_length, numeric_precision, numeric_scale
+             FROM duckdb_columns()
+             WHERE {}
+             ORDER BY column_index",
+            filter
+        );
+        let pk_sql = format!(
+            "SELECT constraint_column_names FROM duckdb_constraints()
+             WHERE {} AND constraint_type = 'PRIMARY KEY'",
+            filter
+        );
+
+        self.with_conn(move |conn| {
+            let (_, pk_rows) = Self::query_values(conn, &pk_sql)?;
+            let primary_keys: Vec<String> = pk_rows
+                .iter()
+                .flat_map(|row| Self::value_to_strings(&row[0]))
+                .collect();
+
+            let (_, rows) = Self::query_values(conn, &columns_sql)?;
+            Ok(rows
+                .iter()
+                .map(|row| {
+                    let name = Self::value_to_string(&row[0]).unwrap_or_default();
+                    let default_value = Self::value_to_string(&row[3]);
+                    ColumnInfo {
+                        is_primary_key: primary_keys.contains(&name),
+                        // DuckDB 通过序列实现自增
+                        is_auto_increment: default_value
+                            .as_deref()
+                            .map(|d| d.starts_with("nextval("))
+                            .unwrap_or(false),
+                        name,
+                        data_type: Self::value_to_string(&row[1]).unwrap_or_default(),
+                        nullable: matches!(row[2], Value::Boolean(true)),
+                        default_value,
+                        comment: Self::value_to_string(&row[4]),
+                        character_maximum_length: Self::value_to_i64(&row[5]),
+                        numeric_precision: Self::value_to_i64(&row[6]),
+                        numeric_scale: Self::value_to_i64(&row[7]),
+                    }
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_indexes(&self, table: &str, schema: Option<&str>) -> DbResult<Vec<IndexInfo>> {
+        let filter = format!(
+            "{} AND {} AND table_name = '{}'",
+            Self::database_filter(None),
+            Self::schema_filter(schema),
+            escape_sql_literal(table)
+        );
+        let index_sql = format!(
+            "SELECT index_name, is_unique, is_primary, sql FROM duckdb_indexes() WHERE {} ORDER BY index_name",
+            filter
+        );
+        // 主键和唯一约束不会出现在 duckdb_indexes() 中
+        let constraint_sql = format!(
+            "SELECT constraint_type, constraint_column_names FROM duckdb_constraints()
+             WHERE {} AND constraint_type IN ('PRIMARY KEY', 'UNIQUE')",
+            filter
+        );
+
+        self.with_conn(move |conn| {
+            let mut indexes = Vec::new();
+
+            let (_, constraint_rows) = Self::query_values(conn, &constraint_sql)?;
+            for row in &constraint_rows {
+                let is_primary = Self::value_to_string(&row[0]).as_deref() == Some("PRIMARY KEY");
+                let columns = Self::value_to_strings(&row[1]);
+                indexes.push(IndexInfo {
+                    name: if is_primary {
+                        "PRIMARY".to_string()
+                    } else {
+                        format!("UNIQUE ({})", columns.join(", "))
+                    },
+                    columns,
+                    is_unique: true,
+                    is_primary,
+                    index_type: "ART".to_string(),
+                });
+            }
+
+            let (_, index_rows) = Self::query_values(conn, &index_sql)?;
+            for row in &index_rows {
+                indexes.push(IndexInfo {
+                    name: Self::value_to_string(&row[0]).unwrap_or_default(),
+                    columns: Self::value_to_string(&row[3])
+                        .map(|sql| Self::index_columns_from_sql(&sql))
+                        .unwrap_or_default(),
+                    is_unique: matches!(row[1], Value::Boolean(true)),
+                    is_primary: matches!(row[2], Value::Boolean(true)),
+                    index_type: "ART".to_string(),
+                });
+            }
+
+            Ok(indexes)
+        })
+        .await
+    }
+
+    async fn get_table_options(&self, table: &str, schema: Option<&str>) -> DbResult<TableOptions> {
+        let sql = format!(
+            "SELECT comment FROM duckdb_tables() WHERE {} AND {} AND table_name = '{}'",
+            Self::database_filter(None),
+            Self::schema_filter(schema),
+            escape_sql_literal(table)
+        );
+
+        self.with_conn(move |conn| {
+            let (_, rows) = Self::query_values(conn, &sql)?;
+            Ok(TableOptions {
+                engine: None,
+                charset: None,
+                collation: None,
+                comment: rows.first().and_then(|row| Self::value_to_string(&row[0])),
+                auto_increment: None,
+                extra: Default::default(),
+            })
+        })
+        .await
+    }
+
+    async fn ping(&self) -> DbResult<()> {
+        self.with_conn(|conn| {
+            conn.execute_batch("SELECT 1")
+                .map_err(|e| DbError::ConnectionFailed(e.to_string()))
+        })
+        .await
+    }
+
+    async fn server_info(&self) -> DbResult<ServerInfo> {
+        let version = self
+            .with_conn(|conn| {
+                let (_, rows) = Self::query_values(conn, "SELECT version()")?;
+                Ok(rows
+                    .first()
+                    .and_then(|row| Self::value_to_string(&row[0]))
+                    .unwrap_or_default())
+            })
+            .await
+            .map_err(|e| e.context(Msg::ServerInfoFailed))?;
+        let version = version.trim_start_matches('v').to_string();
+
+        Ok(ServerInfo {
+            flavor: ServerFlavor::DuckDB,
+            version_string: format!("DuckDB {}", version),
+            version,
+            current_user: None,
+            time_zone: None,
+            features: ServerFeatures {
+                window_functions: true,
+                cte: true,
+                json: true,
+                returning: true,
+                upsert: true,
+                sequences: true,
+                check_constraints: true,
+                stored_procedures: false,
+                triggers: false,
+                events: false,
+                transactions: true,
+            },
+        })
+    }
+}
+
+/// DuckDB 事务
+///
+/// 使用独立连接，未提交就丢弃时连接关闭，DuckDB 自动回滚。DuckDB 不支持保存点
+struct DuckDbTransaction {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl DuckDbTransaction {
+    /// 在阻塞线程中执行语句
+    async fn run<T, F>(&self, f: F) -> DbResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> DbResult<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|_| DbError::QueryFailed(Msg::ConnectionBroken.into()))?;
+            f(&conn)
+        })
+        .await
+        .map_err(|e| DbError::QueryFailed(e.to_string()))?
+    }
+
+    fn unsupported_savepoint() -> DbError {
+        DbError::database(
+            ErrorCode::Unsupported,
+            Msg::SavepointUnsupported.with(&[&"DuckDB"]),
+            NativeError::default(),
+        )
+    }
+}
+
+#[async_trait]
+impl Transaction for DuckDbTransaction {
+    async fn execute(&mut self, sql: &str) -> DbResult<u64> {
+        let sql = sql.to_string();
+        self.run(move |conn| {
+            conn.execute(&sql, [])
+                .map(|affected| affected as u64)
+                .map_err(|e| DbError::QueryFailed(e.to_string()))
+        })
+        .await
+    }
+
+    async fn savepoint(&mut self, _name: &str) -> DbResult<()> {
+        Err(Self::unsupported_savepoint())
+    }
+
+    async fn rollback_to_savepoint(&mut self, _name: &str) -> DbResult<()> {
+        Err(Self::unsupported_savepoint())
+    }
+
+    async fn release_savepoint(&mut self, _name: &str) -> DbResult<()> {
+        Err(Self::unsupported_savepoint())
+    }
+
+    async fn commit(self: Box<Self>) -> DbResult<()> {
+        self.run(|conn| {
+            conn.execute_batch("COMMIT")
+                .map_err(|e| DbError::QueryFailed(e.to_string()).context(Msg::CommitFailed))
+        })
+        .await
+    }
+
+    async fn rollback(self: Box<Self>) -> DbResult<()> {
+        self.run(|conn| conn.execute_batch("ROLLBACK").map_err(|e| DbError::QueryFailed(e.to_string())))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_config(path: &str) -> ConnectionConfig {
+        serde_json::from_value(serde_json::json!({
+            "id": "duckdb-test",
+            "name": "duckdb-test",
+            "db_type": "duckdb",
+            "host": "",
+            "port": 0,
+            "username": "",
+            "password": "",
+            "database": path,
+            "ssl": false,
+            "connection_timeout": 10,
+            "pool_size": 1,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_file_view_statement_quoting() {
+        assert!(DuckDbDatabase::file_view_statement("/data/app.duckdb").is_none());
+
+        let (view, sql) = DuckDbDatabase::file_view_statement("/data/it's \"q\".CSV").unwrap();
+        assert_eq!(view, "it's \"q\"");
+        assert_eq!(
+            sql,
+            "CREATE OR REPLACE VIEW \"it's \"\"q\"\"\" AS SELECT * FROM read_csv_auto('/data/it''s \"q\".CSV')"
+        );
+
+        let (view, _) = DuckDbDatabase::file_view_statement("/data/*.parquet").unwrap();
+        assert_eq!(view, "data");
+    }
+
+    #[test]
+    fn test_query_csv_through_view() {
+        let path = std::env::temp_dir().join(format!("datasmith_duckdb_{}.csv", std::process::id()));
+        std::fs::write(&path, "id,name\n1,alpha\n2,beta\n").unwrap();
+
+        let conn = DuckDbDatabase::open_connection(&file_config(path.to_str().unwrap())).unwrap();
+        let view = path.file_stem().unwrap().to_str().unwrap();
+        let output =
+            DuckDbDatabase::execute_statement(&conn, &format!("SELECT id, name FROM \"{}\" ORDER BY id", view));
+        std::fs::remove_file(&path).unwrap();
+
+        let output = output.unwrap();
+        assert!(output.is_query);
+        assert_eq!(output.columns, vec!["id", "name"]);
+        assert_eq!(output.rows.len(), 2);
+        assert_eq!(output.rows[1]["name"], serde_json::json!("beta"));
+    }
+
+    #[test]
+    fn test_memory_database_statements() {
+        let conn = DuckDbDatabase::open_connection(&file_config(":memory:")).unwrap();
+
+        let created = DuckDbDatabase::execute_statement(&conn, "CREATE TABLE t (v INTEGER)").unwrap();
+        assert!(!created.is_query);
+        let inserted = DuckDbDatabase::execute_statement(&conn, "INSERT INTO t VALUES (1), (2), (3)").unwrap();
+        assert_eq!(inserted.affected_rows, 3);
+
+        let (columns, values) = DuckDbDatabase::query_values(&conn, "FROM t SELECT sum(v) AS total").unwrap();
+        assert_eq!(columns, vec!["total"]);
+        assert_eq!(DuckDbDatabase::value_to_i64(&values[0][0]), Some(6));
+    }
+}
@@ -0,0 +1,1030 @@
+use super::registry::{DatabaseDriver, DriverCapabilities, DriverRegistration, ExportFormat, ObjectType};
+use super::traits::*;
+use crate::utils::i18n::Msg;
+use async_trait::async_trait;
+use reqwest::{Client, Method};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// SQL API 单次返回的最大行数
+const SQL_FETCH_SIZE: u64 = 1000;
+
+/// Elasticsearch / OpenSearch 连接（基于 REST API）
+///
+/// 索引作为表、别名作为视图展示；查询支持三种写法：
+/// - Kibana 控制台格式：`GET index/_search` 换行后跟 JSON 请求体
+/// - 纯 Query DSL（以 `{` 开头），发送到 `/_search`
+/// - 其他内容按 SQL 处理（Elasticsearch `_sql` / OpenSearch `_plugins/_sql`）
+pub struct ElasticsearchDatabase {
+    client: Option<Client>,
+    base_url: Option<String>,
+    flavor: Option<ServerFlavor>,
+    config: Option<ConnectionConfig>,
+}
+
+/// 控制台格式中的单个请求
+#[derive(Debug, Clone, PartialEq)]
+struct ConsoleRequest {
+    method: Method,
+    path: String,
+    body: Option<String>,
+}
+
+impl ElasticsearchDatabase {
+    pub fn new() -> Self {
+        Self {
+            client: None,
+            base_url: None,
+            flavor: None,
+            config: None,
+        }
+    }
+
+    /// 构建基础 URL
+    fn build_base_url(config: &ConnectionConfig) -> String {
+        let protocol = if config.ssl { "https" } else { "http" };
+        format!("{}://{}:{}", protocol, config.host, config.port)
+    }
+
+    /// 创建 HTTP 客户端
+    fn build_client(config: &ConnectionConfig) -> DbResult<Client> {
+        Client::builder()
+            .connect_timeout(Duration::from_secs(config.connection_timeout))
+            .build()
+            .map_err(|e| DbError::ConnectionFailed(format!("{}: {}", Msg::CreateHttpClientFailed, e)))
+    }
+
+    /// 构建请求，请求体按原样发送
+    ///
+    /// `_bulk` / `_msearch` 的请求体是 NDJSON，不能再按 JSON 序列化
+    fn build_request(
+        client: &Client,
+        base_url: &str,
+        config: &ConnectionConfig,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+    ) -> DbResult<reqwest::Request> {
+        let mut request = client.request(method, format!("{}{}", base_url, path));
+        if !config.username.is_empty() {
+            request = request.basic_auth(&config.username, Some(&config.password));
+        }
+        if let Some(body) = body {
+            let content_type = if Self::is_ndjson_path(path) {
+                "application/x-ndjson"
+            } else {
+                "application/json"
+            };
+            request = request.header("Content-Type", content_type).body(body);
+        }
+        request
+            .build()
+            .map_err(|e| DbError::QueryFailed(format!("{}: {}", Msg::RequestFailed.with(&[&path]), e)))
+    }
+
+    /// 请求体是否为 NDJSON（`_bulk`、`_msearch` 及其模板接口）
+    fn is_ndjson_path(path: &str) -> bool {
+        let endpoint = path.split('?').next().unwrap_or(path);
+        endpoint.split('/').any(|segment| segment == "_bulk" || segment == "_msearch")
+    }
+
+    /// 发送请求并返回响应体（JSON 或纯文本）
+    async fn send(
+        client: &Client,
+        base_url: &str,
+        config: &ConnectionConfig,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+    ) -> DbResult<Value> {
+        let path = if path.starts_with('/') {
+            path.to_string()
+        } else {
+            format!("/{}", path)
+        };
+
+        let request = Self::build_request(client, base_url, config, method, &path, body)?;
+        let response = client.execute(request).await.map_err(|e| {
+            let message = format!("{}: {}", Msg::RequestFailed.with(&[&path]), e);
+            if e.is_timeout() {
+                DbError::Timeout(message)
+            } else {
+                DbError::ConnectionFailed(message)
+            }
+        })?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| DbError::QueryFailed(format!("{}: {}", Msg::ReadResponseFailed, e)))?;
+        let value = serde_json::from_str::<Value>(&text).unwrap_or(Value::String(text));
+
+        if !status.is_success() {
+            return Err(Self::response_error(status.as_u16(), &path, &value));
+        }
+
+        Ok(value)
+    }
+
+    /// 根据 HTTP 状态码和 error.type 构造错误
+    fn response_error(status: u16, path: &str, value: &Value) -> DbError {
+        let reason = value
+            .pointer("/error/reason")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .unwrap_or_else(|| value.to_string());
+        let kind = value
+            .pointer("/error/type")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
+        let code = match (status, kind.as_deref()) {
+            (401, _) => ErrorCode::AuthFailed,
+            (403, _) => ErrorCode::PermissionDenied,
+            (404, _) => ErrorCode::NotFound,
+            (408 | 504, _) => ErrorCode::Timeout,
+            (409, _) => ErrorCode::ConstraintViolation,
+            (400, Some(kind)) if kind.contains("parsing") || kind.contains("x_content") => {
+                ErrorCode::SyntaxError
+            }
+            _ => ErrorCode::QueryFailed,
+        };
+        let native = NativeError {
+            errno: Some(status as i64),
+            kind,
+            ..Default::default()
+        };
+        DbError::database(code, format!("{} {}: {}", status, path, reason), native)
+    }
+
+    /// 使用当前连接发送请求
+    async fn request(&self, method: Method, path: &str, body: Option<Value>) -> DbResult<Value> {
+        self.request_text(method, path, body.map(|b| b.to_string())).await
+    }
+
+    /// 使用当前连接发送请求，请求体是原始文本
+    async fn request_text(&self, method: Method, path: &str, body: Option<String>) -> DbResult<Value> {
+        let (client, base_url, config) = match (&self.client, &self.base_url, &self.config) {
+            (Some(client), Some(base_url), Some(config)) => (client, base_url, config),
+            _ => return Err(DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Elasticsearch"]))),
+        };
+
+        Self::send(client, base_url, config, method, path, body).await
+    }
+
+    /// 根据根路径响应识别产品分支
+    fn detect_flavor(root: &Value) -> ServerFlavor {
+        match root.pointer("/version/distribution").and_then(|v| v.as_str()) {
+            Some("opensearch") => ServerFlavor::OpenSearch,
+            _ => ServerFlavor::Elasticsearch,
+        }
+    }
+
+    /// 获取集群健康状态
+    pub async fn cluster_health(&self) -> DbResult<Value> {
+        self.request(Method::GET, "/_cluster/health", None).await
+    }
+
+    /// 解析控制台格式的请求（`METHOD path` 换行后跟可选请求体）
+    fn parse_console(text: &str) -> DbResult<Vec<ConsoleRequest>> {
+        let mut requests: Vec<ConsoleRequest> = Vec::new();
+        let mut body_lines: Vec<&str> = Vec::new();
+
+        let flush = |requests: &mut Vec<ConsoleRequest>, body_lines: &mut Vec<&str>| {
+            if let Some(last) = requests.last_mut() {
+                let body = body_lines.join("\n");
+                if !body.trim().is_empty() {
+                    last.body = Some(body.trim().to_string());
+                }
+            }
+            body_lines.clear();
+        };
+
+        for line in text.lines() {
+            if let Some((method, path)) = Self::parse_request_line(line) {
+                flush(&mut requests, &mut body_lines);
+                requests.push(ConsoleRequest {
+                    method,
+                    path,
+                    body: None,
+                });
+            } else if requests.is_empty() {
+                let trimmed = line.trim();
+                if !trimmed.is_empty() && !trimmed.starts_with('#') && !trimmed.starts_with("//") {
+                    return Err(DbError::QueryFailed(format!("{}: {}", Msg::UnrecognizedRequestLine, trimmed)));
+                }
+            } else {
+                body_lines.push(line);
+            }
+        }
+        flush(&mut requests, &mut body_lines);
+
+        Ok(requests)
+    }
+
+    /// 解析请求行，例如 `GET logs-*/_search`
+    fn parse_request_line(line: &str) -> Option<(Method, String)> {
+        let mut parts = line.split_whitespace();
+        let method = match parts.next()?.to_uppercase().as_str() {
+            "GET" => Method::GET,
+            "POST" => Method::POST,
+            "PUT" => Method::PUT,
+            "DELETE" => Method::DELETE,
+            "HEAD" => Method::HEAD,
+            _ => return None,
+        };
+        let path = parts.next()?;
+        // 避免把 SQL 的 DELETE FROM 当作请求行
+        if path.eq_ignore_ascii_case("FROM") || parts.next().is_some() {
+            return None;
+        }
+        Some((method, path.to_string()))
+    }
+
+    /// 是否为控制台格式
+    fn is_console_request(text: &str) -> bool {
+        text.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))
+            .map(|line| Self::parse_request_line(line).is_some())
+            .unwrap_or(false)
+    }
+
+    /// 将嵌套对象展开为点分隔的列
+    fn flatten_object(prefix: &str, object: &Map<String, Value>, row: &mut Vec<(String, Value)>) {
+        for (key, value) in object {
+            let name = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                Value::Object(inner) if !inner.is_empty() => Self::flatten_object(&name, inner, row),
+                _ => row.push((name, value.clone())),
+            }
+        }
+    }
+
+    /// 按首次出现顺序收集列并生成结果
+    fn rows_to_result(rows: Vec<Vec<(String, Value)>>, start: Instant) -> QueryResult {
+        let mut columns = Vec::new();
+        let mut seen = HashSet::new();
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                let mut map = HashMap::new();
+                for (key, value) in row {
+                    if seen.insert(key.clone()) {
+                        columns.push(key.clone());
+                    }
+                    map.insert(key, value);
+                }
+                map
+            })
+            .collect();
+
+        QueryResult {
+            columns,
+            rows,
+            affected_rows: 0,
+            execution_time_ms: start.elapsed().as_millis(),
+        }
+    }
+
+    /// 将 _search 响应转换为结果集（每个 hit 一行，_source 展开为列）
+    fn search_to_result(response: &Value, start: Instant) -> QueryResult {
+        let hits = response
+            .pointer("/hits/hits")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        if hits.is_empty() {
+            if let Some(aggs) = response.get("aggregations") {
+                return Self::rows_to_result(vec![vec![("aggregations".to_string(), aggs.clone())]], start);
+            }
+        }
+
+        let rows = hits
+            .iter()
+            .map(|hit| {
+                let mut row = Vec::new();
+                for meta in ["_index", "_id", "_score"] {
+                    row.push((meta.to_string(), hit.get(meta).cloned().unwrap_or(Value::Null)));
+                }
+                if let Some(source) = hit.get("_source").and_then(|v| v.as_object()) {
+                    Self::flatten_object("", source, &mut row);
+                }
+                row
+            })
+            .collect();
+
+        Self::rows_to_result(rows, start)
+    }
+
+    /// 将任意响应转换为结果集
+    fn response_to_result(path: &str, response: &Value, start: Instant) -> QueryResult {
+        if path.contains("_search") && response.get("hits").is_some() {
+            return Self::search_to_result(response, start);
+        }
+
+        let rows = match response {
+            // _cat 等接口的 JSON 数组
+            Value::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    Value::Object(object) => {
+                        let mut row = Vec::new();
+                        Self::flatten_object("", object, &mut row);
+                        row
+                    }
+                    other => vec![("value".to_string(), other.clone())],
+                })
+                .collect(),
+            Value::Object(object) => {
+                let mut row = Vec::new();
+                Self::flatten_object("", object, &mut row);
+                vec![row]
+            }
+            // 纯文本响应（例如未指定 format=json 的 _cat 接口）
+            Value::String(text) => text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| vec![("result".to_string(), Value::String(line.to_string()))])
+                .collect(),
+            other => vec![vec![("result".to_string(), other.clone())]],
+        };
+
+        Self::rows_to_result(rows, start)
+    }
+
+    /// 将 SQL API 响应转换为结果集
+    ///
+    /// Elasticsearch 返回 `columns` + `rows`，OpenSearch (jdbc 格式) 返回 `schema` + `datarows`
+    fn sql_to_result(response: &Value, start: Instant) -> QueryResult {
+        let columns: Vec<String> = response
+            .get("columns")
+            .or_else(|| response.get("schema"))
+            .and_then(|v| v.as_array())
+            .map(|cols| {
+                cols.iter()
+                    .map(|c| {
+                        c.get("alias")
+                            .or_else(|| c.get("name"))
+                            .and_then(|n| n.as_str())
+                            .unwrap_or_default()
+                            .to_string()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let rows = response
+            .get("rows")
+            .or_else(|| response.get("datarows"))
+            .and_then(|v| v.as_array())
+            .map(|rows| {
+                rows.iter()
+                    .filter_map(|row| row.as_array())
+                    .map(|values| {
+                        columns
+                            .iter()
+                            .cloned()
+                            .zip(values.iter().cloned())
+                            .collect::<HashMap<_, _>>()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        QueryResult {
+            columns,
+            rows,
+            affected_rows: 0,
+            execution_time_ms: start.elapsed().as_millis(),
+        }
+    }
+
+    /// 通过 SQL API 执行查询
+    async fn execute_sql(&self, sql: &str, start: Instant) -> DbResult<QueryResult> {
+        let sql = sql.trim().trim_end_matches(';');
+
+        match self.flavor {
+            Some(ServerFlavor::OpenSearch) => {
+                let response = self
+                    .request(
+                        Method::POST,
+                        "/_plugins/_sql?format=jdbc",
+                        Some(json!({ "query": sql })),
+                    )
+                    .await?;
+                Ok(Self::sql_to_result(&response, start))
+            }
+            _ => {
+                let response = self
+                    .request(
+                        Method::POST,
+                        "/_sql?format=json",
+                        Some(json!({ "query": sql, "fetch_size": SQL_FETCH_SIZE })),
+                    )
+                    .await?;
+
+                // 只返回第一页，及时关闭游标释放服务端资源
+                if let Some(cursor) = response.get("cursor").and_then(|c| c.as_str()) {
+                    let _ = self
+                        .request(Method::POST, "/_sql/close", Some(json!({ "cursor": cursor })))
+                        .await;
+                }
+
+                Ok(Self::sql_to_result(&response, start))
+            }
+        }
+    }
+
+    /// 通过 SQL API 逐页读取全部结果，每页的行写入 `sink`
+    ///
+    /// 第一页带列定义；后续页只用游标请求，直到响应中不再有游标
+    async fn stream_sql(&self, sql: &str, sink: &mut dyn RowSink) -> DbResult<u64> {
+        let sql = sql.trim().trim_end_matches(';');
+        let (path, close_path) = match self.flavor {
+            Some(ServerFlavor::OpenSearch) => ("/_plugins/_sql?format=jdbc", "/_plugins/_sql/close"),
+            _ => ("/_sql?format=json", "/_sql/close"),
+        };
+
+        let mut response = self
+            .request(Method::POST, path, Some(json!({ "query": sql, "fetch_size": SQL_FETCH_SIZE })))
+            .await?;
+        let first = Self::sql_to_result(&response, Instant::now());
+        let columns = first.columns.clone();
+        let mut count = match first.write_to(sink) {
+            Ok(count) => count,
+            Err(e) => {
+                self.close_sql_cursor(close_path, &response).await;
+                return Err(e);
+            }
+        };
+
+        while let Some(cursor) = response.get("cursor").and_then(|c| c.as_str()).map(String::from) {
+            let page = self
+                .request(Method::POST, path, Some(json!({ "cursor": cursor })))
+                .await;
+            response = match page {
+                Ok(page) => page,
+                Err(e) => {
+                    self.close_sql_cursor(close_path, &response).await;
+                    return Err(e);
+                }
+            };
+
+            let rows = response
+                .get("rows")
+                .or_else(|| response.get("datarows"))
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+            for row in rows {
+                let mut values = match row {
+                    Value::Array(values) => values,
+                    _ => continue,
+                };
+                values.resize(columns.len(), Value::Null);
+                if let Err(e) = sink.row(values) {
+                    self.close_sql_cursor(close_path, &response).await;
+                    return Err(e);
+                }
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// 中途停止读取时关闭响应中的游标，释放服务端资源
+    async fn close_sql_cursor(&self, close_path: &str, response: &Value) {
+        if let Some(cursor) = response.get("cursor").and_then(|c| c.as_str()) {
+            let _ = self
+                .request(Method::POST, close_path, Some(json!({ "cursor": cursor })))
+                .await;
+        }
+    }
+
+    /// 将映射展开为列信息（对象字段以点分隔，多字段如 `name.keyword` 单独列出）
+    fn mapping_to_columns(properties: &Map<String, Value>, prefix: &str, columns: &mut Vec<ColumnInfo>) {
+        for (name, definition) in properties {
+            let full_name = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", prefix, name)
+            };
+
+            let data_type = definition
+                .get("type")
+                .and_then(|t| t.as_str())
+                .unwrap_or("object")
+                .to_string();
+
+            if let Some(children) = definition.get("properties").and_then(|p| p.as_object()) {
+                // nested 类型本身也作为一列展示
+                if data_type == "nested" {
+                    columns.push(Self::column(&full_name, &data_type));
+                }
+                Self::mapping_to_columns(children, &full_name, columns);
+                continue;
+            }
+
+            columns.push(Self::column(&full_name, &data_type));
+
+            if let Some(fields) = definition.get("fields").and_then(|f| f.as_object()) {
+                for (sub_name, sub_definition) in fields {
+                    let sub_type = sub_definition
+                        .get("type")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or("keyword");
+                    columns.push(Self::column(&format!("{}.{}", full_name, sub_name), sub_type));
+                }
+            }
+        }
+    }
+
+    fn column(name: &str, data_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            is_auto_increment: false,
+            comment: None,
+            character_maximum_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+        }
+    }
+
+    /// 读取 _cat 接口中的数值字段（以字符串返回）
+    fn cat_number(item: &Value, key: &str) -> Option<u64> {
+        item.get(key)
+            .and_then(|v| v.as_str().and_then(|s| s.parse().ok()).or_else(|| v.as_u64()))
+    }
+}
+
+impl Default for ElasticsearchDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Elasticsearch 驱动
+pub struct ElasticsearchDriver;
+
+inventory::submit! {
+    DriverRegistration { factory: || Arc::new(ElasticsearchDriver) }
+}
+
+impl DatabaseDriver for ElasticsearchDriver {
+    fn db_type(&self) -> DatabaseType {
+        DatabaseType::Elasticsearch
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Elasticsearch"
+    }
+
+    fn create(&self) -> DbResult<Box<dyn DatabaseOperations>> {
+        Ok(Box::new(ElasticsearchDatabase::new()))
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            search: true,
+            // 索引作为表、别名作为视图展示
+            object_types: vec![ObjectType::Table, ObjectType::View],
+            export_formats: ExportFormat::for_results(),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl DatabaseOperations for ElasticsearchDatabase {
+    async fn test_connection(&self, config: &ConnectionConfig) -> DbResult<bool> {
+        let client = Self::build_client(config)?;
+        Self::send(&client, &Self::build_base_url(config), config, Method::GET, "/", None)
+            .await
+            .map_err(DbError::connecting)?;
+        Ok(true)
+    }
+
+    async fn connect(&mut self, config: ConnectionConfig) -> DbResult<()> {
+        let client = Self::build_client(&config)?;
+        let base_url = Self::build_base_url(&config);
+
+        let root = Self::send(&client, &base_url, &config, Method::GET, "/", None)
+            .await
+            .map_err(DbError::connecting)?;
+
+        self.flavor = Some(Self::detect_flavor(&root));
+        self.client = Some(client);
+        self.base_url = Some(base_url);
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> DbResult<()> {
+        self.client = None;
+        self.base_url = None;
+        self.flavor = None;
+        self.config = None;
+        Ok(())
+    }
+
+    async fn execute_query(&self, sql: &str, _database: Option<&str>) -> DbResult<QueryResult> {
+        let start = Instant::now();
+        let text = sql.trim();
+
+        if Self::is_console_request(text) {
+            let requests = Self::parse_console(text)?;
+            let mut last = None;
+            for request in requests {
+                // 请求体按原文发送；NDJSON 必须以换行结尾
+                let body = request.body.clone().map(|mut body| {
+                    if Self::is_ndjson_path(&request.path) && !body.ends_with('\n') {
+                        body.push('\n');
+                    }
+                    body
+                });
+                let response = self
+                    .request_text(request.method.clone(), &request.path, body)
+                    .await?;
+                last = Some((request.path, response));
+            }
+
+            return Ok(match last {
+                Some((path, response)) => Self::response_to_result(&path, &response, start),
+                None => Self::rows_to_result(Vec::new(), start),
+            });
+        }
+
+        if text.starts_with('{') {
+            let body: Value = serde_json::from_str(text)
+                .map_err(|e| DbError::QueryFailed(format!("{}: {}", Msg::QueryDslParseFailed, e)))?;
+            let response = self.request(Method::POST, "/_search", Some(body)).await?;
+            return Ok(Self::search_to_result(&response, start));
+        }
+
+        self.execute_sql(text, start).await
+    }
+
+    async fn stream_query(
+        &self,
+        sql: &str,
+        database: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> DbResult<u64> {
+        let text = sql.trim();
+        // 控制台请求和查询 DSL 只返回一个响应，SQL 按游标分页读取
+        if Self::is_console_request(text) || text.starts_with('{') {
+            return self.execute_query(sql, database).await?.write_to(sink);
+        }
+        self.stream_sql(text, sink).await
+    }
+
+    async fn get_databases(&self) -> DbResult<Vec<DatabaseInfo>> {
+        // 集群本身作为唯一的“数据库”
+        let health = self.cluster_health().await?;
+        let name = health
+            .get("cluster_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("default")
+            .to_string();
+
+        Ok(vec![DatabaseInfo {
+            name,
+            charset: None,
+            collation: None,
+        }])
+    }
+
+    async fn get_tables(&self, _database: Option<&str>) -> DbResult<Vec<TableInfo>> {
+        let indices = self
+            .request(
+                Method::GET,
+                "/_cat/indices?format=json&bytes=b&h=index,health,status,docs.count,store.size",
+                None,
+            )
+            .await?;
+
+        let mut tables: Vec<TableInfo> = indices
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| {
+                        let name = item.get("index")?.as_str()?.to_string();
+                        // 隐藏系统索引
+                        if name.starts_with('.') {
+                            return None;
+                        }
+                        let health = item.get("health").and_then(|v| v.as_str()).unwrap_or("");
+                        let status = item.get("status").and_then(|v| v.as_str()).unwrap_or("");
+                        Some(TableInfo {
+                            name,
+                            schema: None,
+                            table_type: "INDEX".to_string(),
+                            engine: None,
+                            rows: Self::cat_number(item, "docs.count"),
+                            size_mb: Self::cat_number(item, "store.size")
+                                .map(|bytes| bytes as f64 / 1024.0 / 1024.0),
+                            comment: Some(format!("{} / {}", health, status)),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tables)
+    }
+
+    async fn get_views(&self, _database: Option<&str>) -> DbResult<Vec<TableInfo>> {
+        // 别名作为视图展示
+        let aliases = self
+            .request(Method::GET, "/_cat/aliases?format=json&h=alias,index", None)
+            .await?;
+
+        let mut views: Vec<TableInfo> = Vec::new();
+        for item in aliases.as_array().cloned().unwrap_or_default() {
+            let (Some(alias), Some(index)) = (
+                item.get("alias").and_then(|v| v.as_str()),
+                item.get("index").and_then(|v| v.as_str()),
+            ) else {
+                continue;
+            };
+            if alias.starts_with('.') {
+                continue;
+            }
+            match views.iter_mut().find(|v| v.name == alias) {
+                Some(view) => {
+                    if let Some(ref mut comment) = view.comment {
+                        comment.push_str(", ");
+                        comment.push_str(index);
+                    }
+                }
+                None => views.push(TableInfo {
+                    name: alias.to_string(),
+                    schema: None,
+                    table_type: "ALIAS".to_string(),
+                    engine: None,
+                    rows: None,
+                    size_mb: None,
+                    comment: Some(index.to_string()),
+                }),
+            }
+        }
+
+        views.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(views)
+    }
+
+    async fn get_table_structure(
+        &self,
+        table: &str,
+        _schema: Option<&str>,
+        _database: Option<&str>,
+    ) -> DbResult<Vec<ColumnInfo>> {
+        let mapping = self
+            .request(Method::GET, &format!("/{}/_mapping", table), None)
+            .await?;
+
+        let mut columns = vec![ColumnInfo {
+            is_primary_key: true,
+            nullable: false,
+            ..Self::column("_id", "keyword")
+        }];
+
+        // 别名或通配符可能对应多个索引，按字段名去重合并
+        let mut seen = HashSet::new();
+        for index_mapping in mapping.as_object().map(|m| m.values()).into_iter().flatten() {
+            if let Some(properties) = index_mapping
+                .pointer("/mappings/properties")
+                .and_then(|p| p.as_object())
+            {
+                let mut index_columns = Vec::new();
+                Self::mapping_to_columns(properties, "", &mut index_columns);
+                for column in index_columns {
+                    if seen.insert(column.name.clone()) {
+                        columns.push(column);
+                    }
+                }
+            }
+        }
+
+        Ok(columns)
+    }
+
+    async fn get_indexes(&self, _table: &str, _schema: Option<&str>) -> DbResult<Vec<IndexInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_table_options(&self, table: &str, _schema: Option<&str>) -> DbResult<TableOptions> {
+        let settings = self
+            .request(Method::GET, &format!("/{}/_settings", table), None)
+            .await?;
+
+        let index_settings = settings
+            .as_object()
+            .and_then(|m| m.values().next())
+            .and_then(|v| v.pointer("/settings/index"));
+
+        let setting = |key: &str| {
+            index_settings
+                .and_then(|s| s.get(key))
+                .and_then(|v| v.as_str())
+                .unwrap_or("-")
+                .to_string()
+        };
+
+        Ok(TableOptions {
+            engine: None,
+            charset: None,
+            collation: None,
+            comment: Some(Msg::ShardsReplicas.with(&[
+                &setting("number_of_shards"),
+                &setting("number_of_replicas"),
+            ])),
+            auto_increment: None,
+            extra: Default::default(),
+        })
+    }
+
+    async fn ping(&self) -> DbResult<()> {
+        self.request(Method::GET, "/", None).await.map(|_| ())
+    }
+
+    async fn server_info(&self) -> DbResult<ServerInfo> {
+        let root = self.request(Method::GET, "/", None).await?;
+        let flavor = Self::detect_flavor(&root);
+        let version = root
+            .pointer("/version/number")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        // 当前用户需要安全插件，未启用时忽略
+        let current_user = match flavor {
+            ServerFlavor::OpenSearch => self
+                .request(Method::GET, "/_plugins/_security/authinfo", None)
+                .await
+                .ok()
+                .and_then(|v| v.get("user_name").and_then(|u| u.as_str()).map(String::from)),
+            _ => self
+                .request(Method::GET, "/_security/_authenticate", None)
+                .await
+                .ok()
+                .and_then(|v| v.get("username").and_then(|u| u.as_str()).map(String::from)),
+        };
+
+        let name = match flavor {
+            ServerFlavor::OpenSearch => "OpenSearch",
+            _ => "Elasticsearch",
+        };
+
+        Ok(ServerInfo {
+            flavor,
+            version_string: format!("{} {}", name, version),
+            version,
+            current_user,
+            time_zone: None,
+            features: ServerFeatures::default(),
+        })
+    }
+
+    fn as_search(&self) -> Option<&dyn SearchOperations> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl SearchOperations for ElasticsearchDatabase {
+    async fn cluster_health(&self) -> DbResult<Value> {
+        ElasticsearchDatabase::cluster_health(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_console_requests() {
+        let text = "# 查询\nGET logs/_search\n{\n  \"query\": { \"match_all\": {} }\n}\n\nGET _cat/indices?format=json\n";
+        let requests = ElasticsearchDatabase::parse_console(text).unwrap();
+
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].path, "logs/_search");
+        assert!(requests[0].body.as_deref().unwrap().contains("match_all"));
+        assert_eq!(requests[1].path, "_cat/indices?format=json");
+        assert!(requests[1].body.is_none());
+    }
+
+    #[test]
+    fn test_bulk_request_body() {
+        let text = "POST _bulk\n{\"index\":{\"_index\":\"logs\"}}\n{\"message\":\"a\"}\n";
+        let requests = ElasticsearchDatabase::parse_console(text).unwrap();
+        let body = format!("{}\n", requests[0].body.as_deref().unwrap());
+        assert_eq!(body, "{\"index\":{\"_index\":\"logs\"}}\n{\"message\":\"a\"}\n");
+
+        let config: ConnectionConfig = serde_json::from_value(json!({
+            "id": "es", "name": "es", "db_type": "elasticsearch", "host": "localhost", "port": 9200,
+            "username": "", "password": "", "database": null, "ssl": false,
+            "connection_timeout": 10, "pool_size": 1
+        }))
+        .unwrap();
+        let request = ElasticsearchDatabase::build_request(
+            &Client::new(),
+            "http://localhost:9200",
+            &config,
+            Method::POST,
+            "/_bulk",
+            Some(body.clone()),
+        )
+        .unwrap();
+
+        assert_eq!(request.body().and_then(|b| b.as_bytes()), Some(body.as_bytes()));
+        assert_eq!(request.headers()["Content-Type"], "application/x-ndjson");
+        assert!(ElasticsearchDatabase::is_ndjson_path("/logs/_msearch?pretty"));
+        assert!(!ElasticsearchDatabase::is_ndjson_path("/logs/_search"));
+    }
+
+    #[test]
+    fn test_sql_is_not_console_request() {
+        assert!(!ElasticsearchDatabase::is_console_request("SELECT * FROM logs"));
+        assert!(!ElasticsearchDatabase::is_console_request("DELETE FROM logs"));
+        assert!(ElasticsearchDatabase::is_console_request("DELETE /logs"));
+    }
+
+    #[test]
+    fn test_mapping_to_columns() {
+        let properties = json!({
+            "title": { "type": "text", "fields": { "keyword": { "type": "keyword" } } },
+            "user": { "properties": { "name": { "type": "keyword" } } },
+            "tags": { "type": "nested", "properties": { "value": { "type": "keyword" } } }
+        });
+
+        let mut columns = Vec::new();
+        ElasticsearchDatabase::mapping_to_columns(properties.as_object().unwrap(), "", &mut columns);
+        let names: Vec<(&str, &str)> = columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.data_type.as_str()))
+            .collect();
+
+        assert!(names.contains(&("title", "text")));
+        assert!(names.contains(&("title.keyword", "keyword")));
+        assert!(names.contains(&("user.name", "keyword")));
+        assert!(names.contains(&("tags", "nested")));
+        assert!(names.contains(&("tags.value", "keyword")));
+    }
+
+    #[test]
+    fn test_search_and_sql_results() {
+        let start = Instant::now();
+        let search = json!({
+            "hits": { "hits": [
+                { "_index": "logs", "_id": "1", "_score": 1.0, "_source": { "level": "info", "http": { "status": 200 } } }
+            ] }
+        });
+        let result = ElasticsearchDatabase::search_to_result(&search, start);
+        assert_eq!(&result.columns[..3], &["_index", "_id", "_score"]);
+        assert!(result.columns.contains(&"http.status".to_string()));
+        assert!(result.columns.contains(&"level".to_string()));
+        assert_eq!(result.rows[0]["http.status"], json!(200));
+
+        let sql = json!({
+            "columns": [{ "name": "level", "type": "keyword" }, { "name": "count", "type": "long" }],
+            "rows": [["info", 3], ["error", 1]]
+        });
+        let result = ElasticsearchDatabase::sql_to_result(&sql, start);
+        assert_eq!(result.columns, vec!["level", "count"]);
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[1]["count"], json!(1));
+    }
+
+    #[test]
+    fn test_response_error() {
+        let body = json!({"error": {"type": "index_not_found_exception", "reason": "no such index [logs]"}});
+        let err = ElasticsearchDatabase::response_error(404, "/logs/_search", &body);
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        assert_eq!(
+            err.native_error().and_then(|n| n.kind.as_deref()),
+            Some("index_not_found_exception")
+        );
+
+        let body = json!({"error": {"type": "parsing_exception", "reason": "unknown query [mach]"}});
+        let err = ElasticsearchDatabase::response_error(400, "/logs/_search", &body);
+        assert_eq!(err.code(), ErrorCode::SyntaxError);
+    }
+}
@@ -0,0 +1,345 @@
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::i18n::Msg;
+
+/// 数据库操作结果
+pub type DbResult<T> = Result<T, DbError>;
+
+/// 稳定的错误码（前端据此区分错误类型，不依赖错误消息文本）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    ConnectionFailed,
+    ConnectionLost,
+    AuthFailed,
+    Timeout,
+    SyntaxError,
+    ConstraintViolation,
+    PermissionDenied,
+    NotFound,
+    QueryFailed,
+    Unsupported,
+    ConfigError,
+    Other,
+}
+
+impl ErrorCode {
+    /// 错误类型的显示名称（按当前语言）
+    pub fn label(&self) -> &'static str {
+        let msg = match self {
+            ErrorCode::ConnectionFailed => Msg::CodeConnectionFailed,
+            ErrorCode::ConnectionLost => Msg::CodeConnectionLost,
+            ErrorCode::AuthFailed => Msg::CodeAuthFailed,
+            ErrorCode::Timeout => Msg::CodeTimeout,
+            ErrorCode::SyntaxError => Msg::CodeSyntaxError,
+            ErrorCode::ConstraintViolation => Msg::CodeConstraintViolation,
+            ErrorCode::PermissionDenied => Msg::CodePermissionDenied,
+            ErrorCode::NotFound => Msg::CodeNotFound,
+            ErrorCode::QueryFailed => Msg::CodeQueryFailed,
+            ErrorCode::Unsupported => Msg::CodeUnsupported,
+            ErrorCode::ConfigError => Msg::CodeConfigError,
+            ErrorCode::Other => Msg::CodeOther,
+        };
+        msg.text()
+    }
+}
+
+/// 数据库原生错误信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NativeError {
+    /// SQLSTATE（PostgreSQL、MySQL 等）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sqlstate: Option<String>,
+    /// 数值错误码（MySQL errno、SQL Server 错误号、ClickHouse/MongoDB 错误码、HTTP 状态码）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errno: Option<i64>,
+    /// 错误类别名称（Redis 错误前缀、Elasticsearch error.type 等）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+impl NativeError {
+    pub fn is_empty(&self) -> bool {
+        self.sqlstate.is_none() && self.errno.is_none() && self.kind.is_none()
+    }
+}
+
+/// 数据库错误
+#[derive(Debug, thiserror::Error)]
+pub enum DbError {
+    #[error("{label}: {0}", label = ErrorCode::ConnectionFailed.label())]
+    ConnectionFailed(String),
+
+    #[error("{label}: {0}", label = ErrorCode::QueryFailed.label())]
+    QueryFailed(String),
+
+    #[error("{}", Msg::UnsupportedDatabase)]
+    UnsupportedDatabase,
+
+    #[error("{label}: {0}", label = ErrorCode::ConfigError.label())]
+    ConfigError(String),
+
+    #[error("{0}")]
+    Other(String),
+
+    #[error("{label}: {0}", label = ErrorCode::Timeout.label())]
+    Timeout(String),
+
+    #[error("{label}: {0}", label = ErrorCode::ConnectionLost.label())]
+    ConnectionLost(String),
+
+    /// 数据库返回的错误，附带原生错误码
+    #[error("{}: {message}", code.label())]
+    Database {
+        code: ErrorCode,
+        message: String,
+        native: NativeError,
+    },
+}
+
+impl DbError {
+    /// 数据库返回的错误
+    pub fn database(code: ErrorCode, message: impl Into<String>, native: NativeError) -> Self {
+        DbError::Database {
+            code,
+            message: message.into(),
+            native,
+        }
+    }
+
+    /// 机器可读的错误码
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            DbError::ConnectionFailed(_) => ErrorCode::ConnectionFailed,
+            DbError::QueryFailed(_) => ErrorCode::QueryFailed,
+            DbError::UnsupportedDatabase => ErrorCode::Unsupported,
+            DbError::ConfigError(_) => ErrorCode::ConfigError,
+            DbError::Other(_) => ErrorCode::Other,
+            DbError::Timeout(_) => ErrorCode::Timeout,
+            DbError::ConnectionLost(_) => ErrorCode::ConnectionLost,
+            DbError::Database { code, .. } => *code,
+        }
+    }
+
+    /// 原生错误信息
+    pub fn native_error(&self) -> Option<&NativeError> {
+        match self {
+            DbError::Database { native, .. } if !native.is_empty() => Some(native),
+            _ => None,
+        }
+    }
+
+    /// 在错误消息前加上上下文（保留错误码和原生错误信息）
+    pub fn context(self, prefix: impl std::fmt::Display) -> Self {
+        let wrap = |message: String| format!("{}: {}", prefix, message);
+        match self {
+            DbError::ConnectionFailed(m) => DbError::ConnectionFailed(wrap(m)),
+            DbError::QueryFailed(m) => DbError::QueryFailed(wrap(m)),
+            DbError::ConfigError(m) => DbError::ConfigError(wrap(m)),
+            DbError::Other(m) => DbError::Other(wrap(m)),
+            DbError::Timeout(m) => DbError::Timeout(wrap(m)),
+            DbError::ConnectionLost(m) => DbError::ConnectionLost(wrap(m)),
+            DbError::Database { code, message, native } => DbError::Database {
+                code,
+                message: wrap(message),
+                native,
+            },
+            other => other,
+        }
+    }
+
+    /// 建立连接阶段的错误：未归类的查询错误改为连接失败
+    pub fn connecting(self) -> Self {
+        match self {
+            DbError::QueryFailed(m) => DbError::ConnectionFailed(m),
+            DbError::ConnectionLost(m) => DbError::ConnectionFailed(m),
+            DbError::Database {
+                code: ErrorCode::QueryFailed | ErrorCode::ConnectionLost,
+                message,
+                native,
+            } => DbError::Database {
+                code: ErrorCode::ConnectionFailed,
+                message,
+                native,
+            },
+            other => other,
+        }
+    }
+}
+
+/// 序列化为 { code, message, native } 对象
+impl Serialize for DbError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let native = self.native_error();
+        let mut state = serializer.serialize_struct("DbError", 2 + native.is_some() as usize)?;
+        state.serialize_field("code", &self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        if let Some(native) = native {
+            state.serialize_field("native", native)?;
+        }
+        state.end()
+    }
+}
+
+impl From<Msg> for DbError {
+    fn from(msg: Msg) -> Self {
+        DbError::Other(msg.into())
+    }
+}
+
+/// 命令层的校验错误（连接不存在、参数不合法等）
+impl From<String> for DbError {
+    fn from(message: String) -> Self {
+        DbError::Other(message)
+    }
+}
+
+impl From<&str> for DbError {
+    fn from(message: &str) -> Self {
+        DbError::from(message.to_string())
+    }
+}
+
+/// 根据 SQLSTATE 推断错误码
+pub fn classify_sqlstate(sqlstate: &str) -> Option<ErrorCode> {
+    let code = match sqlstate {
+        "42601" | "42000" | "42P02" => ErrorCode::SyntaxError,
+        "42501" => ErrorCode::PermissionDenied,
+        "42P01" | "42703" | "42883" | "3D000" | "3F000" | "42S02" | "42S22" => ErrorCode::NotFound,
+        "57014" | "HYT00" | "HYT01" | "55P03" => ErrorCode::Timeout,
+        "57P01" | "57P02" | "57P03" => ErrorCode::ConnectionLost,
+        s if s.starts_with("28") => ErrorCode::AuthFailed,
+        s if s.starts_with("23") => ErrorCode::ConstraintViolation,
+        s if s.starts_with("08") => ErrorCode::ConnectionLost,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// 根据 MySQL / MariaDB / TiDB 错误号推断错误码
+pub fn classify_mysql_errno(errno: i64) -> Option<ErrorCode> {
+    let code = match errno {
+        1045 | 1698 | 1251 => ErrorCode::AuthFailed,
+        1064 | 1149 => ErrorCode::SyntaxError,
+        1044 | 1142 | 1143 | 1227 | 1370 => ErrorCode::PermissionDenied,
+        1049 | 1051 | 1054 | 1146 | 1305 => ErrorCode::NotFound,
+        1062 | 1048 | 1216 | 1217 | 1451 | 1452 | 1557 | 1586 | 3819 => {
+            ErrorCode::ConstraintViolation
+        }
+        1205 | 3024 | 1317 => ErrorCode::Timeout,
+        2006 | 2013 | 2055 => ErrorCode::ConnectionLost,
+        2002 | 2003 | 2005 => ErrorCode::ConnectionFailed,
+        _ => return None,
+    };
+    Some(code)
+}
+
+#[cfg(feature = "sqlx")]
+impl From<sqlx::Error> for DbError {
+    fn from(e: sqlx::Error) -> Self {
+        match e {
+            sqlx::Error::Database(db_err) => {
+                use sqlx::error::ErrorKind;
+
+                let mut native = NativeError {
+                    sqlstate: db_err.code().map(|c| c.into_owned()),
+                    ..Default::default()
+                };
+                let mut code = None;
+
+                // MySQL 多个错误共用 SQLSTATE 42000，错误号更具体，优先使用
+                #[cfg(feature = "mysql")]
+                if let Some(mysql_err) = db_err.try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>() {
+                    native.errno = Some(mysql_err.number() as i64);
+                    code = classify_mysql_errno(mysql_err.number() as i64);
+                }
+                // SQLite 的 code 是扩展结果码而不是 SQLSTATE
+                #[cfg(feature = "sqlite")]
+                if db_err.try_downcast_ref::<sqlx::sqlite::SqliteError>().is_some() {
+                    native.errno = native.sqlstate.take().and_then(|c| c.parse().ok());
+                }
+
+                let code = code
+                    .or_else(|| match db_err.kind() {
+                        ErrorKind::UniqueViolation
+                        | ErrorKind::ForeignKeyViolation
+                        | ErrorKind::NotNullViolation
+                        | ErrorKind::CheckViolation => Some(ErrorCode::ConstraintViolation),
+                        _ => None,
+                    })
+                    .or_else(|| native.sqlstate.as_deref().and_then(classify_sqlstate))
+                    .unwrap_or(ErrorCode::QueryFailed);
+
+                DbError::database(code, db_err.message(), native)
+            }
+            sqlx::Error::PoolTimedOut => DbError::Timeout(Msg::PoolTimeout.into()),
+            sqlx::Error::PoolClosed => DbError::ConnectionLost(Msg::PoolClosed.into()),
+            e @ (sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::WorkerCrashed) => {
+                DbError::ConnectionLost(e.to_string())
+            }
+            e @ sqlx::Error::Configuration(_) => DbError::ConfigError(e.to_string()),
+            e => DbError::QueryFailed(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_sqlstate() {
+        assert_eq!(classify_sqlstate("28P01"), Some(ErrorCode::AuthFailed));
+        assert_eq!(classify_sqlstate("23505"), Some(ErrorCode::ConstraintViolation));
+        assert_eq!(classify_sqlstate("42601"), Some(ErrorCode::SyntaxError));
+        assert_eq!(classify_sqlstate("42P01"), Some(ErrorCode::NotFound));
+        assert_eq!(classify_sqlstate("57014"), Some(ErrorCode::Timeout));
+        assert_eq!(classify_sqlstate("08006"), Some(ErrorCode::ConnectionLost));
+        assert_eq!(classify_sqlstate("XX000"), None);
+    }
+
+    #[test]
+    fn test_classify_mysql_errno() {
+        assert_eq!(classify_mysql_errno(1045), Some(ErrorCode::AuthFailed));
+        assert_eq!(classify_mysql_errno(1064), Some(ErrorCode::SyntaxError));
+        assert_eq!(classify_mysql_errno(1142), Some(ErrorCode::PermissionDenied));
+        assert_eq!(classify_mysql_errno(1146), Some(ErrorCode::NotFound));
+        assert_eq!(classify_mysql_errno(1062), Some(ErrorCode::ConstraintViolation));
+        assert_eq!(classify_mysql_errno(2013), Some(ErrorCode::ConnectionLost));
+        assert_eq!(classify_mysql_errno(1), None);
+    }
+
+    #[test]
+    fn test_serialize_as_object() {
+        let err = DbError::database(
+            ErrorCode::ConstraintViolation,
+            "Duplicate entry '1' for key 'PRIMARY'",
+            NativeError {
+                sqlstate: Some("23000".to_string()),
+                errno: Some(1062),
+                kind: None,
+            },
+        )
+        .context(Msg::StatementFailed.with(&[&2]));
+
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "constraint_violation");
+        let expected = format!(
+            "{}: {}: Duplicate entry '1' for key 'PRIMARY'",
+            Msg::CodeConstraintViolation,
+            Msg::StatementFailed.with(&[&2])
+        );
+        assert_eq!(json["message"], expected);
+        assert_eq!(json["native"]["errno"], 1062);
+        assert!(json["native"].get("kind").is_none());
+
+        let json = serde_json::to_value(DbError::from(Msg::ConnectionNotFound)).unwrap();
+        assert_eq!(json["code"], "other");
+        assert_eq!(json["message"], String::from(Msg::ConnectionNotFound));
+        assert!(json.get("native").is_none());
+    }
+}
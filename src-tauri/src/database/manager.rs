@@ -1,26 +1,127 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+use super::registry::{DatabaseDriver, DriverCapabilities, DriverRegistry};
 use super::traits::*;
-#[cfg(feature = "mysql")]
-use super::mysql::MySqlDatabase;
+use crate::utils::i18n::Msg;
+use crate::models::{ConnectionHealth, ConnectionStatus};
+
+/// 健康检查间隔（秒）
+pub const HEALTH_CHECK_INTERVAL_SECS: u64 = 10;
+/// 健康检查 ping 超时
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+/// 重连退避的初始间隔
+const RECONNECT_BACKOFF_BASE: Duration = Duration::from_secs(5);
+/// 重连退避的最大间隔
+const RECONNECT_BACKOFF_MAX: Duration = Duration::from_secs(300);
+
+/// 单个连接的健康状态
+#[derive(Debug, Clone)]
+struct HealthState {
+    status: ConnectionStatus,
+    error: Option<(String, ErrorCode)>,
+    retry_attempts: u32,
+    next_retry: Option<Instant>,
+    /// 连接可用但读取服务器信息失败时的错误
+    server_info_error: Option<String>,
+}
+
+impl HealthState {
+    fn connected() -> Self {
+        Self {
+            status: ConnectionStatus::Connected,
+            error: None,
+            retry_attempts: 0,
+            next_retry: None,
+            server_info_error: None,
+        }
+    }
+
+    /// 已连接的状态，并记录读取服务器信息时的错误
+    fn connected_with(server_info: &DbResult<ServerInfo>) -> Self {
+        Self {
+            server_info_error: server_info.as_ref().err().map(|e| e.to_string()),
+            ..Self::connected()
+        }
+    }
+
+    fn to_report(&self, connection_id: &str) -> ConnectionHealth {
+        ConnectionHealth {
+            connection_id: connection_id.to_string(),
+            status: self.status.clone(),
+            error: self.error.as_ref().map(|(message, _)| message.clone()),
+            error_code: self.error.as_ref().map(|(_, code)| *code),
+            retry_attempts: self.retry_attempts,
+            next_retry_secs: self
+                .next_retry
+                .map(|t| t.saturating_duration_since(Instant::now()).as_secs()),
+            server_info_error: self.server_info_error.clone(),
+        }
+    }
+}
+
+/// 第 n 次重连失败后的等待时间（指数退避）
+fn reconnect_backoff(attempts: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+    RECONNECT_BACKOFF_BASE
+        .saturating_mul(factor)
+        .min(RECONNECT_BACKOFF_MAX)
+}
 
 /// 数据库连接管理器
+///
+/// 内部状态均由 Arc 共享，克隆后可在后台任务中使用（例如健康检查）
+#[derive(Clone)]
 pub struct ConnectionManager {
-    connections: Arc<RwLock<HashMap<String, Box<dyn DatabaseOperations>>>>,
+    // 已注册的数据库驱动
+    registry: Arc<DriverRegistry>,
+    // 已建立的连接；操作前克隆出连接再释放锁，长时间的查询不会阻塞连接的建立和断开
+    connections: Arc<RwLock<HashMap<String, Arc<dyn DatabaseOperations>>>>,
     // 存储连接ID到数据库类型的映射
     connection_types: Arc<RwLock<HashMap<String, DatabaseType>>>,
+    // 连接建立时读取的服务器信息
+    server_infos: Arc<RwLock<HashMap<String, ServerInfo>>>,
+    // 连接配置（用于自动重连）
+    configs: Arc<RwLock<HashMap<String, ConnectionConfig>>>,
+    // 连接健康状态
+    health: Arc<RwLock<HashMap<String, HealthState>>>,
 }
 
 impl ConnectionManager {
     pub fn new() -> Self {
+        Self::with_registry(DriverRegistry::builtin())
+    }
+
+    /// 使用指定的驱动注册表创建连接管理器
+    pub fn with_registry(registry: Arc<DriverRegistry>) -> Self {
         Self {
+            registry,
             connections: Arc::new(RwLock::new(HashMap::new())),
             connection_types: Arc::new(RwLock::new(HashMap::new())),
+            server_infos: Arc::new(RwLock::new(HashMap::new())),
+            configs: Arc::new(RwLock::new(HashMap::new())),
+            health: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// 根据数据库类型创建驱动实例
+    fn new_database(&self, db_type: &DatabaseType) -> DbResult<Box<dyn DatabaseOperations>> {
+        self.registry.get(db_type)?.create()
+    }
+
+    /// 获取数据库类型对应的驱动
+    pub fn driver(&self, db_type: &DatabaseType) -> DbResult<Arc<dyn DatabaseDriver>> {
+        self.registry.get(db_type)
+    }
+
+    /// 获取连接使用的驱动
+    pub async fn get_driver(&self, connection_id: &str) -> DbResult<Arc<dyn DatabaseDriver>> {
+        let db_type = self.get_database_type(connection_id).await?;
+        self.driver(&db_type)
+    }
+
     /// 创建数据库实例
     pub async fn create_connection(
         &self,
@@ -28,105 +129,84 @@ impl ConnectionManager {
     ) -> DbResult<String> {
         let connection_id = config.id.clone();
         
-        let mut db: Box<dyn DatabaseOperations> = match config.db_type {
-            #[cfg(feature = "mysql")]
-            DatabaseType::MySQL => Box::new(MySqlDatabase::new()),
-            
-            #[cfg(feature = "postgresql")]
-            DatabaseType::PostgreSQL => {
-                Box::new(super::postgresql::PostgreSqlDatabase::new())
-            }
-            
-            #[cfg(feature = "sqlite")]
-            DatabaseType::SQLite => {
-                Box::new(super::sqlite::SqliteDatabase::new())
-            }
-            
-            #[cfg(feature = "mongodb-support")]
-            DatabaseType::MongoDB => {
-                Box::new(super::mongodb::MongoDatabase::new())
-            }
-            
-            #[cfg(feature = "redis-support")]
-            DatabaseType::Redis => {
-                Box::new(super::redis::RedisDatabase::new())
-            }
-            
-            _ => return Err(DbError::UnsupportedDatabase),
-        };
+        let mut db = self.new_database(&config.db_type)?;
 
         // 保存数据库类型
         let db_type = config.db_type.clone();
         
         // 连接数据库
-        db.connect(config).await?;
+        db.connect(config.clone()).await?;
+
+        // 读取服务器信息（失败不影响连接，错误记录在健康状态中）
+        let server_info = db.server_info().await;
+        let health_state = HealthState::connected_with(&server_info);
+        if let Ok(info) = server_info {
+            let mut server_infos = self.server_infos.write().await;
+            server_infos.insert(connection_id.clone(), info);
+        }
 
         // 存储连接
         let mut connections = self.connections.write().await;
-        connections.insert(connection_id.clone(), db);
-        
+        connections.insert(connection_id.clone(), Arc::from(db));
+        drop(connections);
+
         // 存储数据库类型
         let mut connection_types = self.connection_types.write().await;
         connection_types.insert(connection_id.clone(), db_type);
 
+        // 保存配置和健康状态，供健康检查与自动重连使用
+        let mut configs = self.configs.write().await;
+        configs.insert(connection_id.clone(), config);
+        let mut health = self.health.write().await;
+        health.insert(connection_id.clone(), health_state);
+
         Ok(connection_id)
     }
 
     /// 获取连接
-    pub async fn get_connection(
-        &self,
-        connection_id: &str,
-    ) -> DbResult<Arc<RwLock<HashMap<String, Box<dyn DatabaseOperations>>>>> {
-        let connections = self.connections.read().await;
-        if connections.contains_key(connection_id) {
-            Ok(self.connections.clone())
-        } else {
-            Err(DbError::ConnectionFailed("连接不存在".to_string()))
-        }
+    ///
+    /// 返回连接的共享引用，调用期间不持有连接表的锁
+    pub async fn get_connection(&self, connection_id: &str) -> DbResult<Arc<dyn DatabaseOperations>> {
+        self.connections
+            .read()
+            .await
+            .get(connection_id)
+            .cloned()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::ConnectionNotFound.into()))
     }
 
     /// 测试连接
-    pub async fn test_connection(&self, config: &ConnectionConfig) -> DbResult<bool> {
-        let db: Box<dyn DatabaseOperations> = match config.db_type {
-            #[cfg(feature = "mysql")]
-            DatabaseType::MySQL => Box::new(MySqlDatabase::new()),
-            
-            #[cfg(feature = "postgresql")]
-            DatabaseType::PostgreSQL => {
-                Box::new(super::postgresql::PostgreSqlDatabase::new())
-            }
-            
-            #[cfg(feature = "sqlite")]
-            DatabaseType::SQLite => {
-                Box::new(super::sqlite::SqliteDatabase::new())
-            }
-            
-            #[cfg(feature = "mongodb-support")]
-            DatabaseType::MongoDB => {
-                Box::new(super::mongodb::MongoDatabase::new())
-            }
-            
-            #[cfg(feature = "redis-support")]
-            DatabaseType::Redis => {
-                Box::new(super::redis::RedisDatabase::new())
-            }
-            
-            _ => return Err(DbError::UnsupportedDatabase),
-        };
+    ///
+    /// 连接成功即视为测试通过；读取服务器信息失败不影响结果，以内层错误返回给调用方作为提示
+    pub async fn test_connection(&self, config: &ConnectionConfig) -> DbResult<DbResult<ServerInfo>> {
+        let mut db = self.new_database(&config.db_type)?;
+
+        // 建立临时连接并读取服务器信息
+        db.connect(config.clone()).await?;
+        let info = db.server_info().await;
+        db.disconnect().await?;
 
-        db.test_connection(config).await
+        Ok(info)
     }
 
     /// 断开连接
     pub async fn disconnect(&self, connection_id: &str) -> DbResult<()> {
-        let mut connections = self.connections.write().await;
-        if let Some(mut db) = connections.remove(connection_id) {
-            db.disconnect().await?;
+        let removed = self.connections.write().await.remove(connection_id);
+        if let Some(db) = removed {
+            close_connection(db).await?;
         }
-        
+
         // 同时删除数据库类型映射
         let mut connection_types = self.connection_types.write().await;
         connection_types.remove(connection_id);
+
+        let mut server_infos = self.server_infos.write().await;
+        server_infos.remove(connection_id);
+
+        let mut configs = self.configs.write().await;
+        configs.remove(connection_id);
+        let mut health = self.health.write().await;
+        health.remove(connection_id);
         
         Ok(())
     }
@@ -138,21 +218,35 @@ impl ConnectionManager {
         sql: &str,
         database: Option<&str>,
     ) -> DbResult<QueryResult> {
-        let connections = self.connections.read().await;
-        let db = connections
-            .get(connection_id)
-            .ok_or_else(|| DbError::ConnectionFailed("连接不存在".to_string()))?;
-        
+        let db = self.get_connection(connection_id).await?;
         db.execute_query(sql, database).await
     }
 
+    /// 流式执行单条查询，逐行写入 sink
+    pub async fn stream_query(
+        &self,
+        connection_id: &str,
+        sql: &str,
+        database: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> DbResult<u64> {
+        let db = self.get_connection(connection_id).await?;
+        db.stream_query(sql, database, sink).await
+    }
+
+    /// 在连接上开始事务
+    pub async fn begin_transaction(
+        &self,
+        connection_id: &str,
+        database: Option<&str>,
+    ) -> DbResult<Box<dyn Transaction>> {
+        let db = self.get_connection(connection_id).await?;
+        db.begin_transaction(database).await
+    }
+
     /// 获取数据库列表
     pub async fn get_databases(&self, connection_id: &str) -> DbResult<Vec<DatabaseInfo>> {
-        let connections = self.connections.read().await;
-        let db = connections
-            .get(connection_id)
-            .ok_or_else(|| DbError::ConnectionFailed("连接不存在".to_string()))?;
-        
+        let db = self.get_connection(connection_id).await?;
         db.get_databases().await
     }
 
@@ -162,11 +256,7 @@ impl ConnectionManager {
         connection_id: &str,
         database: Option<&str>,
     ) -> DbResult<Vec<TableInfo>> {
-        let connections = self.connections.read().await;
-        let db = connections
-            .get(connection_id)
-            .ok_or_else(|| DbError::ConnectionFailed("连接不存在".to_string()))?;
-        
+        let db = self.get_connection(connection_id).await?;
         // PostgreSQL 的 get_tables 方法内部会处理数据库切换
         db.get_tables(database).await
     }
@@ -177,11 +267,7 @@ impl ConnectionManager {
         connection_id: &str,
         database: Option<&str>,
     ) -> DbResult<Vec<TableInfo>> {
-        let connections = self.connections.read().await;
-        let db = connections
-            .get(connection_id)
-            .ok_or_else(|| DbError::ConnectionFailed("连接不存在".to_string()))?;
-        
+        let db = self.get_connection(connection_id).await?;
         db.get_views(database).await
     }
 
@@ -193,11 +279,7 @@ impl ConnectionManager {
         schema: Option<&str>,
         database: Option<&str>,
     ) -> DbResult<Vec<ColumnInfo>> {
-        let connections = self.connections.read().await;
-        let db = connections
-            .get(connection_id)
-            .ok_or_else(|| DbError::ConnectionFailed("连接不存在".to_string()))?;
-        
+        let db = self.get_connection(connection_id).await?;
         db.get_table_structure(table, schema, database).await
     }
     
@@ -208,21 +290,206 @@ impl ConnectionManager {
         table: &str,
         schema: Option<&str>,
     ) -> DbResult<TableOptions> {
-        let connections = self.connections.read().await;
-        let db = connections
-            .get(connection_id)
-            .ok_or_else(|| DbError::ConnectionFailed("连接不存在".to_string()))?;
-
+        let db = self.get_connection(connection_id).await?;
         db.get_table_options(table, schema).await
     }
     
+    /// 获取连接的服务器信息（优先使用连接时缓存的结果）
+    pub async fn get_server_info(&self, connection_id: &str) -> DbResult<ServerInfo> {
+        if let Some(info) = self.server_infos.read().await.get(connection_id) {
+            return Ok(info.clone());
+        }
+
+        let info = self.get_connection(connection_id).await?.server_info().await?;
+
+        let mut server_infos = self.server_infos.write().await;
+        server_infos.insert(connection_id.to_string(), info.clone());
+        Ok(info)
+    }
+    
+    /// 获取连接的能力（结合连接时读取的服务器信息）
+    pub async fn get_capabilities(&self, connection_id: &str) -> DbResult<DriverCapabilities> {
+        let driver = self.get_driver(connection_id).await?;
+        Ok(match self.get_server_info(connection_id).await {
+            Ok(info) => driver.server_capabilities(&info),
+            Err(_) => driver.capabilities(),
+        })
+    }
+    
     /// 获取连接的数据库类型
     pub async fn get_database_type(&self, connection_id: &str) -> DbResult<DatabaseType> {
         let connection_types = self.connection_types.read().await;
         connection_types
             .get(connection_id)
             .cloned()
-            .ok_or_else(|| DbError::ConnectionFailed("连接不存在".to_string()))
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::ConnectionNotFound.into()))
+    }
+}
+
+impl ConnectionManager {
+    /// 获取连接健康状态（未建立的连接返回 Disconnected）
+    pub async fn get_health(&self, connection_id: &str) -> ConnectionHealth {
+        let health = self.health.read().await;
+        match health.get(connection_id) {
+            Some(state) => state.to_report(connection_id),
+            None => ConnectionHealth {
+                connection_id: connection_id.to_string(),
+                status: ConnectionStatus::Disconnected,
+                error: None,
+                error_code: None,
+                retry_attempts: 0,
+                next_retry_secs: None,
+                server_info_error: None,
+            },
+        }
+    }
+
+    /// 获取所有已建立连接的健康状态
+    pub async fn all_health(&self) -> Vec<ConnectionHealth> {
+        let health = self.health.read().await;
+        health
+            .iter()
+            .map(|(id, state)| state.to_report(id))
+            .collect()
+    }
+
+    /// 更新健康状态，返回更新后的状态
+    async fn set_health(&self, connection_id: &str, state: HealthState) -> Option<ConnectionHealth> {
+        let mut health = self.health.write().await;
+        // 检查期间连接可能已被用户断开
+        let entry = health.get_mut(connection_id)?;
+        *entry = state;
+        Some(entry.to_report(connection_id))
+    }
+
+    /// 执行一轮健康检查
+    ///
+    /// 对正常的连接发送 ping，失败时标记为 Error 并立即尝试重连；
+    /// 处于 Error 状态的连接按指数退避时间重连。返回状态发生变化的连接。
+    pub async fn check_health(&self) -> Vec<ConnectionHealth> {
+        let ids: Vec<String> = self.configs.read().await.keys().cloned().collect();
+        let mut changed = Vec::new();
+
+        for id in ids {
+            let Some(state) = self.health.read().await.get(&id).cloned() else {
+                continue;
+            };
+
+            match state.status {
+                ConnectionStatus::Connected => {
+                    // 克隆出连接后再 ping，超时的 ping 不会阻塞连接表
+                    let Ok(db) = self.get_connection(&id).await else {
+                        continue;
+                    };
+                    let result = tokio::time::timeout(PING_TIMEOUT, db.ping())
+                        .await
+                        .unwrap_or_else(|_| Err(DbError::Timeout(Msg::HealthCheckTimeout.into())));
+                    drop(db);
+
+                    if let Err(e) = result {
+                        changed.extend(self.try_reconnect(&id, 0, Some((e.to_string(), e.code()))).await);
+                    }
+                }
+                ConnectionStatus::Error => {
+                    let due = state.next_retry.map(|t| Instant::now() >= t).unwrap_or(true);
+                    if due {
+                        changed.extend(self.try_reconnect(&id, state.retry_attempts, state.error).await);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        changed
+    }
+
+    /// 手动重连（重置退避计数），返回过程中的状态变化
+    pub async fn reconnect(&self, connection_id: &str) -> DbResult<Vec<ConnectionHealth>> {
+        if !self.configs.read().await.contains_key(connection_id) {
+            return Err(DbError::ConnectionFailed(Msg::ConnectionNotFound.into()));
+        }
+
+        Ok(self.try_reconnect(connection_id, 0, None).await)
+    }
+
+    /// 使用保存的配置重建连接，返回过程中的状态变化（Connecting → Connected/Error）
+    async fn try_reconnect(
+        &self,
+        connection_id: &str,
+        previous_attempts: u32,
+        previous_error: Option<(String, ErrorCode)>,
+    ) -> Vec<ConnectionHealth> {
+        let mut changed = Vec::new();
+
+        let Some(config) = self.configs.read().await.get(connection_id).cloned() else {
+            return changed;
+        };
+
+        changed.extend(
+            self.set_health(
+                connection_id,
+                HealthState {
+                    status: ConnectionStatus::Connecting,
+                    error: previous_error,
+                    retry_attempts: previous_attempts,
+                    next_retry: None,
+                    server_info_error: None,
+                },
+            )
+            .await,
+        );
+
+        let result = async {
+            let mut db = self.new_database(&config.db_type)?;
+            let timeout = Duration::from_secs(config.connection_timeout.max(1));
+            tokio::time::timeout(timeout, db.connect(config.clone()))
+                .await
+                .map_err(|_| DbError::Timeout(Msg::ReconnectTimeout.into()))??;
+            Ok::<_, DbError>(db)
+        }
+        .await;
+
+        let state = match result {
+            Ok(mut db) => {
+                let server_info = db.server_info().await;
+
+                let mut connections = self.connections.write().await;
+                // 重连期间连接被断开则丢弃新连接
+                if !self.configs.read().await.contains_key(connection_id) {
+                    drop(connections);
+                    let _ = db.disconnect().await;
+                    return changed;
+                }
+                let replaced = connections.insert(connection_id.to_string(), Arc::from(db));
+                drop(connections);
+
+                // 关闭被替换的旧连接，释放其连接池或客户端
+                if let Some(old) = replaced {
+                    let _ = close_connection(old).await;
+                }
+
+                let state = HealthState::connected_with(&server_info);
+                if let Ok(info) = server_info {
+                    let mut server_infos = self.server_infos.write().await;
+                    server_infos.insert(connection_id.to_string(), info);
+                }
+                state
+            }
+            Err(e) => {
+                let attempts = previous_attempts + 1;
+                let backoff = reconnect_backoff(attempts);
+                HealthState {
+                    status: ConnectionStatus::Error,
+                    error: Some((e.to_string(), e.code())),
+                    retry_attempts: attempts,
+                    next_retry: Some(Instant::now() + backoff),
+                    server_info_error: None,
+                }
+            }
+        };
+
+        changed.extend(self.set_health(connection_id, state).await);
+        changed
     }
 }
 
@@ -232,3 +499,13 @@ impl Default for ConnectionManager {
     }
 }
 
+/// 断开从连接表移除的连接
+///
+/// 仍有操作（例如流式导出）在使用时无法取得独占引用，
+/// 此时由最后一个使用者释放，驱动随之关闭连接池或客户端
+async fn close_connection(mut db: Arc<dyn DatabaseOperations>) -> DbResult<()> {
+    match Arc::get_mut(&mut db) {
+        Some(db) => db.disconnect().await,
+        None => Ok(()),
+    }
+}
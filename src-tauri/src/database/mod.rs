@@ -1,5 +1,7 @@
+pub mod error;
 pub mod traits;
 pub mod manager;
+pub mod registry;
 pub mod sql_splitter;
 
 #[cfg(feature = "mysql")]
@@ -11,13 +13,25 @@ pub mod postgresql;
 #[cfg(feature = "sqlite")]
 pub mod sqlite;
 
+#[cfg(feature = "sqlserver-support")]
+pub mod sqlserver;
+
+#[cfg(feature = "duckdb-support")]
+pub mod duckdb;
+
 #[cfg(feature = "mongodb-support")]
 pub mod mongodb;
 
 #[cfg(feature = "redis-support")]
 pub mod redis;
 
+#[cfg(feature = "elasticsearch-support")]
+pub mod elasticsearch;
+
+#[cfg(feature = "clickhouse-support")]
+pub mod clickhouse;
+
 pub use traits::*;
-pub use manager::ConnectionManager;
-pub use sql_splitter::{SqlSplitter, SqlDialect, is_query_statement, truncate_sql};
+pub use manager::{ConnectionManager, HEALTH_CHECK_INTERVAL_SECS};
+pub use sql_splitter::{SqlSplitter, is_query_statement, truncate_sql};
 
@@ -1,6 +1,9 @@
 use async_trait::async_trait;
 use mongodb::{Client, options::ClientOptions};
+use super::registry::{DatabaseDriver, DriverCapabilities, DriverRegistration, ExportFormat, ObjectType};
 use super::traits::*;
+use crate::utils::i18n::Msg;
+use std::sync::Arc;
 
 /// MongoDB 数据库连接
 pub struct MongoDatabase {
@@ -40,6 +43,41 @@ impl MongoDatabase {
     }
 }
 
+impl Default for MongoDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// MongoDB 驱动
+pub struct MongoDriver;
+
+inventory::submit! {
+    DriverRegistration { factory: || Arc::new(MongoDriver) }
+}
+
+impl DatabaseDriver for MongoDriver {
+    fn db_type(&self) -> DatabaseType {
+        DatabaseType::MongoDB
+    }
+
+    fn display_name(&self) -> &'static str {
+        "MongoDB"
+    }
+
+    fn create(&self) -> DbResult<Box<dyn DatabaseOperations>> {
+        Ok(Box::new(MongoDatabase::new()))
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            object_types: vec![ObjectType::Collection, ObjectType::Index],
+            export_formats: ExportFormat::for_results(),
+            ..Default::default()
+        }
+    }
+}
+
 #[async_trait]
 impl DatabaseOperations for MongoDatabase {
     async fn test_connection(&self, config: &ConnectionConfig) -> DbResult<bool> {
@@ -48,22 +86,23 @@ impl DatabaseOperations for MongoDatabase {
         // 解析连接选项
         let mut client_options = ClientOptions::parse(&uri)
             .await
-            .map_err(|e| DbError::ConnectionFailed(format!("解析 MongoDB URI 失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).connecting().context(Msg::ParseUriFailed.with(&[&"MongoDB"])))?;
         
         // 设置连接超时
         client_options.connect_timeout = Some(std::time::Duration::from_secs(config.connection_timeout));
         client_options.server_selection_timeout = Some(std::time::Duration::from_secs(config.connection_timeout));
+        client_options.app_name = session_setting(&config.application_name).map(String::from);
         
         // 创建客户端
         let client = Client::with_options(client_options)
-            .map_err(|e| DbError::ConnectionFailed(format!("创建 MongoDB 客户端失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).connecting().context(Msg::CreateClientFailed.with(&[&"MongoDB"])))?;
         
         // 测试连接 - 执行 ping 命令
         let admin_db = client.database("admin");
         admin_db
             .run_command(mongodb::bson::doc! { "ping": 1 })
             .await
-            .map_err(|e| DbError::ConnectionFailed(format!("连接 MongoDB 失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).connecting().context(Msg::ConnectToFailed.with(&[&"MongoDB"])))?;
         
         Ok(true)
     }
@@ -74,15 +113,16 @@ impl DatabaseOperations for MongoDatabase {
         // 解析连接选项
         let mut client_options = ClientOptions::parse(&uri)
             .await
-            .map_err(|e| DbError::ConnectionFailed(format!("解析 MongoDB URI 失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).connecting().context(Msg::ParseUriFailed.with(&[&"MongoDB"])))?;
         
         // 设置连接超时
         client_options.connect_timeout = Some(std::time::Duration::from_secs(config.connection_timeout));
         client_options.server_selection_timeout = Some(std::time::Duration::from_secs(config.connection_timeout));
+        client_options.app_name = session_setting(&config.application_name).map(String::from);
         
         // 创建客户端
         let client = Client::with_options(client_options)
-            .map_err(|e| DbError::ConnectionFailed(format!("创建 MongoDB 客户端失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).connecting().context(Msg::CreateClientFailed.with(&[&"MongoDB"])))?;
         
         self.client = Some(client);
         self.config = Some(config);
@@ -97,20 +137,20 @@ impl DatabaseOperations for MongoDatabase {
     }
 
     async fn execute_query(&self, _sql: &str, _database: Option<&str>) -> DbResult<QueryResult> {
-        Err(DbError::Other("MongoDB 不支持 SQL 查询，请使用 MongoDB Shell 命令或查询语法".to_string()))
+        Err(DbError::Other(Msg::SqlNotSupported.with(&[&"MongoDB", &"MongoDB Shell"])))
     }
 
     async fn get_databases(&self) -> DbResult<Vec<DatabaseInfo>> {
         let client = self
             .client
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 MongoDB".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"MongoDB"])))?;
         
         // 列出所有数据库
         let db_names = client
             .list_database_names()
             .await
-            .map_err(|e| DbError::QueryFailed(format!("获取数据库列表失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::ListDatabasesFailed))?;
         
         let databases = db_names
             .into_iter()
@@ -128,11 +168,11 @@ impl DatabaseOperations for MongoDatabase {
         let client = self
             .client
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 MongoDB".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"MongoDB"])))?;
         
         let db_name = database.or_else(|| {
             self.config.as_ref().and_then(|c| c.database.as_deref())
-        }).ok_or_else(|| DbError::ConfigError("未指定数据库".to_string()))?;
+        }).ok_or_else(|| DbError::ConfigError(Msg::DatabaseNotSpecified.into()))?;
         
         let db = client.database(db_name);
         
@@ -140,7 +180,7 @@ impl DatabaseOperations for MongoDatabase {
         let collection_names = db
             .list_collection_names()
             .await
-            .map_err(|e| DbError::QueryFailed(format!("获取集合列表失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::ListCollectionsFailed))?;
         
         let mut tables = Vec::new();
         for name in collection_names {
@@ -175,11 +215,11 @@ impl DatabaseOperations for MongoDatabase {
         let client = self
             .client
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 MongoDB".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"MongoDB"])))?;
         
         let db_name = schema.or_else(|| {
             self.config.as_ref().and_then(|c| c.database.as_deref())
-        }).ok_or_else(|| DbError::ConfigError("未指定数据库".to_string()))?;
+        }).ok_or_else(|| DbError::ConfigError(Msg::DatabaseNotSpecified.into()))?;
         
         let db = client.database(db_name);
         let collection = db.collection::<mongodb::bson::Document>(table);
@@ -188,7 +228,7 @@ impl DatabaseOperations for MongoDatabase {
         let mut cursor = collection
             .list_indexes()
             .await
-            .map_err(|e| DbError::QueryFailed(format!("获取索引列表失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::ListIndexesFailed))?;
         
         use futures::stream::StreamExt;
         let mut indexes = Vec::new();
@@ -222,7 +262,7 @@ impl DatabaseOperations for MongoDatabase {
                     });
                 }
                 Err(e) => {
-                    return Err(DbError::QueryFailed(format!("读取索引信息失败: {}", e)));
+                    return Err(DbError::from(e).context(Msg::ReadIndexFailed));
                 }
             }
         }
@@ -238,11 +278,109 @@ impl DatabaseOperations for MongoDatabase {
             collation: None,
             comment: None,
             auto_increment: None,
+            extra: Default::default(),
         })
     }
     
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    async fn ping(&self) -> DbResult<()> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"MongoDB"])))?;
+
+        client
+            .database("admin")
+            .run_command(mongodb::bson::doc! { "ping": 1 })
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::ConnectToFailed.with(&[&"MongoDB"])))?;
+        Ok(())
+    }
+    
+    async fn server_info(&self) -> DbResult<ServerInfo> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"MongoDB"])))?;
+
+        let admin_db = client.database("admin");
+        let build_info = admin_db
+            .run_command(mongodb::bson::doc! { "buildInfo": 1 })
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::ServerInfoFailed))?;
+        let version = build_info.get_str("version").unwrap_or_default().to_string();
+
+        // 当前认证用户（未认证时为空）
+        let current_user = admin_db
+            .run_command(mongodb::bson::doc! { "connectionStatus": 1 })
+            .await
+            .ok()
+            .and_then(|status| {
+                let users = status.get_document("authInfo").ok()?.get_array("authenticatedUsers").ok()?;
+                let user = users.first()?.as_document()?;
+                Some(format!(
+                    "{}@{}",
+                    user.get_str("user").ok()?,
+                    user.get_str("db").ok()?
+                ))
+            });
+
+        let mut info = ServerInfo {
+            flavor: ServerFlavor::MongoDB,
+            version: version.clone(),
+            version_string: format!("MongoDB {}", version),
+            current_user,
+            time_zone: None,
+            features: ServerFeatures::default(),
+        };
+        info.features.transactions = info.version_at_least(4, 0);
+
+        Ok(info)
     }
 }
 
+/// 按 MongoDB 服务器错误码推断错误码
+fn classify_server_code(code: i32) -> Option<ErrorCode> {
+    let code = match code {
+        18 => ErrorCode::AuthFailed,
+        13 | 8000 => ErrorCode::PermissionDenied,
+        9 | 17287 => ErrorCode::SyntaxError,
+        26 => ErrorCode::NotFound,
+        11000 | 11001 | 121 => ErrorCode::ConstraintViolation,
+        50 | 262 => ErrorCode::Timeout,
+        _ => return None,
+    };
+    Some(code)
+}
+
+impl From<mongodb::error::Error> for DbError {
+    fn from(e: mongodb::error::Error) -> Self {
+        use mongodb::error::{ErrorKind, WriteFailure};
+
+        let server_code = match e.kind.as_ref() {
+            ErrorKind::Command(command) => Some((command.code, command.code_name.clone())),
+            ErrorKind::Write(WriteFailure::WriteError(write)) => {
+                Some((write.code, write.code_name.clone().unwrap_or_default()))
+            }
+            _ => None,
+        };
+        if let Some((number, name)) = server_code {
+            let native = NativeError {
+                errno: Some(number as i64),
+                kind: Some(name).filter(|n| !n.is_empty()),
+                ..Default::default()
+            };
+            let code = classify_server_code(number).unwrap_or(ErrorCode::QueryFailed);
+            return DbError::database(code, e.to_string(), native);
+        }
+
+        match e.kind.as_ref() {
+            ErrorKind::Authentication { message, .. } => {
+                DbError::database(ErrorCode::AuthFailed, message.clone(), NativeError::default())
+            }
+            ErrorKind::ServerSelection { .. } => DbError::ConnectionFailed(e.to_string()),
+            ErrorKind::Io(_) => DbError::ConnectionLost(e.to_string()),
+            ErrorKind::InvalidArgument { .. } => DbError::ConfigError(e.to_string()),
+            _ => DbError::QueryFailed(e.to_string()),
+        }
+    }
+}
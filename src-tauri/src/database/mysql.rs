@@ -1,17 +1,26 @@
 use async_trait::async_trait;
 use sqlx::mysql::MySqlPoolOptions;
-use sqlx::{Column, MySql, Pool, Row};
+use sqlx::{Column, MySql, Pool, Row, TypeInfo};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use url::Url;
 
+use super::registry::{
+    DatabaseDriver, DriverCapabilities, DriverRegistration, EditingCapabilities, ExplainFormat,
+    ExportFormat, ObjectType,
+};
 use super::traits::*;
+use crate::utils::i18n::Msg;
 use super::sql_splitter::{SqlSplitter, SqlDialect, is_query_statement, truncate_sql};
 
 /// MySQL 数据库连接
 pub struct MySqlDatabase {
     pool: Option<Pool<MySql>>,
     config: Option<ConnectionConfig>,
+    /// 连接时识别的产品分支（MySQL / MariaDB / TiDB）及实际版本
+    flavor: ServerFlavor,
+    version: String,
 }
 
 impl MySqlDatabase {
@@ -19,6 +28,8 @@ impl MySqlDatabase {
         Self {
             pool: None,
             config: None,
+            flavor: ServerFlavor::MySQL,
+            version: String::new(),
         }
     }
 
@@ -44,6 +55,134 @@ impl MySqlDatabase {
 
         url.to_string()
     }
+
+    /// 构建会话初始化语句（字符集、时区、初始化 SQL）
+    ///
+    /// MySQL 没有会话级的应用名称设置，application_name 不生效
+    fn session_init_statements(config: &ConnectionConfig) -> Vec<String> {
+        let mut statements = Vec::new();
+        if let Some(charset) = session_setting(&config.charset) {
+            statements.push(format!("SET NAMES '{}'", escape_sql_literal(charset)));
+        }
+        if let Some(time_zone) = session_setting(&config.time_zone) {
+            statements.push(format!("SET time_zone = '{}'", escape_sql_literal(time_zone)));
+        }
+        statements.extend(config.init_sql_statements(SqlDialect::mysql()));
+        statements
+    }
+}
+
+impl Default for MySqlDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// MySQL 驱动
+pub struct MySqlDriver;
+
+inventory::submit! {
+    DriverRegistration { factory: || Arc::new(MySqlDriver) }
+}
+
+impl DatabaseDriver for MySqlDriver {
+    fn db_type(&self) -> DatabaseType {
+        DatabaseType::MySQL
+    }
+
+    fn display_name(&self) -> &'static str {
+        "MySQL"
+    }
+
+    fn create(&self) -> DbResult<Box<dyn DatabaseOperations>> {
+        Ok(Box::new(MySqlDatabase::new()))
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            sql: true,
+            object_types: vec![
+                ObjectType::Table,
+                ObjectType::View,
+                ObjectType::Procedure,
+                ObjectType::Function,
+                ObjectType::Trigger,
+                ObjectType::Event,
+                ObjectType::Sequence,
+                ObjectType::Index,
+            ],
+            editing: EditingCapabilities::all(),
+            transactions: true,
+            explain_formats: vec![ExplainFormat::Text, ExplainFormat::Json],
+            export_formats: ExportFormat::for_sql(),
+            ..Default::default()
+        }
+    }
+
+    fn server_capabilities(&self, info: &ServerInfo) -> DriverCapabilities {
+        let mut capabilities = self.capabilities().with_server_features(&info.features);
+        capabilities.explain_formats = match info.flavor {
+            // TiDB 的 EXPLAIN 只输出表格形式
+            ServerFlavor::TiDB => vec![ExplainFormat::Text],
+            ServerFlavor::MariaDB if !info.version_at_least(10, 1) => vec![ExplainFormat::Text],
+            ServerFlavor::MariaDB => vec![ExplainFormat::Text, ExplainFormat::Json],
+            // EXPLAIN FORMAT=TREE 从 MySQL 8.0.16 开始提供
+            _ if parse_version(&info.version) >= (8, 0, 16) => {
+                vec![ExplainFormat::Text, ExplainFormat::Json, ExplainFormat::Tree]
+            }
+            _ => vec![ExplainFormat::Text, ExplainFormat::Json],
+        };
+        capabilities
+    }
+
+    fn dialect(&self) -> Option<SqlDialect> {
+        Some(SqlDialect::mysql())
+    }
+
+    /// 反引号，内部的反引号写两次
+    fn quote_identifier(&self, identifier: &str) -> String {
+        format!("`{}`", identifier.replace('`', "``"))
+    }
+
+    /// 默认把反斜杠当作转义符
+    fn string_literal(&self, value: &str) -> String {
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+    }
+
+    /// DDL 会隐式提交
+    fn transactional_ddl(&self) -> bool {
+        false
+    }
+
+    /// 外键检查的设置只在当前连接生效，整个脚本需要在一次 execute_query 中执行
+    fn truncate_table_sql(&self, table_ref: &str) -> String {
+        format!(
+            "SET FOREIGN_KEY_CHECKS = 0;\nTRUNCATE TABLE {};\nSET FOREIGN_KEY_CHECKS = 1;",
+            table_ref
+        )
+    }
+
+    fn list_routines_sql(&self, database: &str, routine_type: &str) -> Option<String> {
+        Some(format!(
+            "SELECT ROUTINE_NAME, ROUTINE_TYPE, CREATED, LAST_ALTERED, ROUTINE_COMMENT
+             FROM information_schema.ROUTINES 
+             WHERE ROUTINE_SCHEMA = {} AND ROUTINE_TYPE = {}
+             ORDER BY ROUTINE_NAME",
+            self.string_literal(database),
+            self.string_literal(routine_type)
+        ))
+    }
+
+    fn list_triggers_sql(&self, database: &str) -> Option<String> {
+        Some(format!(
+            "SELECT TRIGGER_NAME, EVENT_MANIPULATION, EVENT_OBJECT_TABLE, 
+                    ACTION_TIMING, CREATED
+             FROM information_schema.TRIGGERS 
+             WHERE TRIGGER_SCHEMA = {}
+             ORDER BY TRIGGER_NAME",
+            self.string_literal(database)
+        ))
+    }
 }
 
 #[async_trait]
@@ -62,7 +201,7 @@ impl DatabaseOperations for MySqlDatabase {
         // 配置连接池选项
         let pool_options = MySqlPoolOptions::new()
             .max_connections(1) // 测试时只需要1个连接
-            .acquire_timeout(Duration::from_secs(config.connection_timeout as u64))
+            .acquire_timeout(Duration::from_secs(config.connection_timeout))
             .idle_timeout(Some(Duration::from_secs(30)));
         
         match pool_options.connect(&connection_string).await {
@@ -76,10 +215,10 @@ impl DatabaseOperations for MySqlDatabase {
                 
                 match result {
                     Ok(_) => Ok(true),
-                    Err(e) => Err(DbError::ConnectionFailed(e.to_string())),
+                    Err(e) => Err(DbError::from(e).connecting()),
                 }
             }
-            Err(e) => Err(DbError::ConnectionFailed(e.to_string())),
+            Err(e) => Err(DbError::from(e).connecting()),
         }
     }
 
@@ -94,19 +233,41 @@ impl DatabaseOperations for MySqlDatabase {
             config.connection_timeout
         ));
         
+        // 每个新建的池连接都执行会话初始化语句
+        let init_statements = Arc::new(Self::session_init_statements(&config));
+        
         // 配置连接池选项
         let pool_options = MySqlPoolOptions::new()
-            .max_connections(config.pool_size as u32)
-            .acquire_timeout(Duration::from_secs(config.connection_timeout as u64))
+            .max_connections(config.pool_size)
+            .acquire_timeout(Duration::from_secs(config.connection_timeout))
             .idle_timeout(Some(Duration::from_secs(300))) // 5分钟空闲超时
-            .max_lifetime(Some(Duration::from_secs(1800))); // 30分钟最大生命周期
+            .max_lifetime(Some(Duration::from_secs(1800))) // 30分钟最大生命周期
+            .after_connect(move |conn, _meta| {
+                let init_statements = init_statements.clone();
+                Box::pin(async move {
+                    use sqlx::Executor;
+                    for sql in init_statements.iter() {
+                        conn.execute(sql.as_str()).await?;
+                    }
+                    Ok(())
+                })
+            });
         
         let pool = pool_options.connect(&connection_string)
             .await
-            .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+            .map_err(|e| DbError::from(e).connecting())?;
+        
+        // 识别产品分支，部分元数据查询依赖于此
+        let version_string: String = sqlx::query_scalar("SELECT CAST(VERSION() AS CHAR)")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| DbError::from(e).connecting())?;
+        let (flavor, version) = Self::detect_flavor(&version_string);
         
         self.pool = Some(pool);
         self.config = Some(config);
+        self.flavor = flavor;
+        self.version = version;
         
         Ok(())
     }
@@ -116,6 +277,8 @@ impl DatabaseOperations for MySqlDatabase {
             pool.close().await;
         }
         self.config = None;
+        self.flavor = ServerFlavor::MySQL;
+        self.version.clear();
         Ok(())
     }
 
@@ -125,7 +288,7 @@ impl DatabaseOperations for MySqlDatabase {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         let total_start = Instant::now();
 
@@ -155,7 +318,7 @@ impl DatabaseOperations for MySqlDatabase {
         // 多条语句：在同一连接上依次执行
         let mut conn = pool.acquire()
             .await
-            .map_err(|e| DbError::QueryFailed(format!("获取连接失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::AcquireConnectionFailed))?;
         
         // 如果指定了数据库，先切换数据库上下文
         if let Some(db_name) = database {
@@ -164,7 +327,7 @@ impl DatabaseOperations for MySqlDatabase {
                 println!("设置数据库上下文: {}", use_sql);
                 conn.execute(use_sql.as_str())
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("切换数据库失败: {}", e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::SwitchDatabaseFailed))?;
             }
         }
         
@@ -245,11 +408,88 @@ impl DatabaseOperations for MySqlDatabase {
         }
     }
 
+    async fn stream_query(
+        &self,
+        sql: &str,
+        database: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> DbResult<u64> {
+        use futures::TryStreamExt;
+        use sqlx::Executor;
+
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        let mut conn = pool.acquire()
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::AcquireConnectionFailed))?;
+
+        if let Some(db_name) = database.filter(|name| !name.is_empty()) {
+            conn.execute(format!("USE `{}`", db_name.replace('`', "``")).as_str())
+                .await
+                .map_err(|e| DbError::from(e).context(Msg::SwitchDatabaseFailed))?;
+        }
+
+        // 逐行读取，列名取自第一行
+        let mut count = 0u64;
+        {
+            let mut rows = sqlx::query(sql).fetch(&mut *conn);
+            while let Some(row) = rows.try_next().await.map_err(DbError::from)? {
+                if count == 0 {
+                    let columns: Vec<String> =
+                        row.columns().iter().map(|c| c.name().to_string()).collect();
+                    sink.columns(&columns)?;
+                }
+                let values = (0..row.columns().len())
+                    .map(|idx| Self::column_value(&row, idx))
+                    .collect();
+                sink.row(values)?;
+                count += 1;
+            }
+        }
+
+        // 没有数据时通过语句描述获取列名
+        if count == 0 {
+            let columns: Vec<String> = (&mut *conn)
+                .describe(sql)
+                .await
+                .map(|d| d.columns().iter().map(|c| c.name().to_string()).collect())
+                .unwrap_or_default();
+            sink.columns(&columns)?;
+        }
+
+        Ok(count)
+    }
+
+    async fn begin_transaction(&self, database: Option<&str>) -> DbResult<Box<dyn Transaction>> {
+        use sqlx::Executor;
+
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::BeginTransactionFailed))?;
+
+        if let Some(db_name) = database.filter(|name| !name.is_empty()) {
+            tx.execute(format!("USE `{}`", db_name.replace('`', "``")).as_str())
+                .await
+                .map_err(|e| DbError::from(e).context(Msg::SwitchDatabaseFailed))?;
+        }
+
+        Ok(Box::new(MySqlTransaction(tx)))
+    }
+
     async fn get_databases(&self) -> DbResult<Vec<DatabaseInfo>> {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         let rows = sqlx::query(
             "SELECT SCHEMA_NAME, DEFAULT_CHARACTER_SET_NAME, DEFAULT_COLLATION_NAME 
@@ -258,13 +498,13 @@ impl DatabaseOperations for MySqlDatabase {
         )
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         let mut databases = Vec::new();
         for row in rows {
             let name_bytes: Vec<u8> = row
                 .try_get("SCHEMA_NAME")
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                .map_err(DbError::from)?;
 
             let name = String::from_utf8_lossy(&name_bytes).into_owned();
             let charset: Option<String> =
@@ -287,29 +527,37 @@ impl DatabaseOperations for MySqlDatabase {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         let db_name = database.or_else(|| {
             self.config.as_ref().and_then(|c| c.database.as_deref())
-        }).ok_or_else(|| DbError::ConfigError("未指定数据库".to_string()))?;
-
-        let rows = sqlx::query(
+        }).ok_or_else(|| DbError::ConfigError(Msg::DatabaseNotSpecified.into()))?;
+
+        // MariaDB / TiDB 的序列也登记在 TABLES 中（TABLE_TYPE = 'SEQUENCE'），
+        // 由 get_sequences 单独列出；MariaDB 系统版本表保留 'SYSTEM VERSIONED' 类型
+        let sequence_filter = match self.flavor {
+            ServerFlavor::MariaDB | ServerFlavor::TiDB => " AND TABLE_TYPE <> 'SEQUENCE'",
+            _ => "",
+        };
+        let sql = format!(
             "SELECT TABLE_NAME, TABLE_TYPE, ENGINE, TABLE_ROWS, 
                     ROUND(((DATA_LENGTH + INDEX_LENGTH) / 1024 / 1024), 2) AS SIZE_MB,
                     TABLE_COMMENT
              FROM information_schema.TABLES 
-             WHERE TABLE_SCHEMA = ?
-             ORDER BY TABLE_NAME"
-        )
+             WHERE TABLE_SCHEMA = ?{}
+             ORDER BY TABLE_NAME",
+            sequence_filter
+        );
+        let rows = sqlx::query(&sql)
         .bind(db_name)
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         let mut tables = Vec::new();
         for row in rows {
             let name_bytes: Vec<u8> = row.try_get("TABLE_NAME")
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                .map_err(DbError::from)?;
             let name = String::from_utf8_lossy(&name_bytes).into_owned();
 
             let table_type_bytes: Vec<u8> = row.try_get("TABLE_TYPE")
@@ -335,14 +583,14 @@ impl DatabaseOperations for MySqlDatabase {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         let db_name = schema.or_else(|| {
             self.config.as_ref().and_then(|c| c.database.as_deref())
-        }).ok_or_else(|| DbError::ConfigError("未指定数据库".to_string()))?;
+        }).ok_or_else(|| DbError::ConfigError(Msg::DatabaseNotSpecified.into()))?;
 
         let rows = sqlx::query(
-            "SELECT COLUMN_NAME, DATA_TYPE, IS_NULLABLE, COLUMN_DEFAULT, 
+            "SELECT COLUMN_NAME, DATA_TYPE, COLUMN_TYPE, IS_NULLABLE, COLUMN_DEFAULT, 
                     COLUMN_KEY, EXTRA, COLUMN_COMMENT, CHARACTER_MAXIMUM_LENGTH,
                     NUMERIC_PRECISION, NUMERIC_SCALE
              FROM information_schema.COLUMNS 
@@ -353,17 +601,25 @@ impl DatabaseOperations for MySqlDatabase {
         .bind(table)
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         let mut columns = Vec::new();
         for row in rows {
             let name_bytes: Vec<u8> = row.try_get("COLUMN_NAME")
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                .map_err(DbError::from)?;
             let name = String::from_utf8_lossy(&name_bytes).into_owned();
 
             let data_type_bytes: Vec<u8> = row.try_get("DATA_TYPE")
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
-            let data_type = String::from_utf8_lossy(&data_type_bytes).into_owned();
+                .map_err(DbError::from)?;
+            let mut data_type = String::from_utf8_lossy(&data_type_bytes).into_owned();
+
+            // DATA_TYPE 不含 unsigned 修饰，从 COLUMN_TYPE（例如 int(10) unsigned）中补上
+            let column_type = row.try_get::<Vec<u8>, _>("COLUMN_TYPE")
+                .map(|b| String::from_utf8_lossy(&b).to_lowercase())
+                .unwrap_or_default();
+            if column_type.split_whitespace().any(|w| w == "unsigned") {
+                data_type.push_str(" unsigned");
+            }
 
             let is_nullable_bytes: Vec<u8> = row.try_get("IS_NULLABLE")
                 .unwrap_or_else(|_| Vec::new());
@@ -398,12 +654,17 @@ impl DatabaseOperations for MySqlDatabase {
                 .flatten()
                 .map(|v| v as i64);
 
+            let mut default_value = row.try_get::<Vec<u8>, _>("COLUMN_DEFAULT").ok()
+                .map(|b| String::from_utf8_lossy(&b).into_owned());
+            if self.flavor == ServerFlavor::MariaDB {
+                default_value = default_value.and_then(|v| Self::normalize_mariadb_default(&v));
+            }
+
             columns.push(ColumnInfo {
                 name,
                 data_type,
                 nullable: is_nullable.to_uppercase() == "YES",
-                default_value: row.try_get::<Vec<u8>, _>("COLUMN_DEFAULT").ok()
-                    .map(|b| String::from_utf8_lossy(&b).into_owned()),
+                default_value,
                 is_primary_key: column_key == "PRI",
                 is_auto_increment: extra.contains("auto_increment"),
                 comment: row.try_get::<Vec<u8>, _>("COLUMN_COMMENT").ok()
@@ -421,11 +682,11 @@ impl DatabaseOperations for MySqlDatabase {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         let db_name = schema.or_else(|| {
             self.config.as_ref().and_then(|c| c.database.as_deref())
-        }).ok_or_else(|| DbError::ConfigError("未指定数据库".to_string()))?;
+        }).ok_or_else(|| DbError::ConfigError(Msg::DatabaseNotSpecified.into()))?;
 
         let rows = sqlx::query(
             "SELECT INDEX_NAME, COLUMN_NAME, NON_UNIQUE, INDEX_TYPE, SEQ_IN_INDEX
@@ -437,7 +698,7 @@ impl DatabaseOperations for MySqlDatabase {
         .bind(table)
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         // 按索引名分组
         let mut index_map: HashMap<String, IndexInfo> = HashMap::new();
@@ -476,14 +737,14 @@ impl DatabaseOperations for MySqlDatabase {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         let db_name = schema.or_else(|| {
             self.config.as_ref().and_then(|c| c.database.as_deref())
-        }).ok_or_else(|| DbError::ConfigError("未指定数据库".to_string()))?;
+        }).ok_or_else(|| DbError::ConfigError(Msg::DatabaseNotSpecified.into()))?;
 
         let rows = sqlx::query(
-            "SELECT ENGINE, TABLE_COLLATION, TABLE_COMMENT, AUTO_INCREMENT,
+            "SELECT ENGINE, TABLE_COLLATION, TABLE_COMMENT, AUTO_INCREMENT, TABLE_TYPE,
                     CCSA.CHARACTER_SET_NAME as CHARSET
              FROM information_schema.TABLES T
              LEFT JOIN information_schema.COLLATION_CHARACTER_SET_APPLICABILITY CCSA
@@ -494,7 +755,7 @@ impl DatabaseOperations for MySqlDatabase {
         .bind(table)
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         if let Some(row) = rows.first() {
             let engine: Option<String> = row.try_get("ENGINE")
@@ -515,12 +776,31 @@ impl DatabaseOperations for MySqlDatabase {
             
             let auto_increment: Option<u64> = row.try_get("AUTO_INCREMENT").ok();
 
+            let table_type = row.try_get::<Vec<u8>, _>("TABLE_TYPE")
+                .map(|b| String::from_utf8_lossy(&b).into_owned())
+                .unwrap_or_default();
+
+            let mut extra = HashMap::new();
+            match self.flavor {
+                ServerFlavor::MariaDB => {
+                    extra.insert(
+                        "system_versioning".to_string(),
+                        (table_type == "SYSTEM VERSIONED").to_string(),
+                    );
+                }
+                ServerFlavor::TiDB => {
+                    extra.extend(self.tidb_table_options(pool, db_name, table).await?);
+                }
+                _ => {}
+            }
+
             Ok(TableOptions {
                 engine,
                 charset,
                 collation,
                 comment,
                 auto_increment,
+                extra,
             })
         } else {
             Ok(TableOptions {
@@ -529,16 +809,236 @@ impl DatabaseOperations for MySqlDatabase {
                 collation: None,
                 comment: None,
                 auto_increment: None,
+                extra: HashMap::new(),
             })
         }
     }
     
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    async fn ping(&self) -> DbResult<()> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        sqlx::query("SELECT 1")
+            .execute(pool)
+            .await
+            .map_err(DbError::from)?;
+        Ok(())
+    }
+    
+    async fn server_info(&self) -> DbResult<ServerInfo> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        let row = sqlx::query(
+            "SELECT CAST(VERSION() AS CHAR), CAST(CURRENT_USER() AS CHAR), \
+             CAST(@@session.time_zone AS CHAR), CAST(@@system_time_zone AS CHAR)",
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(|e| DbError::from(e).context(Msg::ServerInfoFailed))?;
+
+        let version_string: String = row.try_get(0).unwrap_or_default();
+        let current_user: Option<String> = row.try_get(1).ok();
+        let session_tz: Option<String> = row.try_get(2).ok();
+        let system_tz: Option<String> = row.try_get(3).ok();
+
+        // time_zone 为 SYSTEM 时使用系统时区
+        let time_zone = match session_tz {
+            Some(tz) if tz.eq_ignore_ascii_case("SYSTEM") => system_tz.or(Some(tz)),
+            tz => tz,
+        };
+
+        let (flavor, version) = Self::detect_flavor(&version_string);
+        let mut info = ServerInfo {
+            flavor,
+            version,
+            version_string,
+            current_user,
+            time_zone,
+            features: ServerFeatures::default(),
+        };
+        info.features = Self::detect_features(&info);
+
+        Ok(info)
     }
 }
 
 impl MySqlDatabase {
+    /// 读取 TiDB 特有的表属性：主键类型、行 ID 打散信息和放置策略
+    async fn tidb_table_options(
+        &self,
+        pool: &Pool<MySql>,
+        db_name: &str,
+        table: &str,
+    ) -> DbResult<HashMap<String, String>> {
+        // 放置策略（Placement Rules in SQL）从 TiDB 6.0 开始提供
+        let placement_column = if parse_version(&self.version) >= (6, 0, 0) {
+            ", TIDB_PLACEMENT_POLICY_NAME"
+        } else {
+            ""
+        };
+        let sql = format!(
+            "SELECT TIDB_PK_TYPE, TIDB_ROW_ID_SHARDING_INFO{}
+             FROM information_schema.TABLES
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+            placement_column
+        );
+        let row = sqlx::query(&sql)
+            .bind(db_name)
+            .bind(table)
+            .fetch_optional(pool)
+            .await
+            .map_err(DbError::from)?;
+
+        let mut options = HashMap::new();
+        if let Some(row) = row {
+            for (key, column) in [
+                ("pk_type", "TIDB_PK_TYPE"),
+                ("row_id_sharding", "TIDB_ROW_ID_SHARDING_INFO"),
+                ("placement_policy", "TIDB_PLACEMENT_POLICY_NAME"),
+            ] {
+                let value = row.try_get::<Option<Vec<u8>>, _>(column)
+                    .ok()
+                    .flatten()
+                    .map(|b| String::from_utf8_lossy(&b).into_owned());
+                if let Some(value) = value.filter(|v| !v.is_empty()) {
+                    options.insert(key.to_string(), value);
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    /// MariaDB 10.2.7+ 的 COLUMN_DEFAULT 以 SQL 表达式形式返回：
+    /// 无默认值时为字符串 `NULL`，字符串字面量带单引号，这里转换为与 MySQL 一致的形式
+    fn normalize_mariadb_default(value: &str) -> Option<String> {
+        if value.eq_ignore_ascii_case("NULL") {
+            return None;
+        }
+        match value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+            Some(literal) => Some(literal.replace("''", "'").replace("\\\\", "\\")),
+            None => Some(value.to_string()),
+        }
+    }
+
+    /// 根据 VERSION() 返回值识别产品分支和实际版本
+    ///
+    /// - MariaDB: `10.11.6-MariaDB-log`，旧版复制协议下为 `5.5.5-10.11.6-MariaDB`
+    /// - TiDB: `8.0.11-TiDB-v7.5.0`
+    fn detect_flavor(version_string: &str) -> (ServerFlavor, String) {
+        let lower = version_string.to_lowercase();
+
+        if let Some(pos) = lower.find("tidb-") {
+            let version = version_string[pos + 5..]
+                .split(|c: char| c == '-' || c.is_whitespace())
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            return (ServerFlavor::TiDB, version);
+        }
+
+        let version = version_string
+            .strip_prefix("5.5.5-")
+            .unwrap_or(version_string)
+            .split('-')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        if lower.contains("mariadb") {
+            (ServerFlavor::MariaDB, version)
+        } else {
+            (ServerFlavor::MySQL, version)
+        }
+    }
+
+    /// 根据产品分支和版本推断特性
+    fn detect_features(info: &ServerInfo) -> ServerFeatures {
+        match info.flavor {
+            ServerFlavor::MariaDB => ServerFeatures {
+                window_functions: info.version_at_least(10, 2),
+                cte: info.version_at_least(10, 2),
+                json: info.version_at_least(10, 2),
+                returning: info.version_at_least(10, 5),
+                upsert: true,
+                sequences: info.version_at_least(10, 3),
+                check_constraints: info.version_at_least(10, 2),
+                stored_procedures: true,
+                triggers: true,
+                events: true,
+                transactions: true,
+            },
+            ServerFlavor::TiDB => ServerFeatures {
+                window_functions: true,
+                cte: info.version_at_least(5, 1),
+                json: true,
+                returning: false,
+                upsert: true,
+                sequences: true,
+                check_constraints: info.version_at_least(7, 2),
+                stored_procedures: false,
+                triggers: false,
+                events: false,
+                transactions: true,
+            },
+            _ => ServerFeatures {
+                window_functions: info.version_at_least(8, 0),
+                cte: info.version_at_least(8, 0),
+                json: info.version_at_least(5, 7),
+                returning: false,
+                upsert: true,
+                sequences: false,
+                check_constraints: parse_version(&info.version) >= (8, 0, 16),
+                stored_procedures: true,
+                triggers: true,
+                events: true,
+                transactions: true,
+            },
+        }
+    }
+
+    /// 按列读取一个值（依次尝试常见类型）
+    fn column_value(row: &sqlx::mysql::MySqlRow, idx: usize) -> serde_json::Value {
+        // 二进制列统一写成 \x 十六进制（与 PostgreSQL 的 bytea 一致），文本列不做转换，
+        // 导出时按列类型还原为字节
+        let type_name = row.column(idx).type_info().name();
+        if matches!(type_name, "BINARY" | "VARBINARY" | "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB") {
+            return match row.try_get::<Option<Vec<u8>>, _>(idx) {
+                Ok(Some(bytes)) => {
+                    let hex_string = bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+                    serde_json::Value::String(format!("\\x{}", hex_string))
+                }
+                _ => serde_json::Value::Null,
+            };
+        }
+
+        // 尝试不同的数据类型获取
+        if let Ok(s) = row.try_get::<String, _>(idx) {
+            serde_json::Value::String(s)
+        } else if let Ok(i) = row.try_get::<i64, _>(idx) {
+            serde_json::Value::Number(serde_json::Number::from(i))
+        } else if let Ok(u) = row.try_get::<u64, _>(idx) {
+            // 无符号整数（BIGINT UNSIGNED 可超出 i64 范围）
+            serde_json::Value::Number(serde_json::Number::from(u))
+        } else if let Ok(f) = row.try_get::<f64, _>(idx) {
+            serde_json::Number::from_f64(f)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null)
+        } else if let Ok(b) = row.try_get::<bool, _>(idx) {
+            serde_json::Value::Bool(b)
+        } else {
+            // 如果都失败了，尝试获取原始字节并转换为字符串
+            match row.try_get::<Option<Vec<u8>>, _>(idx) {
+                Ok(Some(bytes)) => serde_json::Value::String(String::from_utf8_lossy(&bytes).into_owned()),
+                _ => serde_json::Value::Null,
+            }
+        }
+    }
+
     /// 处理查询结果
     fn process_query_result(&self, rows: Vec<sqlx::mysql::MySqlRow>, start: std::time::Instant) -> DbResult<QueryResult> {
         use std::collections::HashMap;
@@ -560,29 +1060,7 @@ impl MySqlDatabase {
             for (row_idx, row) in rows.iter().enumerate() {
                 let mut row_map = HashMap::new();
                 for (idx, column) in row.columns().iter().enumerate() {
-                    // 尝试不同的数据类型获取
-                    let value = if let Ok(s) = row.try_get::<String, _>(idx) {
-                        serde_json::Value::String(s)
-                    } else if let Ok(i) = row.try_get::<i64, _>(idx) {
-                        serde_json::Value::Number(serde_json::Number::from(i))
-                    } else if let Ok(f) = row.try_get::<f64, _>(idx) {
-                        serde_json::Number::from_f64(f)
-                            .map(serde_json::Value::Number)
-                            .unwrap_or(serde_json::Value::Null)
-                    } else if let Ok(b) = row.try_get::<bool, _>(idx) {
-                        serde_json::Value::Bool(b)
-                    } else {
-                        // 如果都失败了，尝试获取原始字节并转换为字符串
-                        match row.try_get::<Option<Vec<u8>>, _>(idx) {
-                            Ok(Some(bytes)) => {
-                                match String::from_utf8(bytes) {
-                                    Ok(s) => serde_json::Value::String(s),
-                                    Err(_) => serde_json::Value::Null,
-                                }
-                            }
-                            _ => serde_json::Value::Null,
-                        }
-                    };
+                    let value = Self::column_value(row, idx);
                     
                     row_map.insert(column.name().to_string(), value.clone());
                     
@@ -633,20 +1111,20 @@ impl MySqlDatabase {
                 if !db_name.is_empty() {
                     let mut conn = pool.acquire()
                         .await
-                        .map_err(|e| DbError::QueryFailed(format!("获取连接失败: {}", e)))?;
+                        .map_err(|e| DbError::from(e).context(Msg::AcquireConnectionFailed))?;
                     
                     // 设置数据库上下文
                     let use_sql = format!("USE `{}`", db_name);
                     println!("设置数据库上下文: {}", use_sql);
                     conn.execute(use_sql.as_str())
                         .await
-                        .map_err(|e| DbError::QueryFailed(format!("切换数据库失败: {}", e)))?;
+                        .map_err(|e| DbError::from(e).context(Msg::SwitchDatabaseFailed))?;
                     
                     // 在同一连接上执行用户的原生SQL
                     let rows = sqlx::query(sql)
                         .fetch_all(&mut *conn)
                         .await
-                        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                        .map_err(DbError::from)?;
                         
                     return self.process_query_result(rows, start);
                 }
@@ -656,7 +1134,7 @@ impl MySqlDatabase {
             let rows = sqlx::query(sql)
                 .fetch_all(pool)
                 .await
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                .map_err(DbError::from)?;
                 
             self.process_query_result(rows, start)
         } else {
@@ -665,20 +1143,20 @@ impl MySqlDatabase {
                 if !db_name.is_empty() {
                     let mut conn = pool.acquire()
                         .await
-                        .map_err(|e| DbError::QueryFailed(format!("获取连接失败: {}", e)))?;
+                        .map_err(|e| DbError::from(e).context(Msg::AcquireConnectionFailed))?;
                     
                     // 设置数据库上下文
                     let use_sql = format!("USE `{}`", db_name);
                     println!("设置数据库上下文: {}", use_sql);
                     conn.execute(use_sql.as_str())
                         .await
-                        .map_err(|e| DbError::QueryFailed(format!("切换数据库失败: {}", e)))?;
+                        .map_err(|e| DbError::from(e).context(Msg::SwitchDatabaseFailed))?;
                     
                     // 在同一连接上执行用户的原生SQL
                     let result = sqlx::query(sql)
                         .execute(&mut *conn)
                         .await
-                        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                        .map_err(DbError::from)?;
 
                     let duration = start.elapsed();
 
@@ -695,7 +1173,7 @@ impl MySqlDatabase {
             let result = sqlx::query(sql)
                 .execute(pool)
                 .await
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                .map_err(DbError::from)?;
 
             let duration = start.elapsed();
 
@@ -708,3 +1186,61 @@ impl MySqlDatabase {
         }
     }
 }
+
+/// MySQL 事务
+struct MySqlTransaction(sqlx::Transaction<'static, MySql>);
+
+#[async_trait]
+impl Transaction for MySqlTransaction {
+    async fn execute(&mut self, sql: &str) -> DbResult<u64> {
+        use sqlx::Executor;
+
+        let result = (&mut *self.0).execute(sql).await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn commit(self: Box<Self>) -> DbResult<()> {
+        self.0
+            .commit()
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::CommitFailed))
+    }
+
+    async fn rollback(self: Box<Self>) -> DbResult<()> {
+        Ok(self.0.rollback().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_flavor() {
+        assert_eq!(
+            MySqlDatabase::detect_flavor("5.5.5-10.11.6-MariaDB-log"),
+            (ServerFlavor::MariaDB, "10.11.6".to_string())
+        );
+        assert_eq!(
+            MySqlDatabase::detect_flavor("8.0.11-TiDB-v7.5.0"),
+            (ServerFlavor::TiDB, "v7.5.0".to_string())
+        );
+        assert_eq!(
+            MySqlDatabase::detect_flavor("8.0.36"),
+            (ServerFlavor::MySQL, "8.0.36".to_string())
+        );
+    }
+
+    #[test]
+    fn test_normalize_mariadb_default() {
+        assert_eq!(MySqlDatabase::normalize_mariadb_default("NULL"), None);
+        assert_eq!(
+            MySqlDatabase::normalize_mariadb_default("'it''s'"),
+            Some("it's".to_string())
+        );
+        assert_eq!(
+            MySqlDatabase::normalize_mariadb_default("current_timestamp()"),
+            Some("current_timestamp()".to_string())
+        );
+    }
+}
@@ -1,10 +1,18 @@
 use async_trait::async_trait;
+use sqlx::postgres::PgPoolOptions;
 use sqlx::{Column, PgPool, Pool, Postgres, Row};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 use url::Url;
 
+use super::sql_splitter::SqlDialect;
+use super::registry::{
+    DatabaseDriver, DriverCapabilities, DriverRegistration, EditingCapabilities, ExplainFormat,
+    ExportFormat, ObjectType,
+};
 use super::traits::*;
+use crate::utils::i18n::Msg;
 
 /// PostgreSQL 数据库连接
 pub struct PostgreSqlDatabase {
@@ -49,6 +57,42 @@ impl PostgreSqlDatabase {
         url.to_string()
     }
 
+    /// 构建会话初始化语句（字符集、时区、应用名称、初始化 SQL）
+    fn session_init_statements(config: &ConnectionConfig) -> Vec<String> {
+        let mut statements = Vec::new();
+        if let Some(charset) = session_setting(&config.charset) {
+            statements.push(format!("SET client_encoding TO '{}'", escape_sql_literal(charset)));
+        }
+        if let Some(time_zone) = session_setting(&config.time_zone) {
+            statements.push(format!("SET TIME ZONE '{}'", escape_sql_literal(time_zone)));
+        }
+        if let Some(name) = session_setting(&config.application_name) {
+            statements.push(format!("SET application_name = '{}'", escape_sql_literal(name)));
+        }
+        statements.extend(config.init_sql_statements(SqlDialect::postgresql()));
+        statements
+    }
+
+    /// 创建连接池，每个新建的池连接都执行会话初始化语句
+    async fn connect_pool(config: &ConnectionConfig) -> Result<PgPool, sqlx::Error> {
+        let connection_string = Self::build_connection_string(config);
+        let init_statements = Arc::new(Self::session_init_statements(config));
+
+        PgPoolOptions::new()
+            .after_connect(move |conn, _meta| {
+                let init_statements = init_statements.clone();
+                Box::pin(async move {
+                    use sqlx::Executor;
+                    for sql in init_statements.iter() {
+                        conn.execute(sql.as_str()).await?;
+                    }
+                    Ok(())
+                })
+            })
+            .connect(&connection_string)
+            .await
+    }
+
     /// 使用指定的连接池执行查询
     async fn execute_query_with_pool(
         &self,
@@ -93,7 +137,7 @@ impl PostgreSqlDatabase {
                 let rows = sqlx::query(stmt)
                     .fetch_all(pool)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("语句 {} 执行失败: {}", idx + 1, e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::StatementFailed.with(&[&(idx + 1)])))?;
                 
                 // 保存最后一个查询结果
                 last_query_result = Some(self.process_query_result_pg(rows, start)?);
@@ -102,7 +146,7 @@ impl PostgreSqlDatabase {
                 let result = sqlx::query(stmt)
                     .execute(pool)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("语句 {} 执行失败: {}", idx + 1, e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::StatementFailed.with(&[&(idx + 1)])))?;
                 
                 total_affected_rows += result.rows_affected();
             }
@@ -142,7 +186,7 @@ impl PostgreSqlDatabase {
             let rows = sqlx::query(sql)
                 .fetch_all(pool)
                 .await
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                .map_err(DbError::from)?;
 
             self.process_query_result_pg(rows, start)
         } else {
@@ -150,7 +194,7 @@ impl PostgreSqlDatabase {
             let result = sqlx::query(sql)
                 .execute(pool)
                 .await
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                .map_err(DbError::from)?;
 
             Ok(QueryResult {
                 columns: vec![],
@@ -161,6 +205,92 @@ impl PostgreSqlDatabase {
         }
     }
     
+    /// 按列读取一个值（依次尝试常见类型）
+    fn column_value(row: &sqlx::postgres::PgRow, idx: usize) -> serde_json::Value {
+        // 尝试多种数据类型获取
+        if let Ok(s) = row.try_get::<String, _>(idx) {
+            serde_json::Value::String(s)
+        } else if let Ok(i) = row.try_get::<i64, _>(idx) {
+            serde_json::Value::Number(serde_json::Number::from(i))
+        } else if let Ok(i) = row.try_get::<i32, _>(idx) {
+            serde_json::Value::Number(serde_json::Number::from(i as i64))
+        } else if let Ok(i) = row.try_get::<i16, _>(idx) {
+            serde_json::Value::Number(serde_json::Number::from(i as i64))
+        } else if let Ok(f) = row.try_get::<f64, _>(idx) {
+            serde_json::Number::from_f64(f)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null)
+        } else if let Ok(f) = row.try_get::<f32, _>(idx) {
+            serde_json::Number::from_f64(f as f64)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null)
+        } else if let Ok(b) = row.try_get::<bool, _>(idx) {
+            serde_json::Value::Bool(b)
+        } else if let Ok(Some(bytes)) = row.try_get::<Option<Vec<u8>>, _>(idx) {
+            // bytea 统一写成 \x 十六进制，导出时按列类型还原为字节
+            let hex_string = bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+            serde_json::Value::String(format!("\\x{}", hex_string))
+        } else if let Ok(s) = row.try_get::<chrono::NaiveDateTime, _>(idx) {
+            serde_json::Value::String(s.to_string())
+        } else if let Ok(s) = row.try_get::<chrono::NaiveDate, _>(idx) {
+            serde_json::Value::String(s.to_string())
+        } else if let Ok(s) = row.try_get::<chrono::NaiveTime, _>(idx) {
+            serde_json::Value::String(s.to_string())
+        } else if let Ok(s) = row.try_get::<chrono::DateTime<chrono::Utc>, _>(idx) {
+            serde_json::Value::String(s.to_string())
+        } else if let Ok(json) = row.try_get::<serde_json::Value, _>(idx) {
+            json
+        } else if let Ok(uuid) = row.try_get::<sqlx::types::Uuid, _>(idx) {
+            serde_json::Value::String(uuid.to_string())
+        } else if let Ok(None) = row.try_get::<Option<String>, _>(idx) {
+            serde_json::Value::Null
+        } else {
+            match row.try_get::<Option<String>, _>(idx) {
+                Ok(Some(s)) => serde_json::Value::String(s),
+                _ => serde_json::Value::Null,
+            }
+        }
+    }
+
+    /// 使用指定连接池流式读取单条查询
+    async fn stream_with_pool(
+        pool: &Pool<Postgres>,
+        sql: &str,
+        sink: &mut dyn RowSink,
+    ) -> DbResult<u64> {
+        use futures::TryStreamExt;
+        use sqlx::Executor;
+
+        // 逐行读取，列名取自第一行
+        let mut count = 0u64;
+        let mut rows = sqlx::query(sql).fetch(pool);
+        while let Some(row) = rows.try_next().await.map_err(DbError::from)? {
+            if count == 0 {
+                let columns: Vec<String> =
+                    row.columns().iter().map(|c| c.name().to_string()).collect();
+                sink.columns(&columns)?;
+            }
+            let values = (0..row.columns().len())
+                .map(|idx| Self::column_value(&row, idx))
+                .collect();
+            sink.row(values)?;
+            count += 1;
+        }
+        drop(rows);
+
+        // 没有数据时通过语句描述获取列名
+        if count == 0 {
+            let columns: Vec<String> = pool
+                .describe(sql)
+                .await
+                .map(|d| d.columns().iter().map(|c| c.name().to_string()).collect())
+                .unwrap_or_default();
+            sink.columns(&columns)?;
+        }
+
+        Ok(count)
+    }
+
     /// 处理查询结果（PostgreSQL）
     fn process_query_result_pg(
         &self,
@@ -180,56 +310,7 @@ impl PostgreSqlDatabase {
             for (row_idx, row) in rows.iter().enumerate() {
                 let mut row_map = HashMap::new();
                 for (idx, column) in row.columns().iter().enumerate() {
-                    // 尝试多种数据类型获取
-                    let value = if let Ok(s) = row.try_get::<String, _>(idx) {
-                        serde_json::Value::String(s)
-                    } else if let Ok(i) = row.try_get::<i64, _>(idx) {
-                        serde_json::Value::Number(serde_json::Number::from(i))
-                    } else if let Ok(i) = row.try_get::<i32, _>(idx) {
-                        serde_json::Value::Number(serde_json::Number::from(i as i64))
-                    } else if let Ok(i) = row.try_get::<i16, _>(idx) {
-                        serde_json::Value::Number(serde_json::Number::from(i as i64))
-                    } else if let Ok(f) = row.try_get::<f64, _>(idx) {
-                        serde_json::Number::from_f64(f)
-                            .map(serde_json::Value::Number)
-                            .unwrap_or(serde_json::Value::Null)
-                    } else if let Ok(f) = row.try_get::<f32, _>(idx) {
-                        serde_json::Number::from_f64(f as f64)
-                            .map(serde_json::Value::Number)
-                            .unwrap_or(serde_json::Value::Null)
-                    } else if let Ok(b) = row.try_get::<bool, _>(idx) {
-                        serde_json::Value::Bool(b)
-                    } else if let Ok(Some(bytes)) = row.try_get::<Option<Vec<u8>>, _>(idx) {
-                        // 处理 bytea 等二进制数据
-                        match String::from_utf8(bytes.clone()) {
-                            Ok(s) => serde_json::Value::String(s),
-                            Err(_) => {
-                                let hex_string = bytes.iter()
-                                    .map(|b| format!("{:02x}", b))
-                                    .collect::<String>();
-                                serde_json::Value::String(format!("\\x{}", hex_string))
-                            }
-                        }
-                    } else if let Ok(s) = row.try_get::<chrono::NaiveDateTime, _>(idx) {
-                        serde_json::Value::String(s.to_string())
-                    } else if let Ok(s) = row.try_get::<chrono::NaiveDate, _>(idx) {
-                        serde_json::Value::String(s.to_string())
-                    } else if let Ok(s) = row.try_get::<chrono::NaiveTime, _>(idx) {
-                        serde_json::Value::String(s.to_string())
-                    } else if let Ok(s) = row.try_get::<chrono::DateTime<chrono::Utc>, _>(idx) {
-                        serde_json::Value::String(s.to_string())
-                    } else if let Ok(json) = row.try_get::<serde_json::Value, _>(idx) {
-                        json
-                    } else if let Ok(uuid) = row.try_get::<sqlx::types::Uuid, _>(idx) {
-                        serde_json::Value::String(uuid.to_string())
-                    } else if let Ok(None) = row.try_get::<Option<String>, _>(idx) {
-                        serde_json::Value::Null
-                    } else {
-                        match row.try_get::<Option<String>, _>(idx) {
-                            Ok(Some(s)) => serde_json::Value::String(s),
-                            _ => serde_json::Value::Null,
-                        }
-                    };
+                    let value = Self::column_value(row, idx);
                     
                     row_map.insert(column.name().to_string(), value.clone());
                     
@@ -299,7 +380,7 @@ impl PostgreSqlDatabase {
         .bind(&table_lower)
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         // 获取主键信息 - 同时尝试原始名称和小写名称
         let pk_rows = sqlx::query(
@@ -315,7 +396,7 @@ impl PostgreSqlDatabase {
         .bind(&table_lower)
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         let primary_keys: Vec<String> = pk_rows
             .iter()
@@ -352,12 +433,132 @@ impl PostgreSqlDatabase {
     }
 }
 
+impl Default for PostgreSqlDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// PostgreSQL 驱动
+pub struct PostgreSqlDriver;
+
+inventory::submit! {
+    DriverRegistration { factory: || Arc::new(PostgreSqlDriver) }
+}
+
+impl DatabaseDriver for PostgreSqlDriver {
+    fn db_type(&self) -> DatabaseType {
+        DatabaseType::PostgreSQL
+    }
+
+    fn display_name(&self) -> &'static str {
+        "PostgreSQL"
+    }
+
+    fn create(&self) -> DbResult<Box<dyn DatabaseOperations>> {
+        Ok(Box::new(PostgreSqlDatabase::new()))
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            sql: true,
+            schemas: true,
+            object_types: vec![
+                ObjectType::Table,
+                ObjectType::View,
+                ObjectType::Procedure,
+                ObjectType::Function,
+                ObjectType::Trigger,
+                ObjectType::Sequence,
+                ObjectType::Index,
+            ],
+            editing: EditingCapabilities::all(),
+            transactions: true,
+            explain_formats: vec![
+                ExplainFormat::Text,
+                ExplainFormat::Json,
+                ExplainFormat::Xml,
+                ExplainFormat::Yaml,
+            ],
+            export_formats: ExportFormat::for_sql(),
+            ..Default::default()
+        }
+    }
+
+    fn server_capabilities(&self, info: &ServerInfo) -> DriverCapabilities {
+        let mut capabilities = self.capabilities().with_server_features(&info.features);
+        if info.flavor == ServerFlavor::CockroachDB {
+            // CockroachDB 的 EXPLAIN 不支持 FORMAT 选项
+            capabilities.explain_formats = vec![ExplainFormat::Text];
+        }
+        capabilities
+    }
+
+    fn dialect(&self) -> Option<SqlDialect> {
+        Some(SqlDialect::postgresql())
+    }
+
+    fn default_schema(&self) -> Option<&'static str> {
+        Some("public")
+    }
+
+    fn binary_literal(&self, hex: &str) -> String {
+        format!("'\\x{}'::bytea", hex)
+    }
+
+    /// 语句出错后事务进入失效状态，只能回滚
+    fn error_aborts_transaction(&self) -> bool {
+        true
+    }
+
+    /// CASCADE 自动处理引用该表的外键
+    fn truncate_table_sql(&self, table_ref: &str) -> String {
+        format!("TRUNCATE TABLE {} CASCADE;", table_ref)
+    }
+
+    /// 列名为小写且没有 ROUTINE_COMMENT，查询在目标数据库中执行
+    fn list_routines_sql(&self, _database: &str, routine_type: &str) -> Option<String> {
+        Some(format!(
+            "SELECT routine_schema AS \"ROUTINE_SCHEMA\", routine_name AS \"ROUTINE_NAME\",
+                    routine_type AS \"ROUTINE_TYPE\"
+             FROM information_schema.routines
+             WHERE routine_type = {} AND routine_schema NOT IN ('pg_catalog', 'information_schema')
+             ORDER BY routine_schema, routine_name",
+            self.string_literal(routine_type)
+        ))
+    }
+
+    /// information_schema.triggers 每个触发事件一行，按触发器合并
+    fn list_triggers_sql(&self, _database: &str) -> Option<String> {
+        Some(
+            "SELECT trigger_name AS \"TRIGGER_NAME\",
+                    string_agg(event_manipulation, ', ') AS \"EVENT_MANIPULATION\",
+                    event_object_table AS \"EVENT_OBJECT_TABLE\", action_timing AS \"ACTION_TIMING\"
+             FROM information_schema.triggers
+             WHERE trigger_schema NOT IN ('pg_catalog', 'information_schema')
+             GROUP BY trigger_schema, trigger_name, event_object_table, action_timing
+             ORDER BY trigger_name"
+                .to_string(),
+        )
+    }
+
+    fn create_table_sql(&self, database: &str, schema: Option<&str>, table: &str, flavor: Option<ServerFlavor>) -> String {
+        // CockroachDB 原生支持 SHOW CREATE TABLE（返回 create_statement 列）
+        if flavor == Some(ServerFlavor::CockroachDB) {
+            return format!("SHOW CREATE TABLE {}", self.table_ref(database, schema, table));
+        }
+        format!(
+            "SELECT 'CREATE TABLE ' || schemaname || '.' || tablename || ' (' || array_to_string(array_agg(column_name || ' ' || data_type), ', ') || ')' as \"Create Table\" FROM pg_tables t JOIN information_schema.columns c ON c.table_name = t.tablename AND c.table_schema = t.schemaname WHERE t.schemaname = {} AND t.tablename = {} GROUP BY schemaname, tablename",
+            self.string_literal(schema.unwrap_or("public")),
+            self.string_literal(table)
+        )
+    }
+}
+
 #[async_trait]
 impl DatabaseOperations for PostgreSqlDatabase {
     async fn test_connection(&self, config: &ConnectionConfig) -> DbResult<bool> {
-        let connection_string = Self::build_connection_string(config);
-        
-        match PgPool::connect(&connection_string).await {
+        match Self::connect_pool(config).await {
             Ok(pool) => {
                 // 测试查询
                 let result = sqlx::query("SELECT 1")
@@ -368,19 +569,17 @@ impl DatabaseOperations for PostgreSqlDatabase {
                 
                 match result {
                     Ok(_) => Ok(true),
-                    Err(e) => Err(DbError::ConnectionFailed(e.to_string())),
+                    Err(e) => Err(DbError::from(e).connecting()),
                 }
             }
-            Err(e) => Err(DbError::ConnectionFailed(e.to_string())),
+            Err(e) => Err(DbError::from(e).connecting()),
         }
     }
 
     async fn connect(&mut self, config: ConnectionConfig) -> DbResult<()> {
-        let connection_string = Self::build_connection_string(&config);
-        
-        let pool = PgPool::connect(&connection_string)
+        let pool = Self::connect_pool(&config)
             .await
-            .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+            .map_err(|e| DbError::from(e).connecting())?;
         
         // 如果已经有连接，先关闭
         if let Some(old_pool) = self.pool.take() {
@@ -403,7 +602,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
             // 重新连接
             self.connect(new_config).await
         } else {
-            Err(DbError::ConnectionFailed("未连接到数据库".to_string()))
+            Err(DbError::ConnectionFailed(Msg::NotConnected.into()))
         }
     }
 
@@ -416,8 +615,6 @@ impl DatabaseOperations for PostgreSqlDatabase {
     }
 
     async fn execute_query(&self, sql: &str, database: Option<&str>) -> DbResult<QueryResult> {
-        let start = Instant::now();
-
         // PostgreSQL 中数据库切换需要重新连接
         // 如果指定了数据库且与当前连接的数据库不同，需要创建临时连接
         let pool = if let Some(db_name) = database {
@@ -426,23 +623,13 @@ impl DatabaseOperations for PostgreSqlDatabase {
                     // 创建临时连接到指定数据库
                     let temp_config = ConnectionConfig {
                         id: format!("temp_{}", config.id),
-                        name: config.name.clone(),
-                        db_type: config.db_type.clone(),
-                        host: config.host.clone(),
-                        port: config.port,
-                        username: config.username.clone(),
-                        password: config.password.clone(),
                         database: Some(db_name.to_string()),
-                        ssl: config.ssl,
-                        connection_timeout: config.connection_timeout,
-                        pool_size: config.pool_size,
+                        ..config.clone()
                     };
                     
-                    let connection_string = Self::build_connection_string(&temp_config);
-
-                    let temp_pool = PgPool::connect(&connection_string)
+                    let temp_pool = Self::connect_pool(&temp_config)
                         .await
-                        .map_err(|e| DbError::ConnectionFailed(format!("连接到数据库 {} 失败: {}", db_name, e)))?;
+                        .map_err(|e| DbError::from(e).connecting().context(Msg::ConnectToDatabaseFailed.with(&[&db_name])))?;
                     
                     // 使用临时连接执行查询
                     let result = self.execute_query_with_pool(&temp_pool, sql).await;
@@ -455,21 +642,86 @@ impl DatabaseOperations for PostgreSqlDatabase {
             
             // 如果是同一个数据库，使用当前连接
             self.pool.as_ref()
-                .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?
+                .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?
         } else {
             // 没有指定数据库，使用当前连接
             self.pool.as_ref()
-                .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?
+                .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?
         };
 
         self.execute_query_with_pool(pool, sql).await
     }
 
+    async fn stream_query(
+        &self,
+        sql: &str,
+        database: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> DbResult<u64> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        // 与 execute_query 相同，其他数据库需要临时连接
+        if let (Some(db_name), Some(config)) = (database, self.config.as_ref()) {
+            if config.database.as_deref() != Some(db_name) {
+                let temp_config = ConnectionConfig {
+                    id: format!("temp_{}", config.id),
+                    database: Some(db_name.to_string()),
+                    ..config.clone()
+                };
+                let temp_pool = Self::connect_pool(&temp_config)
+                    .await
+                    .map_err(|e| DbError::from(e).connecting().context(Msg::ConnectToDatabaseFailed.with(&[&db_name])))?;
+
+                let result = Self::stream_with_pool(&temp_pool, sql, sink).await;
+                temp_pool.close().await;
+                return result;
+            }
+        }
+
+        Self::stream_with_pool(pool, sql, sink).await
+    }
+
+    async fn begin_transaction(&self, database: Option<&str>) -> DbResult<Box<dyn Transaction>> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        // 其他数据库需要临时连接，随事务结束关闭
+        let mut temp_pool = None;
+        if let (Some(db_name), Some(config)) = (database, self.config.as_ref()) {
+            if config.database.as_deref() != Some(db_name) {
+                let temp_config = ConnectionConfig {
+                    id: format!("temp_{}", config.id),
+                    database: Some(db_name.to_string()),
+                    ..config.clone()
+                };
+                temp_pool = Some(
+                    Self::connect_pool(&temp_config)
+                        .await
+                        .map_err(|e| DbError::from(e).connecting().context(Msg::ConnectToDatabaseFailed.with(&[&db_name])))?,
+                );
+            }
+        }
+
+        let tx = temp_pool
+            .as_ref()
+            .unwrap_or(pool)
+            .begin()
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::BeginTransactionFailed))?;
+
+        Ok(Box::new(PgTransaction { tx, temp_pool }))
+    }
+
     async fn get_databases(&self) -> DbResult<Vec<DatabaseInfo>> {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         let rows = sqlx::query(
             "SELECT datname, pg_encoding_to_char(encoding) AS encoding, datcollate
@@ -479,7 +731,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
         )
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         let mut databases = Vec::new();
         for row in rows {
@@ -503,23 +755,13 @@ impl DatabaseOperations for PostgreSqlDatabase {
                     // 创建临时连接到指定数据库
                     let temp_config = ConnectionConfig {
                         id: format!("temp_{}", config.id),
-                        name: config.name.clone(),
-                        db_type: config.db_type.clone(),
-                        host: config.host.clone(),
-                        port: config.port,
-                        username: config.username.clone(),
-                        password: config.password.clone(),
                         database: Some(db_name.to_string()),
-                        ssl: config.ssl,
-                        connection_timeout: config.connection_timeout,
-                        pool_size: config.pool_size,
+                        ..config.clone()
                     };
                     
-                    let connection_string = Self::build_connection_string(&temp_config);
-                    
-                    let temp_pool = PgPool::connect(&connection_string)
+                    let temp_pool = Self::connect_pool(&temp_config)
                         .await
-                        .map_err(|e| DbError::ConnectionFailed(format!("连接到数据库 {} 失败: {}", db_name, e)))?;
+                        .map_err(|e| DbError::from(e).connecting().context(Msg::ConnectToDatabaseFailed.with(&[&db_name])))?;
                     
                     // 使用临时连接查询
                     // 使用 pg_class 和 pg_namespace 获取原始表名（保持大小写）
@@ -540,7 +782,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
                     )
                     .fetch_all(&temp_pool)
                     .await
-                    .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                    .map_err(DbError::from)?;
 
                     let mut tables = Vec::new();
                     for row in rows {
@@ -563,11 +805,11 @@ impl DatabaseOperations for PostgreSqlDatabase {
             
             // 如果是同一个数据库，使用当前连接
             self.pool.as_ref()
-                .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?
+                .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?
         } else {
             // 没有指定数据库，使用当前连接
             self.pool.as_ref()
-                .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?
+                .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?
         };
 
         // 查询表列表
@@ -589,7 +831,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
         )
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         let mut tables = Vec::new();
         for row in rows {
@@ -618,23 +860,13 @@ impl DatabaseOperations for PostgreSqlDatabase {
                     // 创建临时连接到指定数据库
                     let temp_config = ConnectionConfig {
                         id: format!("temp_{}", config.id),
-                        name: config.name.clone(),
-                        db_type: config.db_type.clone(),
-                        host: config.host.clone(),
-                        port: config.port,
-                        username: config.username.clone(),
-                        password: config.password.clone(),
                         database: Some(db_name.to_string()),
-                        ssl: config.ssl,
-                        connection_timeout: config.connection_timeout,
-                        pool_size: config.pool_size,
+                        ..config.clone()
                     };
                     
-                    let connection_string = Self::build_connection_string(&temp_config);
-                    
-                    let temp_pool = PgPool::connect(&connection_string)
+                    let temp_pool = Self::connect_pool(&temp_config)
                         .await
-                        .map_err(|e| DbError::ConnectionFailed(format!("连接到数据库 {} 失败: {}", db_name, e)))?;
+                        .map_err(|e| DbError::from(e).connecting().context(Msg::ConnectToDatabaseFailed.with(&[&db_name])))?;
                     
                     // 使用临时连接查询
                     // 使用 pg_class 和 pg_namespace 获取原始视图名（保持大小写）
@@ -655,7 +887,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
                     )
                     .fetch_all(&temp_pool)
                     .await
-                    .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                    .map_err(DbError::from)?;
 
                     let mut views = Vec::new();
                     for row in rows {
@@ -678,11 +910,11 @@ impl DatabaseOperations for PostgreSqlDatabase {
             
             // 如果是同一个数据库，使用当前连接
             self.pool.as_ref()
-                .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?
+                .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?
         } else {
             // 没有指定数据库，使用当前连接
             self.pool.as_ref()
-                .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?
+                .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?
         };
 
         // 查询视图列表
@@ -704,7 +936,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
         )
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         let mut views = Vec::new();
         for row in rows {
@@ -734,18 +966,17 @@ impl DatabaseOperations for PostgreSqlDatabase {
             if current_db == db_name {
                 // 同一个数据库，使用当前连接
                 self.pool.as_ref()
-                    .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?
+                    .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?
             } else {
                 // 不同的数据库，创建临时连接
                 let config = self.config.as_ref()
-                    .ok_or_else(|| DbError::ConnectionFailed("连接配置不存在".to_string()))?;
+                    .ok_or_else(|| DbError::ConnectionFailed(Msg::ConnectionConfigNotFound.into()))?;
                 
                 let mut temp_config = config.clone();
                 temp_config.database = Some(db_name.to_string());
                 
-                let temp_connection_string = Self::build_connection_string(&temp_config);
-                let temp_pool = PgPool::connect(&temp_connection_string).await
-                    .map_err(|e| DbError::ConnectionFailed(format!("连接数据库失败: {}", e)))?;
+                let temp_pool = Self::connect_pool(&temp_config).await
+                    .map_err(|e| DbError::from(e).connecting().context(Msg::AcquireConnectionFailed))?;
                 
                 // 使用临时连接执行查询
                 let result = self.get_table_structure_with_pool(&temp_pool, table, schema).await;
@@ -755,7 +986,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
         } else {
             // 没有指定数据库，使用当前连接
             self.pool.as_ref()
-                .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?
+                .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?
         };
 
         self.get_table_structure_with_pool(pool, table, schema).await
@@ -765,7 +996,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         let schema_name = schema.unwrap_or("public");
 
@@ -789,7 +1020,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
         .bind(schema_name)
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         // 按索引名分组
         let mut index_map: HashMap<String, IndexInfo> = HashMap::new();
@@ -818,7 +1049,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
     
     async fn get_table_options(&self, table: &str, schema: Option<&str>) -> DbResult<TableOptions> {
         let pool = self.pool.as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
         let schema_name = schema.unwrap_or("public");
 
         let row = sqlx::query(
@@ -831,7 +1062,7 @@ impl DatabaseOperations for PostgreSqlDatabase {
         .bind(schema_name)
         .fetch_optional(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         let comment = row.and_then(|r| r.try_get("comment").ok());
 
@@ -841,11 +1072,99 @@ impl DatabaseOperations for PostgreSqlDatabase {
             collation: None,
             comment,
             auto_increment: None,
+            extra: Default::default(),
         })
     }
     
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    async fn ping(&self) -> DbResult<()> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        sqlx::query("SELECT 1")
+            .execute(pool)
+            .await
+            .map_err(DbError::from)?;
+        Ok(())
+    }
+    
+    async fn server_info(&self) -> DbResult<ServerInfo> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        let row = sqlx::query(
+            "SELECT version(), current_user::text, current_setting('TimeZone'), \
+             current_setting('server_version')",
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(|e| DbError::from(e).context(Msg::ServerInfoFailed))?;
+
+        let version_string: String = row.try_get(0).unwrap_or_default();
+        let current_user: Option<String> = row.try_get(1).ok();
+        let time_zone: Option<String> = row.try_get(2).ok();
+        let server_version: String = row.try_get(3).unwrap_or_default();
+
+        // CockroachDB: "CockroachDB CCL v23.1.11 (x86_64-pc-linux-gnu, ...)"
+        let (flavor, version) = if version_string.contains("CockroachDB") {
+            let version = version_string
+                .split_whitespace()
+                .find(|part| part.starts_with('v'))
+                .unwrap_or_default()
+                .to_string();
+            (ServerFlavor::CockroachDB, version)
+        } else {
+            // server_version 形如 "16.1 (Debian 16.1-1.pgdg120+1)"
+            let version = server_version
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            (ServerFlavor::PostgreSQL, version)
+        };
+
+        let mut info = ServerInfo {
+            flavor,
+            version,
+            version_string,
+            current_user,
+            time_zone,
+            features: ServerFeatures::default(),
+        };
+
+        info.features = match flavor {
+            ServerFlavor::CockroachDB => ServerFeatures {
+                window_functions: true,
+                cte: true,
+                json: true,
+                returning: true,
+                upsert: true,
+                sequences: true,
+                check_constraints: true,
+                stored_procedures: info.version_at_least(23, 2),
+                triggers: info.version_at_least(24, 3),
+                events: false,
+                transactions: true,
+            },
+            _ => ServerFeatures {
+                window_functions: true,
+                cte: true,
+                json: info.version_at_least(9, 4),
+                returning: true,
+                upsert: info.version_at_least(9, 5),
+                sequences: true,
+                check_constraints: true,
+                stored_procedures: true,
+                triggers: true,
+                events: false,
+                transactions: true,
+            },
+        };
+
+        Ok(info)
     }
 }
 
@@ -859,33 +1178,25 @@ impl PostgreSqlDatabase {
         while let Some(ch) = chars.next() {
             match ch {
                 // 单行注释 --
-                '-' => {
-                    if chars.peek() == Some(&'-') {
-                        // 跳过直到行尾
-                        chars.next(); // 消耗第二个 -
-                        while let Some(&c) = chars.peek() {
-                            if c == '\n' {
-                                chars.next();
-                                break;
-                            }
+                '-' if chars.peek() == Some(&'-') => {
+                    // 跳过直到行尾
+                    chars.next(); // 消耗第二个 -
+                    while let Some(&c) = chars.peek() {
+                        if c == '\n' {
                             chars.next();
+                            break;
                         }
-                    } else {
-                        current_statement.push(ch);
+                        chars.next();
                     }
                 }
                 // 多行注释 /* */
-                '/' => {
-                    if chars.peek() == Some(&'*') {
-                        chars.next(); // 消耗 *
-                        while let Some(c) = chars.next() {
-                            if c == '*' && chars.peek() == Some(&'/') {
-                                chars.next(); // 消耗 /
-                                break;
-                            }
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next(); // 消耗 *
+                    while let Some(c) = chars.next() {
+                        if c == '*' && chars.peek() == Some(&'/') {
+                            chars.next(); // 消耗 /
+                            break;
                         }
-                    } else {
-                        current_statement.push(ch);
                     }
                 }
                 // 单引号字符串
@@ -945,7 +1256,7 @@ impl PostgreSqlDatabase {
                         let end_tag = tag.clone();
                         let mut content = String::new();
                         
-                        while let Some(c) = chars.next() {
+                        for c in chars.by_ref() {
                             content.push(c);
                             if content.ends_with(&end_tag) {
                                 break;
@@ -978,4 +1289,45 @@ impl PostgreSqlDatabase {
         
         statements
     }
-}
\ No newline at end of file
+}
+
+/// PostgreSQL 事务
+struct PgTransaction {
+    tx: sqlx::Transaction<'static, Postgres>,
+    /// 访问其他数据库时使用的临时连接池
+    temp_pool: Option<PgPool>,
+}
+
+impl PgTransaction {
+    async fn close(temp_pool: Option<PgPool>) {
+        if let Some(pool) = temp_pool {
+            pool.close().await;
+        }
+    }
+}
+
+#[async_trait]
+impl Transaction for PgTransaction {
+    async fn execute(&mut self, sql: &str) -> DbResult<u64> {
+        use sqlx::Executor;
+
+        let result = (&mut *self.tx).execute(sql).await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn commit(self: Box<Self>) -> DbResult<()> {
+        let result = self
+            .tx
+            .commit()
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::CommitFailed));
+        PgTransaction::close(self.temp_pool).await;
+        result
+    }
+
+    async fn rollback(self: Box<Self>) -> DbResult<()> {
+        let result = self.tx.rollback().await;
+        PgTransaction::close(self.temp_pool).await;
+        Ok(result?)
+    }
+}
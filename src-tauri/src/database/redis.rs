@@ -1,6 +1,11 @@
+use super::registry::{DatabaseDriver, DriverCapabilities, DriverRegistration, ExportFormat, ObjectType};
 use super::traits::*;
+use crate::utils::i18n::Msg;
 use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
 use redis::{aio::MultiplexedConnection, Client};
+use std::collections::HashMap;
+use std::sync::Arc;
 use url::Url;
 
 /// Redis 数据库连接
@@ -43,6 +48,44 @@ impl RedisDatabase {
     }
 }
 
+impl Default for RedisDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Redis 驱动
+pub struct RedisDriver;
+
+inventory::submit! {
+    DriverRegistration { factory: || Arc::new(RedisDriver) }
+}
+
+impl DatabaseDriver for RedisDriver {
+    fn db_type(&self) -> DatabaseType {
+        DatabaseType::Redis
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Redis"
+    }
+
+    fn create(&self) -> DbResult<Box<dyn DatabaseOperations>> {
+        Ok(Box::new(RedisDatabase::new()))
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            key_value: true,
+            object_types: vec![ObjectType::Key],
+            // MULTI / EXEC
+            transactions: true,
+            export_formats: ExportFormat::for_results(),
+            ..Default::default()
+        }
+    }
+}
+
 #[async_trait]
 impl DatabaseOperations for RedisDatabase {
     async fn test_connection(&self, config: &ConnectionConfig) -> DbResult<bool> {
@@ -50,18 +93,18 @@ impl DatabaseOperations for RedisDatabase {
         
         // 创建客户端
         let client = Client::open(url)
-            .map_err(|e| DbError::ConnectionFailed(format!("创建 Redis 客户端失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).connecting().context(Msg::CreateClientFailed.with(&[&"Redis"])))?;
         
         // 获取连接
         let mut conn = client.get_multiplexed_async_connection()
             .await
-            .map_err(|e| DbError::ConnectionFailed(format!("连接 Redis 失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).connecting().context(Msg::ConnectToFailed.with(&[&"Redis"])))?;
         
         // 发送 PING 命令测试连接
         let _: String = redis::cmd("PING")
             .query_async(&mut conn)
             .await
-            .map_err(|e| DbError::ConnectionFailed(format!("PING 命令失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).connecting().context(Msg::PingFailed))?;
         
         Ok(true)
     }
@@ -71,12 +114,22 @@ impl DatabaseOperations for RedisDatabase {
         
         // 创建客户端
         let client = Client::open(url)
-            .map_err(|e| DbError::ConnectionFailed(format!("创建 Redis 客户端失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).connecting().context(Msg::CreateClientFailed.with(&[&"Redis"])))?;
         
         // 获取连接
-        let conn = client.get_multiplexed_async_connection()
+        let mut conn = client.get_multiplexed_async_connection()
             .await
-            .map_err(|e| DbError::ConnectionFailed(format!("连接 Redis 失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).connecting().context(Msg::ConnectToFailed.with(&[&"Redis"])))?;
+        
+        // 设置客户端名称（名称中不能包含空格）
+        if let Some(name) = session_setting(&config.application_name) {
+            let _: () = redis::cmd("CLIENT")
+                .arg("SETNAME")
+                .arg(name.replace(char::is_whitespace, "_"))
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| DbError::from(e).connecting().context(Msg::SetClientNameFailed))?;
+        }
         
         self.connection = Some(conn);
         self.config = Some(config);
@@ -91,7 +144,7 @@ impl DatabaseOperations for RedisDatabase {
     }
 
     async fn execute_query(&self, _sql: &str, _database: Option<&str>) -> DbResult<QueryResult> {
-        Err(DbError::Other("Redis 不支持 SQL 查询，请使用 Redis 命令".to_string()))
+        Err(DbError::Other(Msg::SqlNotSupported.with(&[&"Redis", &Msg::RedisCommands])))
     }
 
     async fn get_databases(&self) -> DbResult<Vec<DatabaseInfo>> {
@@ -111,7 +164,7 @@ impl DatabaseOperations for RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         // 克隆连接以供使用（MultiplexedConnection 实现了 Clone）
         let mut conn = conn.clone();
@@ -123,7 +176,7 @@ impl DatabaseOperations for RedisDatabase {
                     .arg(db_num)
                     .query_async(&mut conn)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("切换数据库失败: {}", e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::SwitchDatabaseFailed))?;
             }
         }
         
@@ -133,45 +186,41 @@ impl DatabaseOperations for RedisDatabase {
             .arg("*")
             .query_async(&mut conn)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("获取键列表失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::ListKeysFailed))?;
         
         // 按键的前缀分组统计
-        let mut key_groups: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
+        let mut key_groups: std::collections::HashMap<Option<String>, u64> = std::collections::HashMap::new();
         
         for key in &keys {
             // 提取键的前缀（以 : 分隔）
-            let prefix = if let Some(pos) = key.find(':') {
-                &key[..pos]
-            } else {
-                "(无前缀)"
-            };
+            // 没有前缀的键归入 None 分组
+            let prefix = key.find(':').map(|pos| key[..pos].to_string());
             
-            *key_groups.entry(prefix.to_string()).or_insert(0) += 1;
+            *key_groups.entry(prefix).or_insert(0) += 1;
         }
         
         // 转换为 TableInfo 格式
         let mut tables: Vec<TableInfo> = key_groups
             .into_iter()
             .map(|(prefix, count)| TableInfo {
-                name: if prefix == "(无前缀)" {
-                    format!("{} ({} 个键)", prefix, count)
-                } else {
-                    format!("{}:* ({} 个键)", prefix, count)
+                name: match prefix {
+                    Some(prefix) => Msg::KeyGroupName.with(&[&format!("{}:*", prefix), &count]),
+                    None => Msg::KeyGroupName.with(&[&Msg::NoKeyPrefix, &count]),
                 },
                 schema: database.map(|s| s.to_string()),
                 table_type: "KEY_GROUP".to_string(),
                 engine: Some("Redis".to_string()),
                 rows: Some(count),
                 size_mb: None,
-                comment: Some(format!("键前缀分组，共 {} 个键", count)),
+                comment: Some(Msg::KeyGroupComment.with(&[&count])),
             })
             .collect();
         
         // 按键数量降序排序
-        tables.sort_by(|a, b| b.rows.unwrap_or(0).cmp(&a.rows.unwrap_or(0)));
+        tables.sort_by_key(|t| std::cmp::Reverse(t.rows.unwrap_or(0)));
         
         // 如果键总数不多（比如少于 20 个），也可以直接显示所有键
-        if keys.len() <= 20 && keys.len() > 0 {
+        if keys.len() <= 20 && !keys.is_empty() {
             tables.clear();
             for key in keys {
                 // 获取键的类型
@@ -214,11 +263,63 @@ impl DatabaseOperations for RedisDatabase {
             collation: None,
             comment: None,
             auto_increment: None,
+            extra: Default::default(),
         })
     }
     
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    async fn ping(&self) -> DbResult<()> {
+        let conn = self
+            .connection
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
+
+        let mut conn = conn.clone();
+        let _: String = redis::cmd("PING")
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::PingFailed))?;
+        Ok(())
+    }
+    
+    async fn server_info(&self) -> DbResult<ServerInfo> {
+        let info = self.get_server_info().await?;
+
+        // Valkey 在 INFO server 中返回 server_name:valkey 和 valkey_version
+        let (flavor, version) = match info.get("valkey_version") {
+            Some(version) => (ServerFlavor::Valkey, version.clone()),
+            None => (
+                ServerFlavor::Redis,
+                info.get("redis_version").cloned().unwrap_or_default(),
+            ),
+        };
+
+        // ACL WHOAMI 需要 Redis 6.0+
+        let current_user = match self.execute_command("ACL", vec!["WHOAMI".to_string()]).await {
+            Ok(redis::Value::BulkString(bytes)) => Some(String::from_utf8_lossy(&bytes).to_string()),
+            Ok(redis::Value::SimpleString(user)) => Some(user),
+            _ => None,
+        };
+
+        let name = match flavor {
+            ServerFlavor::Valkey => "Valkey",
+            _ => "Redis",
+        };
+
+        Ok(ServerInfo {
+            flavor,
+            version_string: format!("{} {}", name, version),
+            version,
+            current_user,
+            time_zone: None,
+            features: ServerFeatures {
+                transactions: true,
+                ..Default::default()
+            },
+        })
+    }
+
+    fn as_key_value(&self) -> Option<&dyn KeyValueOperations> {
+        Some(self)
     }
 }
 
@@ -228,7 +329,7 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
@@ -239,22 +340,22 @@ impl RedisDatabase {
         
         cmd.query_async(&mut conn)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("执行命令失败: {}", e)))
+            .map_err(|e| DbError::from(e).context(Msg::ExecuteCommandFailed))
     }
     
     /// 获取 Redis 服务器信息
-    pub async fn get_server_info(&self) -> DbResult<std::collections::HashMap<String, String>> {
+    pub async fn get_server_info(&self) -> DbResult<HashMap<String, String>> {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
         let info: String = redis::cmd("INFO")
             .query_async(&mut conn)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("获取服务器信息失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::ServerInfoFailed))?;
         
         // 解析 INFO 命令的输出
         let mut result = std::collections::HashMap::new();
@@ -280,7 +381,7 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
@@ -289,31 +390,31 @@ impl RedisDatabase {
             .arg(key)
             .query_async(&mut conn)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("获取键类型失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::GetKeyTypeFailed))?;
         
         match key_type.as_str() {
             "string" => {
                 let value: String = conn.get(key)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("获取字符串值失败: {}", e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::GetValueFailed))?;
                 Ok(redis::Value::BulkString(value.into_bytes()))
             }
             "list" => {
                 let values: Vec<String> = conn.lrange(key, 0, -1)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("获取列表值失败: {}", e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::GetValueFailed))?;
                 Ok(redis::Value::Array(values.into_iter().map(|v| redis::Value::BulkString(v.into_bytes())).collect()))
             }
             "set" => {
                 let values: Vec<String> = conn.smembers(key)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("获取集合值失败: {}", e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::GetValueFailed))?;
                 Ok(redis::Value::Array(values.into_iter().map(|v| redis::Value::BulkString(v.into_bytes())).collect()))
             }
             "zset" => {
                 let values: Vec<(String, f64)> = conn.zrange_withscores(key, 0, -1)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("获取有序集合值失败: {}", e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::GetValueFailed))?;
                 
                 let formatted: Vec<redis::Value> = values.into_iter()
                     .map(|(member, score)| {
@@ -329,7 +430,7 @@ impl RedisDatabase {
             "hash" => {
                 let values: std::collections::HashMap<String, String> = conn.hgetall(key)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("获取哈希值失败: {}", e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::GetValueFailed))?;
                 
                 let formatted: Vec<redis::Value> = values.into_iter()
                     .flat_map(|(k, v)| vec![
@@ -351,18 +452,18 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
         if let Some(ttl_seconds) = ttl {
             conn.set_ex::<_, _, ()>(key, value, ttl_seconds)
                 .await
-                .map_err(|e| DbError::QueryFailed(format!("设置键值失败: {}", e)))?;
+                .map_err(|e| DbError::from(e).context(Msg::SetValueFailed))?;
         } else {
             conn.set::<_, _, ()>(key, value)
                 .await
-                .map_err(|e| DbError::QueryFailed(format!("设置键值失败: {}", e)))?;
+                .map_err(|e| DbError::from(e).context(Msg::SetValueFailed))?;
         }
         
         Ok(())
@@ -375,13 +476,13 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
         conn.del::<_, ()>(key)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("删除键失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::DeleteKeyFailed))?;
         
         Ok(())
     }
@@ -391,7 +492,7 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
@@ -399,7 +500,7 @@ impl RedisDatabase {
             .arg(key)
             .query_async(&mut conn)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("获取 TTL 失败: {}", e)))
+            .map_err(|e| DbError::from(e).context(Msg::GetTtlFailed))
     }
     
     /// 设置 List 类型的值
@@ -409,20 +510,20 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
         // 先删除旧值
         conn.del::<_, ()>(key)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("删除旧值失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::DeleteOldValueFailed))?;
         
         // 如果有新值，则设置
         if !values.is_empty() {
             conn.rpush::<_, _, ()>(key, values)
                 .await
-                .map_err(|e| DbError::QueryFailed(format!("设置 List 值失败: {}", e)))?;
+                .map_err(|e| DbError::from(e).context(Msg::SetValueFailed))?;
         }
         
         Ok(())
@@ -435,20 +536,20 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
         // 先删除旧值
         conn.del::<_, ()>(key)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("删除旧值失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::DeleteOldValueFailed))?;
         
         // 如果有新值，则设置
         if !members.is_empty() {
             conn.sadd::<_, _, ()>(key, members)
                 .await
-                .map_err(|e| DbError::QueryFailed(format!("设置 Set 值失败: {}", e)))?;
+                .map_err(|e| DbError::from(e).context(Msg::SetValueFailed))?;
         }
         
         Ok(())
@@ -461,21 +562,21 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
         // 先删除旧值
         conn.del::<_, ()>(key)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("删除旧值失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::DeleteOldValueFailed))?;
         
         // 如果有新值，则设置
         if !members.is_empty() {
             for (member, score) in members {
                 conn.zadd::<_, _, _, ()>(key, score, member)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("设置 ZSet 值失败: {}", e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::SetValueFailed))?;
             }
         }
         
@@ -489,20 +590,20 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
         // 先删除旧值
         conn.del::<_, ()>(key)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("删除旧值失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::DeleteOldValueFailed))?;
         
         // 如果有新值，则设置
         if !fields.is_empty() {
             conn.hset_multiple::<_, _, _, ()>(key, &fields.iter().map(|(f, v)| (f.as_str(), v.as_str())).collect::<Vec<_>>())
                 .await
-                .map_err(|e| DbError::QueryFailed(format!("设置 Hash 值失败: {}", e)))?;
+                .map_err(|e| DbError::from(e).context(Msg::SetValueFailed))?;
         }
         
         Ok(())
@@ -515,19 +616,19 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
         if ttl > 0 {
             conn.expire::<_, ()>(key, ttl)
                 .await
-                .map_err(|e| DbError::QueryFailed(format!("设置 TTL 失败: {}", e)))?;
+                .map_err(|e| DbError::from(e).context(Msg::SetTtlFailed))?;
         } else if ttl == -1 {
             // 移除过期时间，设置为永不过期
             conn.persist::<_, ()>(key)
                 .await
-                .map_err(|e| DbError::QueryFailed(format!("移除 TTL 失败: {}", e)))?;
+                .map_err(|e| DbError::from(e).context(Msg::RemoveTtlFailed))?;
         }
         
         Ok(())
@@ -540,15 +641,194 @@ impl RedisDatabase {
         let conn = self
             .connection
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到 Redis".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnectedTo.with(&[&"Redis"])))?;
         
         let mut conn = conn.clone();
         
         conn.rename::<_, _, ()>(old_key, new_key)
             .await
-            .map_err(|e| DbError::QueryFailed(format!("重命名失败: {}", e)))?;
+            .map_err(|e| DbError::from(e).context(Msg::RenameFailed))?;
         
         Ok(())
     }
 }
 
+// 命令层通过 KeyValueOperations 访问 Redis，值统一转换为 JSON
+#[async_trait]
+impl KeyValueOperations for RedisDatabase {
+    async fn execute_command(&self, command: &str, args: Vec<String>) -> DbResult<serde_json::Value> {
+        RedisDatabase::execute_command(self, command, args)
+            .await
+            .map(redis_value_to_json)
+    }
+
+    async fn get_server_info(&self) -> DbResult<HashMap<String, String>> {
+        RedisDatabase::get_server_info(self).await
+    }
+
+    async fn get_key_value(&self, key: &str) -> DbResult<serde_json::Value> {
+        RedisDatabase::get_key_value(self, key)
+            .await
+            .map(redis_value_to_json)
+    }
+
+    async fn get_key_ttl(&self, key: &str) -> DbResult<i64> {
+        RedisDatabase::get_key_ttl(self, key).await
+    }
+
+    async fn set_key_value(&self, key: &str, value: &str, ttl: Option<u64>) -> DbResult<()> {
+        RedisDatabase::set_key_value(self, key, value, ttl).await
+    }
+
+    async fn delete_key(&self, key: &str) -> DbResult<()> {
+        RedisDatabase::delete_key(self, key).await
+    }
+
+    async fn set_list_value(&self, key: &str, values: Vec<String>) -> DbResult<()> {
+        RedisDatabase::set_list_value(self, key, values).await
+    }
+
+    async fn set_set_value(&self, key: &str, members: Vec<String>) -> DbResult<()> {
+        RedisDatabase::set_set_value(self, key, members).await
+    }
+
+    async fn set_zset_value(&self, key: &str, members: Vec<(String, f64)>) -> DbResult<()> {
+        RedisDatabase::set_zset_value(self, key, members).await
+    }
+
+    async fn set_hash_value(&self, key: &str, fields: Vec<(String, String)>) -> DbResult<()> {
+        RedisDatabase::set_hash_value(self, key, fields).await
+    }
+
+    async fn set_key_ttl(&self, key: &str, ttl: i64) -> DbResult<()> {
+        RedisDatabase::set_key_ttl(self, key, ttl).await
+    }
+
+    async fn rename_key(&self, old_key: &str, new_key: &str) -> DbResult<()> {
+        RedisDatabase::rename_key(self, old_key, new_key).await
+    }
+}
+
+/// 将 Redis Value 转换为 JSON
+fn redis_value_to_json(value: redis::Value) -> serde_json::Value {
+    match value {
+        redis::Value::Nil => serde_json::Value::Null,
+        redis::Value::Int(i) => serde_json::json!(i),
+        redis::Value::BulkString(data) => {
+            // 尝试将字节转换为字符串
+            match String::from_utf8(data.clone()) {
+                Ok(s) => serde_json::Value::String(s),
+                Err(_) => {
+                    // 如果不是有效的 UTF-8，返回 base64 编码
+                    serde_json::Value::String(general_purpose::STANDARD.encode(&data))
+                }
+            }
+        }
+        redis::Value::Array(values) => {
+            let arr: Vec<serde_json::Value> = values.into_iter().map(redis_value_to_json).collect();
+            serde_json::Value::Array(arr)
+        }
+        redis::Value::SimpleString(s) => serde_json::Value::String(s),
+        redis::Value::Okay => serde_json::Value::String("OK".to_string()),
+        redis::Value::Map(map) => {
+            let obj: serde_json::Map<String, serde_json::Value> = map
+                .into_iter()
+                .map(|(k, v)| {
+                    let key = match k {
+                        redis::Value::BulkString(bytes) => {
+                            String::from_utf8_lossy(&bytes).to_string()
+                        }
+                        redis::Value::SimpleString(s) => s,
+                        _ => format!("{:?}", k),
+                    };
+                    (key, redis_value_to_json(v))
+                })
+                .collect();
+            serde_json::Value::Object(obj)
+        }
+        redis::Value::Attribute {
+            data,
+            attributes: _,
+        } => {
+            // For attributes, just return the data part
+            redis_value_to_json(*data)
+        }
+        redis::Value::Set(values) => {
+            let arr: Vec<serde_json::Value> = values.into_iter().map(redis_value_to_json).collect();
+            serde_json::Value::Array(arr)
+        }
+        redis::Value::Double(f) => serde_json::json!(f),
+        redis::Value::Boolean(b) => serde_json::json!(b),
+        redis::Value::VerbatimString { format: _, text } => serde_json::Value::String(text),
+        redis::Value::BigNumber(n) => serde_json::Value::String(format!("{}", n)),
+        redis::Value::Push { kind: _, data } => {
+            let arr: Vec<serde_json::Value> = data.into_iter().map(redis_value_to_json).collect();
+            serde_json::Value::Array(arr)
+        }
+        redis::Value::ServerError(err) => {
+            serde_json::json!({
+                "error": true,
+                "message": err.details().unwrap_or("Unknown error"),
+                "kind": format!("{:?}", err.kind())
+            })
+        }
+        _ => serde_json::json!({
+            "error": true,
+            "message": "Unsupported redis value type",
+            "debug": format!("{:?}", value)
+        }),
+    }
+}
+
+/// 按 Redis 错误前缀推断错误码
+fn classify_error_code(code: &str, detail: &str) -> Option<ErrorCode> {
+    let detail = detail.to_lowercase();
+    let code = match code {
+        "NOAUTH" | "WRONGPASS" => ErrorCode::AuthFailed,
+        "NOPERM" => ErrorCode::PermissionDenied,
+        "WRONGTYPE" => ErrorCode::ConstraintViolation,
+        "ERR" if detail.contains("unknown command")
+            || detail.contains("wrong number of arguments")
+            || detail.contains("syntax error") =>
+        {
+            ErrorCode::SyntaxError
+        }
+        "BUSY" => ErrorCode::Timeout,
+        _ => return None,
+    };
+    Some(code)
+}
+
+impl From<redis::RedisError> for DbError {
+    fn from(e: redis::RedisError) -> Self {
+        if e.is_timeout() {
+            return DbError::Timeout(e.to_string());
+        }
+        if e.is_connection_refusal() {
+            return DbError::ConnectionFailed(e.to_string());
+        }
+        if e.is_connection_dropped() || e.is_io_error() {
+            return DbError::ConnectionLost(e.to_string());
+        }
+        if e.kind() == redis::ErrorKind::AuthenticationFailed {
+            let native = NativeError {
+                kind: e.code().map(String::from),
+                ..Default::default()
+            };
+            return DbError::database(ErrorCode::AuthFailed, e.to_string(), native);
+        }
+
+        match e.code() {
+            Some(code) => {
+                let native = NativeError {
+                    kind: Some(code.to_string()),
+                    ..Default::default()
+                };
+                let code = classify_error_code(code, e.detail().unwrap_or_default())
+                    .unwrap_or(ErrorCode::QueryFailed);
+                DbError::database(code, e.to_string(), native)
+            }
+            None => DbError::QueryFailed(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,440 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use super::sql_splitter::SqlDialect;
+use super::traits::*;
+use crate::utils::sql_formatter::SqlFormatter;
+
+/// 数据库对象类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectType {
+    Table,
+    View,
+    Procedure,
+    Function,
+    Trigger,
+    Event,
+    Sequence,
+    Index,
+    Collection,
+    Key,
+}
+
+/// 执行计划输出格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExplainFormat {
+    Text,
+    Json,
+    Tree,
+    Xml,
+    Yaml,
+}
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    /// 对象数组
+    Json,
+    /// 每行一个 JSON 对象
+    Jsonl,
+    /// `{columns, rows}`，行是按列顺序的数组
+    #[serde(rename = "json_columns")]
+    JsonColumns,
+    Xml,
+    /// Markdown 表格
+    Markdown,
+    /// 独立的 HTML 页面
+    Html,
+    /// INSERT 语句
+    Sql,
+    /// 建表语句
+    Ddl,
+    /// Excel 工作簿
+    Xlsx,
+    Parquet,
+    /// Arrow IPC 文件
+    Arrow,
+}
+
+impl ExportFormat {
+    /// 支持 SQL 的数据库可用的导出格式
+    pub fn for_sql() -> Vec<ExportFormat> {
+        vec![
+            ExportFormat::Csv,
+            ExportFormat::Json,
+            ExportFormat::Jsonl,
+            ExportFormat::JsonColumns,
+            ExportFormat::Xml,
+            ExportFormat::Markdown,
+            ExportFormat::Html,
+            ExportFormat::Xlsx,
+            ExportFormat::Parquet,
+            ExportFormat::Arrow,
+            ExportFormat::Sql,
+            ExportFormat::Ddl,
+        ]
+    }
+
+    /// 非 SQL 数据库只能导出查询结果
+    pub fn for_results() -> Vec<ExportFormat> {
+        vec![
+            ExportFormat::Csv,
+            ExportFormat::Json,
+            ExportFormat::Jsonl,
+            ExportFormat::JsonColumns,
+            ExportFormat::Xml,
+            ExportFormat::Markdown,
+            ExportFormat::Html,
+            ExportFormat::Xlsx,
+            ExportFormat::Parquet,
+            ExportFormat::Arrow,
+        ]
+    }
+
+    /// 一个文件能否保存多个结果集（例如每条语句一个工作表）
+    pub fn multi_result(&self) -> bool {
+        matches!(self, ExportFormat::Xlsx)
+    }
+}
+
+/// 数据编辑能力
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EditingCapabilities {
+    pub insert: bool,
+    pub update: bool,
+    pub delete: bool,
+    pub truncate: bool,
+    pub create_table: bool,
+    pub alter_table: bool,
+    pub drop_table: bool,
+}
+
+impl EditingCapabilities {
+    /// 支持全部编辑操作
+    pub fn all() -> Self {
+        Self {
+            insert: true,
+            update: true,
+            delete: true,
+            truncate: true,
+            create_table: true,
+            alter_table: true,
+            drop_table: true,
+        }
+    }
+}
+
+/// 驱动能力（命令层据此选择处理方式，而不是判断具体驱动类型）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DriverCapabilities {
+    /// 支持通过 execute_query 执行 SQL
+    pub sql: bool,
+    /// 对象位于 schema 之下（PostgreSQL、SQL Server、DuckDB）
+    pub schemas: bool,
+    /// 数据库是本地文件（SQLite、DuckDB）
+    pub file_based: bool,
+    /// 提供键值扩展操作（见 KeyValueOperations）
+    pub key_value: bool,
+    /// 提供搜索引擎扩展操作（见 SearchOperations）
+    pub search: bool,
+    /// 可浏览的对象类型
+    pub object_types: Vec<ObjectType>,
+    /// 数据编辑操作
+    pub editing: EditingCapabilities,
+    /// 支持事务
+    pub transactions: bool,
+    /// 支持的执行计划格式
+    pub explain_formats: Vec<ExplainFormat>,
+    /// 支持的导出格式
+    pub export_formats: Vec<ExportFormat>,
+}
+
+impl DriverCapabilities {
+    /// 是否支持某类对象
+    pub fn supports_object(&self, object_type: ObjectType) -> bool {
+        self.object_types.contains(&object_type)
+    }
+
+    /// 按服务器特性去掉实际不可用的对象类型（例如 TiDB 没有存储过程）
+    pub fn with_server_features(mut self, features: &ServerFeatures) -> Self {
+        self.object_types.retain(|object_type| match object_type {
+            ObjectType::Procedure | ObjectType::Function => features.stored_procedures,
+            ObjectType::Trigger => features.triggers,
+            ObjectType::Event => features.events,
+            ObjectType::Sequence => features.sequences,
+            _ => true,
+        });
+        self.transactions &= features.transactions;
+        self
+    }
+}
+
+/// 数据库驱动
+///
+/// 每个后端实现一个驱动并注册到 DriverRegistry，负责创建连接实例以及描述方言相关的信息
+pub trait DatabaseDriver: Send + Sync {
+    /// 驱动对应的数据库类型
+    fn db_type(&self) -> DatabaseType;
+
+    /// 显示名称
+    fn display_name(&self) -> &'static str;
+
+    /// 创建未连接的实例
+    fn create(&self) -> DbResult<Box<dyn DatabaseOperations>>;
+
+    /// 驱动能力
+    fn capabilities(&self) -> DriverCapabilities;
+
+    /// 结合服务器信息的能力（默认按服务器特性过滤，产品分支差异较大的驱动可覆盖）
+    fn server_capabilities(&self, info: &ServerInfo) -> DriverCapabilities {
+        self.capabilities().with_server_features(&info.features)
+    }
+
+    /// 拆分脚本使用的 SQL 方言，不支持 SQL 时返回 None
+    fn dialect(&self) -> Option<SqlDialect> {
+        None
+    }
+
+    /// 引用标识符（默认按标准 SQL 使用双引号）
+    fn quote_identifier(&self, identifier: &str) -> String {
+        SqlFormatter::quote_standard(identifier)
+    }
+
+    /// 未指定 schema 时使用的默认 schema，没有 schema 的数据库返回 None
+    fn default_schema(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// 表、视图的限定名
+    ///
+    /// 默认有 schema 的数据库使用 `schema.table`，否则使用 `database.table`
+    fn table_ref(&self, database: &str, schema: Option<&str>, table: &str) -> String {
+        let qualifier = match self.default_schema() {
+            Some(default) => schema.unwrap_or(default),
+            None => database,
+        };
+        format!("{}.{}", self.quote_identifier(qualifier), self.quote_identifier(table))
+    }
+
+    /// 存储过程、函数、触发器等例程对象的限定名（默认与表相同）
+    fn routine_ref(&self, database: &str, schema: Option<&str>, name: &str) -> String {
+        self.table_ref(database, schema, name)
+    }
+
+    /// 字符串字面量（默认按标准 SQL 只转义单引号）
+    fn string_literal(&self, value: &str) -> String {
+        SqlFormatter::string_standard(value)
+    }
+
+    /// 布尔字面量
+    fn bool_literal(&self, value: bool) -> &'static str {
+        if value { "TRUE" } else { "FALSE" }
+    }
+
+    /// 十六进制二进制字面量
+    fn binary_literal(&self, hex: &str) -> String {
+        format!("X'{}'", hex)
+    }
+
+    /// 查询结果中的二进制列是否以 Base64 文本返回
+    fn binary_as_base64(&self) -> bool {
+        false
+    }
+
+    /// 是否为数据库内部使用的系统表（导出、转储时跳过）
+    fn is_system_table(&self, _table: &str) -> bool {
+        false
+    }
+
+    /// DDL 能否在事务中回滚
+    fn transactional_ddl(&self) -> bool {
+        true
+    }
+
+    /// 语句出错后事务是否失效（需要回滚到保存点才能继续）
+    fn error_aborts_transaction(&self) -> bool {
+        false
+    }
+
+    /// 写入自增列前后需要执行的语句（例如 SQL Server 的 IDENTITY_INSERT）
+    fn identity_insert_sql(&self, _table_ref: &str, _enable: bool) -> Option<String> {
+        None
+    }
+
+    /// 脚本是否使用 GO 分隔批次
+    fn batch_separator(&self) -> bool {
+        false
+    }
+
+    /// 一条 INSERT 最多包含的行数
+    fn max_insert_rows(&self) -> Option<usize> {
+        None
+    }
+
+    /// 更新语句（`assignments` 为已格式化的 `col = value` 列表）
+    fn update_sql(&self, table_ref: &str, assignments: &str, where_clause: &str) -> String {
+        format!("UPDATE {} SET {} WHERE {}", table_ref, assignments, where_clause)
+    }
+
+    /// 查看表数据的查询
+    fn select_rows_sql(&self, table_ref: &str, limit: Option<u32>) -> String {
+        match limit {
+            Some(limit) => format!("SELECT * FROM {} LIMIT {}", table_ref, limit),
+            None => format!("SELECT * FROM {}", table_ref),
+        }
+    }
+
+    /// 清空表的脚本
+    fn truncate_table_sql(&self, table_ref: &str) -> String {
+        format!("TRUNCATE TABLE {};", table_ref)
+    }
+
+    /// 列出存储过程或函数的查询（`routine_type` 为 PROCEDURE 或 FUNCTION），不支持时返回 None
+    fn list_routines_sql(&self, _database: &str, _routine_type: &str) -> Option<String> {
+        None
+    }
+
+    /// 列出触发器的查询，不支持时返回 None
+    fn list_triggers_sql(&self, _database: &str) -> Option<String> {
+        None
+    }
+
+    /// 查询视图定义的语句，结果列为 VIEW_DEFINITION
+    fn view_definition_sql(&self, database: &str, view: &str) -> String {
+        format!(
+            "SELECT VIEW_DEFINITION FROM information_schema.VIEWS 
+             WHERE TABLE_SCHEMA = {} AND TABLE_NAME = {}",
+            self.string_literal(database),
+            self.string_literal(view)
+        )
+    }
+
+    /// 查询建表语句的 SQL，结果中列名包含 create 或为 statement 的列是 DDL
+    fn create_table_sql(&self, database: &str, schema: Option<&str>, table: &str, _flavor: Option<ServerFlavor>) -> String {
+        format!("SHOW CREATE TABLE {}", self.table_ref(database, schema, table))
+    }
+}
+
+/// 驱动注册项，各驱动模块通过 `inventory::submit!` 注册
+pub struct DriverRegistration {
+    pub factory: fn() -> Arc<dyn DatabaseDriver>,
+}
+
+inventory::collect!(DriverRegistration);
+
+/// 驱动注册表
+#[derive(Default)]
+pub struct DriverRegistry {
+    drivers: HashMap<DatabaseType, Arc<dyn DatabaseDriver>>,
+}
+
+impl DriverRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册驱动（同一数据库类型重复注册时替换原有驱动）
+    pub fn register(&mut self, driver: Arc<dyn DatabaseDriver>) {
+        self.drivers.insert(driver.db_type(), driver);
+    }
+
+    /// 获取数据库类型对应的驱动
+    pub fn get(&self, db_type: &DatabaseType) -> DbResult<Arc<dyn DatabaseDriver>> {
+        self.drivers
+            .get(db_type)
+            .cloned()
+            .ok_or(DbError::UnsupportedDatabase)
+    }
+
+    /// 已注册的驱动
+    pub fn drivers(&self) -> impl Iterator<Item = &Arc<dyn DatabaseDriver>> {
+        self.drivers.values()
+    }
+
+    /// 内置驱动（编译进来的驱动模块通过 `inventory::submit!` 自行注册）
+    pub fn builtin() -> Arc<DriverRegistry> {
+        static BUILTIN: OnceLock<Arc<DriverRegistry>> = OnceLock::new();
+        BUILTIN
+            .get_or_init(|| {
+                let mut registry = DriverRegistry::new();
+                for registration in inventory::iter::<DriverRegistration> {
+                    registry.register((registration.factory)());
+                }
+                Arc::new(registry)
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_registry() {
+        let registry = DriverRegistry::builtin();
+        for driver in registry.drivers() {
+            let same = registry.get(&driver.db_type()).unwrap();
+            assert_eq!(same.display_name(), driver.display_name());
+            // 支持 SQL 的驱动必须提供方言
+            assert_eq!(driver.capabilities().sql, driver.dialect().is_some());
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "mysql", feature = "postgresql", feature = "sqlite", feature = "sqlserver-support"))]
+    fn test_driver_table_ref() {
+        let registry = DriverRegistry::builtin();
+        let table_ref = |db_type| registry.get(&db_type).unwrap().table_ref("app", None, "a`b]");
+        assert_eq!(table_ref(DatabaseType::MySQL), "`app`.`a``b]`");
+        assert_eq!(table_ref(DatabaseType::SQLite), "\"a`b]\"");
+        assert_eq!(table_ref(DatabaseType::SqlServer), "[app].[dbo].[a`b]]]");
+        assert_eq!(table_ref(DatabaseType::PostgreSQL), "\"public\".\"a`b]\"");
+
+        let sqlserver = registry.get(&DatabaseType::SqlServer).unwrap();
+        assert_eq!(sqlserver.routine_ref("app", Some("sales"), "p"), "[sales].[p]");
+    }
+
+    #[test]
+    fn test_default_quote_identifier() {
+        struct Dummy;
+        impl DatabaseDriver for Dummy {
+            fn db_type(&self) -> DatabaseType {
+                DatabaseType::MongoDB
+            }
+            fn display_name(&self) -> &'static str {
+                "Dummy"
+            }
+            fn create(&self) -> DbResult<Box<dyn DatabaseOperations>> {
+                Err(DbError::Other("dummy driver".to_string()))
+            }
+            fn capabilities(&self) -> DriverCapabilities {
+                DriverCapabilities::default()
+            }
+        }
+
+        assert_eq!(Dummy.quote_identifier("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn test_with_server_features() {
+        let capabilities = DriverCapabilities {
+            object_types: vec![ObjectType::Table, ObjectType::Procedure, ObjectType::Trigger],
+            transactions: true,
+            ..Default::default()
+        };
+        let features = ServerFeatures {
+            triggers: true,
+            ..Default::default()
+        };
+
+        let capabilities = capabilities.with_server_features(&features);
+        assert_eq!(capabilities.object_types, vec![ObjectType::Table, ObjectType::Trigger]);
+        assert!(!capabilities.transactions);
+    }
+}
@@ -1,12 +1,12 @@
-/// SQL语句分割器
-/// 完全基于DBeaver的SQLScriptParser实现
-/// 
-/// DBeaver核心逻辑翻译自:
-/// org.jkiss.dbeaver.model.sql.parser.SQLScriptParser
+//! SQL语句分割器
+//! 完全基于DBeaver的SQLScriptParser实现
+//! 
+//! DBeaver核心逻辑翻译自:
+//! org.jkiss.dbeaver.model.sql.parser.SQLScriptParser
 
-
-/// Token类型 - 对应DBeaver的SQLTokenType
+/// Token类型 - 对应DBeaver的SQLTokenType（保留 DBeaver 的命名）
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
 pub enum SqlTokenType {
     T_UNKNOWN,
     T_KEYWORD,
@@ -37,6 +37,8 @@ pub struct SqlDialect {
     pub block_header_strings: Vec<String>,
     pub inner_block_prefixes: Vec<String>,
     pub delimiter_after_block: bool,
+    /// 批处理分隔符（SQL Server 的 GO），必须单独成行；设置后按批拆分，批内的分号不再拆分
+    pub batch_separator: Option<String>,
 }
 
 impl Default for SqlDialect {
@@ -67,6 +69,7 @@ impl SqlDialect {
             ],
             inner_block_prefixes: vec!["AS".to_string(), "IS".to_string()],
             delimiter_after_block: true,
+            batch_separator: None,
         }
     }
 
@@ -90,6 +93,34 @@ impl SqlDialect {
         config.script_delimiter_redefiner = None;
         config
     }
+
+    pub fn clickhouse() -> Self {
+        let mut config = Self::mysql();
+        // ClickHouse 支持 heredoc 字符串和 `#!`/`# ` 注释，没有存储过程块
+        config.string_quote_strings.push(("$$".to_string(), "$$".to_string()));
+        config.single_line_comments = vec!["-- ".to_string(), "#!".to_string(), "# ".to_string()];
+        config.script_delimiter_redefiner = None;
+        config.block_bound_strings = Vec::new();
+        config.block_header_strings = Vec::new();
+        config.inner_block_prefixes = Vec::new();
+        config.delimiter_after_block = false;
+        config
+    }
+
+    pub fn sqlserver() -> Self {
+        let mut config = Self::mysql();
+        // T-SQL 字符串没有反斜杠转义，单引号通过 '' 转义
+        config.escape_char = '\0';
+        config.identifier_quote_strings = vec![
+            ("[".to_string(), "]".to_string()),
+            ("\"".to_string(), "\"".to_string()),
+        ];
+        config.single_line_comments = vec!["--".to_string()];
+        config.supports_nested_comments = true;
+        config.script_delimiter_redefiner = None;
+        config.batch_separator = Some("GO".to_string());
+        config
+    }
 }
 
 /// 脚本块信息 - 对应DBeaver的ScriptBlockInfo
@@ -157,6 +188,10 @@ impl<'a> SqlScanner<'a> {
         self.current_delimiter = delimiter;
     }
 
+    fn set_position(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
     /// 读取下一个Token - 核心扫描逻辑
     fn next_token(&mut self) -> Token {
         if self.pos >= self.chars.len() {
@@ -438,7 +473,6 @@ impl SqlSplitter {
         let mut bracket_depth = 0i32;
         let mut prev_not_empty_token_type = SqlTokenType::T_UNKNOWN;
         let mut first_keyword: Option<String> = None;
-        let mut last_keyword: Option<String> = None;
 
         loop {
             let token = scanner.next_token();
@@ -485,13 +519,33 @@ impl SqlSplitter {
                             cur_block = None;
                             prev_not_empty_token_type = SqlTokenType::T_UNKNOWN;
                             first_keyword = None;
-                            last_keyword = None;
                             continue;
                         }
                     }
                 }
             }
 
+            // 处理批处理分隔符（GO），`GO n` 将前一批重复执行 n 次
+            if token_type == SqlTokenType::T_KEYWORD {
+                if let Some((end_pos, count)) = self.batch_separator_end(&chars, token_offset, token_length) {
+                    if has_valuable_tokens {
+                        let stmt = self.extract_statement(&chars, statement_start, token_offset);
+                        if !stmt.trim().is_empty() {
+                            statements.extend(std::iter::repeat_n(stmt, count));
+                        }
+                    }
+
+                    scanner.set_position(end_pos);
+                    statement_start = end_pos;
+                    has_valuable_tokens = false;
+                    bracket_depth = 0;
+                    cur_block = None;
+                    prev_not_empty_token_type = SqlTokenType::T_UNKNOWN;
+                    first_keyword = None;
+                    continue;
+                }
+            }
+
             // 跳过空白
             if token.is_whitespace {
                 continue;
@@ -578,12 +632,18 @@ impl SqlSplitter {
                token_type == SqlTokenType::T_BLOCK_END ||
                token_type == SqlTokenType::T_BLOCK_HEADER {
                 let word: String = chars[token_offset..token_offset + token_length].iter().collect();
-                last_keyword = Some(word.clone());
                 if first_keyword.is_none() {
                     first_keyword = Some(word);
                 }
             }
 
+            // 按批拆分时，分号只是批内语句的结束符
+            if token_type == SqlTokenType::T_DELIMITER && self.dialect.batch_separator.is_some() {
+                has_valuable_tokens = true;
+                prev_not_empty_token_type = token_type;
+                continue;
+            }
+
             // 处理分隔符
             if token_type == SqlTokenType::T_DELIMITER {
                 // 如果在括号或块内，忽略分隔符
@@ -606,7 +666,6 @@ impl SqlSplitter {
                 cur_block = None;
                 prev_not_empty_token_type = SqlTokenType::T_UNKNOWN;
                 first_keyword = None;
-                last_keyword = None;
                 continue;
             }
 
@@ -617,6 +676,41 @@ impl SqlSplitter {
         statements
     }
 
+    /// 判断关键字是否为单独成行的批处理分隔符（允许 `GO 5` 形式的重复次数），返回行尾位置和重复次数
+    fn batch_separator_end(&self, chars: &[char], offset: usize, length: usize) -> Option<(usize, usize)> {
+        let separator = self.dialect.batch_separator.as_ref()?;
+        let word: String = chars[offset..offset + length].iter().collect();
+        if !word.eq_ignore_ascii_case(separator) {
+            return None;
+        }
+
+        // 前面只能是同一行的空白
+        let line_start = chars[..offset]
+            .iter()
+            .rposition(|&c| c == '\n' || c == '\r')
+            .map(|p| p + 1)
+            .unwrap_or(0);
+        if !chars[line_start..offset].iter().all(|c| c.is_whitespace()) {
+            return None;
+        }
+
+        // 后面只能是空白和可选的正整数重复次数
+        let mut pos = offset + length;
+        while pos < chars.len() && chars[pos] != '\n' && chars[pos] != '\r' {
+            pos += 1;
+        }
+        let rest: String = chars[offset + length..pos].iter().collect();
+        let count = match rest.trim() {
+            "" => 1,
+            count => count.parse::<usize>().ok().filter(|n| *n > 0)?,
+        };
+        while pos < chars.len() && (chars[pos] == '\r' || chars[pos] == '\n') {
+            pos += 1;
+        }
+
+        Some((pos, count))
+    }
+
     /// 解析DELIMITER命令
     fn parse_delimiter_command(&self, chars: &[char], start: usize) -> Option<(String, usize)> {
         let mut pos = start;
@@ -811,6 +905,30 @@ SELECT 2;
         assert_eq!(statements.len(), 2);
     }
 
+    #[test]
+    fn test_clickhouse_statements() {
+        let mut splitter = SqlSplitter::new(SqlDialect::clickhouse());
+        let sql = "SELECT 'a\\';b', $$x;y$$; # comment;\nSELECT CASE WHEN 1 THEN 2 END; SELECT 3;";
+        let statements = splitter.split(sql);
+        assert_eq!(statements.len(), 3);
+    }
+
+    #[test]
+    fn test_sqlserver_go_batches() {
+        let mut splitter = SqlSplitter::new(SqlDialect::sqlserver());
+        let sql = "CREATE PROCEDURE dbo.p AS\nSELECT 1; SELECT 'GO';\ngo\nSELECT [GO] FROM t; -- GO\nGO 2\nSELECT 3";
+        let statements = splitter.split(sql);
+        assert_eq!(statements.len(), 4);
+        assert!(statements[0].ends_with("SELECT 'GO';"));
+        // GO 2 重复前一批
+        assert_eq!(statements[1], statements[2]);
+        assert_eq!(statements[3], "SELECT 3");
+
+        // 重复次数不是正整数时不作为分隔符
+        let statements = splitter.split("SELECT 1\nGO 0\nSELECT 2\nGO 1 2");
+        assert_eq!(statements.len(), 1);
+    }
+
     #[test]
     fn test_quoted_identifier() {
         let mut splitter = SqlSplitter::new(SqlDialect::mysql());
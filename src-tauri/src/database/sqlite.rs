@@ -1,9 +1,17 @@
 use async_trait::async_trait;
+use sqlx::sqlite::SqlitePoolOptions;
 use sqlx::{Column, Pool, Row, Sqlite, SqlitePool};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 
+use super::sql_splitter::SqlDialect;
+use super::registry::{
+    DatabaseDriver, DriverCapabilities, DriverRegistration, EditingCapabilities, ExplainFormat,
+    ExportFormat, ObjectType,
+};
 use super::traits::*;
+use crate::utils::i18n::Msg;
 
 /// SQLite 数据库连接
 pub struct SqliteDatabase {
@@ -38,14 +46,111 @@ impl SqliteDatabase {
             }
         }
     }
+
+    /// 创建连接池，每个新建的池连接都执行初始化 SQL（例如 PRAGMA foreign_keys=ON）
+    ///
+    /// SQLite 没有字符集、时区和应用名称等会话设置
+    async fn connect_pool(config: &ConnectionConfig) -> Result<SqlitePool, sqlx::Error> {
+        let connection_string = Self::build_connection_string(config);
+        let init_statements = Arc::new(config.init_sql_statements(SqlDialect::sqlite()));
+
+        SqlitePoolOptions::new()
+            .after_connect(move |conn, _meta| {
+                let init_statements = init_statements.clone();
+                Box::pin(async move {
+                    use sqlx::Executor;
+                    for sql in init_statements.iter() {
+                        conn.execute(sql.as_str()).await?;
+                    }
+                    Ok(())
+                })
+            })
+            .connect(&connection_string)
+            .await
+    }
+}
+
+impl Default for SqliteDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SQLite 驱动
+pub struct SqliteDriver;
+
+inventory::submit! {
+    DriverRegistration { factory: || Arc::new(SqliteDriver) }
+}
+
+impl DatabaseDriver for SqliteDriver {
+    fn db_type(&self) -> DatabaseType {
+        DatabaseType::SQLite
+    }
+
+    fn display_name(&self) -> &'static str {
+        "SQLite"
+    }
+
+    fn create(&self) -> DbResult<Box<dyn DatabaseOperations>> {
+        Ok(Box::new(SqliteDatabase::new()))
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            sql: true,
+            file_based: true,
+            object_types: vec![
+                ObjectType::Table,
+                ObjectType::View,
+                ObjectType::Trigger,
+                ObjectType::Index,
+            ],
+            // SQLite 没有 TRUNCATE
+            editing: EditingCapabilities {
+                truncate: false,
+                ..EditingCapabilities::all()
+            },
+            transactions: true,
+            explain_formats: vec![ExplainFormat::Text],
+            export_formats: ExportFormat::for_sql(),
+            ..Default::default()
+        }
+    }
+
+    fn dialect(&self) -> Option<SqlDialect> {
+        Some(SqlDialect::sqlite())
+    }
+
+    /// 只使用表名，连接即对应一个数据库文件
+    fn table_ref(&self, _database: &str, _schema: Option<&str>, table: &str) -> String {
+        self.quote_identifier(table)
+    }
+
+    fn bool_literal(&self, value: bool) -> &'static str {
+        if value { "1" } else { "0" }
+    }
+
+    /// sqlite_sequence、sqlite_stat1 等由 SQLite 自己维护
+    fn is_system_table(&self, table: &str) -> bool {
+        table.starts_with("sqlite_")
+    }
+
+    fn list_triggers_sql(&self, _database: &str) -> Option<String> {
+        Some(
+            "SELECT name AS TRIGGER_NAME, tbl_name AS EVENT_OBJECT_TABLE
+             FROM sqlite_master
+             WHERE type = 'trigger'
+             ORDER BY name"
+                .to_string(),
+        )
+    }
 }
 
 #[async_trait]
 impl DatabaseOperations for SqliteDatabase {
     async fn test_connection(&self, config: &ConnectionConfig) -> DbResult<bool> {
-        let connection_string = Self::build_connection_string(config);
-        
-        match SqlitePool::connect(&connection_string).await {
+        match Self::connect_pool(config).await {
             Ok(pool) => {
                 // 测试查询
                 let result = sqlx::query("SELECT 1")
@@ -56,19 +161,17 @@ impl DatabaseOperations for SqliteDatabase {
                 
                 match result {
                     Ok(_) => Ok(true),
-                    Err(e) => Err(DbError::ConnectionFailed(e.to_string())),
+                    Err(e) => Err(DbError::from(e).connecting()),
                 }
             }
-            Err(e) => Err(DbError::ConnectionFailed(e.to_string())),
+            Err(e) => Err(DbError::from(e).connecting()),
         }
     }
 
     async fn connect(&mut self, config: ConnectionConfig) -> DbResult<()> {
-        let connection_string = Self::build_connection_string(&config);
-        
-        let pool = SqlitePool::connect(&connection_string)
+        let pool = Self::connect_pool(&config)
             .await
-            .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+            .map_err(|e| DbError::from(e).connecting())?;
         
         self.pool = Some(pool);
         self.config = Some(config);
@@ -88,7 +191,7 @@ impl DatabaseOperations for SqliteDatabase {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         let start = Instant::now();
 
@@ -129,7 +232,7 @@ impl DatabaseOperations for SqliteDatabase {
                 let rows = sqlx::query(stmt)
                     .fetch_all(pool)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("语句 {} 执行失败: {}", idx + 1, e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::StatementFailed.with(&[&(idx + 1)])))?;
                 
                 // 保存最后一个查询结果
                 last_query_result = Some(self.process_query_result_sqlite(rows, start)?);
@@ -138,7 +241,7 @@ impl DatabaseOperations for SqliteDatabase {
                 let result = sqlx::query(stmt)
                     .execute(pool)
                     .await
-                    .map_err(|e| DbError::QueryFailed(format!("语句 {} 执行失败: {}", idx + 1, e)))?;
+                    .map_err(|e| DbError::from(e).context(Msg::StatementFailed.with(&[&(idx + 1)])))?;
                 
                 total_affected_rows += result.rows_affected();
             }
@@ -160,6 +263,64 @@ impl DatabaseOperations for SqliteDatabase {
         }
     }
 
+    async fn stream_query(
+        &self,
+        sql: &str,
+        _database: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> DbResult<u64> {
+        use futures::TryStreamExt;
+        use sqlx::Executor;
+
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        // 逐行读取，列名取自第一行
+        let mut count = 0u64;
+        let mut rows = sqlx::query(sql).fetch(pool);
+        while let Some(row) = rows.try_next().await.map_err(DbError::from)? {
+            if count == 0 {
+                let columns: Vec<String> =
+                    row.columns().iter().map(|c| c.name().to_string()).collect();
+                sink.columns(&columns)?;
+            }
+            let values = (0..row.columns().len())
+                .map(|idx| Self::column_value(&row, idx))
+                .collect();
+            sink.row(values)?;
+            count += 1;
+        }
+        drop(rows);
+
+        // 没有数据时通过语句描述获取列名
+        if count == 0 {
+            let columns: Vec<String> = pool
+                .describe(sql)
+                .await
+                .map(|d| d.columns().iter().map(|c| c.name().to_string()).collect())
+                .unwrap_or_default();
+            sink.columns(&columns)?;
+        }
+
+        Ok(count)
+    }
+
+    async fn begin_transaction(&self, _database: Option<&str>) -> DbResult<Box<dyn Transaction>> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        let tx = pool
+            .begin()
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::BeginTransactionFailed))?;
+
+        Ok(Box::new(SqliteTransaction(tx)))
+    }
+
     async fn get_databases(&self) -> DbResult<Vec<DatabaseInfo>> {
         // SQLite 是单文件数据库，固定返回 "main" 作为数据库名称
         // 不显示文件路径，因为用户已经在连接名中看到了
@@ -174,7 +335,7 @@ impl DatabaseOperations for SqliteDatabase {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         let rows = sqlx::query(
             "SELECT 
@@ -187,7 +348,7 @@ impl DatabaseOperations for SqliteDatabase {
         )
         .fetch_all(pool)
         .await
-        .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+        .map_err(DbError::from)?;
 
         let mut tables = Vec::new();
         for row in rows {
@@ -222,14 +383,14 @@ impl DatabaseOperations for SqliteDatabase {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         // 使用 PRAGMA table_info 获取表结构
         let pragma_sql = format!("PRAGMA table_info(\"{}\")", table);
         let rows = sqlx::query(&pragma_sql)
             .fetch_all(pool)
             .await
-            .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+            .map_err(DbError::from)?;
 
         let mut columns = Vec::new();
         for row in rows {
@@ -260,14 +421,14 @@ impl DatabaseOperations for SqliteDatabase {
         let pool = self
             .pool
             .as_ref()
-            .ok_or_else(|| DbError::ConnectionFailed("未连接到数据库".to_string()))?;
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
 
         // 获取索引列表
         let pragma_sql = format!("PRAGMA index_list(\"{}\")", table);
         let rows = sqlx::query(&pragma_sql)
             .fetch_all(pool)
             .await
-            .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+            .map_err(DbError::from)?;
 
         let mut indexes = Vec::new();
         for row in rows {
@@ -279,7 +440,7 @@ impl DatabaseOperations for SqliteDatabase {
             let col_rows = sqlx::query(&index_info_sql)
                 .fetch_all(pool)
                 .await
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                .map_err(DbError::from)?;
 
             let mut columns = Vec::new();
             for col_row in col_rows {
@@ -307,11 +468,58 @@ impl DatabaseOperations for SqliteDatabase {
             collation: None,
             comment: None,
             auto_increment: None,
+            extra: Default::default(),
         })
     }
     
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    async fn ping(&self) -> DbResult<()> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        sqlx::query("SELECT 1")
+            .execute(pool)
+            .await
+            .map_err(DbError::from)?;
+        Ok(())
+    }
+    
+    async fn server_info(&self) -> DbResult<ServerInfo> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+
+        let version: String = sqlx::query_scalar("SELECT sqlite_version()")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::ServerInfoFailed))?;
+
+        let mut info = ServerInfo {
+            flavor: ServerFlavor::SQLite,
+            version: version.clone(),
+            version_string: format!("SQLite {}", version),
+            current_user: None,
+            time_zone: None,
+            features: ServerFeatures::default(),
+        };
+
+        info.features = ServerFeatures {
+            window_functions: info.version_at_least(3, 25),
+            cte: parse_version(&info.version) >= (3, 8, 3),
+            json: info.version_at_least(3, 38),
+            returning: info.version_at_least(3, 35),
+            upsert: info.version_at_least(3, 24),
+            sequences: false,
+            check_constraints: true,
+            stored_procedures: false,
+            triggers: true,
+            events: false,
+            transactions: true,
+        };
+
+        Ok(info)
     }
 }
 
@@ -325,33 +533,25 @@ impl SqliteDatabase {
         while let Some(ch) = chars.next() {
             match ch {
                 // 单行注释 --
-                '-' => {
-                    if chars.peek() == Some(&'-') {
-                        // 跳过直到行尾
-                        chars.next(); // 消耗第二个 -
-                        while let Some(&c) = chars.peek() {
-                            if c == '\n' {
-                                chars.next();
-                                break;
-                            }
+                '-' if chars.peek() == Some(&'-') => {
+                    // 跳过直到行尾
+                    chars.next(); // 消耗第二个 -
+                    while let Some(&c) = chars.peek() {
+                        if c == '\n' {
                             chars.next();
+                            break;
                         }
-                    } else {
-                        current_statement.push(ch);
+                        chars.next();
                     }
                 }
                 // 多行注释 /* */
-                '/' => {
-                    if chars.peek() == Some(&'*') {
-                        chars.next(); // 消耗 *
-                        while let Some(c) = chars.next() {
-                            if c == '*' && chars.peek() == Some(&'/') {
-                                chars.next(); // 消耗 /
-                                break;
-                            }
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next(); // 消耗 *
+                    while let Some(c) = chars.next() {
+                        if c == '*' && chars.peek() == Some(&'/') {
+                            chars.next(); // 消耗 /
+                            break;
                         }
-                    } else {
-                        current_statement.push(ch);
                     }
                 }
                 // 单引号字符串
@@ -387,7 +587,7 @@ impl SqliteDatabase {
                 // 方括号标识符（SQLite特有）
                 '[' => {
                     current_statement.push(ch);
-                    while let Some(c) = chars.next() {
+                    for c in chars.by_ref() {
                         current_statement.push(c);
                         if c == ']' {
                             break;
@@ -435,7 +635,7 @@ impl SqliteDatabase {
             let rows = sqlx::query(sql)
                 .fetch_all(pool)
                 .await
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                .map_err(DbError::from)?;
 
             self.process_query_result_sqlite(rows, start)
         } else {
@@ -443,7 +643,7 @@ impl SqliteDatabase {
             let result = sqlx::query(sql)
                 .execute(pool)
                 .await
-                .map_err(|e| DbError::QueryFailed(e.to_string()))?;
+                .map_err(DbError::from)?;
 
             Ok(QueryResult {
                 columns: vec![],
@@ -454,6 +654,12 @@ impl SqliteDatabase {
         }
     }
     
+    /// 按列读取一个值（统一按文本读取）
+    fn column_value(row: &sqlx::sqlite::SqliteRow, idx: usize) -> serde_json::Value {
+        let value: Option<String> = row.try_get(idx).ok();
+        serde_json::Value::String(value.unwrap_or_default())
+    }
+
     /// 处理查询结果（SQLite）
     fn process_query_result_sqlite(
         &self,
@@ -473,11 +679,7 @@ impl SqliteDatabase {
             for row in &rows {
                 let mut row_map = HashMap::new();
                 for (idx, column) in row.columns().iter().enumerate() {
-                    let value: Option<String> = row.try_get(idx).ok();
-                    row_map.insert(
-                        column.name().to_string(),
-                        serde_json::Value::String(value.unwrap_or_default()),
-                    );
+                    row_map.insert(column.name().to_string(), Self::column_value(row, idx));
                 }
                 result_rows.push(row_map);
             }
@@ -492,3 +694,26 @@ impl SqliteDatabase {
     }
 }
 
+/// SQLite 事务
+struct SqliteTransaction(sqlx::Transaction<'static, Sqlite>);
+
+#[async_trait]
+impl Transaction for SqliteTransaction {
+    async fn execute(&mut self, sql: &str) -> DbResult<u64> {
+        use sqlx::Executor;
+
+        let result = (&mut *self.0).execute(sql).await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn commit(self: Box<Self>) -> DbResult<()> {
+        self.0
+            .commit()
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::CommitFailed))
+    }
+
+    async fn rollback(self: Box<Self>) -> DbResult<()> {
+        Ok(self.0.rollback().await?)
+    }
+}
@@ -0,0 +1,923 @@
+use super::sql_splitter::{is_query_statement, SqlDialect, SqlSplitter};
+use super::registry::{
+    DatabaseDriver, DriverCapabilities, DriverRegistration, EditingCapabilities, ExplainFormat,
+    ExportFormat, ObjectType,
+};
+use super::traits::*;
+use crate::utils::i18n::Msg;
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use futures::TryStreamExt;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tiberius::{AuthMethod, Client, ColumnData, Config, EncryptionLevel, FromSql, QueryItem};
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
+
+type SqlServerClient = Client<Compat<TcpStream>>;
+
+/// SQL Server 数据库连接（基于 tiberius / TDS 协议）
+///
+/// 单个 TDS 连接同一时间只能执行一个请求，因此客户端由互斥锁保护。
+/// 脚本按 `GO` 拆分成批，每批作为一个整体发送给服务器。
+pub struct SqlServerDatabase {
+    client: Option<Arc<Mutex<SqlServerClient>>>,
+    config: Option<ConnectionConfig>,
+    /// 连接当前所在的数据库（最近一次 USE 的目标）
+    current_database: Arc<Mutex<Option<String>>>,
+}
+
+impl SqlServerDatabase {
+    pub fn new() -> Self {
+        Self {
+            client: None,
+            config: None,
+            current_database: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 构建 tiberius 配置
+    ///
+    /// 未启用 SSL 时仍使用 TLS 加密登录包（服务器要求），此时信任自签名证书；
+    /// 启用 SSL 时校验服务器证书，除非连接设置了信任服务器证书
+    fn build_config(config: &ConnectionConfig) -> Config {
+        let mut tds_config = Config::new();
+        tds_config.host(&config.host);
+        tds_config.port(config.port);
+        tds_config.authentication(AuthMethod::sql_server(&config.username, &config.password));
+        if let Some(db) = config.database.as_deref().filter(|d| !d.is_empty()) {
+            tds_config.database(db);
+        }
+        if let Some(name) = session_setting(&config.application_name) {
+            tds_config.application_name(name);
+        }
+        if config.ssl {
+            tds_config.encryption(EncryptionLevel::Required);
+            if config.trust_server_certificate {
+                tds_config.trust_cert();
+            }
+        } else {
+            tds_config.encryption(EncryptionLevel::Off);
+            tds_config.trust_cert();
+        }
+        tds_config
+    }
+
+    /// 建立 TDS 连接，处理 Azure SQL 的路由重定向
+    async fn open_client(config: &ConnectionConfig) -> DbResult<SqlServerClient> {
+        let mut tds_config = Self::build_config(config);
+        let timeout = Duration::from_secs(config.connection_timeout);
+
+        let connect = async {
+            let tcp = TcpStream::connect(tds_config.get_addr())
+                .await
+                .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+            tcp.set_nodelay(true)
+                .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+
+            match Client::connect(tds_config.clone(), tcp.compat_write()).await {
+                Ok(client) => Ok(client),
+                Err(tiberius::error::Error::Routing { host, port }) => {
+                    tds_config.host(&host);
+                    tds_config.port(port);
+                    let tcp = TcpStream::connect(tds_config.get_addr())
+                        .await
+                        .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+                    tcp.set_nodelay(true)
+                        .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+                    Client::connect(tds_config, tcp.compat_write())
+                        .await
+                        .map_err(|e| DbError::from(e).connecting())
+                }
+                Err(e) => Err(DbError::from(e).connecting()),
+            }
+        };
+
+        let mut client = tokio::time::timeout(timeout, connect)
+            .await
+            .map_err(|_| DbError::Timeout(Msg::ConnectTimeout.into()))??;
+
+        // SQL Server 没有会话级字符集和时区设置，只执行初始化 SQL
+        for sql in config.init_sql_statements(SqlDialect::sqlserver()) {
+            client
+                .simple_query(sql)
+                .await
+                .map_err(|e| DbError::from(e).context(Msg::InitSqlFailed))?
+                .into_results()
+                .await
+                .map_err(|e| DbError::from(e).context(Msg::InitSqlFailed))?;
+        }
+
+        Ok(client)
+    }
+
+    fn client(&self) -> DbResult<Arc<Mutex<SqlServerClient>>> {
+        self.client
+            .clone()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))
+    }
+
+    /// 用方括号引用标识符
+    fn quote(identifier: &str) -> String {
+        format!("[{}]", identifier.replace(']', "]]"))
+    }
+
+    /// 必要时切换连接的当前数据库
+    async fn use_database(&self, client: &mut SqlServerClient, database: Option<&str>) -> DbResult<()> {
+        let Some(database) = database.filter(|d| !d.is_empty()) else {
+            return Ok(());
+        };
+
+        let mut current = self.current_database.lock().await;
+        if current.as_deref() == Some(database) {
+            return Ok(());
+        }
+
+        client
+            .simple_query(format!("USE {}", Self::quote(database)))
+            .await
+            .map_err(DbError::from)?
+            .into_results()
+            .await
+            .map_err(DbError::from)?;
+        *current = Some(database.to_string());
+        Ok(())
+    }
+
+    /// 只包含一条 DML 语句的批通过 RPC 执行以获取影响行数
+    ///
+    /// RPC 执行会丢弃结果集，因此包含多条语句的批（例如 INSERT 后跟 SELECT）按普通查询执行
+    fn is_dml(sql: &str) -> bool {
+        let upper = sql.trim_start().to_uppercase();
+        let is_dml = ["INSERT", "UPDATE", "DELETE", "MERGE"]
+            .iter()
+            .any(|kw| upper.starts_with(kw))
+            && !upper.contains(" OUTPUT ");
+        if !is_dml {
+            return false;
+        }
+
+        // 批内按分号拆分，只有一条语句时才是单独的 DML
+        let mut dialect = SqlDialect::sqlserver();
+        dialect.batch_separator = None;
+        SqlSplitter::new(dialect).split(sql).len() == 1
+    }
+
+    /// 执行一批 SQL，返回最后一个结果集（列名 + 行）
+    async fn run_batch(
+        client: &mut SqlServerClient,
+        sql: &str,
+    ) -> DbResult<(Option<(Vec<String>, Vec<Vec<Value>>)>, u64)> {
+        if Self::is_dml(sql) && !is_query_statement(sql) {
+            let result = client
+                .execute(sql, &[])
+                .await
+                .map_err(DbError::from)?;
+            return Ok((None, result.total()));
+        }
+
+        let mut stream = client
+            .simple_query(sql)
+            .await
+            .map_err(DbError::from)?;
+
+        let mut last: Option<(Vec<String>, Vec<Vec<Value>>)> = None;
+        while let Some(item) = stream
+            .try_next()
+            .await
+            .map_err(DbError::from)?
+        {
+            match item {
+                QueryItem::Metadata(meta) => {
+                    let columns = meta.columns().iter().map(|c| c.name().to_string()).collect();
+                    last = Some((columns, Vec::new()));
+                }
+                QueryItem::Row(row) => {
+                    if let Some((_, rows)) = last.as_mut() {
+                        rows.push(row.cells().map(|(_, data)| Self::cell_to_json(data)).collect());
+                    }
+                }
+            }
+        }
+
+        Ok((last, 0))
+    }
+
+    /// 将单元格转换为 JSON
+    fn cell_to_json(data: &ColumnData<'static>) -> Value {
+        match data {
+            ColumnData::U8(v) => json!(v),
+            ColumnData::I16(v) => json!(v),
+            ColumnData::I32(v) => json!(v),
+            ColumnData::I64(v) => json!(v),
+            ColumnData::F32(v) => json!(v),
+            ColumnData::F64(v) => json!(v),
+            ColumnData::Bit(v) => json!(v),
+            ColumnData::String(v) => json!(v.as_deref()),
+            ColumnData::Guid(v) => json!(v.map(|g| g.to_string())),
+            ColumnData::Binary(v) => json!(v.as_deref().map(|b| general_purpose::STANDARD.encode(b))),
+            // DECIMAL 使用字符串避免精度损失
+            ColumnData::Numeric(v) => json!(v.map(|n| n.to_string())),
+            ColumnData::Xml(v) => json!(v.as_deref().map(|x| x.to_string())),
+            ColumnData::DateTime(_) | ColumnData::SmallDateTime(_) | ColumnData::DateTime2(_) => {
+                json!(chrono::NaiveDateTime::from_sql(data)
+                    .ok()
+                    .flatten()
+                    .map(|dt| dt.to_string()))
+            }
+            ColumnData::Date(_) => {
+                json!(chrono::NaiveDate::from_sql(data).ok().flatten().map(|d| d.to_string()))
+            }
+            ColumnData::Time(_) => {
+                json!(chrono::NaiveTime::from_sql(data).ok().flatten().map(|t| t.to_string()))
+            }
+            ColumnData::DateTimeOffset(_) => {
+                json!(chrono::DateTime::<chrono::FixedOffset>::from_sql(data)
+                    .ok()
+                    .flatten()
+                    .map(|dt| dt.to_rfc3339()))
+            }
+        }
+    }
+
+    /// 执行元数据查询并返回行
+    async fn query_rows(&self, sql: &str, database: Option<&str>) -> DbResult<Vec<Vec<Value>>> {
+        let client = self.client()?;
+        let mut client = client.lock().await;
+        self.use_database(&mut client, database).await?;
+        let (result, _) = Self::run_batch(&mut client, sql).await?;
+        Ok(result.map(|(_, rows)| rows).unwrap_or_default())
+    }
+
+    fn as_string(value: &Value) -> Option<String> {
+        match value {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    fn as_i64(value: &Value) -> Option<i64> {
+        value.as_i64().or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+    }
+
+    fn as_bool(value: &Value) -> bool {
+        value.as_bool().unwrap_or_else(|| Self::as_i64(value).unwrap_or(0) != 0)
+    }
+
+    /// 对象名（schema.name）的 N'' 字面量，用于 OBJECT_ID
+    fn object_name_literal(table: &str, schema: Option<&str>) -> String {
+        format!(
+            "N'{}.{}'",
+            escape_sql_literal(&Self::quote(schema.unwrap_or("dbo"))),
+            escape_sql_literal(&Self::quote(table))
+        )
+    }
+
+    /// 列出表或视图
+    async fn list_objects(&self, database: Option<&str>, views: bool) -> DbResult<Vec<TableInfo>> {
+        let sql = if views {
+            "SELECT s.name, v.name, CAST(ep.value AS NVARCHAR(4000))
+             FROM sys.views v
+             JOIN sys.schemas s ON s.schema_id = v.schema_id
+             LEFT JOIN sys.extended_properties ep
+               ON ep.major_id = v.object_id AND ep.minor_id = 0 AND ep.name = 'MS_Description'
+             WHERE v.is_ms_shipped = 0
+             ORDER BY s.name, v.name"
+                .to_string()
+        } else {
+            "SELECT s.name, t.name, CAST(ep.value AS NVARCHAR(4000)),
+                    (SELECT SUM(p.rows) FROM sys.partitions p
+                      WHERE p.object_id = t.object_id AND p.index_id IN (0, 1)),
+                    (SELECT SUM(a.total_pages) * 8 FROM sys.partitions p
+                      JOIN sys.allocation_units a ON a.container_id = p.partition_id
+                      WHERE p.object_id = t.object_id)
+             FROM sys.tables t
+             JOIN sys.schemas s ON s.schema_id = t.schema_id
+             LEFT JOIN sys.extended_properties ep
+               ON ep.major_id = t.object_id AND ep.minor_id = 0 AND ep.name = 'MS_Description'
+             WHERE t.is_ms_shipped = 0
+             ORDER BY s.name, t.name"
+                .to_string()
+        };
+
+        Ok(self
+            .query_rows(&sql, database)
+            .await?
+            .iter()
+            .map(|row| TableInfo {
+                name: Self::as_string(&row[1]).unwrap_or_default(),
+                schema: Self::as_string(&row[0]),
+                table_type: if views { "VIEW".to_string() } else { "TABLE".to_string() },
+                engine: None,
+                rows: row.get(3).and_then(Self::as_i64).map(|n| n as u64),
+                size_mb: row.get(4).and_then(Self::as_i64).map(|kb| kb as f64 / 1024.0),
+                comment: Self::as_string(&row[2]),
+            })
+            .collect())
+    }
+}
+
+impl Default for SqlServerDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SQL Server 驱动
+pub struct SqlServerDriver;
+
+inventory::submit! {
+    DriverRegistration { factory: || Arc::new(SqlServerDriver) }
+}
+
+impl DatabaseDriver for SqlServerDriver {
+    fn db_type(&self) -> DatabaseType {
+        DatabaseType::SqlServer
+    }
+
+    fn display_name(&self) -> &'static str {
+        "SQL Server"
+    }
+
+    fn create(&self) -> DbResult<Box<dyn DatabaseOperations>> {
+        Ok(Box::new(SqlServerDatabase::new()))
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            sql: true,
+            schemas: true,
+            object_types: vec![
+                ObjectType::Table,
+                ObjectType::View,
+                ObjectType::Procedure,
+                ObjectType::Function,
+                ObjectType::Trigger,
+                ObjectType::Sequence,
+                ObjectType::Index,
+            ],
+            editing: EditingCapabilities::all(),
+            transactions: true,
+            // SET SHOWPLAN_TEXT / SHOWPLAN_XML
+            explain_formats: vec![ExplainFormat::Text, ExplainFormat::Xml],
+            export_formats: ExportFormat::for_sql(),
+            ..Default::default()
+        }
+    }
+
+    fn dialect(&self) -> Option<SqlDialect> {
+        Some(SqlDialect::sqlserver())
+    }
+
+    /// 方括号，内部的 ] 写成 ]]
+    fn quote_identifier(&self, identifier: &str) -> String {
+        format!("[{}]", identifier.replace(']', "]]"))
+    }
+
+    fn default_schema(&self) -> Option<&'static str> {
+        Some("dbo")
+    }
+
+    /// `[database].[schema].[table]`
+    fn table_ref(&self, database: &str, schema: Option<&str>, table: &str) -> String {
+        format!(
+            "{}.{}.{}",
+            self.quote_identifier(database),
+            self.quote_identifier(schema.unwrap_or("dbo")),
+            self.quote_identifier(table)
+        )
+    }
+
+    /// DROP PROCEDURE 等语句不接受数据库名前缀
+    fn routine_ref(&self, _database: &str, schema: Option<&str>, name: &str) -> String {
+        format!("{}.{}", self.quote_identifier(schema.unwrap_or("dbo")), self.quote_identifier(name))
+    }
+
+    /// N 前缀保证非 ASCII 字符不受排序规则代码页影响
+    fn string_literal(&self, value: &str) -> String {
+        format!("N'{}'", value.replace('\'', "''"))
+    }
+
+    fn bool_literal(&self, value: bool) -> &'static str {
+        if value { "1" } else { "0" }
+    }
+
+    fn binary_literal(&self, hex: &str) -> String {
+        format!("0x{}", hex)
+    }
+
+    fn binary_as_base64(&self) -> bool {
+        true
+    }
+
+    fn identity_insert_sql(&self, table_ref: &str, enable: bool) -> Option<String> {
+        Some(format!("SET IDENTITY_INSERT {} {}", table_ref, if enable { "ON" } else { "OFF" }))
+    }
+
+    fn batch_separator(&self) -> bool {
+        true
+    }
+
+    /// 一条 INSERT ... VALUES 最多 1000 行
+    fn max_insert_rows(&self) -> Option<usize> {
+        Some(1000)
+    }
+
+    /// 不支持 LIMIT，使用 TOP
+    fn select_rows_sql(&self, table_ref: &str, limit: Option<u32>) -> String {
+        let top_clause = limit.map(|l| format!("TOP {} ", l)).unwrap_or_default();
+        format!("SELECT {}* FROM {}", top_clause, table_ref)
+    }
+
+    /// ROUTINE_SCHEMA 是架构名，查询在目标数据库中执行
+    fn list_routines_sql(&self, _database: &str, routine_type: &str) -> Option<String> {
+        Some(format!(
+            "SELECT ROUTINE_SCHEMA, ROUTINE_NAME, ROUTINE_TYPE, CREATED, LAST_ALTERED
+             FROM INFORMATION_SCHEMA.ROUTINES
+             WHERE ROUTINE_TYPE = {}
+             ORDER BY ROUTINE_SCHEMA, ROUTINE_NAME",
+            self.string_literal(routine_type)
+        ))
+    }
+
+    /// 没有 information_schema.TRIGGERS
+    fn list_triggers_sql(&self, _database: &str) -> Option<String> {
+        Some(
+            "SELECT tr.name AS TRIGGER_NAME,
+                    STUFF((SELECT ', ' + te.type_desc FROM sys.trigger_events te
+                           WHERE te.object_id = tr.object_id FOR XML PATH('')), 1, 2, '') AS EVENT_MANIPULATION,
+                    OBJECT_NAME(tr.parent_id) AS EVENT_OBJECT_TABLE,
+                    CASE WHEN tr.is_instead_of_trigger = 1 THEN 'INSTEAD OF' ELSE 'AFTER' END AS ACTION_TIMING,
+                    tr.create_date AS CREATED
+             FROM sys.triggers tr
+             WHERE tr.parent_class = 1
+             ORDER BY tr.name"
+                .to_string(),
+        )
+    }
+
+    /// INFORMATION_SCHEMA.VIEWS 会截断到 4000 字符
+    fn view_definition_sql(&self, _database: &str, view: &str) -> String {
+        format!(
+            "SELECT OBJECT_DEFINITION(OBJECT_ID({})) AS VIEW_DEFINITION",
+            self.string_literal(&self.quote_identifier(view))
+        )
+    }
+}
+
+#[async_trait]
+impl DatabaseOperations for SqlServerDatabase {
+    async fn test_connection(&self, config: &ConnectionConfig) -> DbResult<bool> {
+        let mut client = Self::open_client(config).await?;
+        Self::run_batch(&mut client, "SELECT 1").await?;
+        let _ = client.close().await;
+        Ok(true)
+    }
+
+    async fn connect(&mut self, config: ConnectionConfig) -> DbResult<()> {
+        let client = Self::open_client(&config).await?;
+
+        self.client = Some(Arc::new(Mutex::new(client)));
+        *self.current_database.lock().await = config.database.clone().filter(|d| !d.is_empty());
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> DbResult<()> {
+        if let Some(client) = self.client.take() {
+            if let Ok(client) = Arc::try_unwrap(client) {
+                let _ = client.into_inner().close().await;
+            }
+        }
+        *self.current_database.lock().await = None;
+        self.config = None;
+        Ok(())
+    }
+
+    async fn execute_query(&self, sql: &str, database: Option<&str>) -> DbResult<QueryResult> {
+        let start = Instant::now();
+        let batches = SqlSplitter::new(SqlDialect::sqlserver()).split(sql);
+
+        let client = self.client()?;
+        let mut client = client.lock().await;
+        self.use_database(&mut client, database).await?;
+
+        let mut total_affected_rows: u64 = 0;
+        let mut last_result: Option<(Vec<String>, Vec<Vec<Value>>)> = None;
+
+        for (idx, batch) in batches.iter().enumerate() {
+            let (result, affected) = Self::run_batch(&mut client, batch).await.map_err(|e| {
+                if batches.len() > 1 {
+                    e.context(Msg::BatchFailed.with(&[&(idx + 1)]))
+                } else {
+                    e
+                }
+            })?;
+
+            // 脚本中的 USE 会改变连接的当前数据库
+            if batch.trim_start().to_uppercase().starts_with("USE ") {
+                *self.current_database.lock().await = None;
+            }
+
+            total_affected_rows += affected;
+            if result.is_some() {
+                last_result = result;
+            }
+        }
+
+        Ok(match last_result {
+            Some((columns, values)) => {
+                let rows: Vec<HashMap<String, Value>> = values
+                    .into_iter()
+                    .map(|row| columns.iter().cloned().zip(row).collect())
+                    .collect();
+                QueryResult {
+                    affected_rows: rows.len() as u64 + total_affected_rows,
+                    columns,
+                    rows,
+                    execution_time_ms: start.elapsed().as_millis(),
+                }
+            }
+            None => QueryResult {
+                columns: vec![],
+                rows: vec![],
+                affected_rows: total_affected_rows,
+                execution_time_ms: start.elapsed().as_millis(),
+            },
+        })
+    }
+
+    async fn stream_query(
+        &self,
+        sql: &str,
+        database: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> DbResult<u64> {
+        let client = self.client()?;
+        let mut client = client.lock().await;
+        self.use_database(&mut client, database).await?;
+
+        // 按 TDS 数据包逐行读取；只输出第一个结果集，其余结果集读取后丢弃。
+        // 中途停止时未读完的数据由下一次查询前的 flush 丢弃
+        let mut stream = client.simple_query(sql).await.map_err(DbError::from)?;
+        let mut result_sets = 0;
+        let mut count = 0u64;
+        while let Some(item) = stream.try_next().await.map_err(DbError::from)? {
+            match item {
+                QueryItem::Metadata(meta) => {
+                    result_sets += 1;
+                    if result_sets == 1 {
+                        let columns: Vec<String> =
+                            meta.columns().iter().map(|c| c.name().to_string()).collect();
+                        sink.columns(&columns)?;
+                    }
+                }
+                QueryItem::Row(row) if result_sets == 1 => {
+                    sink.row(row.cells().map(|(_, data)| Self::cell_to_json(data)).collect())?;
+                    count += 1;
+                }
+                QueryItem::Row(_) => {}
+            }
+        }
+
+        if result_sets == 0 {
+            sink.columns(&[])?;
+        }
+        Ok(count)
+    }
+
+    async fn begin_transaction(&self, database: Option<&str>) -> DbResult<Box<dyn Transaction>> {
+        // 事务期间独占连接
+        let mut client = self.client()?.lock_owned().await;
+        self.use_database(&mut client, database).await?;
+
+        client
+            .simple_query("BEGIN TRANSACTION")
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::BeginTransactionFailed))?
+            .into_results()
+            .await
+            .map_err(|e| DbError::from(e).context(Msg::BeginTransactionFailed))?;
+
+        Ok(Box::new(SqlServerTransaction { client: Some(client) }))
+    }
+
+    async fn get_databases(&self) -> DbResult<Vec<DatabaseInfo>> {
+        Ok(self
+            .query_rows(
+                "SELECT name, collation_name FROM sys.databases WHERE state = 0 ORDER BY name",
+                None,
+            )
+            .await?
+            .iter()
+            .map(|row| DatabaseInfo {
+                name: Self::as_string(&row[0]).unwrap_or_default(),
+                charset: None,
+                collation: Self::as_string(&row[1]),
+            })
+            .collect())
+    }
+
+    async fn get_tables(&self, database: Option<&str>) -> DbResult<Vec<TableInfo>> {
+        self.list_objects(database, false).await
+    }
+
+    async fn get_views(&self, database: Option<&str>) -> DbResult<Vec<TableInfo>> {
+        self.list_objects(database, true).await
+    }
+
+    async fn get_table_structure(&self, table: &str, schema: Option<&str>, database: Option<&str>) -> DbResult<Vec<ColumnInfo>> {
+        let sql = format!(
+            "SELECT c.name, ty.name, c.is_nullable, dc.definition,
+                    CAST(CASE WHEN EXISTS (
+                        SELECT 1 FROM sys.indexes i
+                        JOIN sys.index_columns ic ON ic.object_id = i.object_id AND ic.index_id = i.index_id
+                        WHERE i.object_id = c.object_id AND i.is_primary_key = 1 AND ic.column_id = c.column_id
+                    ) THEN 1 ELSE 0 END AS BIT),
+                    c.is_identity,
+                    CAST(ep.value AS NVARCHAR(4000)),
+                    CAST(CASE
+                        WHEN ty.name IN ('nchar', 'nvarchar') AND c.max_length > 0 THEN c.max_length / 2
+                        WHEN ty.name IN ('char', 'varchar', 'binary', 'varbinary', 'nchar', 'nvarchar') THEN c.max_length
+                    END AS INT),
+                    CAST(c.precision AS INT), CAST(c.scale AS INT)
+             FROM sys.columns c
+             JOIN sys.types ty ON ty.user_type_id = c.user_type_id
+             LEFT JOIN sys.default_constraints dc ON dc.object_id = c.default_object_id
+             LEFT JOIN sys.extended_properties ep
+               ON ep.major_id = c.object_id AND ep.minor_id = c.column_id AND ep.name = 'MS_Description'
+             WHERE c.object_id = OBJECT_ID({})
+             ORDER BY c.column_id",
+            Self::object_name_literal(table, schema)
+        );
+
+        Ok(self
+            .query_rows(&sql, database)
+            .await?
+            .iter()
+            .map(|row| {
+                let data_type = Self::as_string(&row[1]).unwrap_or_default();
+                // 精度和小数位只对数值类型有意义
+                let numeric = matches!(data_type.as_str(), "decimal" | "numeric");
+                ColumnInfo {
+                    name: Self::as_string(&row[0]).unwrap_or_default(),
+                    nullable: Self::as_bool(&row[2]),
+                    default_value: Self::as_string(&row[3]),
+                    is_primary_key: Self::as_bool(&row[4]),
+                    is_auto_increment: Self::as_bool(&row[5]),
+                    comment: Self::as_string(&row[6]),
+                    character_maximum_length: Self::as_i64(&row[7]),
+                    numeric_precision: if numeric { Self::as_i64(&row[8]) } else { None },
+                    numeric_scale: if numeric { Self::as_i64(&row[9]) } else { None },
+                    data_type,
+                }
+            })
+            .collect())
+    }
+
+    async fn get_indexes(&self, table: &str, schema: Option<&str>) -> DbResult<Vec<IndexInfo>> {
+        let sql = format!(
+            "SELECT i.name, i.is_unique, i.is_primary_key, i.type_desc, c.name
+             FROM sys.indexes i
+             JOIN sys.index_columns ic ON ic.object_id = i.object_id AND ic.index_id = i.index_id
+             JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id
+             WHERE i.object_id = OBJECT_ID({}) AND ic.is_included_column = 0
+             ORDER BY i.name, ic.key_ordinal",
+            Self::object_name_literal(table, schema)
+        );
+
+        let mut indexes: Vec<IndexInfo> = Vec::new();
+        for row in self.query_rows(&sql, None).await? {
+            let name = Self::as_string(&row[0]).unwrap_or_default();
+            let column = Self::as_string(&row[4]).unwrap_or_default();
+            match indexes.iter_mut().find(|idx| idx.name == name) {
+                Some(index) => index.columns.push(column),
+                None => indexes.push(IndexInfo {
+                    name,
+                    columns: vec![column],
+                    is_unique: Self::as_bool(&row[1]),
+                    is_primary: Self::as_bool(&row[2]),
+                    index_type: Self::as_string(&row[3]).unwrap_or_default(),
+                }),
+            }
+        }
+
+        Ok(indexes)
+    }
+
+    async fn get_table_options(&self, table: &str, schema: Option<&str>) -> DbResult<TableOptions> {
+        let object_name = Self::object_name_literal(table, schema);
+        let sql = format!(
+            "SELECT CAST(ep.value AS NVARCHAR(4000)),
+                    CAST(DATABASEPROPERTYEX(DB_NAME(), 'Collation') AS NVARCHAR(128)),
+                    CAST(IDENT_CURRENT({}) AS BIGINT)
+             FROM (SELECT OBJECT_ID({}) AS object_id) o
+             LEFT JOIN sys.extended_properties ep
+               ON ep.major_id = o.object_id AND ep.minor_id = 0 AND ep.name = 'MS_Description'",
+            object_name, object_name
+        );
+
+        let rows = self.query_rows(&sql, None).await?;
+        let row = rows.first();
+
+        Ok(TableOptions {
+            engine: None,
+            charset: None,
+            collation: row.and_then(|r| Self::as_string(&r[1])),
+            comment: row.and_then(|r| Self::as_string(&r[0])),
+            auto_increment: row.and_then(|r| Self::as_i64(&r[2])).map(|v| v as u64),
+            extra: Default::default(),
+        })
+    }
+
+    async fn switch_database(&mut self, database: &str) -> DbResult<()> {
+        let client = self.client()?;
+        let mut client = client.lock().await;
+        self.use_database(&mut client, Some(database)).await
+    }
+
+    async fn ping(&self) -> DbResult<()> {
+        let client = self.client()?;
+        let mut client = client.lock().await;
+        Self::run_batch(&mut client, "SELECT 1")
+            .await
+            .map(|_| ())
+    }
+
+    async fn server_info(&self) -> DbResult<ServerInfo> {
+        let rows = self
+            .query_rows(
+                "SELECT CAST(SERVERPROPERTY('ProductVersion') AS NVARCHAR(128)), @@VERSION, SUSER_SNAME(),
+                        DATENAME(TZOFFSET, SYSDATETIMEOFFSET())",
+                None,
+            )
+            .await
+            .map_err(|e| e.context(Msg::ServerInfoFailed))?;
+        let row = rows.first().cloned().unwrap_or_default();
+        let field = |idx: usize| row.get(idx).and_then(Self::as_string);
+
+        let version = field(0).unwrap_or_default();
+        // @@VERSION 的第一行形如 "Microsoft SQL Server 2022 (RTM) - 16.0.1000.6 (X64)"
+        let version_string = field(1)
+            .and_then(|v| v.lines().next().map(|l| l.trim().to_string()))
+            .unwrap_or_else(|| format!("SQL Server {}", version));
+
+        let mut info = ServerInfo {
+            flavor: ServerFlavor::SqlServer,
+            version,
+            version_string,
+            current_user: field(2),
+            time_zone: field(3),
+            features: ServerFeatures::default(),
+        };
+
+        info.features = ServerFeatures {
+            // SQL Server 2012 (11.x) 起支持完整的窗口函数和序列，2016 (13.x) 起支持 JSON
+            window_functions: info.version_at_least(11, 0),
+            cte: true,
+            json: info.version_at_least(13, 0),
+            returning: false,
+            upsert: true,
+            sequences: info.version_at_least(11, 0),
+            check_constraints: true,
+            stored_procedures: true,
+            triggers: true,
+            events: false,
+            transactions: true,
+        };
+
+        Ok(info)
+    }
+}
+
+/// 按 SQL Server 错误号推断错误码
+fn classify_error_number(number: u32) -> Option<ErrorCode> {
+    let code = match number {
+        18456 | 18452 | 18486 | 18487 | 18488 => ErrorCode::AuthFailed,
+        102 | 105 | 156 | 170 => ErrorCode::SyntaxError,
+        229 | 230 | 262 | 297 | 300 => ErrorCode::PermissionDenied,
+        207 | 208 | 911 | 2812 | 4060 => ErrorCode::NotFound,
+        515 | 547 | 2601 | 2627 => ErrorCode::ConstraintViolation,
+        1222 | 3617 => ErrorCode::Timeout,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// SQL Server 事务，持有连接锁直到结束
+struct SqlServerTransaction {
+    client: Option<OwnedMutexGuard<SqlServerClient>>,
+}
+
+impl SqlServerTransaction {
+    async fn run(&mut self, sql: &str) -> DbResult<()> {
+        let client = self
+            .client
+            .as_mut()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+        client.simple_query(sql).await?.into_results().await?;
+        Ok(())
+    }
+
+    async fn finish(mut self: Box<Self>, sql: &str) -> DbResult<()> {
+        // 失败时保留连接，由 Drop 回滚
+        self.run(sql).await?;
+        self.client = None;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Transaction for SqlServerTransaction {
+    async fn execute(&mut self, sql: &str) -> DbResult<u64> {
+        let client = self
+            .client
+            .as_mut()
+            .ok_or_else(|| DbError::ConnectionFailed(Msg::NotConnected.into()))?;
+        let (_, affected) = SqlServerDatabase::run_batch(client, sql).await?;
+        Ok(affected)
+    }
+
+    async fn savepoint(&mut self, name: &str) -> DbResult<()> {
+        self.run(&format!("SAVE TRANSACTION {}", savepoint_name(name)?)).await
+    }
+
+    async fn rollback_to_savepoint(&mut self, name: &str) -> DbResult<()> {
+        self.run(&format!("ROLLBACK TRANSACTION {}", savepoint_name(name)?)).await
+    }
+
+    /// SQL Server 没有释放保存点的语句，保存点随事务结束
+    async fn release_savepoint(&mut self, _name: &str) -> DbResult<()> {
+        Ok(())
+    }
+
+    async fn commit(self: Box<Self>) -> DbResult<()> {
+        self.finish("COMMIT TRANSACTION")
+            .await
+            .map_err(|e| e.context(Msg::CommitFailed))
+    }
+
+    async fn rollback(self: Box<Self>) -> DbResult<()> {
+        self.finish("ROLLBACK TRANSACTION").await
+    }
+}
+
+impl Drop for SqlServerTransaction {
+    /// 未提交就丢弃时回滚，避免连接停留在事务中
+    fn drop(&mut self) {
+        if let Some(mut client) = self.client.take() {
+            tokio::spawn(async move {
+                if let Ok(stream) = client.simple_query("IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION").await {
+                    let _ = stream.into_results().await;
+                }
+            });
+        }
+    }
+}
+
+impl From<tiberius::error::Error> for DbError {
+    fn from(e: tiberius::error::Error) -> Self {
+        match e {
+            tiberius::error::Error::Server(token) => {
+                let native = NativeError {
+                    errno: Some(token.code() as i64),
+                    ..Default::default()
+                };
+                let code = classify_error_number(token.code()).unwrap_or(ErrorCode::QueryFailed);
+                DbError::database(code, token.message(), native)
+            }
+            e @ (tiberius::error::Error::Io { .. } | tiberius::error::Error::Tls(_)) => {
+                DbError::ConnectionLost(e.to_string())
+            }
+            e => DbError::QueryFailed(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_dml() {
+        assert!(SqlServerDatabase::is_dml("UPDATE t SET a = 1 WHERE id = 2;"));
+        assert!(SqlServerDatabase::is_dml("INSERT INTO t (a) VALUES ('x;y')"));
+        assert!(!SqlServerDatabase::is_dml("INSERT INTO t VALUES (1); SELECT * FROM t"));
+        assert!(!SqlServerDatabase::is_dml("DELETE FROM t OUTPUT deleted.id WHERE id = 1"));
+        assert!(!SqlServerDatabase::is_dml("SELECT 1"));
+    }
+
+    #[test]
+    fn test_savepoint_name() {
+        assert_eq!(savepoint_name("datasmith_import").unwrap(), "datasmith_import");
+        assert!(savepoint_name("_sp1").is_ok());
+        assert!(savepoint_name("").is_err());
+        assert!(savepoint_name("1sp").is_err());
+        assert!(savepoint_name("sp; DROP TABLE t").is_err());
+    }
+}
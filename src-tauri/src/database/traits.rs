@@ -2,6 +2,11 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::sql_splitter::{SqlDialect, SqlSplitter};
+use crate::utils::i18n::Msg;
+
+pub use super::error::{DbError, DbResult, ErrorCode, NativeError};
+
 /// 数据库连接配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionConfig {
@@ -14,22 +19,81 @@ pub struct ConnectionConfig {
     pub password: String,
     pub database: Option<String>,
     pub ssl: bool,
+    /// 启用 SSL 时信任服务器证书（自签名证书，不校验证书链）
+    #[serde(default)]
+    pub trust_server_certificate: bool,
     pub connection_timeout: u64,
     pub pool_size: u32,
+    /// 每个新会话建立后执行的初始化 SQL（可包含多条语句）
+    #[serde(default)]
+    pub init_sql: Option<String>,
+    /// 会话字符集（MySQL: SET NAMES；PostgreSQL: client_encoding）
+    #[serde(default)]
+    pub charset: Option<String>,
+    /// 会话时区
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    /// 应用名称（PostgreSQL: application_name；Redis: CLIENT SETNAME；MongoDB: appName）
+    #[serde(default)]
+    pub application_name: Option<String>,
+}
+
+impl ConnectionConfig {
+    /// 按方言拆分初始化 SQL
+    pub fn init_sql_statements(&self, dialect: SqlDialect) -> Vec<String> {
+        match self.init_sql.as_deref().map(str::trim) {
+            Some(sql) if !sql.is_empty() => SqlSplitter::new(dialect).split(sql),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// 取非空的会话设置值
+pub fn session_setting(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// 校验保存点名称，只允许字母、数字和下划线且不以数字开头（名称直接拼入 SQL）
+pub fn savepoint_name(name: &str) -> DbResult<&str> {
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(name)
+    } else {
+        Err(DbError::QueryFailed(Msg::InvalidSavepointName.with(&[&name])))
+    }
+}
+
+/// 转义 SQL 字符串字面量中的单引号
+pub fn escape_sql_literal(value: &str) -> String {
+    value.replace('\'', "''")
 }
 
 /// 数据库类型枚举
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum DatabaseType {
     MySQL,
     PostgreSQL,
     SQLite,
+    SqlServer,
+    DuckDB,
+    ClickHouse,
     MongoDB,
     Redis,
     Elasticsearch,
 }
 
+impl std::str::FromStr for DatabaseType {
+    type Err = DbError;
+
+    /// 按序列化名称解析（如 "mysql"、"sqlserver"）
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_lowercase()))
+            .map_err(|_| DbError::UnsupportedDatabase)
+    }
+}
+
 /// 查询结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryResult {
@@ -39,6 +103,25 @@ pub struct QueryResult {
     pub execution_time_ms: u128,
 }
 
+impl QueryResult {
+    /// 将已读入内存的结果按行写入 `sink`，返回行数
+    pub fn write_to(self, sink: &mut dyn RowSink) -> DbResult<u64> {
+        sink.columns(&self.columns)?;
+
+        let mut count = 0;
+        for mut row in self.rows {
+            let values = self
+                .columns
+                .iter()
+                .map(|column| row.remove(column).unwrap_or(serde_json::Value::Null))
+                .collect();
+            sink.row(values)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
 /// 单条SQL语句执行结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatementResult {
@@ -48,6 +131,9 @@ pub struct StatementResult {
     pub success: bool,
     /// 错误信息（如果有）
     pub error: Option<String>,
+    /// 错误码（如果有）
+    #[serde(default)]
+    pub error_code: Option<ErrorCode>,
     /// 影响行数
     pub affected_rows: u64,
     /// 执行时间（毫秒）
@@ -127,37 +213,139 @@ pub struct TableOptions {
     pub collation: Option<String>,
     pub comment: Option<String>,
     pub auto_increment: Option<u64>,
+    /// 产品分支特有的表选项（如 MariaDB 系统版本、TiDB 放置策略）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, String>,
 }
 
-/// 数据库操作结果
-pub type DbResult<T> = Result<T, DbError>;
+/// 服务器产品分支
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerFlavor {
+    MySQL,
+    MariaDB,
+    TiDB,
+    PostgreSQL,
+    CockroachDB,
+    SQLite,
+    SqlServer,
+    DuckDB,
+    ClickHouse,
+    MongoDB,
+    Redis,
+    Valkey,
+    Elasticsearch,
+    OpenSearch,
+}
 
-/// 数据库错误
-#[derive(Debug, thiserror::Error)]
-pub enum DbError {
-    #[error("连接失败: {0}")]
-    ConnectionFailed(String),
+/// 服务器支持的特性（用于调整生成的 SQL）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerFeatures {
+    /// 窗口函数
+    pub window_functions: bool,
+    /// WITH 公用表表达式
+    pub cte: bool,
+    /// JSON 类型/函数
+    pub json: bool,
+    /// INSERT/UPDATE/DELETE ... RETURNING
+    pub returning: bool,
+    /// UPSERT（ON DUPLICATE KEY / ON CONFLICT）
+    pub upsert: bool,
+    /// 序列
+    pub sequences: bool,
+    /// CHECK 约束（实际生效）
+    pub check_constraints: bool,
+    /// 存储过程和函数
+    pub stored_procedures: bool,
+    /// 触发器
+    pub triggers: bool,
+    /// 事件调度器
+    pub events: bool,
+    /// 事务
+    pub transactions: bool,
+}
 
-    #[error("查询执行失败: {0}")]
-    QueryFailed(String),
+/// 服务器信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerInfo {
+    /// 产品分支
+    pub flavor: ServerFlavor,
+    /// 产品版本（例如 8.0.35、10.11.6、v23.1.0）
+    pub version: String,
+    /// 服务器返回的完整版本描述
+    pub version_string: String,
+    /// 当前登录用户
+    pub current_user: Option<String>,
+    /// 服务器时区
+    pub time_zone: Option<String>,
+    /// 特性标记
+    pub features: ServerFeatures,
+}
 
-    #[error("不支持的数据库类型")]
-    UnsupportedDatabase,
+impl ServerInfo {
+    /// 版本是否不低于指定的 major.minor
+    pub fn version_at_least(&self, major: u32, minor: u32) -> bool {
+        let (ma, mi, _) = parse_version(&self.version);
+        (ma, mi) >= (major, minor)
+    }
+}
 
-    #[error("配置错误: {0}")]
-    ConfigError(String),
+/// 从版本字符串中解析出第一个 `major.minor.patch`（缺失的部分为 0）
+pub fn parse_version(version: &str) -> (u32, u32, u32) {
+    let start = version.find(|c: char| c.is_ascii_digit()).unwrap_or(version.len());
+    let mut parts = version[start..]
+        .split(|c: char| !c.is_ascii_digit())
+        .take(3)
+        .map(|p| p.parse::<u32>().unwrap_or(0));
 
-    #[error("其他错误: {0}")]
-    Other(String),
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
 }
 
-impl Serialize for DbError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.to_string())
+/// 流式查询结果的接收端
+///
+/// 驱动先调用一次 `columns`，再按顺序逐行调用 `row`；
+/// 任一方法返回错误时驱动立即停止读取（导出取消即通过此方式实现）
+pub trait RowSink: Send {
+    /// 结果列名
+    fn columns(&mut self, columns: &[String]) -> DbResult<()>;
+
+    /// 一行数据，值的顺序与列名一致
+    fn row(&mut self, values: Vec<serde_json::Value>) -> DbResult<()>;
+}
+
+/// 数据库事务
+///
+/// 事务内的语句在同一个会话上执行；结束时调用 `commit` 或 `rollback`，
+/// 未结束就丢弃时由驱动回滚
+#[async_trait]
+pub trait Transaction: Send {
+    /// 执行一条语句，返回影响行数
+    async fn execute(&mut self, sql: &str) -> DbResult<u64>;
+
+    /// 创建保存点
+    async fn savepoint(&mut self, name: &str) -> DbResult<()> {
+        self.execute(&format!("SAVEPOINT {}", savepoint_name(name)?)).await.map(|_| ())
+    }
+
+    /// 回滚到保存点（保存点之后的修改被撤销，事务继续）
+    async fn rollback_to_savepoint(&mut self, name: &str) -> DbResult<()> {
+        self.execute(&format!("ROLLBACK TO SAVEPOINT {}", savepoint_name(name)?)).await.map(|_| ())
     }
+
+    /// 释放保存点
+    async fn release_savepoint(&mut self, name: &str) -> DbResult<()> {
+        self.execute(&format!("RELEASE SAVEPOINT {}", savepoint_name(name)?)).await.map(|_| ())
+    }
+
+    /// 提交事务
+    async fn commit(self: Box<Self>) -> DbResult<()>;
+
+    /// 回滚事务
+    async fn rollback(self: Box<Self>) -> DbResult<()>;
 }
 
 /// 数据库操作 Trait
@@ -175,6 +363,28 @@ pub trait DatabaseOperations: Send + Sync {
     /// 执行查询
     async fn execute_query(&self, sql: &str, database: Option<&str>) -> DbResult<QueryResult>;
 
+    /// 流式执行单条查询，逐行写入 sink，返回写入的行数
+    ///
+    /// 默认实现先取得完整结果再逐行写入；能按游标读取的驱动应覆盖此方法，
+    /// 避免把整个结果集放在内存中
+    async fn stream_query(
+        &self,
+        sql: &str,
+        database: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> DbResult<u64> {
+        self.execute_query(sql, database).await?.write_to(sink)
+    }
+
+    /// 开始事务（默认实现返回不支持）
+    async fn begin_transaction(&self, _database: Option<&str>) -> DbResult<Box<dyn Transaction>> {
+        Err(DbError::database(
+            ErrorCode::Unsupported,
+            Msg::TransactionUnsupported.text(),
+            NativeError::default(),
+        ))
+    }
+
     /// 获取数据库列表
     async fn get_databases(&self) -> DbResult<Vec<DatabaseInfo>>;
 
@@ -201,7 +411,67 @@ pub trait DatabaseOperations: Send + Sync {
         Ok(())
     }
     
-    /// 获取 Any 引用，用于向下转型
-    fn as_any(&self) -> &dyn std::any::Any;
+    /// 检查连接是否仍然可用（用于健康检查）
+    async fn ping(&self) -> DbResult<()>;
+    
+    /// 获取服务器信息（版本、产品分支、当前用户、时区和特性）
+    async fn server_info(&self) -> DbResult<ServerInfo>;
+    
+    /// 键值操作扩展（Redis 等），不支持时返回 None
+    fn as_key_value(&self) -> Option<&dyn KeyValueOperations> {
+        None
+    }
+    
+    /// 搜索引擎扩展（Elasticsearch 等），不支持时返回 None
+    fn as_search(&self) -> Option<&dyn SearchOperations> {
+        None
+    }
+}
+
+/// 键值数据库扩展操作，值统一转换为 JSON
+#[async_trait]
+pub trait KeyValueOperations: Send + Sync {
+    /// 执行原生命令
+    async fn execute_command(&self, command: &str, args: Vec<String>) -> DbResult<serde_json::Value>;
+    
+    /// 获取服务器统计信息（INFO）
+    async fn get_server_info(&self) -> DbResult<HashMap<String, String>>;
+    
+    /// 获取键的值
+    async fn get_key_value(&self, key: &str) -> DbResult<serde_json::Value>;
+    
+    /// 获取键的 TTL（秒）
+    async fn get_key_ttl(&self, key: &str) -> DbResult<i64>;
+    
+    /// 设置字符串值
+    async fn set_key_value(&self, key: &str, value: &str, ttl: Option<u64>) -> DbResult<()>;
+    
+    /// 删除键
+    async fn delete_key(&self, key: &str) -> DbResult<()>;
+    
+    /// 覆盖 List 的值
+    async fn set_list_value(&self, key: &str, values: Vec<String>) -> DbResult<()>;
+    
+    /// 覆盖 Set 的值
+    async fn set_set_value(&self, key: &str, members: Vec<String>) -> DbResult<()>;
+    
+    /// 覆盖 ZSet 的值
+    async fn set_zset_value(&self, key: &str, members: Vec<(String, f64)>) -> DbResult<()>;
+    
+    /// 覆盖 Hash 的值
+    async fn set_hash_value(&self, key: &str, fields: Vec<(String, String)>) -> DbResult<()>;
+    
+    /// 设置 TTL（-1 表示移除过期时间）
+    async fn set_key_ttl(&self, key: &str, ttl: i64) -> DbResult<()>;
+    
+    /// 重命名键
+    async fn rename_key(&self, old_key: &str, new_key: &str) -> DbResult<()>;
+}
+
+/// 搜索引擎扩展操作
+#[async_trait]
+pub trait SearchOperations: Send + Sync {
+    /// 获取集群健康状态
+    async fn cluster_health(&self) -> DbResult<serde_json::Value>;
 }
 
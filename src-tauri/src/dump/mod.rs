@@ -0,0 +1,238 @@
+//! 数据库备份与恢复
+//!
+//! 不依赖 mysqldump、pg_dump 等客户端工具：按元数据读取表、视图、存储过程和触发器的定义，
+//! 连同表数据写成一个 SQL 文件（可选 gzip 压缩）；恢复时按连接的方言拆分后逐条执行
+
+pub mod schema;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read, Write};
+
+use crate::database::DatabaseType;
+
+/// 备份选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DumpOptions {
+    /// 导出表和视图的结构
+    pub schema: bool,
+    /// 导出表数据
+    pub data: bool,
+    /// 导出存储过程、函数和事件
+    pub routines: bool,
+    pub triggers: bool,
+    /// 创建前先删除已存在的对象
+    pub drop_existing: bool,
+    /// 每条 INSERT 包含的行数
+    pub batch_size: usize,
+    /// 使用 gzip 压缩
+    pub gzip: bool,
+    /// 只备份这些表（为空时备份全部）
+    pub tables: Vec<String>,
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        Self {
+            schema: true,
+            data: true,
+            routines: true,
+            triggers: true,
+            drop_existing: false,
+            batch_size: 500,
+            gzip: false,
+            tables: Vec::new(),
+        }
+    }
+}
+
+/// 备份阶段
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DumpStage {
+    Schema,
+    Data,
+    Routines,
+    Triggers,
+}
+
+/// 备份进度
+#[derive(Debug, Clone, Serialize)]
+pub struct DumpProgress {
+    pub dump_id: String,
+    pub stage: DumpStage,
+    /// 正在处理的对象
+    pub object: String,
+    /// 已完成的表数
+    pub tables_done: usize,
+    pub tables_total: usize,
+    /// 已写入的数据行数
+    pub rows: u64,
+    pub elapsed_ms: u128,
+}
+
+/// 恢复进度
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreProgress {
+    pub restore_id: String,
+    /// 已执行的语句数
+    pub executed: usize,
+    pub total: usize,
+    pub failed: usize,
+    pub elapsed_ms: u128,
+}
+
+/// 备份文件输出，按选项压缩
+pub enum DumpOutput<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+}
+
+impl<W: Write> DumpOutput<W> {
+    pub fn new(out: W, gzip: bool) -> Self {
+        if gzip {
+            Self::Gzip(GzEncoder::new(out, Compression::default()))
+        } else {
+            Self::Plain(out)
+        }
+    }
+
+    /// 写完压缩流的结尾并刷新
+    pub fn finish(self) -> io::Result<W> {
+        let mut out = match self {
+            Self::Plain(out) => out,
+            Self::Gzip(encoder) => encoder.finish()?,
+        };
+        out.flush()?;
+        Ok(out)
+    }
+}
+
+impl<W: Write> Write for DumpOutput<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(out) => out.write(buf),
+            Self::Gzip(out) => out.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(out) => out.flush(),
+            Self::Gzip(out) => out.flush(),
+        }
+    }
+}
+
+/// 读取备份脚本，按文件头自动识别 gzip 压缩
+pub fn decode_script(bytes: Vec<u8>) -> io::Result<String> {
+    let mut text = String::new();
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        GzDecoder::new(bytes.as_slice()).read_to_string(&mut text)?;
+    } else {
+        text = String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    }
+    Ok(text.strip_prefix('\u{feff}').map(str::to_string).unwrap_or(text))
+}
+
+/// 按外键排列建表顺序：被引用的表在前
+///
+/// `foreign_keys` 为 (表, 被引用的表)；自引用忽略，存在循环引用的表按原顺序放在最后。
+/// 循环引用的表不能靠顺序导入：PostgreSQL 和 SQL Server 的外键在数据之后添加，MySQL 关闭外键检查
+pub fn dependency_order(tables: &[String], foreign_keys: &[(String, String)]) -> Vec<String> {
+    let known: HashSet<&String> = tables.iter().collect();
+    let mut depends_on: HashMap<&String, HashSet<&String>> = HashMap::new();
+    for (table, referenced) in foreign_keys {
+        if table != referenced && known.contains(table) && known.contains(referenced) {
+            depends_on.entry(table).or_default().insert(referenced);
+        }
+    }
+
+    let mut ordered: Vec<String> = Vec::with_capacity(tables.len());
+    let mut done: HashSet<&String> = HashSet::new();
+    loop {
+        let ready: Vec<&String> = tables
+            .iter()
+            .filter(|t| !done.contains(t))
+            .filter(|t| depends_on.get(t).is_none_or(|deps| deps.iter().all(|d| done.contains(d))))
+            .collect();
+        if ready.is_empty() {
+            break;
+        }
+        for table in ready {
+            done.insert(table);
+            ordered.push(table.clone());
+        }
+    }
+
+    ordered.extend(tables.iter().filter(|t| !done.contains(t)).cloned());
+    ordered
+}
+
+/// 脚本中一条语句的文本
+///
+/// `block` 表示过程体中含有分号（存储过程、触发器等），MySQL 需要临时修改分隔符；
+/// SQL Server 每条语句单独作为一个批处理
+pub fn statement_text(db_type: &DatabaseType, sql: &str, block: bool) -> String {
+    let sql = sql.trim();
+    match db_type {
+        DatabaseType::SqlServer => format!("{}\nGO\n", sql),
+        DatabaseType::MySQL if block => format!("DELIMITER ;;\n{};;\nDELIMITER ;\n", sql),
+        // 过程体以 END 结尾时末尾的分号不能省略，其余语句去掉自带的分号后统一补上
+        _ if block => format!("{};\n", sql),
+        _ => format!("{};\n", sql.trim_end_matches(';').trim_end()),
+    }
+}
+
+/// 脚本开头的会话设置和结尾的恢复语句
+pub fn session_statements(db_type: &DatabaseType) -> (Vec<&'static str>, Vec<&'static str>) {
+    match db_type {
+        DatabaseType::MySQL => (
+            vec!["SET NAMES utf8mb4", "SET FOREIGN_KEY_CHECKS = 0"],
+            vec!["SET FOREIGN_KEY_CHECKS = 1"],
+        ),
+        DatabaseType::PostgreSQL => (vec!["SET client_encoding = 'UTF8'"], Vec::new()),
+        _ => (Vec::new(), Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_order() {
+        let tables: Vec<String> = ["orders", "items", "users", "a", "b"].iter().map(|t| t.to_string()).collect();
+        let fks: Vec<(String, String)> = [
+            ("orders", "users"),
+            ("items", "orders"),
+            ("users", "users"),
+            ("a", "b"),
+            ("b", "a"),
+        ]
+        .iter()
+        .map(|(t, r)| (t.to_string(), r.to_string()))
+        .collect();
+        assert_eq!(dependency_order(&tables, &fks), vec!["users", "orders", "items", "a", "b"]);
+    }
+
+    #[test]
+    fn test_gzip_script() {
+        let mut out = DumpOutput::new(Vec::new(), true);
+        out.write_all(statement_text(&DatabaseType::MySQL, "CREATE PROCEDURE p() BEGIN SELECT 1; END", true).as_bytes())
+            .unwrap();
+        let bytes = out.finish().unwrap();
+        assert!(bytes.starts_with(&[0x1f, 0x8b]));
+        assert_eq!(
+            decode_script(bytes).unwrap(),
+            "DELIMITER ;;\nCREATE PROCEDURE p() BEGIN SELECT 1; END;;\nDELIMITER ;\n"
+        );
+
+        assert_eq!(decode_script(b"\xef\xbb\xbfSELECT 1;".to_vec()).unwrap(), "SELECT 1;");
+        assert_eq!(statement_text(&DatabaseType::SqlServer, "CREATE VIEW v AS SELECT 1", false), "CREATE VIEW v AS SELECT 1\nGO\n");
+    }
+}
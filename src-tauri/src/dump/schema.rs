@@ -0,0 +1,644 @@
+//! 按数据库类型读取备份对象的定义
+//!
+//! MySQL、ClickHouse 使用 SHOW CREATE，SQLite、DuckDB 读取系统表中保存的原始语句；
+//! PostgreSQL 和 SQL Server 没有整表的建表语句，按列、约束和索引拼接
+
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::database::registry::ObjectType;
+use crate::database::{
+    escape_sql_literal, ColumnInfo, ConnectionManager, DatabaseType, DbError, DbResult, ErrorCode,
+    NativeError, TableInfo,
+};
+use crate::export::sql::declared_type;
+use crate::utils::i18n::Msg;
+use crate::utils::sql_formatter::SqlFormatter;
+
+type Row = HashMap<String, Value>;
+
+/// 备份中的一个数据库对象
+#[derive(Debug, Clone)]
+pub struct DumpObject {
+    pub name: String,
+    /// 删除已存在对象的语句
+    pub drop: Option<String>,
+    /// 创建语句（建表后可能还有建索引的语句）
+    pub create: Vec<String>,
+    /// 定义中含有分号的过程体（存储过程、触发器等）
+    pub block: bool,
+    /// 写入数据后再添加的外键约束（ALTER TABLE ... ADD CONSTRAINT）
+    pub constraints: Vec<String>,
+}
+
+/// 按列名读取文本值（忽略大小写）
+fn text(row: &Row, key: &str) -> Option<String> {
+    row.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .and_then(|(_, value)| match value {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        })
+}
+
+/// 读取一个数据库中各类对象的定义
+pub struct SchemaReader<'a> {
+    manager: &'a ConnectionManager,
+    connection_id: &'a str,
+    database: &'a str,
+    pub db_type: DatabaseType,
+}
+
+impl<'a> SchemaReader<'a> {
+    pub async fn new(manager: &'a ConnectionManager, connection_id: &'a str, database: &'a str) -> DbResult<Self> {
+        let db_type = manager.get_database_type(connection_id).await?;
+        let supported = matches!(
+            db_type,
+            DatabaseType::MySQL
+                | DatabaseType::PostgreSQL
+                | DatabaseType::SQLite
+                | DatabaseType::SqlServer
+                | DatabaseType::DuckDB
+                | DatabaseType::ClickHouse
+        );
+        if !supported {
+            return Err(DbError::database(
+                ErrorCode::Unsupported,
+                Msg::DumpUnsupported.with(&[&format!("{:?}", db_type)]),
+                NativeError::default(),
+            ));
+        }
+
+        Ok(Self {
+            manager,
+            connection_id,
+            database,
+            db_type,
+        })
+    }
+
+    async fn query(&self, sql: &str) -> DbResult<Vec<Row>> {
+        Ok(self
+            .manager
+            .execute_query(self.connection_id, sql, Some(self.database))
+            .await?
+            .rows)
+    }
+
+    /// 查询结果第一行的某列
+    async fn query_text(&self, sql: &str, key: &str) -> DbResult<Option<String>> {
+        Ok(self.query(sql).await?.first().and_then(|row| text(row, key)))
+    }
+
+    async fn supports(&self, object_type: ObjectType) -> bool {
+        self.manager
+            .get_capabilities(self.connection_id)
+            .await
+            .map(|capabilities| capabilities.supports_object(object_type))
+            .unwrap_or(false)
+    }
+
+    fn quote(&self, identifier: &str) -> String {
+        SqlFormatter::quote_identifier(&self.db_type, identifier)
+    }
+
+    /// 是否按架构区分对象
+    pub fn uses_schema(&self) -> bool {
+        matches!(
+            self.db_type,
+            DatabaseType::PostgreSQL | DatabaseType::SqlServer | DatabaseType::DuckDB
+        )
+    }
+
+    /// 表的唯一标识，与外键查询返回的名称一致
+    pub fn table_key(&self, table: &TableInfo) -> String {
+        match (&table.schema, self.uses_schema()) {
+            (Some(schema), true) => format!("{}.{}", schema, table.name),
+            _ => table.name.clone(),
+        }
+    }
+
+    /// 带架构的对象引用
+    pub fn qualified(&self, schema: Option<&str>, name: &str) -> String {
+        match schema.filter(|_| self.uses_schema()) {
+            Some(schema) => format!("{}.{}", self.quote(schema), self.quote(name)),
+            None => self.quote(name),
+        }
+    }
+
+    /// 对象名的字符串字面量（用于系统表查询条件）
+    fn literal(&self, value: &str) -> String {
+        format!("'{}'", escape_sql_literal(value))
+    }
+
+    /// 外键依赖：(表, 被引用的表)
+    pub async fn foreign_keys(&self) -> DbResult<Vec<(String, String)>> {
+        let sql = match self.db_type {
+            DatabaseType::MySQL => format!(
+                "SELECT TABLE_NAME AS table_name, REFERENCED_TABLE_NAME AS referenced_table_name
+                 FROM information_schema.KEY_COLUMN_USAGE
+                 WHERE TABLE_SCHEMA = {0} AND REFERENCED_TABLE_SCHEMA = {0}",
+                self.literal(self.database)
+            ),
+            DatabaseType::PostgreSQL => "SELECT cn.nspname || '.' || c.relname AS table_name,
+                    rn.nspname || '.' || r.relname AS referenced_table_name
+                 FROM pg_constraint k
+                 JOIN pg_class c ON c.oid = k.conrelid
+                 JOIN pg_namespace cn ON cn.oid = c.relnamespace
+                 JOIN pg_class r ON r.oid = k.confrelid
+                 JOIN pg_namespace rn ON rn.oid = r.relnamespace
+                 WHERE k.contype = 'f'"
+                .to_string(),
+            DatabaseType::SqlServer => "SELECT SCHEMA_NAME(p.schema_id) + '.' + p.name AS table_name,
+                    SCHEMA_NAME(r.schema_id) + '.' + r.name AS referenced_table_name
+                 FROM sys.foreign_keys fk
+                 JOIN sys.tables p ON p.object_id = fk.parent_object_id
+                 JOIN sys.tables r ON r.object_id = fk.referenced_object_id"
+                .to_string(),
+            DatabaseType::SQLite => "SELECT m.name AS table_name, p.\"table\" AS referenced_table_name
+                 FROM sqlite_master m JOIN pragma_foreign_key_list(m.name) p
+                 WHERE m.type = 'table'"
+                .to_string(),
+            DatabaseType::DuckDB => "SELECT schema_name || '.' || table_name AS table_name,
+                    schema_name || '.' || referenced_table AS referenced_table_name
+                 FROM duckdb_constraints()
+                 WHERE constraint_type = 'FOREIGN KEY'"
+                .to_string(),
+            _ => return Ok(Vec::new()),
+        };
+
+        Ok(self
+            .query(&sql)
+            .await?
+            .iter()
+            .filter_map(|row| Some((text(row, "table_name")?, text(row, "referenced_table_name")?)))
+            .collect())
+    }
+
+    /// 需要事先创建的架构（PostgreSQL、SQL Server 的默认架构除外）
+    pub fn schema_statements(&self, tables: &[TableInfo]) -> Vec<String> {
+        let mut schemas: Vec<&str> = tables.iter().filter_map(|t| t.schema.as_deref()).collect();
+        schemas.sort();
+        schemas.dedup();
+        schemas
+            .into_iter()
+            .filter_map(|schema| match self.db_type {
+                DatabaseType::PostgreSQL if schema != "public" => {
+                    Some(format!("CREATE SCHEMA IF NOT EXISTS {}", self.quote(schema)))
+                }
+                DatabaseType::SqlServer if schema != "dbo" => Some(format!(
+                    "IF SCHEMA_ID(N{}) IS NULL EXEC('CREATE SCHEMA {}')",
+                    self.literal(schema),
+                    self.quote(schema).replace('\'', "''")
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 表的建表语句和索引
+    ///
+    /// PostgreSQL 和 SQL Server 的外键单独放在 `constraints` 中，写入数据后再添加，
+    /// 循环引用的表也能按任意顺序建表和导入；MySQL 在脚本中关闭外键检查
+    pub async fn table(&self, table: &TableInfo, columns: &[ColumnInfo]) -> DbResult<DumpObject> {
+        let qualified = self.qualified(table.schema.as_deref(), &table.name);
+        let schema = table.schema.as_deref().unwrap_or_default();
+        let mut constraints = Vec::new();
+        let create = match self.db_type {
+            DatabaseType::MySQL | DatabaseType::ClickHouse => {
+                let sql = format!("SHOW CREATE TABLE {}", qualified);
+                let ddl = self.query(&sql).await?.first().and_then(|row| {
+                    text(row, "Create Table").or_else(|| text(row, "statement"))
+                });
+                ddl.into_iter().collect()
+            }
+            DatabaseType::SQLite => {
+                let sql = format!(
+                    "SELECT sql FROM sqlite_master
+                     WHERE tbl_name = {} AND type IN ('table', 'index') AND sql IS NOT NULL
+                     ORDER BY type DESC",
+                    self.literal(&table.name)
+                );
+                self.query(&sql).await?.iter().filter_map(|row| text(row, "sql")).collect()
+            }
+            DatabaseType::DuckDB => {
+                let condition = format!(
+                    "schema_name = {} AND table_name = {} AND sql IS NOT NULL",
+                    self.literal(schema),
+                    self.literal(&table.name)
+                );
+                let sql = format!(
+                    "SELECT sql FROM duckdb_tables() WHERE {0}
+                     UNION ALL SELECT sql FROM duckdb_indexes() WHERE {0}",
+                    condition
+                );
+                self.query(&sql).await?.iter().filter_map(|row| text(row, "sql")).collect()
+            }
+            DatabaseType::PostgreSQL => self.postgres_table(&qualified, columns, &mut constraints).await?,
+            _ => self.sqlserver_table(&qualified, columns, &mut constraints).await?,
+        };
+
+        if create.is_empty() {
+            return Err(DbError::Other(Msg::TableStructureUnavailable.with(&[&self.database, &table.name])));
+        }
+        Ok(DumpObject {
+            name: self.table_key(table),
+            drop: Some(format!("DROP TABLE IF EXISTS {}", qualified)),
+            create,
+            block: false,
+            constraints,
+        })
+    }
+
+    /// 列定义（PostgreSQL 的序列默认值改为 serial 类型，SQL Server 的自增列加上 IDENTITY）
+    fn column_definition(&self, column: &ColumnInfo) -> String {
+        let mut sql_type = declared_type(column);
+        let mut default = column.default_value.clone();
+        if column.is_auto_increment {
+            match self.db_type {
+                DatabaseType::PostgreSQL if default.as_deref().is_some_and(|d| d.starts_with("nextval(")) => {
+                    sql_type = match sql_type.as_str() {
+                        "bigint" => "bigserial",
+                        "smallint" => "smallserial",
+                        _ => "serial",
+                    }
+                    .to_string();
+                    default = None;
+                }
+                DatabaseType::SqlServer => sql_type.push_str(" IDENTITY(1,1)"),
+                _ => {}
+            }
+        }
+
+        let mut definition = format!("  {} {}", self.quote(&column.name), sql_type);
+        if let Some(default) = default {
+            definition.push_str(&format!(" DEFAULT {}", default));
+        }
+        if !column.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        definition
+    }
+
+    async fn postgres_table(
+        &self,
+        qualified: &str,
+        columns: &[ColumnInfo],
+        constraints: &mut Vec<String>,
+    ) -> DbResult<Vec<String>> {
+        let regclass = format!("{}::regclass", self.literal(qualified));
+        let mut definitions: Vec<String> = columns.iter().map(|c| self.column_definition(c)).collect();
+
+        let sql = format!(
+            "SELECT conname, contype, pg_get_constraintdef(oid) AS definition
+             FROM pg_constraint
+             WHERE conrelid = {} AND contype IN ('p', 'u', 'c', 'f')
+             ORDER BY contype DESC, conname",
+            regclass
+        );
+        for row in self.query(&sql).await? {
+            if let (Some(name), Some(definition)) = (text(&row, "conname"), text(&row, "definition")) {
+                if text(&row, "contype").as_deref() == Some("f") {
+                    constraints.push(format!(
+                        "ALTER TABLE {} ADD CONSTRAINT {} {}",
+                        qualified,
+                        self.quote(&name),
+                        definition
+                    ));
+                } else {
+                    definitions.push(format!("  CONSTRAINT {} {}", self.quote(&name), definition));
+                }
+            }
+        }
+
+        let mut statements = vec![format!("CREATE TABLE {} (\n{}\n)", qualified, definitions.join(",\n"))];
+
+        // 约束自带的索引随约束创建
+        let sql = format!(
+            "SELECT pg_get_indexdef(i.indexrelid) AS definition
+             FROM pg_index i
+             WHERE i.indrelid = {0}
+               AND NOT EXISTS (SELECT 1 FROM pg_constraint k WHERE k.conindid = i.indexrelid AND k.conrelid = {0})",
+            regclass
+        );
+        statements.extend(self.query(&sql).await?.iter().filter_map(|row| text(row, "definition")));
+        Ok(statements)
+    }
+
+    async fn sqlserver_table(
+        &self,
+        qualified: &str,
+        columns: &[ColumnInfo],
+        constraints: &mut Vec<String>,
+    ) -> DbResult<Vec<String>> {
+        let mut definitions: Vec<String> = columns.iter().map(|c| self.column_definition(c)).collect();
+
+        let primary_keys: Vec<String> = columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| self.quote(&c.name))
+            .collect();
+        if !primary_keys.is_empty() {
+            definitions.push(format!("  PRIMARY KEY ({})", primary_keys.join(", ")));
+        }
+
+        let sql = format!(
+            "SELECT fk.name AS constraint_name, pc.name AS column_name,
+                    SCHEMA_NAME(r.schema_id) AS referenced_schema, r.name AS referenced_table,
+                    rc.name AS referenced_column
+             FROM sys.foreign_keys fk
+             JOIN sys.foreign_key_columns fkc ON fkc.constraint_object_id = fk.object_id
+             JOIN sys.columns pc ON pc.object_id = fkc.parent_object_id AND pc.column_id = fkc.parent_column_id
+             JOIN sys.tables r ON r.object_id = fkc.referenced_object_id
+             JOIN sys.columns rc ON rc.object_id = fkc.referenced_object_id AND rc.column_id = fkc.referenced_column_id
+             WHERE fk.parent_object_id = OBJECT_ID(N{})
+             ORDER BY fk.name, fkc.constraint_column_id",
+            self.literal(qualified)
+        );
+        // 约束名 → (列, 被引用的表, 被引用的列)
+        let mut foreign_keys: BTreeMap<String, (Vec<String>, String, Vec<String>)> = BTreeMap::new();
+        for row in self.query(&sql).await? {
+            let (Some(name), Some(column), Some(table), Some(referenced)) = (
+                text(&row, "constraint_name"),
+                text(&row, "column_name"),
+                text(&row, "referenced_table"),
+                text(&row, "referenced_column"),
+            ) else {
+                continue;
+            };
+            let table = self.qualified(text(&row, "referenced_schema").as_deref(), &table);
+            let entry = foreign_keys.entry(name).or_insert_with(|| (Vec::new(), table, Vec::new()));
+            entry.0.push(self.quote(&column));
+            entry.2.push(self.quote(&referenced));
+        }
+        for (name, (columns, table, referenced)) in foreign_keys {
+            constraints.push(format!(
+                "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+                qualified,
+                self.quote(&name),
+                columns.join(", "),
+                table,
+                referenced.join(", ")
+            ));
+        }
+
+        Ok(vec![format!("CREATE TABLE {} (\n{}\n)", qualified, definitions.join(",\n"))])
+    }
+
+    /// 视图定义
+    pub async fn view(&self, view: &TableInfo) -> DbResult<Option<DumpObject>> {
+        let qualified = self.qualified(view.schema.as_deref(), &view.name);
+        let schema = view.schema.as_deref().unwrap_or_default();
+        let ddl = match self.db_type {
+            DatabaseType::MySQL => self.query_text(&format!("SHOW CREATE VIEW {}", qualified), "Create View").await?,
+            DatabaseType::ClickHouse => {
+                self.query_text(&format!("SHOW CREATE TABLE {}", qualified), "statement").await?
+            }
+            DatabaseType::SQLite => {
+                let sql = format!(
+                    "SELECT sql FROM sqlite_master WHERE type = 'view' AND name = {}",
+                    self.literal(&view.name)
+                );
+                self.query_text(&sql, "sql").await?
+            }
+            DatabaseType::DuckDB => {
+                let sql = format!(
+                    "SELECT sql FROM duckdb_views() WHERE schema_name = {} AND view_name = {}",
+                    self.literal(schema),
+                    self.literal(&view.name)
+                );
+                self.query_text(&sql, "sql").await?
+            }
+            DatabaseType::PostgreSQL => {
+                let sql = format!(
+                    "SELECT pg_get_viewdef({}::regclass, true) AS definition",
+                    self.literal(&qualified)
+                );
+                self.query_text(&sql, "definition").await?.map(|definition| {
+                    format!("CREATE VIEW {} AS\n{}", qualified, definition.trim().trim_end_matches(';'))
+                })
+            }
+            _ => {
+                let sql = format!("SELECT OBJECT_DEFINITION(OBJECT_ID(N{})) AS definition", self.literal(&qualified));
+                self.query_text(&sql, "definition").await?
+            }
+        };
+
+        Ok(ddl.map(|ddl| DumpObject {
+            name: qualified.clone(),
+            drop: Some(format!("DROP VIEW IF EXISTS {}", qualified)),
+            create: vec![ddl],
+            block: false,
+            constraints: Vec::new(),
+        }))
+    }
+
+    /// 存储过程、函数和事件
+    pub async fn routines(&self) -> DbResult<Vec<DumpObject>> {
+        let has_routines = self.supports(ObjectType::Procedure).await || self.supports(ObjectType::Function).await;
+        match self.db_type {
+            DatabaseType::MySQL => {
+                let mut objects = Vec::new();
+                if has_routines {
+                    let sql = format!(
+                        "SELECT ROUTINE_NAME AS name, ROUTINE_TYPE AS kind FROM information_schema.ROUTINES
+                         WHERE ROUTINE_SCHEMA = {} ORDER BY ROUTINE_TYPE, ROUTINE_NAME",
+                        self.literal(self.database)
+                    );
+                    for row in self.query(&sql).await? {
+                        let (Some(name), Some(kind)) = (text(&row, "name"), text(&row, "kind")) else {
+                            continue;
+                        };
+                        let kind = kind.to_uppercase();
+                        let column = if kind == "FUNCTION" { "Create Function" } else { "Create Procedure" };
+                        objects.extend(self.mysql_object(&kind, &name, column).await?);
+                    }
+                }
+                if self.supports(ObjectType::Event).await {
+                    let sql = format!(
+                        "SELECT EVENT_NAME AS name FROM information_schema.EVENTS
+                         WHERE EVENT_SCHEMA = {} ORDER BY EVENT_NAME",
+                        self.literal(self.database)
+                    );
+                    for row in self.query(&sql).await? {
+                        if let Some(name) = text(&row, "name") {
+                            objects.extend(self.mysql_object("EVENT", &name, "Create Event").await?);
+                        }
+                    }
+                }
+                Ok(objects)
+            }
+            DatabaseType::PostgreSQL if has_routines => {
+                // 扩展创建的函数由扩展自己维护
+                let sql = "SELECT n.nspname AS schema_name, p.proname AS name,
+                        pg_get_functiondef(p.oid) AS definition
+                     FROM pg_proc p
+                     JOIN pg_namespace n ON n.oid = p.pronamespace
+                     WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+                       AND p.prokind IN ('f', 'p')
+                       AND NOT EXISTS (SELECT 1 FROM pg_depend d WHERE d.objid = p.oid AND d.deptype = 'e')
+                     ORDER BY n.nspname, p.proname";
+                Ok(self
+                    .query(sql)
+                    .await?
+                    .iter()
+                    .filter_map(|row| {
+                        let name = self.qualified(text(row, "schema_name").as_deref(), &text(row, "name")?);
+                        Some(DumpObject {
+                            name,
+                            // pg_get_functiondef 生成的是 CREATE OR REPLACE
+                            drop: None,
+                            create: vec![normalize_dollar_quote(&text(row, "definition")?)],
+                            block: true,
+                            constraints: Vec::new(),
+                        })
+                    })
+                    .collect())
+            }
+            DatabaseType::SqlServer if has_routines => {
+                self.sqlserver_objects("'P', 'FN', 'IF', 'TF'", |kind| match kind {
+                    "P" => "PROCEDURE",
+                    _ => "FUNCTION",
+                })
+                .await
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// 触发器
+    pub async fn triggers(&self) -> DbResult<Vec<DumpObject>> {
+        if !self.supports(ObjectType::Trigger).await {
+            return Ok(Vec::new());
+        }
+        match self.db_type {
+            DatabaseType::MySQL => {
+                let sql = format!(
+                    "SELECT TRIGGER_NAME AS name FROM information_schema.TRIGGERS
+                     WHERE TRIGGER_SCHEMA = {} ORDER BY EVENT_OBJECT_TABLE, ACTION_ORDER",
+                    self.literal(self.database)
+                );
+                let mut objects = Vec::new();
+                for row in self.query(&sql).await? {
+                    if let Some(name) = text(&row, "name") {
+                        objects.extend(self.mysql_object("TRIGGER", &name, "SQL Original Statement").await?);
+                    }
+                }
+                Ok(objects)
+            }
+            DatabaseType::SQLite => {
+                let sql = "SELECT name, sql FROM sqlite_master WHERE type = 'trigger' AND sql IS NOT NULL ORDER BY name";
+                Ok(self
+                    .query(sql)
+                    .await?
+                    .iter()
+                    .filter_map(|row| {
+                        let name = text(row, "name")?;
+                        Some(DumpObject {
+                            drop: Some(format!("DROP TRIGGER IF EXISTS {}", self.quote(&name))),
+                            name,
+                            create: vec![text(row, "sql")?],
+                            block: true,
+                            constraints: Vec::new(),
+                        })
+                    })
+                    .collect())
+            }
+            DatabaseType::PostgreSQL => {
+                let sql = "SELECT n.nspname AS schema_name, c.relname AS table_name, t.tgname AS name,
+                        pg_get_triggerdef(t.oid) AS definition
+                     FROM pg_trigger t
+                     JOIN pg_class c ON c.oid = t.tgrelid
+                     JOIN pg_namespace n ON n.oid = c.relnamespace
+                     WHERE NOT t.tgisinternal AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+                     ORDER BY n.nspname, c.relname, t.tgname";
+                Ok(self
+                    .query(sql)
+                    .await?
+                    .iter()
+                    .filter_map(|row| {
+                        let name = text(row, "name")?;
+                        let table = self.qualified(text(row, "schema_name").as_deref(), &text(row, "table_name")?);
+                        Some(DumpObject {
+                            drop: Some(format!("DROP TRIGGER IF EXISTS {} ON {}", self.quote(&name), table)),
+                            name,
+                            create: vec![text(row, "definition")?],
+                            block: false,
+                            constraints: Vec::new(),
+                        })
+                    })
+                    .collect())
+            }
+            DatabaseType::SqlServer => self.sqlserver_objects("'TR'", |_| "TRIGGER").await,
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// MySQL 的 SHOW CREATE {kind}，没有权限查看定义时跳过
+    async fn mysql_object(&self, kind: &str, name: &str, column: &str) -> DbResult<Option<DumpObject>> {
+        let quoted = self.quote(name);
+        let ddl = self.query_text(&format!("SHOW CREATE {} {}", kind, quoted), column).await?;
+        Ok(ddl.map(|ddl| DumpObject {
+            name: name.to_string(),
+            drop: Some(format!("DROP {} IF EXISTS {}", kind, quoted)),
+            create: vec![ddl],
+            block: true,
+            constraints: Vec::new(),
+        }))
+    }
+
+    /// SQL Server 按对象类型读取模块定义（OBJECT_DEFINITION 返回完整的 CREATE 语句）
+    async fn sqlserver_objects(&self, types: &str, kind: impl Fn(&str) -> &'static str) -> DbResult<Vec<DumpObject>> {
+        let sql = format!(
+            "SELECT SCHEMA_NAME(o.schema_id) AS schema_name, o.name, RTRIM(o.type) AS type,
+                    OBJECT_DEFINITION(o.object_id) AS definition
+             FROM sys.objects o
+             WHERE o.type IN ({}) AND o.is_ms_shipped = 0
+             ORDER BY o.name",
+            types
+        );
+        Ok(self
+            .query(&sql)
+            .await?
+            .iter()
+            .filter_map(|row| {
+                let name = self.qualified(text(row, "schema_name").as_deref(), &text(row, "name")?);
+                let kind = kind(text(row, "type").as_deref().unwrap_or_default());
+                Some(DumpObject {
+                    drop: Some(format!("DROP {} IF EXISTS {}", kind, name)),
+                    name,
+                    create: vec![text(row, "definition")?],
+                    block: true,
+                    constraints: Vec::new(),
+                })
+            })
+            .collect())
+    }
+}
+
+/// 把 pg_get_functiondef 生成的 $function$ 标记换成 $$，脚本拆分只识别 $$ 字符串
+fn normalize_dollar_quote(definition: &str) -> String {
+    if definition.contains("$$") {
+        return definition.to_string();
+    }
+    ["$function$", "$procedure$"]
+        .iter()
+        .fold(definition.to_string(), |sql, tag| sql.replace(tag, "$$"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_dollar_quote() {
+        let definition = "CREATE OR REPLACE FUNCTION f()\n RETURNS integer\nAS $function$ SELECT 1; $function$\n";
+        assert_eq!(
+            normalize_dollar_quote(definition),
+            "CREATE OR REPLACE FUNCTION f()\n RETURNS integer\nAS $$ SELECT 1; $$\n"
+        );
+        let nested = "AS $function$ SELECT '$$'; $function$";
+        assert_eq!(normalize_dollar_quote(nested), nested);
+    }
+}
@@ -0,0 +1,188 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use super::csv::{CsvDialect, CsvWriter};
+use super::json::JsonWriter;
+use super::markdown::MarkdownWriter;
+use super::sql::{SqlExportOptions, SqlWriter};
+use super::{write_error, RowWriter};
+use crate::database::{DatabaseType, DbResult};
+use crate::utils::sql_formatter::SqlFormatter;
+
+/// 复制格式
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyFormat {
+    /// 制表符分隔，可直接粘贴到电子表格
+    Tsv,
+    Csv,
+    Markdown,
+    /// 对象数组
+    Json,
+    /// 每行一条 INSERT 语句
+    SqlInsert,
+    /// `(v1, v2, ...)`，多列时为行值元组
+    SqlIn,
+    /// `WHERE` 条件，每行一组等值条件，行之间用 OR 连接
+    SqlWhere,
+}
+
+/// 结果中选中的区域
+#[derive(Debug, Clone, Deserialize)]
+pub struct CopySelection {
+    pub columns: Vec<String>,
+    /// 每行的值，顺序与列名一致
+    pub rows: Vec<Vec<Value>>,
+    pub format: CopyFormat,
+    /// 是否包含表头（TSV 和 CSV）
+    #[serde(default = "default_header")]
+    pub header: bool,
+    /// INSERT 的目标表
+    #[serde(default)]
+    pub table_name: Option<String>,
+}
+
+fn default_header() -> bool {
+    true
+}
+
+/// 按格式生成复制的文本，SQL 格式按 `dialect` 引用标识符和字面量
+pub fn format_selection(selection: &CopySelection, dialect: &DatabaseType) -> DbResult<String> {
+    let text = match selection.format {
+        CopyFormat::Tsv | CopyFormat::Csv => {
+            let dialect = CsvDialect {
+                delimiter: if selection.format == CopyFormat::Tsv { '\t' } else { ',' },
+                header: selection.header,
+                ..Default::default()
+            };
+            let mut buf = Vec::new();
+            write_selection(&mut CsvWriter::with_dialect(&mut buf, &dialect)?, selection)?;
+            String::from_utf8_lossy(&buf).into_owned()
+        }
+        CopyFormat::Markdown => {
+            let mut buf = Vec::new();
+            write_selection(&mut MarkdownWriter::new(&mut buf), selection)?;
+            String::from_utf8_lossy(&buf).into_owned()
+        }
+        CopyFormat::Json => {
+            let mut buf = Vec::new();
+            write_selection(&mut JsonWriter::new(&mut buf), selection)?;
+            String::from_utf8_lossy(&buf).into_owned()
+        }
+        CopyFormat::SqlInsert => {
+            let options = SqlExportOptions {
+                dialect: Some(dialect.clone()),
+                ..Default::default()
+            };
+            let table = selection.table_name.as_deref().unwrap_or("export");
+            let mut buf = Vec::new();
+            write_selection(&mut SqlWriter::with_options(&mut buf, table, &options)?, selection)?;
+            String::from_utf8_lossy(&buf).into_owned()
+        }
+        CopyFormat::SqlIn => in_list(selection, dialect),
+        CopyFormat::SqlWhere => where_clause(selection, dialect),
+    };
+    // 剪贴板内容不需要结尾的换行
+    Ok(text.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn write_selection(writer: &mut dyn RowWriter, selection: &CopySelection) -> DbResult<()> {
+    writer.begin(&selection.columns).map_err(write_error)?;
+    for row in &selection.rows {
+        writer.write_row(row).map_err(write_error)?;
+    }
+    writer.finish().map_err(write_error)
+}
+
+/// 去重后的值列表；NULL 不会匹配 IN，单列时跳过
+fn in_list(selection: &CopySelection, dialect: &DatabaseType) -> String {
+    let mut seen = HashSet::new();
+    let items: Vec<String> = selection
+        .rows
+        .iter()
+        .filter_map(|row| match row.as_slice() {
+            [Value::Null] => None,
+            [value] => Some(SqlFormatter::format_literal(dialect, value)),
+            values => {
+                let literals: Vec<String> =
+                    values.iter().map(|v| SqlFormatter::format_literal(dialect, v)).collect();
+                Some(format!("({})", literals.join(", ")))
+            }
+        })
+        .filter(|item| seen.insert(item.clone()))
+        .collect();
+    format!("({})", items.join(", "))
+}
+
+fn where_clause(selection: &CopySelection, dialect: &DatabaseType) -> String {
+    let multiple = selection.rows.len() > 1 && selection.columns.len() > 1;
+    let conditions: Vec<String> = selection
+        .rows
+        .iter()
+        .map(|row| {
+            let terms: Vec<String> = selection
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| {
+                    let column = SqlFormatter::quote_identifier(dialect, column);
+                    match value {
+                        Value::Null => format!("{} IS NULL", column),
+                        _ => format!("{} = {}", column, SqlFormatter::format_literal(dialect, value)),
+                    }
+                })
+                .collect();
+            let condition = terms.join(" AND ");
+            if multiple {
+                format!("({})", condition)
+            } else {
+                condition
+            }
+        })
+        .collect();
+    format!("WHERE {}", conditions.join("\n   OR "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn selection(format: CopyFormat, columns: &[&str], rows: Vec<Vec<Value>>) -> CopySelection {
+        CopySelection {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows,
+            format,
+            header: true,
+            table_name: Some("users".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_format_selection() {
+        let rows = vec![vec![json!(1), json!("O'Neil\tJr")], vec![json!(2), Value::Null]];
+        let format = |f| format_selection(&selection(f, &["id", "name"], rows.clone()), &DatabaseType::PostgreSQL).unwrap();
+
+        assert_eq!(format(CopyFormat::Tsv), "id\tname\n1\t\"O'Neil\tJr\"\n2\t");
+        assert_eq!(format(CopyFormat::Markdown), "| id | name |\n| --- | --- |\n| 1 | O'Neil\tJr |\n| 2 |  |");
+        assert_eq!(
+            format(CopyFormat::SqlInsert),
+            "INSERT INTO \"users\" (\"id\", \"name\") VALUES (1, 'O''Neil\tJr');\n\
+             INSERT INTO \"users\" (\"id\", \"name\") VALUES (2, NULL);"
+        );
+        assert_eq!(format(CopyFormat::SqlIn), "((1, 'O''Neil\tJr'), (2, NULL))");
+        assert_eq!(
+            format(CopyFormat::SqlWhere),
+            "WHERE (\"id\" = 1 AND \"name\" = 'O''Neil\tJr')\n   OR (\"id\" = 2 AND \"name\" IS NULL)"
+        );
+    }
+
+    #[test]
+    fn test_in_list() {
+        let rows = vec![vec![json!("a")], vec![Value::Null], vec![json!("a")], vec![json!("b\\")]];
+        let copy = selection(CopyFormat::SqlIn, &["code"], rows);
+        assert_eq!(format_selection(&copy, &DatabaseType::MySQL).unwrap(), "('a', 'b\\\\')");
+        assert_eq!(format_selection(&copy, &DatabaseType::SqlServer).unwrap(), "(N'a', N'b\\')");
+    }
+}
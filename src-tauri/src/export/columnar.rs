@@ -0,0 +1,652 @@
+use arrow_array::{
+    ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray, UInt64Array,
+};
+use arrow_ipc::writer::{FileWriter, IpcWriteOptions};
+use arrow_ipc::CompressionType;
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use super::sql::decode_binary;
+use super::{value_to_text, RowWriter};
+use crate::database::{ColumnInfo, DatabaseType, DbError, DbResult};
+use crate::transfer::SqlType;
+use crate::utils::i18n::Msg;
+
+/// 每个 RecordBatch 的行数
+const BATCH_ROWS: usize = 8192;
+
+/// 列式文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnarFormat {
+    Parquet,
+    /// Arrow IPC 文件（Feather V2）
+    Arrow,
+}
+
+/// 压缩算法
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnarCompression {
+    None,
+    #[default]
+    Snappy,
+    Gzip,
+    Lz4,
+    Zstd,
+}
+
+/// Parquet 和 Arrow 导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnarOptions {
+    /// Arrow IPC 只支持 LZ4 和 ZSTD，其他算法按不压缩写入
+    pub compression: ColumnarCompression,
+    /// 压缩级别（GZIP 为 0-9，ZSTD 为 1-22），为空时使用默认级别
+    pub compression_level: Option<i32>,
+    /// Parquet 每个行组的最大行数
+    pub row_group_size: usize,
+    /// 来源表结构，用于确定列类型（导出整表时由后端填充，否则按数据推断）
+    #[serde(skip)]
+    pub table_columns: Vec<ColumnInfo>,
+    /// 来源数据库类型（由后端填充）
+    #[serde(skip)]
+    pub source_type: Option<DatabaseType>,
+    /// 来源驱动把二进制值读取为 Base64 文本（由后端填充）
+    #[serde(skip)]
+    pub binary_base64: bool,
+}
+
+impl Default for ColumnarOptions {
+    fn default() -> Self {
+        Self {
+            compression: ColumnarCompression::Snappy,
+            compression_level: None,
+            row_group_size: 100_000,
+            table_columns: Vec::new(),
+            source_type: None,
+            binary_base64: false,
+        }
+    }
+}
+
+/// 列在文件中的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Boolean,
+    Int16,
+    Int32,
+    Int64,
+    UInt64,
+    Float32,
+    Float64,
+    Decimal(u8, i8),
+    Utf8,
+    Date,
+    Timestamp,
+    /// 带时区的时间统一转换为 UTC
+    TimestampTz,
+    Binary,
+}
+
+impl ColumnKind {
+    fn from_sql_type(sql_type: SqlType, data_type: &str) -> Self {
+        // 无符号整数（例如 MySQL 的 INT UNSIGNED）超出同名有符号类型的范围，按更宽的类型写入
+        let unsigned = data_type.to_lowercase().split_whitespace().any(|w| w == "unsigned");
+        match sql_type {
+            SqlType::Boolean => Self::Boolean,
+            SqlType::SmallInt if unsigned => Self::Int32,
+            SqlType::SmallInt => Self::Int16,
+            SqlType::Integer if unsigned => Self::Int64,
+            SqlType::Integer => Self::Int32,
+            SqlType::BigInt if unsigned => Self::UInt64,
+            SqlType::BigInt => Self::Int64,
+            SqlType::Decimal(Some((p, s))) if (1..=38).contains(&p) && (0..=p).contains(&s) => {
+                Self::Decimal(p as u8, s as i8)
+            }
+            SqlType::Real => Self::Float32,
+            SqlType::Double => Self::Float64,
+            SqlType::Date => Self::Date,
+            SqlType::DateTime => Self::Timestamp,
+            SqlType::DateTimeTz => Self::TimestampTz,
+            SqlType::Binary => Self::Binary,
+            // 不限精度的 DECIMAL 按文本保留全部位数
+            _ => Self::Utf8,
+        }
+    }
+
+    /// 没有表结构时按 JSON 值的类型推断
+    fn infer<'a>(values: impl Iterator<Item = &'a Value>) -> Self {
+        let mut kind = None;
+        for value in values {
+            let current = match value {
+                Value::Null => continue,
+                Value::Bool(_) => Self::Boolean,
+                Value::Number(n) if n.is_i64() => Self::Int64,
+                Value::Number(_) => Self::Float64,
+                _ => return Self::Utf8,
+            };
+            kind = match (kind, current) {
+                (None, k) => Some(k),
+                (Some(a), b) if a == b => Some(a),
+                (Some(Self::Int64 | Self::Float64), Self::Int64 | Self::Float64) => Some(Self::Float64),
+                _ => return Self::Utf8,
+            };
+        }
+        kind.unwrap_or(Self::Utf8)
+    }
+
+    fn data_type(self) -> DataType {
+        match self {
+            Self::Boolean => DataType::Boolean,
+            Self::Int16 => DataType::Int16,
+            Self::Int32 => DataType::Int32,
+            Self::Int64 => DataType::Int64,
+            Self::UInt64 => DataType::UInt64,
+            Self::Float32 => DataType::Float32,
+            Self::Float64 => DataType::Float64,
+            Self::Decimal(p, s) => DataType::Decimal128(p, s),
+            Self::Utf8 => DataType::Utf8,
+            Self::Date => DataType::Date32,
+            Self::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+            Self::TimestampTz => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            Self::Binary => DataType::Binary,
+        }
+    }
+}
+
+/// 已创建的文件写入器（需要先确定表结构）
+enum FileSink<W: Write + Send> {
+    Parquet(ArrowWriter<W>),
+    Arrow(FileWriter<W>),
+}
+
+/// Parquet 和 Arrow IPC 写入器
+///
+/// 有表结构时按列的原始类型写入，否则按第一批数据推断；
+/// 行按 RecordBatch 分批转换为列式数据，不在内存中保留整个结果
+pub struct ColumnarWriter<W: Write + Send> {
+    out: Option<W>,
+    format: ColumnarFormat,
+    options: ColumnarOptions,
+    compression: Compression,
+    columns: Vec<String>,
+    kinds: Vec<ColumnKind>,
+    sink: Option<FileSink<W>>,
+    pending: Vec<Vec<Value>>,
+}
+
+impl<W: Write + Send> ColumnarWriter<W> {
+    pub fn new(out: W, format: ColumnarFormat, options: &ColumnarOptions) -> DbResult<Self> {
+        let level = options.compression_level;
+        let invalid_level = |e: ParquetError| {
+            DbError::ConfigError(format!("{}: {}", Msg::CompressionLevelInvalid.with(&[&level.unwrap_or_default()]), e))
+        };
+        let compression = match options.compression {
+            ColumnarCompression::None => Compression::UNCOMPRESSED,
+            ColumnarCompression::Snappy => Compression::SNAPPY,
+            ColumnarCompression::Lz4 => Compression::LZ4_RAW,
+            ColumnarCompression::Gzip => Compression::GZIP(match level {
+                Some(level) => GzipLevel::try_new(level as u32).map_err(invalid_level)?,
+                None => GzipLevel::default(),
+            }),
+            ColumnarCompression::Zstd => Compression::ZSTD(match level {
+                Some(level) => ZstdLevel::try_new(level).map_err(invalid_level)?,
+                None => ZstdLevel::default(),
+            }),
+        };
+
+        Ok(Self {
+            out: Some(out),
+            format,
+            options: options.clone(),
+            compression,
+            columns: Vec::new(),
+            kinds: Vec::new(),
+            sink: None,
+            pending: Vec::new(),
+        })
+    }
+
+    /// 按表结构或已缓存的行确定各列类型并创建文件写入器
+    fn open(&mut self) -> io::Result<()> {
+        self.kinds = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                match self.options.table_columns.iter().find(|c| &c.name == name) {
+                    Some(column) => {
+                        let source = self.options.source_type.as_ref().unwrap_or(&DatabaseType::MySQL);
+                        ColumnKind::from_sql_type(SqlType::parse(source, column), &column.data_type)
+                    }
+                    None => ColumnKind::infer(self.pending.iter().filter_map(|row| row.get(idx))),
+                }
+            })
+            .collect();
+
+        let fields: Vec<Field> = self
+            .columns
+            .iter()
+            .zip(&self.kinds)
+            .map(|(name, kind)| Field::new(name, kind.data_type(), true))
+            .collect();
+        let schema: SchemaRef = Arc::new(Schema::new(fields));
+
+        let out = self.out.take().ok_or_else(|| io::Error::other("writer closed"))?;
+        self.sink = Some(match self.format {
+            ColumnarFormat::Parquet => {
+                let properties = WriterProperties::builder()
+                    .set_compression(self.compression)
+                    .set_max_row_group_size(self.options.row_group_size.max(1))
+                    .build();
+                FileSink::Parquet(ArrowWriter::try_new(out, schema, Some(properties)).map_err(parquet_error)?)
+            }
+            ColumnarFormat::Arrow => {
+                let compression = match self.options.compression {
+                    ColumnarCompression::Lz4 => Some(CompressionType::LZ4_FRAME),
+                    ColumnarCompression::Zstd => Some(CompressionType::ZSTD),
+                    _ => None,
+                };
+                let options = IpcWriteOptions::default()
+                    .try_with_compression(compression)
+                    .map_err(arrow_error)?;
+                FileSink::Arrow(FileWriter::try_new_with_options(out, &schema, options).map_err(arrow_error)?)
+            }
+        });
+        Ok(())
+    }
+
+    /// 把缓存的行转换为一个 RecordBatch 写入
+    fn flush_pending(&mut self) -> io::Result<()> {
+        if self.sink.is_none() {
+            self.open()?;
+        }
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let rows = std::mem::take(&mut self.pending);
+        let arrays = self
+            .kinds
+            .iter()
+            .enumerate()
+            .map(|(idx, kind)| self.build_array(*kind, idx, &rows))
+            .collect::<io::Result<Vec<ArrayRef>>>()?;
+        let schema = Arc::new(Schema::new(
+            self.columns
+                .iter()
+                .zip(&self.kinds)
+                .map(|(name, kind)| Field::new(name, kind.data_type(), true))
+                .collect::<Vec<_>>(),
+        ));
+        let batch = RecordBatch::try_new(schema, arrays).map_err(arrow_error)?;
+
+        match self.sink.as_mut() {
+            Some(FileSink::Parquet(writer)) => writer.write(&batch).map_err(parquet_error),
+            Some(FileSink::Arrow(writer)) => writer.write(&batch).map_err(arrow_error),
+            None => Ok(()),
+        }
+    }
+
+    /// 一列的数组，值无法转换为列类型时返回错误
+    fn build_array(&self, kind: ColumnKind, idx: usize, rows: &[Vec<Value>]) -> io::Result<ArrayRef> {
+        let values = || rows.iter().map(move |row| row.get(idx).unwrap_or(&Value::Null));
+        let name = &self.columns[idx];
+        Ok(match kind {
+            ColumnKind::Boolean => Arc::new(convert(name, kind, values(), to_bool)?.into_iter().collect::<BooleanArray>()),
+            ColumnKind::Int16 => Arc::new(Int16Array::from(convert(name, kind, values(), |v| {
+                to_i64(v).and_then(|n| i16::try_from(n).ok())
+            })?)),
+            ColumnKind::Int32 => Arc::new(Int32Array::from(convert(name, kind, values(), |v| {
+                to_i64(v).and_then(|n| i32::try_from(n).ok())
+            })?)),
+            ColumnKind::Int64 => Arc::new(Int64Array::from(convert(name, kind, values(), to_i64)?)),
+            ColumnKind::UInt64 => Arc::new(UInt64Array::from(convert(name, kind, values(), to_u64)?)),
+            ColumnKind::Float32 => Arc::new(Float32Array::from(convert(name, kind, values(), |v| {
+                to_f64(v).map(|n| n as f32)
+            })?)),
+            ColumnKind::Float64 => Arc::new(Float64Array::from(convert(name, kind, values(), to_f64)?)),
+            ColumnKind::Decimal(precision, scale) => Arc::new(
+                Decimal128Array::from(convert(name, kind, values(), |v| to_decimal(v, scale))?)
+                    .with_precision_and_scale(precision, scale)
+                    .map_err(arrow_error)?,
+            ),
+            ColumnKind::Utf8 => Arc::new(StringArray::from(convert(name, kind, values(), |v| {
+                Some(value_to_text(v))
+            })?)),
+            ColumnKind::Date => Arc::new(Date32Array::from(convert(name, kind, values(), to_date)?)),
+            ColumnKind::Timestamp => Arc::new(TimestampMicrosecondArray::from(convert(
+                name,
+                kind,
+                values(),
+                |v| to_timestamp(v, false),
+            )?)),
+            ColumnKind::TimestampTz => Arc::new(
+                TimestampMicrosecondArray::from(convert(name, kind, values(), |v| to_timestamp(v, true))?)
+                    .with_timezone("UTC"),
+            ),
+            ColumnKind::Binary => {
+                let base64 = self.options.binary_base64;
+                let bytes = convert(name, kind, values(), |v| match v {
+                    Value::String(s) => Some(decode_binary(s, base64)),
+                    _ => None,
+                })?;
+                Arc::new(bytes.iter().map(|b| b.as_deref()).collect::<BinaryArray>())
+            }
+        })
+    }
+}
+
+impl<W: Write + Send> RowWriter for ColumnarWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> io::Result<()> {
+        // 一个文件只能有一种表结构
+        if self.sink.is_some() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, Msg::SingleStatementRequired.text()));
+        }
+        self.columns = columns.to_vec();
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        self.pending.push(values.to_vec());
+        if self.pending.len() >= BATCH_ROWS {
+            self.flush_pending()?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush_pending()?;
+        let mut out = match self.sink.take() {
+            Some(FileSink::Parquet(writer)) => writer.into_inner().map_err(parquet_error)?,
+            Some(FileSink::Arrow(writer)) => writer.into_inner().map_err(arrow_error)?,
+            None => return Ok(()),
+        };
+        out.flush()
+    }
+}
+
+/// 按列类型转换一列的值，NULL 保持为 NULL
+fn convert<'a, T>(
+    column: &str,
+    kind: ColumnKind,
+    values: impl Iterator<Item = &'a Value>,
+    f: impl Fn(&Value) -> Option<T>,
+) -> io::Result<Vec<Option<T>>> {
+    values
+        .map(|value| match value {
+            Value::Null => Ok(None),
+            _ => f(value).map(Some).ok_or_else(|| {
+                let text: String = value_to_text(value).chars().take(50).collect();
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    Msg::ColumnValueInvalid.with(&[&column, &text, &kind.data_type()]),
+                )
+            }),
+        })
+        .collect()
+}
+
+fn to_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::Number(n) => n.as_f64().map(|n| n != 0.0),
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "1" | "t" | "true" => Some(true),
+            "0" | "f" | "false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn to_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::Bool(b) => Some(*b as i64),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn to_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::Bool(b) => Some(*b as u64),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn to_decimal(value: &Value, scale: i8) -> Option<i128> {
+    let text = match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.clone(),
+        _ => return None,
+    };
+    parse_decimal(&text, scale).or_else(|| {
+        // 科学计数法等形式按浮点数换算
+        let n: f64 = text.trim().parse().ok()?;
+        let scaled = (n * 10f64.powi(scale as i32)).round();
+        scaled.is_finite().then_some(scaled as i128)
+    })
+}
+
+/// 十进制文本转换为按 `scale` 缩放的整数，多余的小数位四舍五入
+fn parse_decimal(text: &str, scale: i8) -> Option<i128> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (int_part.is_empty() && frac_part.is_empty()) || !all_digits(int_part) || !all_digits(frac_part) {
+        return None;
+    }
+
+    let scale = scale.max(0) as usize;
+    let mut frac: String = frac_part.chars().take(scale).collect();
+    while frac.len() < scale {
+        frac.push('0');
+    }
+    let mut scaled: i128 = format!("{}{}", int_part, frac).trim_start_matches('0').parse().or_else(|e| {
+        if format!("{}{}", int_part, frac).bytes().all(|b| b == b'0') {
+            Ok(0)
+        } else {
+            Err(e)
+        }
+    }).ok()?;
+    if frac_part.len() > scale && frac_part.as_bytes()[scale] >= b'5' {
+        scaled += 1;
+    }
+    Some(if negative { -scaled } else { scaled })
+}
+
+/// 自 1970-01-01 起的天数
+fn to_date(value: &Value) -> Option<i32> {
+    let text = value.as_str()?.trim();
+    let date = NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok()?;
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+    i32::try_from((date - epoch).num_days()).ok()
+}
+
+/// 自 1970-01-01 起的微秒数；`with_zone` 时按文本中的时区换算为 UTC，没有时区的按 UTC 处理
+fn to_timestamp(value: &Value, with_zone: bool) -> Option<i64> {
+    let text = value.as_str()?.trim();
+    if with_zone {
+        let zoned = DateTime::parse_from_rfc3339(text)
+            .or_else(|_| DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f%#z"))
+            .or_else(|_| DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f %#z"));
+        if let Ok(time) = zoned {
+            return Some(time.timestamp_micros());
+        }
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))
+        .map(|time| time.and_utc().timestamp_micros())
+}
+
+fn parquet_error(e: ParquetError) -> io::Error {
+    io::Error::other(e)
+}
+
+fn arrow_error(e: ArrowError) -> io::Error {
+    io::Error::other(e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Decimal128Type, Int32Type, Int64Type, TimestampMicrosecondType, UInt64Type};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+
+    fn column(name: &str, data_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            is_auto_increment: false,
+            comment: None,
+            character_maximum_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+        }
+    }
+
+    fn write(writer: &mut dyn RowWriter) {
+        let columns: Vec<String> = ["id", "price", "created", "data", "note"].iter().map(|c| c.to_string()).collect();
+        writer.begin(&columns).unwrap();
+        writer
+            .write_row(&[json!(1), json!("12.345"), json!("2024-03-01 08:30:00+08"), json!("\\x0102"), json!("a")])
+            .unwrap();
+        writer.write_row(&[json!(2), Value::Null, Value::Null, Value::Null, json!(3)]).unwrap();
+        writer.finish().unwrap();
+    }
+
+    fn options() -> ColumnarOptions {
+        ColumnarOptions {
+            compression: ColumnarCompression::Zstd,
+            table_columns: vec![
+                column("id", "integer"),
+                column("price", "numeric(10,2)"),
+                column("created", "timestamp with time zone"),
+                column("data", "bytea"),
+            ],
+            source_type: Some(DatabaseType::PostgreSQL),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parquet_writer() {
+        let path = std::env::temp_dir().join(format!("datasmith_export_{}.parquet", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        write(&mut ColumnarWriter::new(file, ColumnarFormat::Parquet, &options()).unwrap());
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+        std::fs::remove_file(&path).unwrap();
+
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Int32);
+        assert_eq!(batch.column(0).as_primitive::<Int32Type>().value(1), 2);
+        assert_eq!(batch.schema().field(1).data_type(), &DataType::Decimal128(10, 2));
+        assert_eq!(batch.column(1).as_primitive::<Decimal128Type>().value(0), 1235);
+        assert!(batch.column(1).is_null(1));
+        // 2024-03-01 00:30:00 UTC
+        assert_eq!(
+            batch.column(2).as_primitive::<TimestampMicrosecondType>().value(0),
+            1_709_253_000_000_000
+        );
+        assert_eq!(batch.column(3).as_binary::<i32>().value(0), &[1, 2]);
+        // 没有表结构的列按数据推断
+        assert_eq!(batch.schema().field(4).data_type(), &DataType::Utf8);
+        assert_eq!(batch.column(4).as_string::<i32>().value(1), "3");
+    }
+
+    #[test]
+    fn test_arrow_writer() {
+        let mut buf = Vec::new();
+        write(&mut ColumnarWriter::new(&mut buf, ColumnarFormat::Arrow, &options()).unwrap());
+
+        let reader = arrow_ipc::reader::FileReader::try_new(io::Cursor::new(buf), None).unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(batches[0].num_rows(), 2);
+        assert_eq!(
+            batches[0].schema().field(2).data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        );
+
+        // 无法转换的值报告列名
+        let mut invalid = Vec::new();
+        let mut writer = ColumnarWriter::new(&mut invalid, ColumnarFormat::Arrow, &options()).unwrap();
+        writer.begin(&["id".to_string()]).unwrap();
+        writer.write_row(&[json!("abc")]).unwrap();
+        assert!(writer.finish().unwrap_err().to_string().contains("id"));
+    }
+
+    #[test]
+    fn test_unsigned_columns() {
+        let options = ColumnarOptions {
+            table_columns: vec![column("id", "int"), column("count", "int unsigned"), column("big", "bigint unsigned")],
+            source_type: Some(DatabaseType::MySQL),
+            ..Default::default()
+        };
+        let mut buf = Vec::new();
+        let mut writer = ColumnarWriter::new(&mut buf, ColumnarFormat::Arrow, &options).unwrap();
+        let columns: Vec<String> = ["id", "count", "big"].iter().map(|c| c.to_string()).collect();
+        writer.begin(&columns).unwrap();
+        writer
+            .write_row(&[json!(-1), json!(4294967295u64), json!(18446744073709551615u64)])
+            .unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+
+        let reader = arrow_ipc::reader::FileReader::try_new(io::Cursor::new(buf), None).unwrap();
+        let batch = reader.map(|b| b.unwrap()).next().unwrap();
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Int32);
+        assert_eq!(batch.column(1).as_primitive::<Int64Type>().value(0), 4294967295);
+        assert_eq!(batch.schema().field(2).data_type(), &DataType::UInt64);
+        assert_eq!(batch.column(2).as_primitive::<UInt64Type>().value(0), u64::MAX);
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse_decimal("12.345", 2), Some(1235));
+        assert_eq!(parse_decimal("-0.5", 0), Some(-1));
+        assert_eq!(parse_decimal("7", 3), Some(7000));
+        assert_eq!(parse_decimal("0.00", 2), Some(0));
+        assert_eq!(parse_decimal("1e3", 0), None);
+        assert_eq!(to_decimal(&json!(1e-3), 3), Some(1));
+        assert_eq!(ColumnKind::infer([json!(1), Value::Null, json!(2.5)].iter()), ColumnKind::Float64);
+        assert_eq!(ColumnKind::infer([json!(true), json!("x")].iter()), ColumnKind::Utf8);
+    }
+}
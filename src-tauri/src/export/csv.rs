@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, Write};
+
+use super::{value_to_text, RowWriter};
+use crate::database::{DbError, DbResult};
+use crate::utils::encoding::{find_encoding, is_utf8, EncodingWriter};
+use crate::utils::i18n::Msg;
+
+/// 何时给字段加引号
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvQuoting {
+    /// 只在包含分隔符、引号或换行时加引号
+    #[default]
+    Necessary,
+    Always,
+    /// 非数字字段都加引号
+    NonNumeric,
+    Never,
+}
+
+/// 行结束符
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvLineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+/// CSV 格式选项，导出和导入共用
+///
+/// 导入时只使用分隔符、引号、NULL 文本和编码，是否有表头由导入选项决定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvDialect {
+    pub delimiter: char,
+    pub quote: char,
+    pub quoting: CsvQuoting,
+    /// 是否写入表头
+    pub header: bool,
+    /// NULL 的文本形式（默认为空字段）
+    pub null_value: String,
+    pub line_ending: CsvLineEnding,
+    /// 是否在文件开头写入 UTF-8 BOM（只对 UTF-8 编码有效，方便 Excel 识别；UTF-16 总是写入 BOM）
+    pub bom: bool,
+    /// 字符编码，如 utf-8、gbk、gb18030
+    pub encoding: String,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            quoting: CsvQuoting::Necessary,
+            header: true,
+            null_value: String::new(),
+            line_ending: CsvLineEnding::Lf,
+            bom: false,
+            encoding: "utf-8".to_string(),
+        }
+    }
+}
+
+impl CsvDialect {
+    /// 分隔符的字节值
+    pub fn delimiter_byte(&self) -> DbResult<u8> {
+        ascii_byte(self.delimiter, Msg::CsvDelimiter)
+    }
+
+    /// 引号的字节值
+    pub fn quote_byte(&self) -> DbResult<u8> {
+        ascii_byte(self.quote, Msg::CsvQuote)
+    }
+}
+
+fn ascii_byte(c: char, name: Msg) -> DbResult<u8> {
+    if c.is_ascii() && c != '\n' && c != '\r' {
+        Ok(c as u8)
+    } else {
+        Err(DbError::ConfigError(Msg::CsvCharInvalid.with(&[&name])))
+    }
+}
+
+/// 按编码转换后的输出
+pub enum TextOutput<W: Write> {
+    Utf8(W),
+    Encoded(EncodingWriter<W>),
+}
+
+impl<W: Write> Write for TextOutput<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Utf8(out) => out.write(buf),
+            Self::Encoded(out) => out.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Utf8(out) => out.flush(),
+            Self::Encoded(out) => out.flush(),
+        }
+    }
+}
+
+/// CSV 写入器
+pub struct CsvWriter<W: Write> {
+    out: ::csv::Writer<TextOutput<W>>,
+    header: bool,
+    null_value: String,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn with_dialect(out: W, dialect: &CsvDialect) -> DbResult<Self> {
+        let encoding = find_encoding(&dialect.encoding).map_err(DbError::ConfigError)?;
+        let mut out = if is_utf8(encoding) {
+            TextOutput::Utf8(out)
+        } else {
+            TextOutput::Encoded(EncodingWriter::new(out, encoding))
+        };
+        if dialect.bom && is_utf8(encoding) {
+            out.write_all("\u{feff}".as_bytes()).map_err(super::write_error)?;
+        }
+
+        let quote_style = match dialect.quoting {
+            CsvQuoting::Necessary => ::csv::QuoteStyle::Necessary,
+            CsvQuoting::Always => ::csv::QuoteStyle::Always,
+            CsvQuoting::NonNumeric => ::csv::QuoteStyle::NonNumeric,
+            CsvQuoting::Never => ::csv::QuoteStyle::Never,
+        };
+        let terminator = match dialect.line_ending {
+            CsvLineEnding::Lf => ::csv::Terminator::Any(b'\n'),
+            CsvLineEnding::Crlf => ::csv::Terminator::CRLF,
+        };
+
+        Ok(Self {
+            out: ::csv::WriterBuilder::new()
+                .delimiter(dialect.delimiter_byte()?)
+                .quote(dialect.quote_byte()?)
+                .quote_style(quote_style)
+                .terminator(terminator)
+                .from_writer(out),
+            header: dialect.header,
+            null_value: dialect.null_value.clone(),
+        })
+    }
+}
+
+impl<W: Write + Send> RowWriter for CsvWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> io::Result<()> {
+        if self.header {
+            self.out.write_record(columns)?;
+        }
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        self.out.write_record(values.iter().map(|value| match value {
+            Value::Null => self.null_value.clone(),
+            _ => value_to_text(value),
+        }))?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write(dialect: &CsvDialect) -> Vec<u8> {
+        let mut buf = Vec::new();
+        {
+            let mut writer = CsvWriter::with_dialect(&mut buf, dialect).unwrap();
+            writer.begin(&["id".to_string(), "名称".to_string()]).unwrap();
+            writer.write_row(&[json!(1), json!("a;b")]).unwrap();
+            writer.write_row(&[json!(2), Value::Null]).unwrap();
+            writer.finish().unwrap();
+        }
+        buf
+    }
+
+    #[test]
+    fn test_csv_dialect() {
+        let dialect = CsvDialect {
+            delimiter: ';',
+            quoting: CsvQuoting::NonNumeric,
+            null_value: "\\N".to_string(),
+            line_ending: CsvLineEnding::Crlf,
+            bom: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            String::from_utf8(write(&dialect)).unwrap(),
+            "\u{feff}\"id\";\"名称\"\r\n1;\"a;b\"\r\n2;\"\\N\"\r\n"
+        );
+
+        let dialect = CsvDialect {
+            header: false,
+            encoding: "gbk".to_string(),
+            bom: true,
+            ..Default::default()
+        };
+        // GBK 不写 BOM
+        assert_eq!(write(&dialect), b"1,a;b\n2,\n");
+
+        let dialect = CsvDialect {
+            delimiter: '，',
+            ..Default::default()
+        };
+        assert!(CsvWriter::with_dialect(Vec::new(), &dialect).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use serde_json::Value;
+use std::io::{self, Write};
+
+use super::xml::escape_xml;
+use super::{value_to_text, RowWriter};
+
+/// 页面内嵌的表格样式
+const STYLE: &str = "body { font-family: -apple-system, 'Segoe UI', sans-serif; font-size: 14px; margin: 16px; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d9d9d9; padding: 4px 8px; white-space: pre-wrap; vertical-align: top; }
+th { background: #fafafa; position: sticky; top: 0; }
+tr:nth-child(even) td { background: #fcfcfc; }
+td.number { text-align: right; }
+td.null { color: #bfbfbf; font-style: italic; }";
+
+/// HTML 写入器，输出可直接用浏览器打开的独立页面
+///
+/// 数字列右对齐，NULL 以灰色斜体显示以区别于空字符串
+pub struct HtmlWriter<W: Write> {
+    out: W,
+    /// 页面标题（一般是表名）
+    title: String,
+}
+
+impl<W: Write> HtmlWriter<W> {
+    pub fn new(out: W, title: &str) -> Self {
+        Self {
+            out,
+            title: title.to_string(),
+        }
+    }
+}
+
+impl<W: Write + Send> RowWriter for HtmlWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> io::Result<()> {
+        let title = escape_xml(if self.title.is_empty() { "export" } else { &self.title });
+        writeln!(self.out, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(self.out, "<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>", title, STYLE)?;
+        writeln!(self.out, "<table>\n<thead>\n<tr>")?;
+        for column in columns {
+            writeln!(self.out, "<th>{}</th>", escape_xml(column))?;
+        }
+        writeln!(self.out, "</tr>\n</thead>\n<tbody>")
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        self.out.write_all(b"<tr>")?;
+        for value in values {
+            match value {
+                Value::Null => self.out.write_all(b"<td class=\"null\">NULL</td>")?,
+                Value::Number(n) => write!(self.out, "<td class=\"number\">{}</td>", n)?,
+                _ => write!(self.out, "<td>{}</td>", escape_xml(&value_to_text(value)))?,
+            }
+        }
+        self.out.write_all(b"</tr>\n")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        writeln!(self.out, "</tbody>\n</table>\n</body>\n</html>")?;
+        self.out.flush()
+    }
+}
@@ -0,0 +1,127 @@
+use serde_json::Value;
+use std::io::{self, Write};
+
+use super::RowWriter;
+
+/// JSON 写入器，输出对象数组，每行一个对象并保持列顺序
+pub struct JsonWriter<W: Write> {
+    out: W,
+    /// 已转义的列名
+    keys: Vec<String>,
+    rows: u64,
+}
+
+impl<W: Write> JsonWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            keys: Vec::new(),
+            rows: 0,
+        }
+    }
+}
+
+impl<W: Write + Send> RowWriter for JsonWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> io::Result<()> {
+        self.keys = columns
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<_, _>>()?;
+        self.out.write_all(b"[")
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        let fields: Vec<String> = self
+            .keys
+            .iter()
+            .zip(values)
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect();
+        let separator = if self.rows == 0 { "\n" } else { ",\n" };
+        write!(self.out, "{}  {{{}}}", separator, fields.join(", "))?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if self.rows > 0 {
+            self.out.write_all(b"\n")?;
+        }
+        self.out.write_all(b"]\n")?;
+        self.out.flush()
+    }
+}
+
+/// JSON Lines 写入器，每行一个对象，便于逐行读取
+pub struct JsonLinesWriter<W: Write> {
+    out: W,
+    keys: Vec<String>,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl<W: Write + Send> RowWriter for JsonLinesWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> io::Result<()> {
+        self.keys = columns
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<_, _>>()?;
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        let fields: Vec<String> = self
+            .keys
+            .iter()
+            .zip(values)
+            .map(|(key, value)| format!("{}:{}", key, value))
+            .collect();
+        writeln!(self.out, "{{{}}}", fields.join(","))
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// 按列输出的 JSON 写入器：`{"columns": [...], "rows": [[...], ...]}`
+///
+/// 列名只写一次，每行是与列顺序一致的数组
+pub struct JsonColumnsWriter<W: Write> {
+    out: W,
+    rows: u64,
+}
+
+impl<W: Write> JsonColumnsWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out, rows: 0 }
+    }
+}
+
+impl<W: Write + Send> RowWriter for JsonColumnsWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> io::Result<()> {
+        write!(self.out, "{{\n  \"columns\": {},\n  \"rows\": [", serde_json::to_string(columns)?)
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        let separator = if self.rows == 0 { "\n" } else { ",\n" };
+        write!(self.out, "{}    {}", separator, serde_json::to_string(values)?)?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if self.rows > 0 {
+            self.out.write_all(b"\n  ")?;
+        }
+        self.out.write_all(b"]\n}\n")?;
+        self.out.flush()
+    }
+}
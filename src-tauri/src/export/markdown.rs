@@ -0,0 +1,45 @@
+use serde_json::Value;
+use std::io::{self, Write};
+
+use super::{value_to_text, RowWriter};
+
+/// Markdown 表格写入器（GFM 格式）
+///
+/// 单元格中的 `|` 转义，换行替换为 `<br>`，NULL 写为空单元格
+pub struct MarkdownWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> MarkdownWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    fn write_line(&mut self, cells: impl Iterator<Item = String>) -> io::Result<()> {
+        let cells: Vec<String> = cells.collect();
+        writeln!(self.out, "| {} |", cells.join(" | "))
+    }
+}
+
+impl<W: Write + Send> RowWriter for MarkdownWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> io::Result<()> {
+        self.write_line(columns.iter().map(|c| escape_cell(c)))?;
+        self.write_line(columns.iter().map(|_| "---".to_string()))
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        self.write_line(values.iter().map(|v| escape_cell(&value_to_text(v))))
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// 转义单元格中会破坏表格结构的字符
+pub fn escape_cell(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
@@ -0,0 +1,345 @@
+//! 查询结果导出
+//!
+//! 各格式的写入器按行写入文件，不保留已写入的数据，
+//! 既用于前端传入的查询结果，也用于从数据库游标直接流式导出
+
+pub mod clipboard;
+pub mod columnar;
+pub mod csv;
+pub mod html;
+pub mod json;
+pub mod markdown;
+pub mod sql;
+pub mod xlsx;
+pub mod xml;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::database::registry::ExportFormat;
+use crate::database::{DbError, DbResult, QueryResult, RowSink};
+use crate::utils::i18n::Msg;
+
+/// 按行写入导出文件
+pub trait RowWriter: Send {
+    /// 写入表头等开头内容
+    fn begin(&mut self, columns: &[String]) -> io::Result<()>;
+
+    /// 写入一行，值的顺序与列名一致
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()>;
+
+    /// 写入结尾内容并刷新缓冲
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// 各格式的导出选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub csv: csv::CsvDialect,
+    pub sql: sql::SqlExportOptions,
+    pub columnar: columnar::ColumnarOptions,
+}
+
+/// 按导出格式创建写入器
+///
+/// `name` 是 SQL 格式的 INSERT 目标表，也是 XLSX 的工作表名、XML 的结果集名和 HTML 的标题
+pub fn create_writer(
+    format: ExportFormat,
+    file: File,
+    name: &str,
+    options: &ExportOptions,
+) -> DbResult<Box<dyn RowWriter>> {
+    let out = BufWriter::new(file);
+    match format {
+        ExportFormat::Csv => Ok(Box::new(csv::CsvWriter::with_dialect(out, &options.csv)?)),
+        ExportFormat::Json => Ok(Box::new(json::JsonWriter::new(out))),
+        ExportFormat::Jsonl => Ok(Box::new(json::JsonLinesWriter::new(out))),
+        ExportFormat::JsonColumns => Ok(Box::new(json::JsonColumnsWriter::new(out))),
+        ExportFormat::Xml => Ok(Box::new(xml::XmlWriter::new(out, name))),
+        ExportFormat::Markdown => Ok(Box::new(markdown::MarkdownWriter::new(out))),
+        ExportFormat::Html => Ok(Box::new(html::HtmlWriter::new(out, name))),
+        ExportFormat::Sql => Ok(Box::new(sql::SqlWriter::with_options(out, name, &options.sql)?)),
+        ExportFormat::Xlsx => Ok(Box::new(xlsx::XlsxWriter::new(out, name))),
+        ExportFormat::Parquet => Ok(Box::new(columnar::ColumnarWriter::new(
+            out,
+            columnar::ColumnarFormat::Parquet,
+            &options.columnar,
+        )?)),
+        ExportFormat::Arrow => Ok(Box::new(columnar::ColumnarWriter::new(
+            out,
+            columnar::ColumnarFormat::Arrow,
+            &options.columnar,
+        )?)),
+        ExportFormat::Ddl => Err(DbError::Other(
+            Msg::ExportFormatUnsupported.with(&[&"DDL"]),
+        )),
+    }
+}
+
+/// 将完整的查询结果写入写入器
+pub fn write_result(writer: &mut dyn RowWriter, data: &QueryResult) -> io::Result<()> {
+    writer.begin(&data.columns)?;
+    write_rows(writer, data)?;
+    writer.finish()
+}
+
+/// 按列顺序写入查询结果的所有行
+pub fn write_rows(writer: &mut dyn RowWriter, data: &QueryResult) -> io::Result<()> {
+    for row in &data.rows {
+        let values: Vec<Value> = data
+            .columns
+            .iter()
+            .map(|col| row.get(col).cloned().unwrap_or(Value::Null))
+            .collect();
+        writer.write_row(&values)?;
+    }
+    Ok(())
+}
+
+/// 单元格的文本形式（字符串不加引号，NULL 为空）
+pub fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        _ => value.to_string(),
+    }
+}
+
+/// 写入文件失败
+pub fn write_error(e: io::Error) -> DbError {
+    DbError::Other(format!("{}: {}", Msg::WriteFileFailed, e))
+}
+
+/// 导出进度
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportProgress {
+    pub export_id: String,
+    /// 已写入的行数
+    pub rows: u64,
+    pub elapsed_ms: u128,
+}
+
+/// 进度回调的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 正在进行的导出任务（导出 ID → 取消标记）
+static EXPORT_JOBS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+
+fn export_jobs() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    EXPORT_JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 导出任务登记，析构时自动注销
+pub struct ExportJob {
+    id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ExportJob {
+    /// 登记导出任务
+    pub fn start(id: &str) -> Self {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if let Ok(mut jobs) = export_jobs().lock() {
+            jobs.insert(id.to_string(), cancelled.clone());
+        }
+        Self {
+            id: id.to_string(),
+            cancelled,
+        }
+    }
+
+    /// 是否已请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for ExportJob {
+    fn drop(&mut self) {
+        if let Ok(mut jobs) = export_jobs().lock() {
+            jobs.remove(&self.id);
+        }
+    }
+}
+
+/// 请求取消导出任务，任务不存在（已结束）时返回 false
+pub fn cancel_export(id: &str) -> bool {
+    export_jobs()
+        .lock()
+        .ok()
+        .and_then(|jobs| jobs.get(id).cloned())
+        .map(|cancelled| cancelled.store(true, Ordering::Relaxed))
+        .is_some()
+}
+
+/// 把流式查询结果写入导出文件的 RowSink
+///
+/// 每行写入前检查取消标记，取消后返回错误以终止驱动读取
+pub struct ExportSink<'a> {
+    writer: Box<dyn RowWriter>,
+    job: &'a ExportJob,
+    on_progress: Box<dyn FnMut(ExportProgress) + Send + 'a>,
+    rows: u64,
+    started: Instant,
+    last_report: Instant,
+}
+
+impl<'a> ExportSink<'a> {
+    pub fn new(
+        writer: Box<dyn RowWriter>,
+        job: &'a ExportJob,
+        on_progress: impl FnMut(ExportProgress) + Send + 'a,
+    ) -> Self {
+        let now = Instant::now();
+        Self {
+            writer,
+            job,
+            on_progress: Box::new(on_progress),
+            rows: 0,
+            started: now,
+            last_report: now,
+        }
+    }
+
+    fn report(&mut self) {
+        self.last_report = Instant::now();
+        (self.on_progress)(ExportProgress {
+            export_id: self.job.id.clone(),
+            rows: self.rows,
+            elapsed_ms: self.started.elapsed().as_millis(),
+        });
+    }
+
+    /// 写入结尾并发送最终进度
+    pub fn finish(mut self) -> DbResult<u64> {
+        self.writer.finish().map_err(write_error)?;
+        self.report();
+        Ok(self.rows)
+    }
+}
+
+impl RowSink for ExportSink<'_> {
+    fn columns(&mut self, columns: &[String]) -> DbResult<()> {
+        self.writer.begin(columns).map_err(write_error)
+    }
+
+    fn row(&mut self, values: Vec<Value>) -> DbResult<()> {
+        if self.job.is_cancelled() {
+            return Err(DbError::Other(Msg::ExportCancelled.into()));
+        }
+
+        self.writer.write_row(&values).map_err(write_error)?;
+        self.rows += 1;
+
+        if self.last_report.elapsed() >= PROGRESS_INTERVAL {
+            self.report();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write_all(writer: &mut dyn RowWriter) {
+        writer.begin(&["id".to_string(), "name".to_string()]).unwrap();
+        writer.write_row(&[json!(1), json!("a,\"b\"")]).unwrap();
+        writer.write_row(&[json!(2), Value::Null]).unwrap();
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_csv_writer() {
+        let mut buf = Vec::new();
+        write_all(&mut csv::CsvWriter::with_dialect(&mut buf, &csv::CsvDialect::default()).unwrap());
+        assert_eq!(String::from_utf8(buf).unwrap(), "id,name\n1,\"a,\"\"b\"\"\"\n2,\n");
+    }
+
+    #[test]
+    fn test_json_writer() {
+        let mut buf = Vec::new();
+        write_all(&mut json::JsonWriter::new(&mut buf));
+        let text = String::from_utf8(buf).unwrap();
+        // 保持列顺序
+        assert!(text.contains(r#"{"id": 1, "name": "a,\"b\""}"#));
+        let parsed: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed, json!([{"id": 1, "name": "a,\"b\""}, {"id": 2, "name": null}]));
+
+        let mut empty = Vec::new();
+        let mut writer = json::JsonWriter::new(&mut empty);
+        writer.begin(&[]).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(String::from_utf8(empty).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn test_json_lines_writers() {
+        let mut buf = Vec::new();
+        write_all(&mut json::JsonLinesWriter::new(&mut buf));
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "{\"id\":1,\"name\":\"a,\\\"b\\\"\"}\n{\"id\":2,\"name\":null}\n"
+        );
+
+        let mut buf = Vec::new();
+        write_all(&mut json::JsonColumnsWriter::new(&mut buf));
+        let parsed: Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(parsed, json!({"columns": ["id", "name"], "rows": [[1, "a,\"b\""], [2, null]]}));
+    }
+
+    #[test]
+    fn test_markup_writers() {
+        let mut buf = Vec::new();
+        write_all(&mut xml::XmlWriter::new(&mut buf, "t"));
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains(r#"<resultset xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" name="t">"#));
+        assert!(text.contains(r#"<field name="name">a,&quot;b&quot;</field>"#));
+        assert!(text.contains(r#"<field name="name" xsi:nil="true" />"#));
+
+        let mut buf = Vec::new();
+        write_all(&mut markdown::MarkdownWriter::new(&mut buf));
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "| id | name |\n| --- | --- |\n| 1 | a,\"b\" |\n| 2 |  |\n"
+        );
+        assert_eq!(markdown::escape_cell("a|b\nc"), "a\\|b<br>c");
+
+        let mut buf = Vec::new();
+        write_all(&mut html::HtmlWriter::new(&mut buf, "<t>"));
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains("<title>&lt;t&gt;</title>"));
+        assert!(text.contains("<tr><td class=\"number\">1</td><td>a,&quot;b&quot;</td></tr>"));
+        assert!(text.contains("<td class=\"null\">NULL</td>"));
+    }
+
+    #[test]
+    fn test_sql_writer() {
+        let mut buf = Vec::new();
+        write_all(&mut sql::SqlWriter::with_options(&mut buf, "t", &sql::SqlExportOptions::default()).unwrap());
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "INSERT INTO `t` (`id`, `name`) VALUES (1, 'a,\"b\"');\n\
+             INSERT INTO `t` (`id`, `name`) VALUES (2, NULL);\n"
+        );
+    }
+
+    #[test]
+    fn test_cancel_export() {
+        let job = ExportJob::start("test-export");
+        assert!(!job.is_cancelled());
+        assert!(cancel_export("test-export"));
+        assert!(job.is_cancelled());
+
+        drop(job);
+        assert!(!cancel_export("test-export"));
+    }
+}
@@ -0,0 +1,531 @@
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, Write};
+
+use super::RowWriter;
+use crate::database::{ColumnInfo, DatabaseType, DbError, DbResult};
+use crate::import::{self, sql::column_sql_type};
+use crate::utils::i18n::Msg;
+use crate::utils::sql_formatter::SqlFormatter;
+
+/// 遇到主键或唯一键冲突时的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlInsertMode {
+    #[default]
+    Insert,
+    /// 跳过冲突的行（INSERT IGNORE、INSERT OR IGNORE、ON CONFLICT DO NOTHING）
+    Ignore,
+    /// 用新行替换冲突的行（REPLACE INTO、INSERT OR REPLACE）
+    Replace,
+    /// 更新冲突行的其他列（ON DUPLICATE KEY UPDATE、ON CONFLICT DO UPDATE）
+    Upsert,
+}
+
+impl SqlInsertMode {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Insert => "INSERT",
+            Self::Ignore => "IGNORE",
+            Self::Replace => "REPLACE",
+            Self::Upsert => "UPSERT",
+        }
+    }
+}
+
+/// SQL INSERT 导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SqlExportOptions {
+    /// 目标数据库类型，决定标识符和字面量的写法
+    /// （流式导出默认与来源连接相同，导出前端传入的结果时默认 MySQL）
+    pub dialect: Option<DatabaseType>,
+    /// 每条 INSERT 语句包含的行数
+    pub batch_size: usize,
+    pub mode: SqlInsertMode,
+    /// 冲突键列，Upsert 在 MySQL 以外的数据库必须指定
+    pub conflict_columns: Vec<String>,
+    /// 是否在 INSERT 之前写入建表语句
+    pub create_table: bool,
+    /// 按二进制字面量写入的列
+    pub binary_columns: Vec<String>,
+    /// 来源表结构，用于建表语句和识别二进制列（导出整表时由后端填充）
+    #[serde(skip)]
+    pub table_columns: Vec<ColumnInfo>,
+    /// 来源驱动把二进制值读取为 Base64 文本（由后端填充）
+    #[serde(skip)]
+    pub binary_base64: bool,
+    /// 目标表所在的架构（备份时由后端填充）
+    #[serde(skip)]
+    pub schema: Option<String>,
+    /// 每条语句后写入 GO 批处理分隔符（SQL Server 备份）
+    #[serde(skip)]
+    pub batch_separator: bool,
+}
+
+impl Default for SqlExportOptions {
+    fn default() -> Self {
+        Self {
+            dialect: None,
+            batch_size: 1,
+            mode: SqlInsertMode::Insert,
+            conflict_columns: Vec::new(),
+            create_table: false,
+            binary_columns: Vec::new(),
+            table_columns: Vec::new(),
+            binary_base64: false,
+            schema: None,
+            batch_separator: false,
+        }
+    }
+}
+
+/// 推断建表语句的列类型时使用的样本行数
+const INFER_ROWS: usize = 100;
+
+/// 是否为二进制列类型
+pub fn is_binary_type(data_type: &str) -> bool {
+    let lower = data_type.to_lowercase();
+    ["blob", "binary", "bytea", "image"]
+        .iter()
+        .any(|name| lower.contains(name))
+}
+
+/// 列的完整类型声明（补上长度和精度）
+pub fn declared_type(column: &ColumnInfo) -> String {
+    let data_type = column.data_type.trim();
+    if data_type.contains('(') {
+        return data_type.to_string();
+    }
+
+    let lower = data_type.to_lowercase();
+    let sized = lower.ends_with("char") || lower.ends_with("binary") || lower.ends_with("varying") || lower == "character";
+    match (column.character_maximum_length, column.numeric_precision, column.numeric_scale) {
+        // SQL Server 的 MAX 长度为 -1
+        (Some(-1), _, _) if sized => format!("{}(MAX)", data_type),
+        (Some(length), _, _) if sized && length > 0 => format!("{}({})", data_type, length),
+        (_, Some(precision), Some(scale)) if lower == "decimal" || lower == "numeric" => {
+            format!("{}({}, {})", data_type, precision, scale)
+        }
+        _ => data_type.to_string(),
+    }
+}
+
+/// 驱动读取的二进制文本还原为字节（PostgreSQL 和 MySQL 为 \x 十六进制，SQL Server 和 DuckDB 为 Base64）
+///
+/// 只用于按表结构确定为二进制的列，普通文本列中形如 `\x41` 的字符串不会被转换
+pub fn decode_binary(text: &str, base64: bool) -> Vec<u8> {
+    if base64 {
+        if let Ok(bytes) = general_purpose::STANDARD.decode(text) {
+            return bytes;
+        }
+    }
+    if let Some(hex) = text.strip_prefix("\\x") {
+        let bytes: Option<Vec<u8>> = (0..hex.len())
+            .step_by(2)
+            .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+            .collect();
+        if let Some(bytes) = bytes {
+            return bytes;
+        }
+    }
+    text.as_bytes().to_vec()
+}
+
+/// 推断建表语句时二进制列的类型
+fn binary_sql_type(dialect: &DatabaseType) -> &'static str {
+    match dialect {
+        DatabaseType::MySQL => "LONGBLOB",
+        DatabaseType::PostgreSQL => "BYTEA",
+        DatabaseType::SqlServer => "VARBINARY(MAX)",
+        DatabaseType::ClickHouse => "Nullable(String)",
+        _ => "BLOB",
+    }
+}
+
+/// SQL INSERT 语句写入器
+///
+/// 按目标数据库引用标识符和写入字面量，攒够一批后写成一条多行 INSERT
+pub struct SqlWriter<W: Write> {
+    out: W,
+    dialect: DatabaseType,
+    options: SqlExportOptions,
+    table_name: String,
+    columns: Vec<String>,
+    /// 各列是否按二进制写入
+    binary: Vec<bool>,
+    /// INSERT INTO ... VALUES
+    prefix: String,
+    /// ON CONFLICT 等冲突处理子句
+    suffix: String,
+    pending: Vec<Vec<Value>>,
+    /// 建表语句是否还未写入
+    create_pending: bool,
+}
+
+impl<W: Write> SqlWriter<W> {
+    pub fn with_options(out: W, table_name: &str, options: &SqlExportOptions) -> DbResult<Self> {
+        let dialect = options.dialect.clone().unwrap_or(DatabaseType::MySQL);
+        let supported = match options.mode {
+            SqlInsertMode::Insert => true,
+            SqlInsertMode::Ignore | SqlInsertMode::Upsert => matches!(
+                dialect,
+                DatabaseType::MySQL | DatabaseType::PostgreSQL | DatabaseType::SQLite | DatabaseType::DuckDB
+            ),
+            SqlInsertMode::Replace => matches!(
+                dialect,
+                DatabaseType::MySQL | DatabaseType::SQLite | DatabaseType::DuckDB
+            ),
+        };
+        if !supported {
+            return Err(DbError::ConfigError(
+                Msg::InsertModeUnsupported.with(&[&format!("{:?}", dialect), &options.mode.label()]),
+            ));
+        }
+        if options.mode == SqlInsertMode::Upsert
+            && dialect != DatabaseType::MySQL
+            && options.conflict_columns.is_empty()
+        {
+            return Err(DbError::ConfigError(Msg::ConflictColumnsRequired.into()));
+        }
+
+        // SQL Server 的 VALUES 最多 1000 行
+        let max_batch = if dialect == DatabaseType::SqlServer { 1000 } else { usize::MAX };
+        let mut options = options.clone();
+        options.batch_size = options.batch_size.clamp(1, max_batch);
+
+        Ok(Self {
+            out,
+            dialect,
+            create_pending: options.create_table,
+            options,
+            table_name: table_name.to_string(),
+            columns: Vec::new(),
+            binary: Vec::new(),
+            prefix: String::new(),
+            suffix: String::new(),
+            pending: Vec::new(),
+        })
+    }
+
+    fn quote(&self, identifier: &str) -> String {
+        SqlFormatter::quote_identifier(&self.dialect, identifier)
+    }
+
+    /// 目标表引用，指定架构时带上架构名
+    fn table_ref(&self) -> String {
+        match &self.options.schema {
+            Some(schema) => format!("{}.{}", self.quote(schema), self.quote(&self.table_name)),
+            None => self.quote(&self.table_name),
+        }
+    }
+
+    /// 冲突处理对应的 INSERT 关键字和结尾子句
+    fn conflict_clauses(&self) -> (&'static str, String) {
+        let dialect = &self.dialect;
+        let keys = &self.options.conflict_columns;
+        let quoted_keys = keys.iter().map(|k| self.quote(k)).collect::<Vec<_>>().join(", ");
+        let updates: Vec<&String> = self.columns.iter().filter(|c| !keys.contains(c)).collect();
+
+        match (self.options.mode, dialect) {
+            (SqlInsertMode::Insert, _) => ("INSERT INTO", String::new()),
+            (SqlInsertMode::Ignore, DatabaseType::MySQL) => ("INSERT IGNORE INTO", String::new()),
+            (SqlInsertMode::Ignore, DatabaseType::PostgreSQL) if keys.is_empty() => {
+                ("INSERT INTO", " ON CONFLICT DO NOTHING".to_string())
+            }
+            (SqlInsertMode::Ignore, DatabaseType::PostgreSQL) => {
+                ("INSERT INTO", format!(" ON CONFLICT ({}) DO NOTHING", quoted_keys))
+            }
+            (SqlInsertMode::Ignore, _) => ("INSERT OR IGNORE INTO", String::new()),
+            (SqlInsertMode::Replace, DatabaseType::MySQL) => ("REPLACE INTO", String::new()),
+            (SqlInsertMode::Replace, _) => ("INSERT OR REPLACE INTO", String::new()),
+            // 所有列都是键时没有可更新的列，冲突的行直接跳过
+            (SqlInsertMode::Upsert, DatabaseType::MySQL) if updates.is_empty() => ("INSERT IGNORE INTO", String::new()),
+            (SqlInsertMode::Upsert, DatabaseType::MySQL) => {
+                let sets: Vec<String> = updates
+                    .iter()
+                    .map(|c| format!("{0} = VALUES({0})", self.quote(c)))
+                    .collect();
+                ("INSERT INTO", format!(" ON DUPLICATE KEY UPDATE {}", sets.join(", ")))
+            }
+            (SqlInsertMode::Upsert, _) if updates.is_empty() => {
+                ("INSERT INTO", format!(" ON CONFLICT ({}) DO NOTHING", quoted_keys))
+            }
+            (SqlInsertMode::Upsert, _) => {
+                let sets: Vec<String> = updates
+                    .iter()
+                    .map(|c| format!("{0} = EXCLUDED.{0}", self.quote(c)))
+                    .collect();
+                (
+                    "INSERT INTO",
+                    format!(" ON CONFLICT ({}) DO UPDATE SET {}", quoted_keys, sets.join(", ")),
+                )
+            }
+        }
+    }
+
+    /// 建表语句，有来源表结构时使用原始类型，否则按第一批数据推断
+    fn create_table_sql(&self) -> String {
+        let mut definitions: Vec<String> = if self.options.table_columns.is_empty() {
+            import::infer_columns(&self.columns, &self.pending)
+                .iter()
+                .zip(&self.binary)
+                .map(|(column, binary)| {
+                    let sql_type = if *binary {
+                        binary_sql_type(&self.dialect).to_string()
+                    } else {
+                        column_sql_type(&self.dialect, column)
+                    };
+                    format!("  {} {}", self.quote(&column.name), sql_type)
+                })
+                .collect()
+        } else {
+            let not_null = self.dialect != DatabaseType::ClickHouse;
+            self.options
+                .table_columns
+                .iter()
+                .map(|column| {
+                    let null = if not_null && !column.nullable { " NOT NULL" } else { "" };
+                    format!("  {} {}{}", self.quote(&column.name), declared_type(column), null)
+                })
+                .collect()
+        };
+
+        let primary_keys: Vec<String> = self
+            .options
+            .table_columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| self.quote(&c.name))
+            .collect();
+        if !primary_keys.is_empty() && self.dialect != DatabaseType::ClickHouse {
+            definitions.push(format!("  PRIMARY KEY ({})", primary_keys.join(", ")));
+        }
+
+        let mut sql = format!(
+            "CREATE TABLE {} (\n{}\n)",
+            self.table_ref(),
+            definitions.join(",\n")
+        );
+        if self.dialect == DatabaseType::ClickHouse {
+            sql.push_str(" ENGINE = MergeTree ORDER BY tuple()");
+        }
+        sql
+    }
+
+    /// 单元格的字面量
+    fn literal(&self, idx: usize, value: &Value) -> String {
+        match value {
+            Value::String(s) if self.binary.get(idx).copied().unwrap_or(false) => {
+                SqlFormatter::format_binary(&self.dialect, &decode_binary(s, self.options.binary_base64))
+            }
+            _ => SqlFormatter::format_literal(&self.dialect, value),
+        }
+    }
+
+    /// 按批写入攒下的行（首次写入前先写建表语句）
+    fn flush_pending(&mut self) -> io::Result<()> {
+        if self.create_pending {
+            self.create_pending = false;
+            let sql = self.create_table_sql();
+            writeln!(self.out, "{};", sql)?;
+            if self.options.batch_separator {
+                writeln!(self.out, "GO")?;
+            }
+            writeln!(self.out)?;
+        }
+
+        let pending = std::mem::take(&mut self.pending);
+        for batch in pending.chunks(self.options.batch_size) {
+            writeln!(self.out, "{};", self.insert_statement(batch))?;
+            if self.options.batch_separator {
+                writeln!(self.out, "GO")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 一批行对应的 INSERT 语句（不含结尾分号），需要先调用 `begin`
+    pub fn insert_statement(&self, rows: &[Vec<Value>]) -> String {
+        let rows: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                let literals: Vec<String> = row
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, value)| self.literal(idx, value))
+                    .collect();
+                format!("({})", literals.join(", "))
+            })
+            .collect();
+        let separator = if rows.len() == 1 { " " } else { "\n" };
+        format!("{}{}{}{}", self.prefix, separator, rows.join(",\n"), self.suffix)
+    }
+}
+
+impl<W: Write + Send> RowWriter for SqlWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> io::Result<()> {
+        self.columns = columns.to_vec();
+        self.binary = columns
+            .iter()
+            .map(|name| {
+                self.options.binary_columns.contains(name)
+                    || self
+                        .options
+                        .table_columns
+                        .iter()
+                        .any(|c| &c.name == name && is_binary_type(&c.data_type))
+            })
+            .collect();
+
+        let quoted: Vec<String> = columns.iter().map(|c| self.quote(c)).collect();
+        let (insert, suffix) = self.conflict_clauses();
+        self.prefix = format!(
+            "{} {} ({}) VALUES",
+            insert,
+            self.table_ref(),
+            quoted.join(", ")
+        );
+        self.suffix = suffix;
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        self.pending.push(values.to_vec());
+        // 推断建表语句时多攒一些样本行
+        let threshold = if self.create_pending && self.options.table_columns.is_empty() {
+            self.options.batch_size.max(INFER_ROWS)
+        } else {
+            self.options.batch_size
+        };
+        if self.pending.len() >= threshold {
+            self.flush_pending()?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush_pending()?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write(table: &str, options: &SqlExportOptions, columns: &[&str], rows: &[Vec<Value>]) -> String {
+        let mut buf = Vec::new();
+        {
+            let mut writer = SqlWriter::with_options(&mut buf, table, options).unwrap();
+            let columns: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+            writer.begin(&columns).unwrap();
+            for row in rows {
+                writer.write_row(row).unwrap();
+            }
+            writer.finish().unwrap();
+        }
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_postgres_upsert_batch() {
+        let options = SqlExportOptions {
+            dialect: Some(DatabaseType::PostgreSQL),
+            batch_size: 2,
+            mode: SqlInsertMode::Upsert,
+            conflict_columns: vec!["id".to_string()],
+            create_table: true,
+            binary_columns: vec!["data".to_string()],
+            ..Default::default()
+        };
+        let rows = vec![
+            vec![json!(1), json!({"a": "b\\c"}), json!("\\x00ff")],
+            vec![json!(2), Value::Null, Value::Null],
+            vec![json!(3), json!([1]), json!("ab")],
+        ];
+        assert_eq!(
+            write("t", &options, &["id", "doc", "data"], &rows),
+            "CREATE TABLE \"t\" (\n  \"id\" BIGINT,\n  \"doc\" TEXT,\n  \"data\" BYTEA\n);\n\n\
+             INSERT INTO \"t\" (\"id\", \"doc\", \"data\") VALUES\n\
+             (1, '{\"a\":\"b\\\\c\"}', '\\x00FF'::bytea),\n(2, NULL, NULL) \
+             ON CONFLICT (\"id\") DO UPDATE SET \"doc\" = EXCLUDED.\"doc\", \"data\" = EXCLUDED.\"data\";\n\
+             INSERT INTO \"t\" (\"id\", \"doc\", \"data\") VALUES (3, '[1]', '\\x6162'::bytea) \
+             ON CONFLICT (\"id\") DO UPDATE SET \"doc\" = EXCLUDED.\"doc\", \"data\" = EXCLUDED.\"data\";\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_modes() {
+        let row = vec![vec![json!(1), json!("it's")]];
+        let options = SqlExportOptions {
+            mode: SqlInsertMode::Ignore,
+            ..Default::default()
+        };
+        assert_eq!(
+            write("t", &options, &["id", "name"], &row),
+            "INSERT IGNORE INTO `t` (`id`, `name`) VALUES (1, 'it''s');\n"
+        );
+
+        let options = SqlExportOptions {
+            dialect: Some(DatabaseType::SQLite),
+            mode: SqlInsertMode::Replace,
+            ..Default::default()
+        };
+        assert_eq!(
+            write("t", &options, &["id", "name"], &row),
+            "INSERT OR REPLACE INTO \"t\" (\"id\", \"name\") VALUES (1, 'it''s');\n"
+        );
+
+        let options = SqlExportOptions {
+            mode: SqlInsertMode::Upsert,
+            conflict_columns: vec!["id".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            write("t", &options, &["id", "name"], &row),
+            "INSERT INTO `t` (`id`, `name`) VALUES (1, 'it''s') ON DUPLICATE KEY UPDATE `name` = VALUES(`name`);\n"
+        );
+
+        let options = SqlExportOptions {
+            dialect: Some(DatabaseType::SqlServer),
+            mode: SqlInsertMode::Ignore,
+            ..Default::default()
+        };
+        assert!(SqlWriter::with_options(Vec::new(), "t", &options).is_err());
+    }
+
+    #[test]
+    fn test_create_table_from_structure() {
+        let column = |name: &str, data_type: &str, length: Option<i64>, primary: bool| ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: !primary,
+            default_value: None,
+            is_primary_key: primary,
+            is_auto_increment: false,
+            comment: None,
+            character_maximum_length: length,
+            numeric_precision: None,
+            numeric_scale: None,
+        };
+        let options = SqlExportOptions {
+            dialect: Some(DatabaseType::SqlServer),
+            create_table: true,
+            table_columns: vec![
+                column("id", "int", None, true),
+                column("name", "nvarchar", Some(-1), false),
+                column("photo", "varbinary", Some(16), false),
+            ],
+            binary_base64: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            write("t", &options, &["id", "name", "photo"], &[vec![json!(1), json!("名"), json!("AAE=")]]),
+            "CREATE TABLE [t] (\n  [id] int NOT NULL,\n  [name] nvarchar(MAX),\n  [photo] varbinary(16),\n  PRIMARY KEY ([id])\n);\n\n\
+             INSERT INTO [t] ([id], [name], [photo]) VALUES (1, N'名', 0x0001);\n"
+        );
+    }
+}
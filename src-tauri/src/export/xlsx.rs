@@ -0,0 +1,327 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook, Worksheet, XlsxError};
+use serde_json::Value;
+use std::io::{self, Seek, Write};
+
+use super::RowWriter;
+
+/// 单个工作表的最大行数（含表头）
+const MAX_ROWS: u32 = 1_048_576;
+/// 自动列宽的上限（字符数）
+const MAX_COLUMN_WIDTH: usize = 60;
+/// 超过该值的整数无法用 Excel 数字精确表示，按文本写入
+const MAX_SAFE_INTEGER: i64 = 1 << 53;
+
+/// Excel 写入器
+///
+/// 每次调用 `begin` 新建一个工作表；数字、布尔和日期写成对应类型的单元格，
+/// 表头加粗并冻结，列宽按内容自动调整。单表超过 Excel 行数上限时续写到新工作表。
+/// 注意工作簿在 `finish` 时才写入文件，写入前整体保存在内存中
+pub struct XlsxWriter<W: Write + Seek + Send> {
+    out: Option<W>,
+    workbook: Workbook,
+    /// 未指定名称时的工作表名称前缀
+    base_name: String,
+    sheet: Option<SheetState>,
+    /// 已创建的工作表名称
+    sheet_names: Vec<String>,
+    header_format: Format,
+    date_format: Format,
+    datetime_format: Format,
+}
+
+/// 当前工作表的写入状态
+struct SheetState {
+    index: usize,
+    /// 请求的名称（续写的工作表在此基础上加序号）
+    title: String,
+    columns: Vec<String>,
+    /// 下一行的行号
+    row: u32,
+    widths: Vec<usize>,
+    /// 行数超限后续写的工作表序号
+    part: usize,
+}
+
+impl<W: Write + Seek + Send> XlsxWriter<W> {
+    pub fn new(out: W, base_name: &str) -> Self {
+        Self {
+            out: Some(out),
+            workbook: Workbook::new(),
+            base_name: base_name.to_string(),
+            sheet: None,
+            sheet_names: Vec::new(),
+            header_format: Format::new().set_bold(),
+            date_format: Format::new().set_num_format("yyyy-mm-dd"),
+            datetime_format: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+        }
+    }
+
+    /// 新建指定名称的工作表并写入表头
+    pub fn start_sheet(&mut self, name: &str, columns: &[String]) -> io::Result<()> {
+        self.close_sheet()?;
+
+        let unique_name = self.unique_sheet_name(name);
+        let index = self.sheet_names.len();
+        self.sheet_names.push(unique_name.clone());
+
+        let worksheet = self.workbook.add_worksheet();
+        worksheet.set_name(unique_name).map_err(xlsx_error)?;
+        for (col, column) in columns.iter().enumerate() {
+            worksheet
+                .write_string_with_format(0, col as u16, column, &self.header_format)
+                .map_err(xlsx_error)?;
+        }
+        worksheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+
+        self.sheet = Some(SheetState {
+            index,
+            title: name.to_string(),
+            columns: columns.to_vec(),
+            row: 1,
+            widths: columns.iter().map(|c| display_width(c)).collect(),
+            part: 1,
+        });
+        Ok(())
+    }
+
+    /// 按内容设置当前工作表的列宽
+    fn close_sheet(&mut self) -> io::Result<()> {
+        if let Some(sheet) = self.sheet.take() {
+            let worksheet = self.workbook.worksheet_from_index(sheet.index).map_err(xlsx_error)?;
+            for (col, width) in sheet.widths.iter().enumerate() {
+                let width = (*width).clamp(4, MAX_COLUMN_WIDTH) + 2;
+                worksheet
+                    .set_column_width(col as u16, width as f64)
+                    .map_err(xlsx_error)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 工作表名称：去掉 Excel 不允许的字符，截断到 31 个字符并避免重名
+    fn unique_sheet_name(&self, name: &str) -> String {
+        let cleaned: String = name
+            .chars()
+            .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+            .collect();
+        let cleaned = match cleaned.trim().trim_matches('\'') {
+            "" => format!("Sheet{}", self.sheet_names.len() + 1),
+            s => s.to_string(),
+        };
+
+        let mut candidate: String = cleaned.chars().take(31).collect();
+        let mut n = 2;
+        while self
+            .sheet_names
+            .iter()
+            .any(|existing| existing.to_lowercase() == candidate.to_lowercase())
+        {
+            let suffix = format!(" ({})", n);
+            let prefix: String = cleaned.chars().take(31 - suffix.chars().count()).collect();
+            candidate = format!("{}{}", prefix, suffix);
+            n += 1;
+        }
+        candidate
+    }
+
+    fn write_cell(
+        worksheet: &mut Worksheet,
+        row: u32,
+        col: u16,
+        value: &Value,
+        date_format: &Format,
+        datetime_format: &Format,
+    ) -> Result<usize, XlsxError> {
+        match value {
+            Value::Null => Ok(0),
+            Value::Bool(b) => {
+                worksheet.write_boolean(row, col, *b)?;
+                Ok(5)
+            }
+            Value::Number(n) => {
+                let text = n.to_string();
+                match n.as_i64() {
+                    // 超出精度的整数保留为文本
+                    Some(i) if i.abs() > MAX_SAFE_INTEGER => {
+                        worksheet.write_string(row, col, &text)?;
+                    }
+                    _ => {
+                        worksheet.write_number(row, col, n.as_f64().unwrap_or_default())?;
+                    }
+                }
+                Ok(text.len())
+            }
+            Value::String(s) => {
+                match parse_datetime(s) {
+                    Some((datetime, true)) => {
+                        worksheet.write_datetime_with_format(row, col, &datetime, date_format)?;
+                    }
+                    Some((datetime, false)) => {
+                        worksheet.write_datetime_with_format(row, col, &datetime, datetime_format)?;
+                    }
+                    None => {
+                        worksheet.write_string(row, col, s)?;
+                    }
+                }
+                Ok(display_width(s))
+            }
+            _ => {
+                let text = value.to_string();
+                worksheet.write_string(row, col, &text)?;
+                Ok(display_width(&text))
+            }
+        }
+    }
+}
+
+impl<W: Write + Seek + Send> RowWriter for XlsxWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> io::Result<()> {
+        let name = match self.sheet_names.len() {
+            0 => self.base_name.clone(),
+            n => format!("{} {}", self.base_name, n + 1),
+        };
+        self.start_sheet(&name, columns)
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        // 超过 Excel 行数上限时续写到新工作表
+        let full = self
+            .sheet
+            .as_ref()
+            .filter(|sheet| sheet.row >= MAX_ROWS)
+            .map(|sheet| (sheet.title.clone(), sheet.columns.clone(), sheet.part + 1));
+        if let Some((title, columns, part)) = full {
+            self.start_sheet(&format!("{} ({})", title, part), &columns)?;
+            if let Some(sheet) = self.sheet.as_mut() {
+                sheet.title = title;
+                sheet.part = part;
+            }
+        }
+
+        let Some(sheet) = self.sheet.as_mut() else {
+            return Ok(());
+        };
+        let worksheet = self.workbook.worksheet_from_index(sheet.index).map_err(xlsx_error)?;
+        for (col, value) in values.iter().enumerate() {
+            let width = Self::write_cell(
+                worksheet,
+                sheet.row,
+                col as u16,
+                value,
+                &self.date_format,
+                &self.datetime_format,
+            )
+            .map_err(xlsx_error)?;
+            if let Some(max) = sheet.widths.get_mut(col) {
+                *max = (*max).max(width);
+            }
+        }
+        sheet.row += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.close_sheet()?;
+        // 没有任何结果时也生成一个空工作表，保证文件可以打开
+        if self.sheet_names.is_empty() {
+            self.start_sheet(&self.base_name.clone(), &[])?;
+            self.close_sheet()?;
+        }
+        if let Some(mut out) = self.out.take() {
+            self.workbook.save_to_writer(&mut out).map_err(xlsx_error)?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+fn xlsx_error(e: XlsxError) -> io::Error {
+    io::Error::other(e)
+}
+
+/// 识别日期或日期时间文本，返回 (值, 是否只有日期)
+fn parse_datetime(text: &str) -> Option<(ExcelDateTime, bool)> {
+    let text = text.trim();
+    if text.len() < 10 || !text.as_bytes()[0].is_ascii_digit() {
+        return None;
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return excel_date(date).map(|d| (d, true));
+    }
+
+    // PostgreSQL 的 timestamptz 文本以 " UTC" 结尾
+    let text = text.strip_suffix(" UTC").unwrap_or(text);
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .and_then(|datetime| {
+            let seconds = datetime.second() as f64 + datetime.nanosecond() as f64 / 1e9;
+            excel_date(datetime.date())?
+                .and_hms(datetime.hour() as u16, datetime.minute() as u8, seconds)
+                .ok()
+        })
+        .map(|d| (d, false))
+}
+
+fn excel_date(date: NaiveDate) -> Option<ExcelDateTime> {
+    // Excel 只支持 1900 年之后的日期
+    let year = u16::try_from(date.year()).ok().filter(|y| *y >= 1900)?;
+    ExcelDateTime::from_ymd(year, date.month() as u8, date.day() as u8).ok()
+}
+
+/// 估算显示宽度（中日韩字符按两个字符宽计算）
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if (c as u32) < 0x1100 { 1 } else { 2 })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_datetime() {
+        assert!(matches!(parse_datetime("2024-02-29"), Some((_, true))));
+        assert!(matches!(parse_datetime("2024-02-29 12:30:45.5"), Some((_, false))));
+        assert!(matches!(parse_datetime("2024-02-29T12:30:45"), Some((_, false))));
+        assert!(matches!(parse_datetime("2024-02-29 12:30:45 UTC"), Some((_, false))));
+        assert!(parse_datetime("2024-02-30").is_none());
+        assert!(parse_datetime("order 2024-02-29").is_none());
+        assert!(parse_datetime("1850-01-01").is_none());
+    }
+
+    #[test]
+    fn test_unique_sheet_name() {
+        let mut writer = XlsxWriter::new(io::Cursor::new(Vec::new()), "Query");
+        writer.start_sheet("a/b", &[]).unwrap();
+        writer.start_sheet("a/b", &[]).unwrap();
+        writer.start_sheet(&"x".repeat(40), &[]).unwrap();
+        writer.start_sheet(&"x".repeat(40), &[]).unwrap();
+        let names = &writer.sheet_names;
+        assert_eq!(names[0], "a_b");
+        assert_eq!(names[1], "a_b (2)");
+        assert_eq!(names[2], "x".repeat(31));
+        assert_eq!(names[3], format!("{} (2)", "x".repeat(27)));
+    }
+
+    #[test]
+    fn test_write_workbook() {
+        let mut buf = io::Cursor::new(Vec::new());
+        {
+            let mut writer = XlsxWriter::new(&mut buf, "Query");
+            writer.begin(&["id".to_string(), "created".to_string()]).unwrap();
+            writer
+                .write_row(&[Value::from(1), Value::from("2024-01-01 08:00:00")])
+                .unwrap();
+            writer.begin(&["flag".to_string()]).unwrap();
+            writer.write_row(&[Value::Bool(true)]).unwrap();
+            writer.finish().unwrap();
+            assert_eq!(writer.sheet_names, vec!["Query", "Query 2"]);
+        }
+        // xlsx 是 zip 文件
+        assert!(buf.into_inner().starts_with(b"PK"));
+    }
+}
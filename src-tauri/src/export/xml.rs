@@ -0,0 +1,75 @@
+use serde_json::Value;
+use std::io::{self, Write};
+
+use super::{value_to_text, RowWriter};
+
+/// XML 写入器，结构与 `mysql --xml` 相同：
+/// `<resultset>` 下每行一个 `<row>`，每列一个带 `name` 属性的 `<field>`，NULL 写为 `xsi:nil`
+pub struct XmlWriter<W: Write> {
+    out: W,
+    /// 结果集名称（一般是表名）
+    name: String,
+    /// 已转义的列名
+    names: Vec<String>,
+}
+
+impl<W: Write> XmlWriter<W> {
+    pub fn new(out: W, name: &str) -> Self {
+        Self {
+            out,
+            name: name.to_string(),
+            names: Vec::new(),
+        }
+    }
+}
+
+impl<W: Write + Send> RowWriter for XmlWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> io::Result<()> {
+        self.names = columns.iter().map(|c| escape_xml(c)).collect();
+        writeln!(self.out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        write!(self.out, r#"<resultset xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance""#)?;
+        if !self.name.is_empty() {
+            write!(self.out, r#" name="{}""#, escape_xml(&self.name))?;
+        }
+        writeln!(self.out, ">")
+    }
+
+    fn write_row(&mut self, values: &[Value]) -> io::Result<()> {
+        writeln!(self.out, "  <row>")?;
+        for (name, value) in self.names.iter().zip(values) {
+            match value {
+                Value::Null => writeln!(self.out, r#"    <field name="{}" xsi:nil="true" />"#, name)?,
+                _ => writeln!(
+                    self.out,
+                    r#"    <field name="{}">{}</field>"#,
+                    name,
+                    escape_xml(&value_to_text(value))
+                )?,
+            }
+        }
+        writeln!(self.out, "  </row>")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        writeln!(self.out, "</resultset>")?;
+        self.out.flush()
+    }
+}
+
+/// 转义 XML/HTML 文本和属性值，并去掉 XML 1.0 不允许的控制字符
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,481 @@
+//! 从 CSV 和电子表格文件导入数据
+//!
+//! 预览文件并推断列类型，再按列映射分批插入到目标表。
+//! 整个导入在一个事务中进行，每批使用保存点，失败的批次逐行重试以找出被拒绝的行
+
+pub mod reader;
+pub mod sql;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::database::{DatabaseType, DbError, DbResult, ErrorCode, Transaction};
+use crate::export::csv::CsvDialect;
+use crate::utils::i18n::Msg;
+pub use reader::RowSource;
+
+/// 用于推断列类型的最大样本行数
+const INFER_SAMPLE_ROWS: usize = 1000;
+/// 单个 INSERT 语句的默认行数
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+/// 默认允许被拒绝的行数，超过后整个导入回滚
+pub const DEFAULT_MAX_ERRORS: usize = 100;
+/// 每批使用的保存点名称
+const BATCH_SAVEPOINT: &str = "datasmith_import";
+
+/// 导入文件格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFileFormat {
+    Csv,
+    Xlsx,
+    Xls,
+    Ods,
+}
+
+impl ImportFileFormat {
+    /// 按扩展名识别格式
+    pub fn from_path(path: &str) -> DbResult<Self> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        match extension.as_str() {
+            "csv" | "txt" => Ok(Self::Csv),
+            "xlsx" | "xlsm" => Ok(Self::Xlsx),
+            "xls" => Ok(Self::Xls),
+            "ods" => Ok(Self::Ods),
+            other => Err(DbError::Other(Msg::ImportFormatUnsupported.with(&[&other]))),
+        }
+    }
+}
+
+/// 读取选项
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReadOptions {
+    /// 工作表名称（电子表格，默认第一个）
+    #[serde(default)]
+    pub sheet: Option<String>,
+    /// 第一行是否为表头
+    #[serde(default = "default_has_header")]
+    pub has_header: bool,
+    /// CSV 格式（分隔符、引号、NULL 文本和编码）
+    #[serde(default)]
+    pub csv: CsvDialect,
+}
+
+fn default_has_header() -> bool {
+    true
+}
+
+/// 推断的列类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Integer,
+    Float,
+    Boolean,
+    Date,
+    DateTime,
+    Text,
+}
+
+impl ColumnType {
+    /// 单个值的类型，NULL 返回 None
+    fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => None,
+            Value::Bool(_) => Some(Self::Boolean),
+            Value::Number(n) if n.is_i64() || n.is_u64() => Some(Self::Integer),
+            Value::Number(_) => Some(Self::Float),
+            Value::String(s) => Some(Self::of_text(s.trim())),
+            _ => Some(Self::Text),
+        }
+    }
+
+    fn of_text(text: &str) -> Self {
+        let digits = text.strip_prefix('-').unwrap_or(text);
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            // 有前导零的编号和超出 i64 的长数字按文本保留
+            let leading_zero = digits.len() > 1 && digits.starts_with('0');
+            return if leading_zero || text.parse::<i64>().is_err() {
+                Self::Text
+            } else {
+                Self::Integer
+            };
+        }
+        if text.contains(|c: char| c.is_ascii_digit())
+            && text.bytes().all(|b| b.is_ascii_digit() || b"+-.eE".contains(&b))
+            && text.parse::<f64>().is_ok_and(f64::is_finite)
+        {
+            return Self::Float;
+        }
+        if text.eq_ignore_ascii_case("true") || text.eq_ignore_ascii_case("false") {
+            return Self::Boolean;
+        }
+        if chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok()
+            || chrono::NaiveDate::parse_from_str(text, "%Y/%m/%d").is_ok()
+        {
+            return Self::Date;
+        }
+        if ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y/%m/%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+            .iter()
+            .any(|format| chrono::NaiveDateTime::parse_from_str(text, format).is_ok())
+        {
+            return Self::DateTime;
+        }
+        Self::Text
+    }
+
+    /// 合并同一列中两个值的类型
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Integer, Self::Float) | (Self::Float, Self::Integer) => Self::Float,
+            (Self::Date, Self::DateTime) | (Self::DateTime, Self::Date) => Self::DateTime,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// 源文件中的列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportColumn {
+    pub name: String,
+    pub column_type: ColumnType,
+    /// 样本中是否有空值
+    pub nullable: bool,
+    /// 样本中文本的最大字符数
+    pub max_length: usize,
+}
+
+/// 文件预览
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub format: ImportFileFormat,
+    /// 工作表名称（CSV 为空）
+    pub sheets: Vec<String>,
+    /// 读取的工作表
+    pub sheet: Option<String>,
+    pub columns: Vec<ImportColumn>,
+    /// 前若干行数据
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// 读取文件开头的数据并推断列类型
+pub fn preview_file(path: &str, options: &ReadOptions, limit: usize) -> DbResult<ImportPreview> {
+    let format = ImportFileFormat::from_path(path)?;
+    let mut source = RowSource::open(path, options)?;
+
+    let mut sample = Vec::new();
+    for item in source.by_ref().take(INFER_SAMPLE_ROWS.max(limit)) {
+        sample.push(item?.1);
+    }
+
+    let columns = infer_columns(&source.columns, &sample);
+    let rows = sample
+        .into_iter()
+        .take(limit)
+        .map(|mut row| {
+            row.resize(columns.len(), Value::Null);
+            row
+        })
+        .collect();
+
+    Ok(ImportPreview {
+        format,
+        sheets: source.sheets,
+        sheet: source.sheet,
+        columns,
+        rows,
+    })
+}
+
+/// 按样本推断各列的类型；没有表头时按列数命名为 column_1、column_2……
+pub fn infer_columns(header: &[String], rows: &[Vec<Value>]) -> Vec<ImportColumn> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0).max(header.len());
+    (0..width)
+        .map(|idx| {
+            let mut column_type: Option<ColumnType> = None;
+            let mut nullable = false;
+            let mut max_length = 0;
+            for value in rows.iter().map(|row| row.get(idx).unwrap_or(&Value::Null)) {
+                match ColumnType::of(value) {
+                    Some(t) => column_type = Some(column_type.map_or(t, |c| c.merge(t))),
+                    None => nullable = true,
+                }
+                if let Value::String(s) = value {
+                    max_length = max_length.max(s.chars().count());
+                }
+            }
+            ImportColumn {
+                name: header
+                    .get(idx)
+                    .cloned()
+                    .unwrap_or_else(|| format!("column_{}", idx + 1)),
+                column_type: column_type.unwrap_or(ColumnType::Text),
+                nullable,
+                max_length,
+            }
+        })
+        .collect()
+}
+
+/// 源列到目标列的映射
+#[derive(Debug, Clone, Deserialize)]
+pub struct ColumnMapping {
+    /// 源列序号（从 0 开始）
+    pub source: usize,
+    /// 目标列名
+    pub target: String,
+}
+
+/// 被拒绝的行
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedRow {
+    /// 文件中的行号（从 1 开始）
+    pub row: u64,
+    pub values: Vec<Value>,
+    pub error: String,
+}
+
+/// 导入进度
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportProgress {
+    pub import_id: String,
+    /// 已读取的行数
+    pub rows: u64,
+    pub inserted: u64,
+    pub rejected: u64,
+    pub elapsed_ms: u128,
+}
+
+/// 在事务中分批插入数据
+///
+/// 每批先整体插入，失败时回滚到保存点并逐行重试，记录被拒绝的行；
+/// 被拒绝的行超过上限时返回错误，由调用方回滚整个事务
+pub struct BatchInserter<'a> {
+    tx: &'a mut dyn Transaction,
+    db_type: DatabaseType,
+    table_ref: String,
+    target_columns: Vec<String>,
+    source_columns: Vec<usize>,
+    /// 表头的列数（无表头时为 0，不检查列数）
+    width: usize,
+    batch_size: usize,
+    max_errors: usize,
+    /// 待插入的 (行号, 源数据)
+    pending: Vec<(u64, Vec<Value>)>,
+    /// 事务是否支持保存点（不支持时无法跳过被拒绝的行）
+    savepoints: bool,
+    pub inserted: u64,
+    pub rejected: Vec<RejectedRow>,
+}
+
+impl<'a> BatchInserter<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        tx: &'a mut dyn Transaction,
+        db_type: DatabaseType,
+        table_ref: String,
+        mappings: &[ColumnMapping],
+        width: usize,
+        batch_size: usize,
+        max_errors: usize,
+    ) -> DbResult<Self> {
+        if mappings.is_empty() {
+            return Err(DbError::Other(Msg::ImportNoColumns.into()));
+        }
+        if let Some(mapping) = mappings.iter().find(|m| width > 0 && m.source >= width) {
+            return Err(DbError::Other(Msg::ImportColumnMissing.with(&[&(mapping.source + 1)])));
+        }
+
+        // SQL Server 的 VALUES 最多 1000 行
+        let max_batch = if db_type == DatabaseType::SqlServer { 1000 } else { usize::MAX };
+        Ok(Self {
+            tx,
+            db_type,
+            table_ref,
+            target_columns: mappings.iter().map(|m| m.target.clone()).collect(),
+            source_columns: mappings.iter().map(|m| m.source).collect(),
+            width,
+            batch_size: batch_size.clamp(1, max_batch),
+            max_errors,
+            pending: Vec::new(),
+            savepoints: true,
+            inserted: 0,
+            rejected: Vec::new(),
+        })
+    }
+
+    /// 加入一行，攒够一批时插入
+    pub async fn push(&mut self, row: u64, values: Vec<Value>) -> DbResult<()> {
+        let extra = values.iter().skip(self.width).any(|v| !v.is_null());
+        if self.width > 0 && extra {
+            let error = Msg::RowColumnCountMismatch.with(&[&row, &values.len(), &self.width]);
+            return self.reject(row, values, error);
+        }
+
+        self.pending.push((row, values));
+        if self.pending.len() >= self.batch_size {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// 插入剩余的行
+    pub async fn flush(&mut self) -> DbResult<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.pending);
+        let rows: Vec<Vec<Value>> = batch.iter().map(|(_, values)| self.mapped(values)).collect();
+
+        let sql = sql::insert_sql(&self.db_type, &self.table_ref, &self.target_columns, &rows);
+        if self.try_execute(&sql).await?.is_ok() {
+            self.inserted += rows.len() as u64;
+            return Ok(());
+        }
+
+        // 整批失败，逐行重试找出被拒绝的行
+        for ((row, values), mapped) in batch.into_iter().zip(rows) {
+            let sql = sql::insert_sql(&self.db_type, &self.table_ref, &self.target_columns, &[mapped]);
+            match self.try_execute(&sql).await? {
+                Ok(()) => self.inserted += 1,
+                Err(e) => self.reject(row, values, e.to_string())?,
+            }
+        }
+        Ok(())
+    }
+
+    /// 在保存点内执行语句；语句失败时回滚到保存点并返回语句错误，保存点操作失败时返回外层错误
+    ///
+    /// 事务不支持保存点（例如 DuckDB）时，失败的语句会使整个事务失效，语句错误作为外层错误返回
+    async fn try_execute(&mut self, sql: &str) -> DbResult<DbResult<()>> {
+        if self.savepoints {
+            match self.tx.savepoint(BATCH_SAVEPOINT).await {
+                Ok(()) => {}
+                Err(e) if e.code() == ErrorCode::Unsupported => self.savepoints = false,
+                Err(e) => return Err(e),
+            }
+        }
+        if !self.savepoints {
+            return self.tx.execute(sql).await.map(|_| Ok(()));
+        }
+
+        let result = self.tx.execute(sql).await.map(|_| ());
+        if result.is_err() {
+            self.tx.rollback_to_savepoint(BATCH_SAVEPOINT).await?;
+        }
+        self.tx.release_savepoint(BATCH_SAVEPOINT).await?;
+        Ok(result)
+    }
+
+    /// 按映射取出目标列的值
+    fn mapped(&self, values: &[Value]) -> Vec<Value> {
+        self.source_columns
+            .iter()
+            .map(|idx| values.get(*idx).cloned().unwrap_or(Value::Null))
+            .collect()
+    }
+
+    fn reject(&mut self, row: u64, values: Vec<Value>, error: String) -> DbResult<()> {
+        self.rejected.push(RejectedRow { row, values, error });
+        if self.rejected.len() > self.max_errors {
+            return Err(DbError::Other(Msg::ImportTooManyErrors.with(&[&self.max_errors])));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_column_type_of_text() {
+        assert_eq!(ColumnType::of_text("42"), ColumnType::Integer);
+        assert_eq!(ColumnType::of_text("-7"), ColumnType::Integer);
+        assert_eq!(ColumnType::of_text("007"), ColumnType::Text);
+        assert_eq!(ColumnType::of_text("12345678901234567890"), ColumnType::Text);
+        assert_eq!(ColumnType::of_text("3.14"), ColumnType::Float);
+        assert_eq!(ColumnType::of_text("1e3"), ColumnType::Float);
+        assert_eq!(ColumnType::of_text("inf"), ColumnType::Text);
+        assert_eq!(ColumnType::of_text("TRUE"), ColumnType::Boolean);
+        assert_eq!(ColumnType::of_text("2024-02-29"), ColumnType::Date);
+        assert_eq!(ColumnType::of_text("2024-02-29 08:00:00"), ColumnType::DateTime);
+        assert_eq!(ColumnType::of_text("2024-02-30"), ColumnType::Text);
+    }
+
+    #[test]
+    fn test_infer_columns() {
+        let header = vec!["id".to_string(), "price".to_string()];
+        let rows = vec![
+            vec![json!("1"), json!("10"), json!("2024-01-01")],
+            vec![json!("2"), json!("9.5"), json!("2024-01-02 10:00:00")],
+            vec![json!("3"), Value::Null],
+        ];
+        let columns = infer_columns(&header, &rows);
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns[0].column_type, ColumnType::Integer);
+        assert!(!columns[0].nullable);
+        assert_eq!(columns[1].column_type, ColumnType::Float);
+        assert!(columns[1].nullable);
+        assert_eq!(columns[2].name, "column_3");
+        assert_eq!(columns[2].column_type, ColumnType::DateTime);
+        assert_eq!(columns[2].max_length, 19);
+    }
+
+    #[test]
+    fn test_preview_csv() {
+        let path = std::env::temp_dir().join(format!("datasmith_import_{}.csv", std::process::id()));
+        std::fs::write(&path, "id,name\n1,\"a,b\"\n2,\n3,\"multi\nline\"\n4,x\n").unwrap();
+
+        let options = ReadOptions {
+            sheet: None,
+            has_header: true,
+            csv: CsvDialect::default(),
+        };
+        let preview = preview_file(path.to_str().unwrap(), &options, 10).unwrap();
+        let mut source = RowSource::open(path.to_str().unwrap(), &options).unwrap();
+        let lines: Vec<u64> = source.by_ref().map(|item| item.unwrap().0).collect();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(preview.format, ImportFileFormat::Csv);
+        assert_eq!(preview.columns[0].name, "id");
+        assert_eq!(preview.columns[0].column_type, ColumnType::Integer);
+        assert_eq!(preview.rows.len(), 4);
+        assert_eq!(preview.rows[0], vec![json!("1"), json!("a,b")]);
+        assert_eq!(preview.rows[1], vec![json!("2"), Value::Null]);
+        assert_eq!(lines, vec![2, 3, 4, 6]);
+    }
+
+    #[test]
+    fn test_preview_csv_dialect() {
+        let path = std::env::temp_dir().join(format!("datasmith_import_gbk_{}.csv", std::process::id()));
+        let (content, _, _) = encoding_rs::GBK.encode("编号;名称\n1;'张;三'\n2;\\N\n3;\n");
+        std::fs::write(&path, &content).unwrap();
+
+        let options = ReadOptions {
+            sheet: None,
+            has_header: true,
+            csv: CsvDialect {
+                delimiter: ';',
+                quote: '\'',
+                null_value: "\\N".to_string(),
+                encoding: "gbk".to_string(),
+                ..Default::default()
+            },
+        };
+        let preview = preview_file(path.to_str().unwrap(), &options, 10).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(preview.columns[1].name, "名称");
+        assert_eq!(preview.rows[0], vec![json!("1"), json!("张;三")]);
+        assert_eq!(preview.rows[1], vec![json!("2"), Value::Null]);
+        assert_eq!(preview.rows[2], vec![json!("3"), json!("")]);
+    }
+}
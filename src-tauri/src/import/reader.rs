@@ -0,0 +1,237 @@
+use calamine::{open_workbook_auto, Data, Range, Reader};
+use serde_json::Value;
+use std::fs::File;
+use std::io::Read;
+
+use super::{ImportFileFormat, ReadOptions};
+use crate::database::{DbError, DbResult};
+use crate::export::csv::CsvDialect;
+use crate::export::value_to_text;
+use crate::utils::encoding::{find_encoding, is_utf8, DecodingReader};
+use crate::utils::i18n::Msg;
+
+/// 按行读取导入文件
+///
+/// CSV 逐条读取；电子表格先由 calamine 读出整张工作表，再按行转换
+pub struct RowSource {
+    inner: Inner,
+    /// 表头中的列名（无表头时为空）
+    pub columns: Vec<String>,
+    /// 工作簿中的工作表名称（CSV 为空）
+    pub sheets: Vec<String>,
+    /// 实际读取的工作表
+    pub sheet: Option<String>,
+}
+
+enum Inner {
+    Csv {
+        records: csv::StringRecordsIntoIter<Box<dyn Read + Send>>,
+        /// 表示 NULL 的文本
+        null_value: String,
+    },
+    Sheet {
+        range: Range<Data>,
+        /// 下一行在区域中的位置
+        next: usize,
+        /// 区域第一行在工作表中的行号（从 0 开始）
+        first_row: usize,
+    },
+}
+
+impl RowSource {
+    /// 打开文件并读取表头
+    pub fn open(path: &str, options: &ReadOptions) -> DbResult<Self> {
+        let format = ImportFileFormat::from_path(path)?;
+        let mut source = match format {
+            ImportFileFormat::Csv => Self::open_csv(path, &options.csv)?,
+            _ => Self::open_workbook(path, options.sheet.as_deref())?,
+        };
+
+        let header = if options.has_header {
+            source.next().transpose()?.map(|(_, row)| row)
+        } else {
+            None
+        };
+        source.columns = match header {
+            Some(row) => column_names(&row),
+            None => Vec::new(),
+        };
+        Ok(source)
+    }
+
+    fn open_csv(path: &str, dialect: &CsvDialect) -> DbResult<Self> {
+        let encoding = find_encoding(&dialect.encoding).map_err(DbError::ConfigError)?;
+        let file = File::open(path)
+            .map_err(|e| DbError::Other(format!("{}: {}", Msg::OpenFileFailed.with(&[&path]), e)))?;
+        // UTF-8 的 BOM 由 csv 去掉，其他编码先转换为 UTF-8
+        let input: Box<dyn Read + Send> = if is_utf8(encoding) {
+            Box::new(file)
+        } else {
+            Box::new(DecodingReader::new(file, encoding))
+        };
+
+        let reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(dialect.delimiter_byte()?)
+            .quote(dialect.quote_byte()?)
+            .from_reader(input);
+
+        Ok(Self {
+            inner: Inner::Csv {
+                records: reader.into_records(),
+                null_value: dialect.null_value.clone(),
+            },
+            columns: Vec::new(),
+            sheets: Vec::new(),
+            sheet: None,
+        })
+    }
+
+    fn open_workbook(path: &str, sheet: Option<&str>) -> DbResult<Self> {
+        let mut workbook = open_workbook_auto(path)
+            .map_err(|e| DbError::Other(format!("{}: {}", Msg::OpenFileFailed.with(&[&path]), e)))?;
+
+        let sheets = workbook.sheet_names();
+        let sheet = match sheet.filter(|s| !s.is_empty()) {
+            Some(name) => sheets
+                .iter()
+                .find(|s| s.as_str() == name)
+                .cloned()
+                .ok_or_else(|| DbError::Other(Msg::SheetNotFound.with(&[&name])))?,
+            None => sheets.first().cloned().unwrap_or_default(),
+        };
+
+        let range = workbook
+            .worksheet_range(&sheet)
+            .map_err(|e| DbError::Other(format!("{}: {}", Msg::ReadFileFailed, e)))?;
+        let first_row = range.start().map(|(row, _)| row as usize).unwrap_or(0);
+
+        Ok(Self {
+            inner: Inner::Sheet {
+                range,
+                next: 0,
+                first_row,
+            },
+            columns: Vec::new(),
+            sheets,
+            sheet: Some(sheet),
+        })
+    }
+}
+
+impl Iterator for RowSource {
+    /// (文件中的行号，从 1 开始, 单元格)
+    type Item = DbResult<(u64, Vec<Value>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            Inner::Csv {
+                records,
+                null_value,
+            } => {
+                let record = match records.next()? {
+                    Ok(record) => record,
+                    Err(e) => return Some(Err(DbError::Other(format!("{}: {}", Msg::ReadFileFailed, e)))),
+                };
+                let line = record.position().map(|p| p.line()).unwrap_or_default();
+                let values = record
+                    .iter()
+                    .map(|field| {
+                        // 默认空字段为 NULL；指定了 NULL 文本（如 \N）时空字段是空字符串
+                        if field == null_value.as_str() {
+                            Value::Null
+                        } else {
+                            Value::String(field.to_string())
+                        }
+                    })
+                    .collect();
+                Some(Ok((line, values)))
+            }
+            Inner::Sheet {
+                range,
+                next,
+                first_row,
+            } => {
+                // 跳过整行为空的行
+                while *next < range.height() {
+                    let row = *next;
+                    *next += 1;
+                    let values: Vec<Value> = (0..range.width())
+                        .map(|col| range.get((row, col)).map(cell_value).unwrap_or(Value::Null))
+                        .collect();
+                    if values.iter().any(|v| !v.is_null()) {
+                        return Some(Ok(((*first_row + row + 1) as u64, values)));
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+/// 单元格转换为 JSON 值（日期转为文本，空单元格和错误值为 NULL）
+fn cell_value(cell: &Data) -> Value {
+    match cell {
+        Data::Int(i) => Value::from(*i),
+        // Excel 的数字都是浮点数，整数值按整数处理
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < 9.0e15 => Value::from(*f as i64),
+        Data::Float(f) => serde_json::Number::from_f64(*f)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        Data::String(s) if s.is_empty() => Value::Null,
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => Value::String(s.clone()),
+        Data::Bool(b) => Value::Bool(*b),
+        Data::DateTime(dt) => match dt.as_datetime().filter(|_| dt.is_datetime()) {
+            Some(datetime) if datetime.time() == chrono::NaiveTime::MIN => {
+                Value::String(datetime.format("%Y-%m-%d").to_string())
+            }
+            Some(datetime) => Value::String(datetime.format("%Y-%m-%d %H:%M:%S").to_string()),
+            None => Value::String(dt.to_string()),
+        },
+        Data::Error(_) | Data::Empty => Value::Null,
+    }
+}
+
+/// 由表头生成列名：空列名按位置命名，重名的加序号
+fn column_names(header: &[Value]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(header.len());
+    for (idx, value) in header.iter().enumerate() {
+        let base = match value_to_text(value).trim() {
+            "" => format!("column_{}", idx + 1),
+            name => name.to_string(),
+        };
+        let mut name = base.clone();
+        let mut n = 2;
+        while names.iter().any(|existing| existing.eq_ignore_ascii_case(&name)) {
+            name = format!("{}_{}", base, n);
+            n += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_names() {
+        let header = vec![
+            Value::from("id"),
+            Value::Null,
+            Value::from("ID"),
+            Value::from(" name "),
+        ];
+        assert_eq!(column_names(&header), vec!["id", "column_2", "ID_2", "name"]);
+    }
+
+    #[test]
+    fn test_cell_value() {
+        assert_eq!(cell_value(&Data::Float(3.0)), Value::from(3));
+        assert_eq!(cell_value(&Data::Float(2.5)), Value::from(2.5));
+        assert_eq!(cell_value(&Data::String(String::new())), Value::Null);
+        assert_eq!(cell_value(&Data::Bool(true)), Value::Bool(true));
+    }
+}
@@ -0,0 +1,137 @@
+use serde_json::Value;
+
+use super::{ColumnType, ImportColumn};
+use crate::database::DatabaseType;
+use crate::utils::sql_formatter::SqlFormatter;
+
+/// VARCHAR 的默认长度，样本中更长的文本使用大文本类型
+const VARCHAR_LENGTH: usize = 255;
+
+/// 推断类型对应的列类型
+pub fn column_sql_type(db_type: &DatabaseType, column: &ImportColumn) -> String {
+    let long_text = column.max_length > VARCHAR_LENGTH;
+    let sql_type = match (db_type, column.column_type) {
+        (DatabaseType::SQLite, ColumnType::Integer | ColumnType::Boolean) => "INTEGER",
+        (DatabaseType::SQLite, ColumnType::Float) => "REAL",
+        (DatabaseType::SQLite, _) => "TEXT",
+
+        (DatabaseType::ClickHouse, ColumnType::Integer) => "Nullable(Int64)",
+        (DatabaseType::ClickHouse, ColumnType::Float) => "Nullable(Float64)",
+        (DatabaseType::ClickHouse, ColumnType::Boolean) => "Nullable(Bool)",
+        (DatabaseType::ClickHouse, ColumnType::Date) => "Nullable(Date32)",
+        (DatabaseType::ClickHouse, ColumnType::DateTime) => "Nullable(DateTime64(3))",
+        (DatabaseType::ClickHouse, ColumnType::Text) => "Nullable(String)",
+
+        (_, ColumnType::Integer) => "BIGINT",
+        (DatabaseType::PostgreSQL, ColumnType::Float) => "DOUBLE PRECISION",
+        (DatabaseType::SqlServer, ColumnType::Float) => "FLOAT",
+        (_, ColumnType::Float) => "DOUBLE",
+        (DatabaseType::SqlServer, ColumnType::Boolean) => "BIT",
+        (_, ColumnType::Boolean) => "BOOLEAN",
+        (_, ColumnType::Date) => "DATE",
+        (DatabaseType::MySQL, ColumnType::DateTime) => "DATETIME",
+        (DatabaseType::SqlServer, ColumnType::DateTime) => "DATETIME2",
+        (_, ColumnType::DateTime) => "TIMESTAMP",
+        (DatabaseType::MySQL, ColumnType::Text) if long_text => "TEXT",
+        (DatabaseType::MySQL, ColumnType::Text) => "VARCHAR(255)",
+        (DatabaseType::SqlServer, ColumnType::Text) if long_text => "NVARCHAR(MAX)",
+        (DatabaseType::SqlServer, ColumnType::Text) => "NVARCHAR(255)",
+        (DatabaseType::DuckDB, ColumnType::Text) => "VARCHAR",
+        (_, ColumnType::Text) => "TEXT",
+    };
+    sql_type.to_string()
+}
+
+/// 按推断的列生成建表语句（所有列允许 NULL，避免样本之外的空值被拒绝）
+pub fn create_table_sql(
+    db_type: &DatabaseType,
+    database: &str,
+    schema: Option<&str>,
+    table: &str,
+    columns: &[ImportColumn],
+) -> String {
+    let definitions: Vec<String> = columns
+        .iter()
+        .map(|column| {
+            format!(
+                "  {} {}",
+                SqlFormatter::quote_identifier(db_type, &column.name),
+                column_sql_type(db_type, column)
+            )
+        })
+        .collect();
+
+    let mut sql = format!(
+        "CREATE TABLE {} (\n{}\n)",
+        SqlFormatter::format_table_ref(db_type, database, table, schema),
+        definitions.join(",\n")
+    );
+    if *db_type == DatabaseType::ClickHouse {
+        sql.push_str(" ENGINE = MergeTree ORDER BY tuple()");
+    }
+    sql
+}
+
+/// 多行 INSERT 语句
+pub fn insert_sql(db_type: &DatabaseType, table_ref: &str, columns: &[String], rows: &[Vec<Value>]) -> String {
+    let quoted_columns: Vec<String> = columns
+        .iter()
+        .map(|column| SqlFormatter::quote_identifier(db_type, column))
+        .collect();
+    let values: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let literals: Vec<String> = row.iter().map(|value| SqlFormatter::format_literal(db_type, value)).collect();
+            format!("({})", literals.join(", "))
+        })
+        .collect();
+
+    format!(
+        "INSERT INTO {} ({}) VALUES\n{}",
+        table_ref,
+        quoted_columns.join(", "),
+        values.join(",\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, column_type: ColumnType) -> ImportColumn {
+        ImportColumn {
+            name: name.to_string(),
+            column_type,
+            nullable: true,
+            max_length: 10,
+        }
+    }
+
+    #[test]
+    fn test_create_table_sql() {
+        let columns = vec![column("id", ColumnType::Integer), column("name", ColumnType::Text)];
+        assert_eq!(
+            create_table_sql(&DatabaseType::PostgreSQL, "app", None, "users", &columns),
+            "CREATE TABLE \"public\".\"users\" (\n  \"id\" BIGINT,\n  \"name\" TEXT\n)"
+        );
+        assert_eq!(
+            create_table_sql(&DatabaseType::MySQL, "app", None, "users", &columns),
+            "CREATE TABLE `app`.`users` (\n  `id` BIGINT,\n  `name` VARCHAR(255)\n)"
+        );
+    }
+
+    #[test]
+    fn test_insert_sql() {
+        let rows = vec![
+            vec![Value::from(1), Value::from("it's \\ ok")],
+            vec![Value::from(2), Value::Null],
+        ];
+        let columns = vec!["id".to_string(), "name".to_string()];
+        assert_eq!(
+            insert_sql(&DatabaseType::MySQL, "`t`", &columns, &rows),
+            "INSERT INTO `t` (`id`, `name`) VALUES\n(1, 'it''s \\\\ ok'),\n(2, NULL)"
+        );
+        assert_eq!(SqlFormatter::format_literal(&DatabaseType::SqlServer, &Value::from("名")), "N'名'");
+        assert_eq!(SqlFormatter::format_literal(&DatabaseType::SQLite, &Value::Bool(true)), "1");
+    }
+}
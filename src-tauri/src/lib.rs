@@ -1,13 +1,15 @@
 pub mod commands;
 pub mod database;
+pub mod export;
+pub mod import;
+pub mod dump;
+pub mod transfer;
 pub mod models;
 pub mod utils;
 
 use database::ConnectionManager;
-use std::sync::Arc;
-use tokio::sync::Mutex;
 
 /// 应用状态
 pub struct AppState {
-    pub connection_manager: Arc<Mutex<ConnectionManager>>,
+    pub connection_manager: ConnectionManager,
 }
@@ -3,17 +3,20 @@
 
 mod commands;
 mod database;
+mod export;
+mod import;
+mod dump;
+mod transfer;
 mod models;
 mod utils;
 
 use database::ConnectionManager;
-use std::sync::Arc;
-use tauri::Manager;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
 
 /// 应用状态
 pub struct AppState {
-    pub connection_manager: Arc<Mutex<ConnectionManager>>,
+    pub connection_manager: ConnectionManager,
 }
 
 fn main() {
@@ -23,7 +26,7 @@ fn main() {
     }
 
     // 初始化连接管理器
-    let connection_manager = Arc::new(Mutex::new(ConnectionManager::new()));
+    let connection_manager = ConnectionManager::new();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::default().build())
@@ -39,6 +42,11 @@ fn main() {
             commands::connection::delete_connection,
             commands::connection::create_connection,
             commands::connection::disconnect_database,
+            commands::connection::get_server_info,
+            commands::connection::get_capabilities,
+            commands::connection::get_connection_status,
+            commands::connection::get_connection_statuses,
+            commands::connection::reconnect_database,
             commands::query::execute_query,
             commands::query::execute_query_batch,
             commands::query::execute_sql_script,
@@ -56,6 +64,8 @@ fn main() {
             commands::metadata::get_functions,
             commands::metadata::get_triggers,
             commands::metadata::get_events,
+            commands::metadata::get_sequences,
+            commands::metadata::get_placement_policies,
             commands::metadata::drop_view,
             commands::metadata::get_view_definition,
             commands::metadata::drop_procedure,
@@ -70,7 +80,18 @@ fn main() {
             commands::export::export_to_csv,
             commands::export::export_to_json,
             commands::export::export_to_sql,
+            commands::export::export_result_to_file,
+            commands::export::export_to_xlsx,
             commands::export::export_table_ddl,
+            commands::export::export_query_to_file,
+            commands::export::cancel_export,
+            commands::export::copy_selection,
+            commands::import::preview_import_file,
+            commands::import::generate_import_ddl,
+            commands::import::import_file_to_table,
+            commands::dump::dump_database,
+            commands::dump::restore_database,
+            commands::transfer::transfer_table,
             commands::utils::read_file,
             commands::utils::write_file,
             commands::redis::execute_redis_command,
@@ -84,8 +105,58 @@ fn main() {
             commands::redis::set_redis_hash_value,
             commands::redis::set_redis_key_ttl,
             commands::redis::rename_redis_key,
+            commands::elasticsearch::get_elasticsearch_cluster_health,
+            commands::security::get_master_password_status,
+            commands::security::enable_master_password,
+            commands::security::change_master_password,
+            commands::security::disable_master_password,
+            commands::security::unlock_master_password,
+            commands::security::lock_master_password,
+            commands::security::set_master_password_auto_lock,
+            commands::security::get_undecryptable_connections,
+            commands::security::migrate_encrypted_credentials,
+            commands::security::rotate_encryption_key,
+            commands::security::recover_connection_credentials,
+            commands::settings::get_locale,
+            commands::settings::set_locale,
         ])
         .setup(|app| {
+            // 加载界面语言等应用设置
+            if let Err(e) = commands::settings::load_app_settings(app.handle()) {
+                eprintln!("警告: 加载应用设置失败: {}", e);
+            }
+
+            // 加载主密码配置
+            if let Err(e) = commands::security::load_security_settings(app.handle()) {
+                eprintln!("警告: 加载安全设置失败: {}", e);
+            }
+
+            // 主密码空闲自动锁定
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(15));
+                loop {
+                    interval.tick().await;
+                    if utils::crypto::lock_if_idle() {
+                        let _ = handle.emit("master-password-locked", ());
+                    }
+                }
+            });
+
+            // 连接健康检查与自动重连
+            let handle = app.handle().clone();
+            let connection_manager = app.state::<AppState>().connection_manager.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval =
+                    tokio::time::interval(Duration::from_secs(database::HEALTH_CHECK_INTERVAL_SECS));
+                loop {
+                    interval.tick().await;
+                    for health in connection_manager.check_health().await {
+                        let _ = handle.emit(commands::connection::CONNECTION_STATUS_EVENT, &health);
+                    }
+                }
+            });
+
             #[cfg(debug_assertions)]
             {
                 let window = app.get_webview_window("main").unwrap();
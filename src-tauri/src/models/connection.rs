@@ -1,3 +1,5 @@
+use crate::database::{ErrorCode, ServerInfo};
+use crate::utils::crypto::CredentialStorage;
 use serde::{Deserialize, Serialize};
 
 /// 连接配置（用于存储和传输）
@@ -9,12 +11,33 @@ pub struct StoredConnection {
     pub host: String,
     pub port: u16,
     pub username: String,
-    /// 加密后的密码
+    /// 加密后的密码（使用系统密钥环时为空）
     pub encrypted_password: Option<String>,
+    /// 加密后的 SSH 私钥口令（使用系统密钥环时为空）
+    #[serde(default)]
+    pub encrypted_ssh_passphrase: Option<String>,
+    /// 凭据存储方式
+    #[serde(default)]
+    pub credential_storage: CredentialStorage,
     pub database: Option<String>,
     pub ssl: bool,
+    /// 启用 SSL 时信任服务器证书
+    #[serde(default)]
+    pub trust_server_certificate: bool,
     pub connection_timeout: u64,
     pub pool_size: u32,
+    /// 会话初始化 SQL（每个新会话建立后执行）
+    #[serde(default)]
+    pub init_sql: Option<String>,
+    /// 会话字符集
+    #[serde(default)]
+    pub charset: Option<String>,
+    /// 会话时区
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    /// 应用名称
+    #[serde(default)]
+    pub application_name: Option<String>,
     pub group: Option<String>,
     pub color: Option<String>,
     pub tags: Vec<String>,
@@ -27,10 +50,38 @@ pub struct StoredConnection {
 pub struct ConnectionTestResult {
     pub success: bool,
     pub message: String,
+    /// 失败时的错误码
+    #[serde(default)]
+    pub error_code: Option<ErrorCode>,
     pub version: Option<String>,
+    /// 服务器信息（产品分支、当前用户、时区、特性）
+    pub server_info: Option<ServerInfo>,
+    /// 读取服务器信息失败时的提示（连接本身已成功）
+    #[serde(default)]
+    pub server_info_error: Option<String>,
     pub ping_time_ms: u128,
 }
 
+/// 无法解密凭据的连接（需要用户重新输入）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndecryptableConnection {
+    pub id: String,
+    pub name: String,
+    /// 密码无法解密
+    pub password: bool,
+    /// SSH 私钥口令无法解密
+    pub ssh_passphrase: bool,
+}
+
+/// 凭据密文迁移结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialMigrationResult {
+    /// 已重新加密的凭据数量
+    pub reencrypted: usize,
+    /// 仍无法解密的连接
+    pub undecryptable: Vec<UndecryptableConnection>,
+}
+
 /// 连接状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -41,3 +92,21 @@ pub enum ConnectionStatus {
     Error,
 }
 
+/// 连接健康状态（通过 connection-status-changed 事件推送给前端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionHealth {
+    pub connection_id: String,
+    pub status: ConnectionStatus,
+    /// 最近一次错误信息
+    pub error: Option<String>,
+    /// 最近一次错误的错误码
+    #[serde(default)]
+    pub error_code: Option<ErrorCode>,
+    /// 连续重连失败次数
+    pub retry_attempts: u32,
+    /// 距下次自动重连的秒数
+    pub next_retry_secs: Option<u64>,
+    /// 读取服务器信息失败的原因（连接仍可用）
+    #[serde(default)]
+    pub server_info_error: Option<String>,
+}
@@ -0,0 +1,401 @@
+//! 跨连接数据传输
+//!
+//! 从一个连接读取表数据并分批写入另一个连接：列类型按方言映射，
+//! 目标表不存在时自动创建，支持追加、清空后写入和按键合并
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::database::{ColumnInfo, DatabaseType};
+use crate::export::sql::is_binary_type;
+use crate::utils::sql_formatter::SqlFormatter;
+
+/// 写入目标表的方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferMode {
+    /// 直接追加
+    #[default]
+    Append,
+    /// 先清空目标表
+    Truncate,
+    /// 按冲突键更新已存在的行
+    Upsert,
+}
+
+/// 传输选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransferOptions {
+    pub mode: TransferMode,
+    /// 目标表不存在时按来源表结构创建
+    pub create_table: bool,
+    /// 每条 INSERT 包含的行数
+    pub batch_size: usize,
+    /// Upsert 的冲突键列（为空时使用目标表主键）
+    pub conflict_columns: Vec<String>,
+}
+
+impl Default for TransferOptions {
+    fn default() -> Self {
+        Self {
+            mode: TransferMode::Append,
+            create_table: true,
+            batch_size: 500,
+            conflict_columns: Vec::new(),
+        }
+    }
+}
+
+/// 传输进度
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferProgress {
+    pub transfer_id: String,
+    /// 已写入的行数
+    pub rows: u64,
+    /// 来源表的总行数（统计失败时为空）
+    pub total: Option<u64>,
+    pub elapsed_ms: u128,
+}
+
+/// 与方言无关的列类型，用于在不同数据库之间转换类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlType {
+    Boolean,
+    SmallInt,
+    Integer,
+    BigInt,
+    /// 精度和小数位数
+    Decimal(Option<(i64, i64)>),
+    Real,
+    Double,
+    Char(Option<i64>),
+    /// 长度为空表示不限长
+    Varchar(Option<i64>),
+    Text,
+    Date,
+    Time,
+    DateTime,
+    DateTimeTz,
+    Binary,
+    Json,
+    Uuid,
+}
+
+impl SqlType {
+    /// 解析来源数据库的列类型，无法识别的类型按文本处理
+    pub fn parse(db_type: &DatabaseType, column: &ColumnInfo) -> Self {
+        let mut data_type = column.data_type.trim().to_lowercase();
+        // ClickHouse 的包装类型
+        for wrapper in ["nullable(", "lowcardinality("] {
+            while let Some(inner) = data_type.strip_prefix(wrapper).and_then(|t| t.strip_suffix(')')) {
+                data_type = inner.to_string();
+            }
+        }
+
+        // 去掉括号中的参数和 unsigned 等修饰，例如 timestamp(6) without time zone
+        let args: Vec<i64> = data_type
+            .split_once('(')
+            .and_then(|(_, rest)| rest.split_once(')'))
+            .map(|(args, _)| args.split(',').filter_map(|a| a.trim().parse().ok()).collect())
+            .unwrap_or_default();
+        let mut base = String::new();
+        let mut depth = 0;
+        for c in data_type.chars() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ if depth == 0 => base.push(c),
+                _ => {}
+            }
+        }
+        let base = base
+            .split_whitespace()
+            .filter(|w| !matches!(*w, "unsigned" | "signed" | "zerofill"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let length = args.first().copied().or(column.character_maximum_length).filter(|l| *l != 0);
+        let precision = match (args.as_slice(), column.numeric_precision, column.numeric_scale) {
+            ([p, s, ..], _, _) => Some((*p, *s)),
+            ([p], _, _) => Some((*p, 0)),
+            (_, Some(p), Some(s)) if p > 0 => Some((p, s)),
+            _ => None,
+        };
+
+        if *db_type == DatabaseType::ClickHouse {
+            match base.as_str() {
+                "int8" | "uint8" | "int16" => return Self::SmallInt,
+                "uint16" | "int32" => return Self::Integer,
+                "uint32" | "int64" => return Self::BigInt,
+                "uint64" | "int128" | "uint128" | "int256" | "uint256" => return Self::Decimal(Some((38, 0))),
+                "float32" => return Self::Real,
+                "float64" => return Self::Double,
+                "string" | "fixedstring" | "enum8" | "enum16" => return Self::Text,
+                "date" | "date32" => return Self::Date,
+                "datetime" | "datetime64" => return Self::DateTime,
+                _ => {}
+            }
+        }
+
+        match base.as_str() {
+            "bool" | "boolean" | "bit" => Self::Boolean,
+            "tinyint" | "smallint" | "int2" | "smallserial" | "utinyint" => Self::SmallInt,
+            "int" | "integer" | "int4" | "mediumint" | "serial" | "usmallint" => Self::Integer,
+            "bigint" | "int8" | "bigserial" | "uinteger" => Self::BigInt,
+            "ubigint" | "hugeint" | "uhugeint" => Self::Decimal(Some((38, 0))),
+            "decimal" | "numeric" | "number" | "dec" => Self::Decimal(precision),
+            "money" | "smallmoney" => Self::Decimal(Some((19, 4))),
+            "real" | "float4" => Self::Real,
+            // MySQL 的 FLOAT 为单精度，其他数据库的 FLOAT 默认为双精度
+            "float" if *db_type == DatabaseType::MySQL => Self::Real,
+            "float" | "double" | "double precision" | "float8" => Self::Double,
+            "char" | "character" | "nchar" | "bpchar" => Self::Char(length),
+            "varchar" | "character varying" | "nvarchar" | "varchar2" | "nvarchar2" => {
+                // SQL Server 的 MAX 长度为 -1
+                Self::Varchar(length.filter(|l| *l > 0))
+            }
+            "date" => Self::Date,
+            "time" | "time without time zone" => Self::Time,
+            "datetime" | "datetime2" | "smalldatetime" | "timestamp" | "timestamp without time zone" => {
+                Self::DateTime
+            }
+            "timestamptz" | "timestamp with time zone" | "datetimeoffset" => Self::DateTimeTz,
+            "json" | "jsonb" => Self::Json,
+            "uuid" | "uniqueidentifier" => Self::Uuid,
+            _ if is_binary_type(&base) => Self::Binary,
+            _ => Self::Text,
+        }
+    }
+
+    /// 目标数据库中的类型名称
+    pub fn render(self, db_type: &DatabaseType) -> String {
+        use DatabaseType::*;
+
+        // 各数据库 DECIMAL 的最大精度
+        let decimal = |max: i64, default: &str| match self {
+            Self::Decimal(Some((p, s))) => {
+                let p = p.clamp(1, max);
+                format!("DECIMAL({}, {})", p, s.clamp(0, p))
+            }
+            _ => default.to_string(),
+        };
+
+        match (db_type, self) {
+            (SQLite, Self::Boolean | Self::SmallInt | Self::Integer | Self::BigInt) => "INTEGER".into(),
+            (SQLite, Self::Decimal(_)) => "NUMERIC".into(),
+            (SQLite, Self::Real | Self::Double) => "REAL".into(),
+            (SQLite, Self::Binary) => "BLOB".into(),
+            (SQLite, _) => "TEXT".into(),
+
+            (ClickHouse, Self::Boolean) => "Bool".into(),
+            (ClickHouse, Self::SmallInt) => "Int16".into(),
+            (ClickHouse, Self::Integer) => "Int32".into(),
+            (ClickHouse, Self::BigInt) => "Int64".into(),
+            (ClickHouse, Self::Decimal(Some((p, s)))) => format!("Decimal({}, {})", p.clamp(1, 76), s.clamp(0, p)),
+            (ClickHouse, Self::Decimal(None)) => "Decimal(38, 10)".into(),
+            (ClickHouse, Self::Real) => "Float32".into(),
+            (ClickHouse, Self::Double) => "Float64".into(),
+            (ClickHouse, Self::Date) => "Date32".into(),
+            (ClickHouse, Self::DateTime | Self::DateTimeTz) => "DateTime64(6)".into(),
+            (ClickHouse, Self::Uuid) => "UUID".into(),
+            (ClickHouse, _) => "String".into(),
+
+            (SqlServer, Self::Boolean) => "BIT".into(),
+            (SqlServer, Self::Double) => "FLOAT".into(),
+            (SqlServer, Self::Decimal(_)) => decimal(38, "DECIMAL(38, 10)"),
+            (SqlServer, Self::Char(Some(n))) if n <= 4000 => format!("NCHAR({})", n),
+            (SqlServer, Self::Varchar(Some(n))) if n <= 4000 => format!("NVARCHAR({})", n),
+            (SqlServer, Self::Char(_) | Self::Varchar(_) | Self::Text | Self::Json) => "NVARCHAR(MAX)".into(),
+            (SqlServer, Self::DateTime) => "DATETIME2".into(),
+            (SqlServer, Self::DateTimeTz) => "DATETIMEOFFSET".into(),
+            (SqlServer, Self::Binary) => "VARBINARY(MAX)".into(),
+            (SqlServer, Self::Uuid) => "UNIQUEIDENTIFIER".into(),
+
+            (MySQL, Self::Boolean) => "TINYINT(1)".into(),
+            (MySQL, Self::Decimal(_)) => decimal(65, "DECIMAL(38, 10)"),
+            (MySQL, Self::Real) => "FLOAT".into(),
+            (MySQL, Self::Char(Some(n))) if n <= 255 => format!("CHAR({})", n),
+            (MySQL, Self::Varchar(Some(n))) if n <= 16383 => format!("VARCHAR({})", n),
+            (MySQL, Self::Varchar(None)) => "VARCHAR(255)".into(),
+            (MySQL, Self::Char(_) | Self::Varchar(_) | Self::Text) => "LONGTEXT".into(),
+            (MySQL, Self::DateTime | Self::DateTimeTz) => "DATETIME(6)".into(),
+            (MySQL, Self::Binary) => "LONGBLOB".into(),
+            (MySQL, Self::Uuid) => "CHAR(36)".into(),
+
+            (PostgreSQL, Self::Decimal(_)) => decimal(1000, "NUMERIC"),
+            (PostgreSQL, Self::Double) => "DOUBLE PRECISION".into(),
+            (PostgreSQL, Self::DateTimeTz) => "TIMESTAMPTZ".into(),
+            (PostgreSQL, Self::Binary) => "BYTEA".into(),
+            (PostgreSQL, Self::Json) => "JSONB".into(),
+
+            (DuckDB, Self::Decimal(_)) => decimal(38, "DECIMAL(38, 10)"),
+            (DuckDB, Self::Char(_) | Self::Varchar(_) | Self::Text) => "VARCHAR".into(),
+            (DuckDB, Self::DateTimeTz) => "TIMESTAMPTZ".into(),
+            (DuckDB, Self::Binary) => "BLOB".into(),
+
+            // 其余按标准 SQL
+            (_, Self::Boolean) => "BOOLEAN".into(),
+            (_, Self::SmallInt) => "SMALLINT".into(),
+            (_, Self::Integer) => "INTEGER".into(),
+            (_, Self::BigInt) => "BIGINT".into(),
+            (_, Self::Decimal(_)) => decimal(38, "DECIMAL(38, 10)"),
+            (_, Self::Real) => "REAL".into(),
+            (_, Self::Double) => "DOUBLE".into(),
+            (_, Self::Char(Some(n))) => format!("CHAR({})", n),
+            (_, Self::Varchar(Some(n))) => format!("VARCHAR({})", n),
+            (_, Self::Char(None) | Self::Varchar(None) | Self::Text) => "TEXT".into(),
+            (_, Self::Date) => "DATE".into(),
+            (_, Self::Time) => "TIME".into(),
+            (_, Self::DateTime) => "TIMESTAMP".into(),
+            (_, Self::DateTimeTz) => "TIMESTAMP WITH TIME ZONE".into(),
+            (_, Self::Binary) => "BLOB".into(),
+            (_, Self::Json) => "JSON".into(),
+            (_, Self::Uuid) => "UUID".into(),
+        }
+    }
+
+    /// 按目标列类型调整来源值，例如 MySQL 的 TINYINT(1) 写入 PostgreSQL 的 BOOLEAN
+    pub fn coerce(self, value: Value) -> Value {
+        match (self, &value) {
+            (Self::Boolean, Value::Number(n)) => Value::Bool(n.as_f64().is_some_and(|n| n != 0.0)),
+            (Self::Boolean, Value::String(s)) => match s.trim().to_lowercase().as_str() {
+                "1" | "t" | "true" | "y" | "yes" => Value::Bool(true),
+                "0" | "f" | "false" | "n" | "no" => Value::Bool(false),
+                _ => value,
+            },
+            _ => value,
+        }
+    }
+}
+
+/// 按来源表结构生成目标数据库的建表语句
+///
+/// 保留 NOT NULL 和主键，自增、默认值和其他约束不复制
+pub fn create_table_sql(
+    source: &DatabaseType,
+    target: &DatabaseType,
+    table_ref: &str,
+    columns: &[ColumnInfo],
+) -> String {
+    let quote = |name: &str| SqlFormatter::quote_identifier(target, name);
+    let primary_keys: Vec<String> = columns.iter().filter(|c| c.is_primary_key).map(|c| quote(&c.name)).collect();
+
+    let mut definitions: Vec<String> = columns
+        .iter()
+        .map(|column| {
+            let sql_type = SqlType::parse(source, column).render(target);
+            match target {
+                // ClickHouse 的主键列不能为 Nullable
+                DatabaseType::ClickHouse if column.nullable && !column.is_primary_key => {
+                    format!("  {} Nullable({})", quote(&column.name), sql_type)
+                }
+                DatabaseType::ClickHouse => format!("  {} {}", quote(&column.name), sql_type),
+                _ if !column.nullable || column.is_primary_key => {
+                    format!("  {} {} NOT NULL", quote(&column.name), sql_type)
+                }
+                _ => format!("  {} {}", quote(&column.name), sql_type),
+            }
+        })
+        .collect();
+
+    if !primary_keys.is_empty() && *target != DatabaseType::ClickHouse {
+        definitions.push(format!("  PRIMARY KEY ({})", primary_keys.join(", ")));
+    }
+
+    let mut sql = format!("CREATE TABLE {} (\n{}\n)", table_ref, definitions.join(",\n"));
+    if *target == DatabaseType::ClickHouse {
+        let order_by = if primary_keys.is_empty() {
+            "tuple()".to_string()
+        } else {
+            format!("({})", primary_keys.join(", "))
+        };
+        sql.push_str(&format!(" ENGINE = MergeTree ORDER BY {}", order_by));
+    }
+    sql
+}
+
+/// 清空目标表的语句
+pub fn truncate_sql(db_type: &DatabaseType, table_ref: &str) -> String {
+    match db_type {
+        DatabaseType::SQLite => format!("DELETE FROM {}", table_ref),
+        _ => format!("TRUNCATE TABLE {}", table_ref),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn column(name: &str, data_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            is_auto_increment: false,
+            comment: None,
+            character_maximum_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+        }
+    }
+
+    #[test]
+    fn test_map_column_type() {
+        let map = |source: DatabaseType, data_type: &str, target: DatabaseType| {
+            SqlType::parse(&source, &column("c", data_type)).render(&target)
+        };
+        assert_eq!(map(DatabaseType::MySQL, "int unsigned", DatabaseType::PostgreSQL), "INTEGER");
+        assert_eq!(map(DatabaseType::MySQL, "float", DatabaseType::PostgreSQL), "REAL");
+        assert_eq!(map(DatabaseType::MySQL, "longblob", DatabaseType::PostgreSQL), "BYTEA");
+        assert_eq!(map(DatabaseType::MySQL, "datetime", DatabaseType::SqlServer), "DATETIME2");
+        assert_eq!(
+            map(DatabaseType::PostgreSQL, "character varying(40)", DatabaseType::MySQL),
+            "VARCHAR(40)"
+        );
+        assert_eq!(
+            map(DatabaseType::PostgreSQL, "timestamp(6) with time zone", DatabaseType::MySQL),
+            "DATETIME(6)"
+        );
+        assert_eq!(map(DatabaseType::PostgreSQL, "numeric(12,2)", DatabaseType::SQLite), "NUMERIC");
+        assert_eq!(map(DatabaseType::PostgreSQL, "numeric(12,2)", DatabaseType::DuckDB), "DECIMAL(12, 2)");
+        assert_eq!(map(DatabaseType::PostgreSQL, "jsonb", DatabaseType::SqlServer), "NVARCHAR(MAX)");
+        assert_eq!(map(DatabaseType::SqlServer, "float", DatabaseType::MySQL), "DOUBLE");
+        assert_eq!(map(DatabaseType::ClickHouse, "Nullable(UInt8)", DatabaseType::PostgreSQL), "SMALLINT");
+        assert_eq!(map(DatabaseType::ClickHouse, "LowCardinality(String)", DatabaseType::MySQL), "LONGTEXT");
+        assert_eq!(map(DatabaseType::SQLite, "geometry", DatabaseType::ClickHouse), "String");
+
+        let mut name = column("name", "nvarchar");
+        name.character_maximum_length = Some(-1);
+        assert_eq!(SqlType::parse(&DatabaseType::SqlServer, &name), SqlType::Varchar(None));
+    }
+
+    #[test]
+    fn test_create_table_sql() {
+        let mut id = column("id", "bigint");
+        id.is_primary_key = true;
+        id.nullable = false;
+        let columns = vec![id, column("title", "varchar(100)")];
+
+        assert_eq!(
+            create_table_sql(&DatabaseType::MySQL, &DatabaseType::PostgreSQL, "\"posts\"", &columns),
+            "CREATE TABLE \"posts\" (\n  \"id\" BIGINT NOT NULL,\n  \"title\" VARCHAR(100),\n  PRIMARY KEY (\"id\")\n)"
+        );
+        assert_eq!(
+            create_table_sql(&DatabaseType::MySQL, &DatabaseType::ClickHouse, "`posts`", &columns),
+            "CREATE TABLE `posts` (\n  `id` Int64,\n  `title` Nullable(String)\n) ENGINE = MergeTree ORDER BY (`id`)"
+        );
+        assert_eq!(truncate_sql(&DatabaseType::SQLite, "\"posts\""), "DELETE FROM \"posts\"");
+    }
+
+    #[test]
+    fn test_coerce_boolean() {
+        assert_eq!(SqlType::Boolean.coerce(json!(1)), json!(true));
+        assert_eq!(SqlType::Boolean.coerce(json!("0")), json!(false));
+        assert_eq!(SqlType::Boolean.coerce(json!("maybe")), json!("maybe"));
+        assert_eq!(SqlType::Integer.coerce(json!(1)), json!(1));
+    }
+}
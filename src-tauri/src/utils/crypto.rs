@@ -4,28 +4,133 @@ use aes_gcm::{
 };
 use argon2::password_hash::rand_core::RngCore;
 use base64::{engine::general_purpose, Engine as _};
-use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-static MASTER_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+use super::i18n::Msg;
+
+/// 机器派生密钥（初始化后可通过密钥轮换替换）
+static MASTER_KEY: Mutex<Option<[u8; 32]>> = Mutex::new(None);
+
+/// 主密码模式的运行时状态（密钥仅在解锁期间驻留内存）
+static MASTER_PASSWORD: Mutex<MasterPasswordState> = Mutex::new(MasterPasswordState::new());
+
+/// 用于校验主密码是否正确的固定明文
+const MASTER_PASSWORD_VERIFIER: &str = "DataSmith master password verifier";
+
+/// 当前密文格式版本前缀
+///
+/// v2 格式为 `v2:<密钥指纹>:<Base64(nonce || 密文)>`；
+/// 无前缀的旧格式（v1）为 `Base64(nonce || 密文)`。
+const CIPHERTEXT_V2_PREFIX: &str = "v2:";
+
+/// 未轮换过密钥时派生机器密钥使用的固定 salt（兼容已有密文）
+const LEGACY_MACHINE_KEY_SALT: &str = "DataSmithSaltV1.0.0.0.0.0.0";
+
+/// 默认空闲自动锁定时间（秒）
+pub const DEFAULT_AUTO_LOCK_SECS: u64 = 15 * 60;
+
+/// 主密码配置（持久化到 security.json，不包含密码本身）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MasterPasswordConfig {
+    /// 每次启用时随机生成的 salt（Base64）
+    pub salt: String,
+    /// 使用派生密钥加密的校验串，用于判断主密码是否正确
+    pub verifier: String,
+    /// 空闲自动锁定时间（秒），0 表示不自动锁定
+    pub auto_lock_secs: u64,
+}
+
+/// 主密码状态（返回给前端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MasterPasswordStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub auto_lock_secs: u64,
+}
+
+struct MasterPasswordState {
+    config: Option<MasterPasswordConfig>,
+    key: Option<[u8; 32]>,
+    last_activity: Option<Instant>,
+}
+
+impl MasterPasswordState {
+    const fn new() -> Self {
+        Self {
+            config: None,
+            key: None,
+            last_activity: None,
+        }
+    }
+
+    /// 是否已超过空闲锁定时间
+    fn is_idle_expired(&self) -> bool {
+        match (&self.config, self.last_activity) {
+            (Some(config), Some(last)) if config.auto_lock_secs > 0 => {
+                last.elapsed() >= Duration::from_secs(config.auto_lock_secs)
+            }
+            _ => false,
+        }
+    }
+
+    fn lock(&mut self) {
+        self.key = None;
+        self.last_activity = None;
+    }
+}
+
+fn master_password_state() -> std::sync::MutexGuard<'static, MasterPasswordState> {
+    MASTER_PASSWORD.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn master_key_state() -> std::sync::MutexGuard<'static, Option<[u8; 32]>> {
+    MASTER_KEY.lock().unwrap_or_else(|e| e.into_inner())
+}
 
 /// 初始化主密钥（使用机器ID派生确定性密钥）
 pub fn initialize_master_key() -> Result<(), String> {
-    MASTER_KEY.get_or_init(|| {
+    let mut state = master_key_state();
+    if state.is_none() {
         // 直接使用机器ID派生密钥，确保确定性和跨重启一致性
-        derive_machine_key()
-    });
+        *state = Some(derive_machine_key(None));
+    }
     Ok(())
 }
 
-/// 使用机器ID派生密钥（备用方案，确定性派生）
-fn derive_machine_key() -> [u8; 32] {
-    use sha2::{Digest, Sha256};
-    
+/// 加载持久化的机器密钥 salt（启动时调用），None 表示从未轮换过密钥
+pub fn load_machine_key_salt(salt: Option<&str>) {
+    *master_key_state() = Some(derive_machine_key(salt));
+}
+
+/// 生成新的随机 salt 及其派生的机器密钥（用于密钥轮换）
+pub fn generate_machine_key() -> (String, [u8; 32]) {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let salt = general_purpose::STANDARD.encode(salt);
+    let key = derive_machine_key(Some(&salt));
+    (salt, key)
+}
+
+/// 切换到轮换后的机器密钥
+pub fn activate_machine_key(key: [u8; 32]) {
+    *master_key_state() = Some(key);
+}
+
+/// 使用本机机器ID和 salt 派生密钥（salt 为 None 时使用旧版本的固定 salt）
+pub fn derive_machine_key(salt: Option<&str>) -> [u8; 32] {
     // 获取机器唯一标识
-    let machine_id = get_machine_id();
-    
-    // 使用固定的 salt 确保确定性
-    let salt = "DataSmithSaltV1.0.0.0.0.0.0";
+    derive_machine_key_from_id(&get_machine_id(), salt)
+}
+
+/// 由指定的机器标识和 salt 派生密钥（用于机器标识变化后迁移旧密文）
+///
+/// salt 为 None 时使用旧版本的固定 salt
+pub fn derive_machine_key_from_id(machine_id: &str, salt: Option<&str>) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+
+    let salt = salt.unwrap_or(LEGACY_MACHINE_KEY_SALT);
     
     // 使用 SHA-256 进行确定性密钥派生
     let mut hasher = Sha256::new();
@@ -113,16 +218,204 @@ fn get_machine_id() -> String {
     }
 }
 
-/// 获取主密钥
-fn get_master_key() -> Result<&'static [u8; 32], String> {
-    MASTER_KEY
-        .get()
-        .ok_or_else(|| "主密钥未初始化，请先调用 initialize_master_key()".to_string())
+/// 获取机器派生密钥（用于主密码启用/停用和密钥轮换时迁移已有密文）
+pub fn machine_key() -> Result<[u8; 32], String> {
+    master_key_state()
+        .ok_or_else(|| String::from(Msg::MasterKeyNotInitialized))
+}
+
+/// 获取当前用于加解密的密钥
+///
+/// 启用主密码后使用由主密码派生的密钥，未解锁或空闲超时时返回错误；
+/// 否则使用机器派生密钥。
+pub fn active_key() -> Result<[u8; 32], String> {
+    let mut state = master_password_state();
+    if state.config.is_none() {
+        return machine_key();
+    }
+
+    if state.is_idle_expired() {
+        state.lock();
+    }
+
+    let key = state
+        .key
+        .ok_or_else(|| String::from(Msg::MasterPasswordLocked))?;
+    state.last_activity = Some(Instant::now());
+    Ok(key)
+}
+
+/// 使用 Argon2id 从主密码和 salt 派生 256 位密钥
+pub fn derive_password_key(password: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("{}: {}", Msg::KeyDerivationFailed, e))?;
+    Ok(key)
+}
+
+/// 根据新的主密码生成配置（随机 salt + 校验串），同时返回派生出的密钥
+pub fn create_master_password_config(
+    password: &str,
+    auto_lock_secs: u64,
+) -> Result<(MasterPasswordConfig, [u8; 32]), String> {
+    if password.is_empty() {
+        return Err(String::from(Msg::MasterPasswordEmpty));
+    }
+
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_password_key(password, &salt)?;
+
+    let config = MasterPasswordConfig {
+        salt: general_purpose::STANDARD.encode(salt),
+        verifier: encrypt_with_key(&key, MASTER_PASSWORD_VERIFIER)?,
+        auto_lock_secs,
+    };
+
+    Ok((config, key))
+}
+
+/// 校验主密码，正确时返回派生密钥
+pub fn verify_master_password(
+    config: &MasterPasswordConfig,
+    password: &str,
+) -> Result<[u8; 32], String> {
+    let salt = general_purpose::STANDARD
+        .decode(&config.salt)
+        .map_err(|e| format!("{}: {}", Msg::Base64DecodeFailed, e))?;
+    let key = derive_password_key(password, &salt)?;
+
+    match decrypt_with_key(&key, &config.verifier) {
+        Ok(text) if text == MASTER_PASSWORD_VERIFIER => Ok(key),
+        _ => Err(String::from(Msg::MasterPasswordWrong)),
+    }
+}
+
+/// 加载持久化的主密码配置（启动时调用），加载后处于锁定状态
+pub fn load_master_password_config(config: Option<MasterPasswordConfig>) {
+    let mut state = master_password_state();
+    state.config = config;
+    state.lock();
+}
+
+/// 启用主密码配置并以给定密钥进入解锁状态
+pub fn activate_master_password(config: MasterPasswordConfig, key: [u8; 32]) {
+    let mut state = master_password_state();
+    state.config = Some(config);
+    state.key = Some(key);
+    state.last_activity = Some(Instant::now());
+}
+
+/// 停用主密码，恢复使用机器派生密钥
+pub fn deactivate_master_password() {
+    let mut state = master_password_state();
+    state.config = None;
+    state.lock();
+}
+
+/// 获取当前主密码配置
+pub fn master_password_config() -> Option<MasterPasswordConfig> {
+    master_password_state().config.clone()
+}
+
+/// 使用主密码解锁
+pub fn unlock_master_password(password: &str) -> Result<(), String> {
+    let config = master_password_config().ok_or_else(|| String::from(Msg::MasterPasswordDisabled))?;
+    let key = verify_master_password(&config, password)?;
+    activate_master_password(config, key);
+    Ok(())
+}
+
+/// 立即锁定（清除内存中的派生密钥）
+pub fn lock_master_password() {
+    master_password_state().lock();
+}
+
+/// 空闲超时则锁定，返回本次是否发生了锁定
+pub fn lock_if_idle() -> bool {
+    let mut state = master_password_state();
+    if state.key.is_some() && state.is_idle_expired() {
+        state.lock();
+        true
+    } else {
+        false
+    }
+}
+
+/// 修改空闲自动锁定时间，返回需要持久化的新配置
+pub fn set_auto_lock_secs(auto_lock_secs: u64) -> Result<MasterPasswordConfig, String> {
+    let mut state = master_password_state();
+    let config = state
+        .config
+        .as_mut()
+        .ok_or_else(|| String::from(Msg::MasterPasswordDisabled))?;
+    config.auto_lock_secs = auto_lock_secs;
+    Ok(config.clone())
+}
+
+/// 获取主密码状态
+pub fn master_password_status() -> MasterPasswordStatus {
+    let state = master_password_state();
+    match state.config {
+        Some(ref config) => MasterPasswordStatus {
+            enabled: true,
+            locked: state.key.is_none() || state.is_idle_expired(),
+            auto_lock_secs: config.auto_lock_secs,
+        },
+        None => MasterPasswordStatus {
+            enabled: false,
+            locked: false,
+            auto_lock_secs: 0,
+        },
+    }
+}
+
+/// 将密文从旧密钥转换为新密钥
+pub fn reencrypt_with_key(
+    encrypted: &str,
+    from_key: &[u8; 32],
+    to_key: &[u8; 32],
+) -> Result<String, String> {
+    let plaintext = decrypt_with_key(from_key, encrypted)?;
+    encrypt_with_key(to_key, &plaintext)
 }
 
 /// 加密密码
 pub fn encrypt_password(password: &str) -> Result<String, String> {
-    let key = get_master_key()?;
+    let key = active_key()?;
+    encrypt_with_key(&key, password)
+}
+
+/// 解密密码
+pub fn decrypt_password(encrypted: &str) -> Result<String, String> {
+    let key = active_key()?;
+    decrypt_with_key(&key, encrypted)
+}
+
+/// 计算密钥指纹（SHA-256 前 8 字节的十六进制），用于识别密文由哪个密钥加密
+fn key_fingerprint(key: &[u8; 32]) -> String {
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(key)
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 密文是否为旧格式（需要迁移升级）
+pub fn is_legacy_ciphertext(encrypted: &str) -> bool {
+    !encrypted.starts_with(CIPHERTEXT_V2_PREFIX)
+}
+
+/// 依次尝试使用多个密钥解密，返回第一个成功的结果
+pub fn decrypt_with_any_key(keys: &[[u8; 32]], encrypted: &str) -> Option<String> {
+    keys.iter().find_map(|key| decrypt_with_key(key, encrypted).ok())
+}
+
+/// 使用指定密钥加密（v2 格式）
+pub fn encrypt_with_key(key: &[u8; 32], password: &str) -> Result<String, String> {
     let cipher = Aes256Gcm::new(key.into());
     
     // 生成随机nonce
@@ -132,27 +425,45 @@ pub fn encrypt_password(password: &str) -> Result<String, String> {
     
     let ciphertext = cipher
         .encrypt(nonce, password.as_bytes())
-        .map_err(|e| format!("加密失败: {}", e))?;
+        .map_err(|e| format!("{}: {}", Msg::EncryptFailed, e))?;
     
     // 将nonce和密文一起编码
     let mut result = Vec::with_capacity(nonce_bytes.len() + ciphertext.len());
     result.extend_from_slice(&nonce_bytes);
     result.extend_from_slice(&ciphertext);
     
-    Ok(general_purpose::STANDARD.encode(result))
+    Ok(format!(
+        "{}{}:{}",
+        CIPHERTEXT_V2_PREFIX,
+        key_fingerprint(key),
+        general_purpose::STANDARD.encode(result)
+    ))
 }
 
-/// 解密密码
-pub fn decrypt_password(encrypted: &str) -> Result<String, String> {
-    let key = get_master_key()?;
+/// 使用指定密钥解密
+pub fn decrypt_with_key(key: &[u8; 32], encrypted: &str) -> Result<String, String> {
     let cipher = Aes256Gcm::new(key.into());
+
+    // v2 格式先校验密钥指纹，便于区分“密钥已变化”与“数据损坏”
+    let payload = match encrypted.strip_prefix(CIPHERTEXT_V2_PREFIX) {
+        Some(rest) => {
+            let (fingerprint, payload) = rest
+                .split_once(':')
+                .ok_or_else(|| String::from(Msg::InvalidCiphertext))?;
+            if fingerprint != key_fingerprint(key) {
+                return Err(String::from(Msg::KeyMismatch));
+            }
+            payload
+        }
+        None => encrypted,
+    };
     
     let data = general_purpose::STANDARD
-        .decode(encrypted)
-        .map_err(|e| format!("Base64 解码失败: {}", e))?;
+        .decode(payload)
+        .map_err(|e| format!("{}: {}", Msg::Base64DecodeFailed, e))?;
     
     if data.len() < 12 {
-        return Err("加密数据格式无效".to_string());
+        return Err(String::from(Msg::InvalidCiphertext));
     }
     
     // 分离nonce和密文
@@ -161,7 +472,297 @@ pub fn decrypt_password(encrypted: &str) -> Result<String, String> {
     
     let plaintext = cipher
         .decrypt(nonce, ciphertext)
-        .map_err(|e| format!("解密失败: {}", e))?;
+        .map_err(|e| format!("{}: {}", Msg::DecryptFailed, e))?;
     
-    String::from_utf8(plaintext).map_err(|e| format!("UTF-8 转换失败: {}", e))
+    String::from_utf8(plaintext).map_err(|e| format!("{}: {}", Msg::Utf8ConvertFailed, e))
+}
+
+/// 系统密钥环中使用的服务名
+const KEYRING_SERVICE: &str = "DataSmith";
+
+/// 连接凭据的存储方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStorage {
+    /// AES-GCM 加密后保存在 connections.json（受主密码保护）
+    #[default]
+    Encrypted,
+    /// 保存到系统密钥环（Secret Service / Keychain / Credential Manager）
+    Keyring,
+}
+
+/// 凭据类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecretKind {
+    /// 数据库密码
+    Password,
+    /// SSH 私钥口令
+    SshPassphrase,
+}
+
+impl SecretKind {
+    /// 所有凭据类型
+    pub const ALL: [SecretKind; 2] = [SecretKind::Password, SecretKind::SshPassphrase];
+
+    /// 密钥环中的账户名
+    fn account(self, connection_id: &str) -> String {
+        match self {
+            SecretKind::Password => connection_id.to_string(),
+            SecretKind::SshPassphrase => format!("{}:ssh-passphrase", connection_id),
+        }
+    }
+}
+
+/// 凭据后端，按连接 ID 和凭据类型保存
+pub trait CredentialBackend: Send + Sync {
+    /// 保存凭据
+    fn set_secret(&self, connection_id: &str, kind: SecretKind, secret: &str) -> Result<(), String>;
+
+    /// 读取凭据，不存在时返回 None
+    fn get_secret(&self, connection_id: &str, kind: SecretKind) -> Result<Option<String>, String>;
+
+    /// 删除凭据（不存在时视为成功）
+    fn delete_secret(&self, connection_id: &str, kind: SecretKind) -> Result<(), String>;
+}
+
+/// 基于 keyring crate 的系统密钥环后端
+pub struct KeyringBackend;
+
+impl KeyringBackend {
+    fn entry(connection_id: &str, kind: SecretKind) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYRING_SERVICE, &kind.account(connection_id))
+            .map_err(|e| format!("{}: {}", Msg::KeyringOpenFailed, e))
+    }
+}
+
+impl CredentialBackend for KeyringBackend {
+    fn set_secret(&self, connection_id: &str, kind: SecretKind, secret: &str) -> Result<(), String> {
+        Self::entry(connection_id, kind)?
+            .set_password(secret)
+            .map_err(|e| format!("{}: {}", Msg::KeyringWriteFailed, e))
+    }
+
+    fn get_secret(&self, connection_id: &str, kind: SecretKind) -> Result<Option<String>, String> {
+        match Self::entry(connection_id, kind)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("{}: {}", Msg::KeyringReadFailed, e)),
+        }
+    }
+
+    fn delete_secret(&self, connection_id: &str, kind: SecretKind) -> Result<(), String> {
+        match Self::entry(connection_id, kind)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("{}: {}", Msg::KeyringDeleteFailed, e)),
+        }
+    }
+}
+
+/// 内存凭据后端（测试用）
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockCredentialBackend {
+    secrets: Mutex<std::collections::HashMap<String, String>>,
+    /// 模拟密钥环不可用
+    pub unavailable: bool,
+}
+
+#[cfg(test)]
+impl CredentialBackend for MockCredentialBackend {
+    fn set_secret(&self, connection_id: &str, kind: SecretKind, secret: &str) -> Result<(), String> {
+        if self.unavailable {
+            return Err(String::from(Msg::KeyringUnavailable));
+        }
+        self.secrets
+            .lock()
+            .unwrap()
+            .insert(kind.account(connection_id), secret.to_string());
+        Ok(())
+    }
+
+    fn get_secret(&self, connection_id: &str, kind: SecretKind) -> Result<Option<String>, String> {
+        if self.unavailable {
+            return Err(String::from(Msg::KeyringUnavailable));
+        }
+        Ok(self.secrets.lock().unwrap().get(&kind.account(connection_id)).cloned())
+    }
+
+    fn delete_secret(&self, connection_id: &str, kind: SecretKind) -> Result<(), String> {
+        if self.unavailable {
+            return Err(String::from(Msg::KeyringUnavailable));
+        }
+        self.secrets.lock().unwrap().remove(&kind.account(connection_id));
+        Ok(())
+    }
+}
+
+/// 凭据的保存结果
+#[derive(Debug, Clone)]
+pub struct StoredSecret {
+    /// 实际使用的存储方式（密钥环不可用时回退为 Encrypted）
+    pub storage: CredentialStorage,
+    /// 需要写入连接配置的密文，保存在密钥环时为 None
+    pub encrypted: Option<String>,
+}
+
+/// 按存储方式保存凭据
+///
+/// 密钥环不可用时回退到 AES-GCM 加密，由调用方把实际的存储方式写回连接配置
+pub fn store_secret(
+    backend: &dyn CredentialBackend,
+    storage: CredentialStorage,
+    connection_id: &str,
+    kind: SecretKind,
+    secret: &str,
+) -> Result<StoredSecret, String> {
+    if storage == CredentialStorage::Keyring && backend.set_secret(connection_id, kind, secret).is_ok() {
+        return Ok(StoredSecret {
+            storage,
+            encrypted: None,
+        });
+    }
+
+    Ok(StoredSecret {
+        storage: CredentialStorage::Encrypted,
+        encrypted: Some(encrypt_password(secret)?),
+    })
+}
+
+/// 读取凭据
+///
+/// 连接配置中存在密文时优先解密（包括密钥环回退写入的情况），
+/// 否则在使用密钥环时从密钥环读取。
+pub fn load_secret(
+    backend: &dyn CredentialBackend,
+    storage: CredentialStorage,
+    connection_id: &str,
+    kind: SecretKind,
+    encrypted: Option<&str>,
+) -> Result<Option<String>, String> {
+    if let Some(encrypted) = encrypted {
+        return decrypt_password(encrypted).map(Some);
+    }
+
+    match storage {
+        CredentialStorage::Keyring => backend.get_secret(connection_id, kind),
+        CredentialStorage::Encrypted => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip_with_key() {
+        let key = [7u8; 32];
+        let encrypted = encrypt_with_key(&key, "s3cret").unwrap();
+        assert_eq!(decrypt_with_key(&key, &encrypted).unwrap(), "s3cret");
+        assert!(decrypt_with_key(&[8u8; 32], &encrypted).is_err());
+    }
+
+    #[test]
+    fn test_master_password_verification() {
+        let (config, key) = create_master_password_config("correct horse", 60).unwrap();
+        assert_eq!(verify_master_password(&config, "correct horse").unwrap(), key);
+        assert!(verify_master_password(&config, "wrong").is_err());
+    }
+
+    #[test]
+    fn test_reencrypt_between_keys() {
+        let old_key = [1u8; 32];
+        let new_key = [2u8; 32];
+        let encrypted = encrypt_with_key(&old_key, "pwd").unwrap();
+        let migrated = reencrypt_with_key(&encrypted, &old_key, &new_key).unwrap();
+        assert_eq!(decrypt_with_key(&new_key, &migrated).unwrap(), "pwd");
+    }
+
+    #[test]
+    fn test_keyring_storage_uses_backend() {
+        initialize_master_key().unwrap();
+        let backend = MockCredentialBackend::default();
+
+        let stored =
+            store_secret(&backend, CredentialStorage::Keyring, "c1", SecretKind::Password, "pwd").unwrap();
+        assert_eq!(stored.storage, CredentialStorage::Keyring);
+        assert!(stored.encrypted.is_none());
+
+        let loaded =
+            load_secret(&backend, CredentialStorage::Keyring, "c1", SecretKind::Password, None).unwrap();
+        assert_eq!(loaded.as_deref(), Some("pwd"));
+
+        let passphrase = load_secret(
+            &backend,
+            CredentialStorage::Keyring,
+            "c1",
+            SecretKind::SshPassphrase,
+            None,
+        )
+        .unwrap();
+        assert!(passphrase.is_none());
+    }
+
+    #[test]
+    fn test_keyring_unavailable_falls_back_to_encryption() {
+        initialize_master_key().unwrap();
+        let backend = MockCredentialBackend {
+            unavailable: true,
+            ..Default::default()
+        };
+
+        let stored =
+            store_secret(&backend, CredentialStorage::Keyring, "c2", SecretKind::Password, "pwd").unwrap();
+        assert_eq!(stored.storage, CredentialStorage::Encrypted);
+        assert!(stored.encrypted.is_some());
+
+        let loaded = load_secret(
+            &backend,
+            stored.storage,
+            "c2",
+            SecretKind::Password,
+            stored.encrypted.as_deref(),
+        )
+        .unwrap();
+        assert_eq!(loaded.as_deref(), Some("pwd"));
+    }
+
+    #[test]
+    fn test_versioned_ciphertext() {
+        let key = derive_machine_key_from_id("machine-a", None);
+        let other = derive_machine_key_from_id("machine-b", None);
+
+        let encrypted = encrypt_with_key(&key, "secret").unwrap();
+        assert!(!is_legacy_ciphertext(&encrypted));
+
+        let err = decrypt_with_key(&other, &encrypted).unwrap_err();
+        assert_eq!(err, String::from(Msg::KeyMismatch));
+        assert_eq!(
+            decrypt_with_any_key(&[other, key], &encrypted).as_deref(),
+            Some("secret")
+        );
+    }
+
+    #[test]
+    fn test_rotated_machine_key_differs() {
+        let legacy = derive_machine_key_from_id("machine-a", None);
+        let rotated = derive_machine_key_from_id("machine-a", Some("c2FsdA=="));
+        assert_ne!(legacy, rotated);
+        assert_eq!(rotated, derive_machine_key_from_id("machine-a", Some("c2FsdA==")));
+
+        let encrypted = encrypt_with_key(&legacy, "secret").unwrap();
+        let migrated = reencrypt_with_key(&encrypted, &legacy, &rotated).unwrap();
+        assert_eq!(decrypt_with_key(&rotated, &migrated).unwrap(), "secret");
+        assert!(decrypt_with_key(&legacy, &migrated).is_err());
+    }
+
+    #[test]
+    fn test_legacy_ciphertext_still_decrypts() {
+        let key = derive_machine_key_from_id("machine-a", None);
+        let encrypted = encrypt_with_key(&key, "secret").unwrap();
+
+        // 去掉版本前缀和指纹即为 v1 格式
+        let legacy = encrypted.rsplit(':').next().unwrap().to_string();
+        assert!(is_legacy_ciphertext(&legacy));
+        assert_eq!(decrypt_with_key(&key, &legacy).unwrap(), "secret");
+    }
 }
@@ -0,0 +1,169 @@
+//! 文本文件的字符编码转换（导出写入、导入读取）
+
+use encoding_rs::{CoderResult, Decoder, Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::io::{self, Read, Write};
+
+use super::i18n::Msg;
+
+/// 按名称查找字符编码（如 utf-8、gbk、gb18030、big5、shift_jis）
+pub fn find_encoding(label: &str) -> Result<&'static Encoding, String> {
+    Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| Msg::EncodingUnsupported.with(&[&label]))
+}
+
+/// 把 UTF-8 文本转换为目标编码后写入
+///
+/// 写入的数据可能在多字节字符中间断开，不完整的字符留到下次写入时处理。
+/// 目标编码无法表示的字符写成 HTML 数字字符引用（&#NNNN;）。
+/// `Encoding::encode` 对 UTF-16 输出的是 UTF-8，UTF-16 单独编码，并在开头写入 BOM
+pub struct EncodingWriter<W: Write> {
+    out: W,
+    encoding: &'static Encoding,
+    pending: Vec<u8>,
+    /// 尚未写入 UTF-16 的 BOM
+    bom: bool,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    pub fn new(out: W, encoding: &'static Encoding) -> Self {
+        Self {
+            out,
+            encoding,
+            pending: Vec::new(),
+            bom: encoding == UTF_16LE || encoding == UTF_16BE,
+        }
+    }
+
+    fn encode(&mut self, text: &str) -> Vec<u8> {
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            let little_endian = self.encoding == UTF_16LE;
+            let units = std::mem::take(&mut self.bom).then_some(0xFEFF).into_iter().chain(text.encode_utf16());
+            units
+                .flat_map(|unit| if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() })
+                .collect()
+        } else {
+            self.encoding.encode(text).0.into_owned()
+        }
+    }
+}
+
+impl<W: Write> Write for EncodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            // 末尾是不完整的字符
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+
+        let pending: Vec<u8> = self.pending.drain(..valid).collect();
+        let text = std::str::from_utf8(&pending).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let bytes = self.encode(text);
+        self.out.write_all(&bytes)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// 读取时把源编码转换为 UTF-8（开头的 BOM 会被识别并去掉）
+pub struct DecodingReader<R: Read> {
+    inner: R,
+    decoder: Decoder,
+    buf: Box<[u8]>,
+    start: usize,
+    end: usize,
+    eof: bool,
+}
+
+impl<R: Read> DecodingReader<R> {
+    pub fn new(inner: R, encoding: &'static Encoding) -> Self {
+        Self {
+            inner,
+            decoder: encoding.new_decoder(),
+            buf: vec![0; 8192].into_boxed_slice(),
+            start: 0,
+            end: 0,
+            eof: false,
+        }
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.start == self.end && !self.eof {
+                self.end = self.inner.read(&mut self.buf)?;
+                self.start = 0;
+                self.eof = self.end == 0;
+            }
+
+            let (result, read, written, _) =
+                self.decoder
+                    .decode_to_utf8(&self.buf[self.start..self.end], out, self.eof);
+            self.start += read;
+
+            if written > 0 || (self.eof && result == CoderResult::InputEmpty) {
+                return Ok(written);
+            }
+        }
+    }
+}
+
+/// 是否为 UTF-8（无需转换）
+pub fn is_utf8(encoding: &'static Encoding) -> bool {
+    encoding == UTF_8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gbk_round_trip() {
+        let gbk = find_encoding("GBK").unwrap();
+        let mut encoded = Vec::new();
+        {
+            let mut writer = EncodingWriter::new(&mut encoded, gbk);
+            let text = "编号,名称\n1,数据\n".as_bytes();
+            // 在多字节字符中间断开写入
+            writer.write_all(&text[..4]).unwrap();
+            writer.write_all(&text[4..]).unwrap();
+        }
+        assert_eq!(&encoded[..4], &[0xB1, 0xE0, 0xBA, 0xC5]);
+
+        let mut decoded = String::new();
+        DecodingReader::new(encoded.as_slice(), gbk)
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "编号,名称\n1,数据\n");
+
+        assert!(find_encoding("no-such-encoding").is_err());
+    }
+
+    #[test]
+    fn test_utf16_output() {
+        for (label, expected) in [
+            ("utf-16le", vec![0xFF, 0xFE, 0x41, 0x00, 0x16, 0x7F]),
+            ("utf-16be", vec![0xFE, 0xFF, 0x00, 0x41, 0x7F, 0x16]),
+        ] {
+            let encoding = find_encoding(label).unwrap();
+            let mut encoded = Vec::new();
+            {
+                let mut writer = EncodingWriter::new(&mut encoded, encoding);
+                let text = "A编".as_bytes();
+                writer.write_all(&text[..2]).unwrap();
+                writer.write_all(&text[2..]).unwrap();
+            }
+            assert_eq!(encoded, expected);
+
+            let mut decoded = String::new();
+            DecodingReader::new(encoded.as_slice(), encoding)
+                .read_to_string(&mut decoded)
+                .unwrap();
+            assert_eq!(decoded, "A编");
+        }
+    }
+}
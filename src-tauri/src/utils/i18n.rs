@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::RwLock;
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+static LOCALE: RwLock<Locale> = RwLock::new(Locale::ZhCn);
+
+/// 当前语言
+pub fn locale() -> Locale {
+    LOCALE.read().map(|l| *l).unwrap_or_default()
+}
+
+/// 切换语言（之后产生的后端消息使用新语言）
+pub fn set_locale(locale: Locale) {
+    if let Ok(mut current) = LOCALE.write() {
+        *current = locale;
+    }
+}
+
+/// 定义消息键及各语言的文本
+macro_rules! messages {
+    ($($key:ident => $zh:literal, $en:literal;)*) => {
+        /// 后端消息键
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Msg {
+            $($key,)*
+        }
+
+        impl Msg {
+            /// 指定语言的文本
+            pub fn text_in(self, locale: Locale) -> &'static str {
+                match (self, locale) {
+                    $(
+                        (Msg::$key, Locale::ZhCn) => $zh,
+                        (Msg::$key, Locale::EnUs) => $en,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+messages! {
+    // 错误码名称（DbError 的消息前缀）
+    CodeConnectionFailed => "连接失败", "Connection failed";
+    CodeConnectionLost => "连接已断开", "Connection lost";
+    CodeAuthFailed => "认证失败", "Authentication failed";
+    CodeTimeout => "操作超时", "Timed out";
+    CodeSyntaxError => "SQL 语法错误", "Syntax error";
+    CodeConstraintViolation => "违反约束", "Constraint violation";
+    CodePermissionDenied => "权限不足", "Permission denied";
+    CodeNotFound => "对象不存在", "Object not found";
+    CodeQueryFailed => "查询执行失败", "Query failed";
+    CodeUnsupported => "不支持的操作", "Unsupported operation";
+    CodeConfigError => "配置错误", "Configuration error";
+    CodeOther => "其他错误", "Error";
+    UnsupportedDatabase => "不支持的数据库类型", "Unsupported database type";
+
+    // 连接
+    ConnectionNotFound => "连接不存在", "Connection does not exist";
+    ConnectionConfigNotFound => "连接配置不存在", "Connection configuration does not exist";
+    ParseConnectionConfigFailed => "解析连接配置失败", "Failed to parse connection configuration";
+    NotConnected => "未连接到数据库", "Not connected to the database";
+    NotConnectedTo => "未连接到 {}", "Not connected to {}";
+    DatabaseNotSpecified => "未指定数据库", "No database specified";
+    NotKeyValueConnection => "不是 Redis 连接", "Not a Redis connection";
+    NotSearchConnection => "不是 Elasticsearch 连接", "Not an Elasticsearch connection";
+    ConnectTimeout => "连接超时", "Connection timed out";
+    ReconnectTimeout => "重连超时", "Reconnect timed out";
+    ReconnectFailed => "重连失败", "Reconnect failed";
+    HealthCheckTimeout => "健康检查超时", "Health check timed out";
+    PoolTimeout => "获取连接超时", "Timed out acquiring a connection";
+    PoolClosed => "连接池已关闭", "Connection pool is closed";
+    ConnectionBroken => "数据库连接已损坏", "Database connection is broken";
+    AcquireConnectionFailed => "获取连接失败", "Failed to acquire a connection";
+    ConnectToDatabaseFailed => "连接到数据库 {} 失败", "Failed to connect to database {}";
+    ConnectToFailed => "连接 {} 失败", "Failed to connect to {}";
+    CreateClientFailed => "创建 {} 客户端失败", "Failed to create {} client";
+    ParseUriFailed => "解析 {} URI 失败", "Failed to parse {} URI";
+    PingFailed => "PING 命令失败", "PING failed";
+    SetClientNameFailed => "设置客户端名称失败", "Failed to set client name";
+    InitSqlFailed => "初始化 SQL 执行失败", "Init SQL failed";
+    ServerInfoFailed => "获取服务器信息失败", "Failed to get server info";
+    ConnectionSucceeded => "连接成功", "Connected successfully";
+    PasswordUndecryptable => "连接 {} 的密码无法解密，请重新输入密码", "The password of connection {} cannot be decrypted, please enter it again";
+
+    // 查询
+    SwitchDatabaseFailed => "切换数据库失败", "Failed to switch database";
+    StatementFailed => "语句 {} 执行失败", "Statement {} failed";
+    BatchFailed => "批 {} 执行失败", "Batch {} failed";
+    TransactionUnsupported => "该数据库类型不支持事务", "This database type does not support transactions";
+    BeginTransactionFailed => "开始事务失败", "Failed to begin transaction";
+    SavepointUnsupported => "{} 不支持保存点", "{} does not support savepoints";
+    InvalidSavepointName => "无效的保存点名称: {}", "Invalid savepoint name: {}";
+    CommitFailed => "提交事务失败", "Failed to commit transaction";
+    SqlNotSupported => "{} 不支持 SQL 查询，请使用 {}", "{} does not support SQL queries, use {} instead";
+    RedisCommands => "Redis 命令", "Redis commands";
+    ListDatabasesFailed => "获取数据库列表失败", "Failed to list databases";
+    ListCollectionsFailed => "获取集合列表失败", "Failed to list collections";
+    ListIndexesFailed => "获取索引列表失败", "Failed to list indexes";
+    ReadIndexFailed => "读取索引信息失败", "Failed to read index information";
+    ShardsReplicas => "分片: {}, 副本: {}", "Shards: {}, replicas: {}";
+    ViewDefinitionNotFound => "未找到视图定义", "View definition not found";
+    DdlNotFound => "未找到DDL语句", "DDL statement not found";
+    EventNotSupported => "该数据库类型不支持 EVENT", "This database type does not support EVENT";
+    TableStructureFailed => "获取表结构失败", "Failed to get table structure";
+    TableStructureUnavailable => "无法获取表 {}.{} 的结构", "Cannot get the structure of table {}.{}";
+    ReadFileFailed => "读取文件失败", "Failed to read file";
+    WriteFileFailed => "写入文件失败", "Failed to write file";
+    AttachFileFailed => "无法读取文件 {} ({})", "Cannot read file {} ({})";
+
+    // 导出
+    ExportCancelled => "导出已取消", "Export cancelled";
+    ExportFormatUnsupported => "不支持导出为 {} 格式", "Exporting as {} is not supported";
+    SingleStatementRequired => "流式导出只支持单条查询语句", "Streaming export requires a single query statement";
+    CsvCharInvalid => "CSV 的{}必须是单个 ASCII 字符", "The CSV {} must be a single ASCII character";
+    CsvDelimiter => "分隔符", "delimiter";
+    CsvQuote => "引号", "quote character";
+    EncodingUnsupported => "不支持的字符编码 {}", "Unsupported character encoding {}";
+    InsertModeUnsupported => "{} 不支持 {} 方式的 INSERT", "{} does not support {} inserts";
+    ConflictColumnsRequired => "ON CONFLICT 需要指定冲突键列", "ON CONFLICT requires key columns";
+    CompressionLevelInvalid => "无效的压缩级别 {}", "Invalid compression level {}";
+    ClipboardWriteFailed => "写入剪贴板失败", "Failed to write to clipboard";
+    ColumnValueInvalid => "列 {} 的值 {} 无法转换为 {}", "Column {} value {} cannot be converted to {}";
+
+    // 导入
+    ImportFormatUnsupported => "不支持导入 {} 文件", "Importing {} files is not supported";
+    OpenFileFailed => "打开文件 {} 失败", "Failed to open file {}";
+    SheetNotFound => "工作表 {} 不存在", "Sheet {} does not exist";
+    ImportNoColumns => "没有要导入的列", "No columns to import";
+    ImportColumnMissing => "文件中没有列 {}", "Column {} is not in the file";
+    ImportTooManyErrors => "被拒绝的行超过 {} 行，导入已回滚", "More than {} rows were rejected, the import was rolled back";
+    RowColumnCountMismatch => "第 {} 行有 {} 列，表头有 {} 列", "Row {} has {} columns but the header has {}";
+
+    // 备份与恢复
+    DumpUnsupported => "{} 不支持备份", "{} does not support dumps";
+    DumpCancelled => "备份已取消", "Dump cancelled";
+    RestoreStatementFailed => "第 {} 条语句执行失败", "Statement {} failed";
+    RestorePartiallyApplied => "前 {} 条语句已生效，无法回滚", "The first {} statements were applied and cannot be rolled back";
+    DumpSessionSettings => "会话设置", "Session settings";
+    DumpDropObjects => "删除已存在的对象", "Drop existing objects";
+    DumpSchemas => "架构", "Schemas";
+    DumpTable => "表 {}", "Table {}";
+    DumpView => "视图 {}", "View {}";
+    DumpTableData => "表 {} 的数据", "Data for table {}";
+    DumpForeignKeys => "外键约束", "Foreign key constraints";
+    DumpRoutine => "例程 {}", "Routine {}";
+    DumpTrigger => "触发器 {}", "Trigger {}";
+    DumpRestoreSession => "恢复会话设置", "Restore session settings";
+
+    // 跨连接数据传输
+    TransferUnsupported => "{} 不支持数据传输", "{} does not support data transfer";
+    TransferTargetMissing => "目标表 {} 不存在", "Target table {} does not exist";
+    TransferNoColumns => "来源表和目标表没有同名的列", "Source and target tables have no columns in common";
+    TransferCancelled => "数据传输已取消", "Transfer cancelled";
+
+    // HTTP 接口（ClickHouse、Elasticsearch）
+    CreateHttpClientFailed => "创建 HTTP 客户端失败", "Failed to create HTTP client";
+    RequestFailed => "请求 {} 失败", "Request {} failed";
+    ReadResponseFailed => "读取响应失败", "Failed to read response";
+    ParseResponseFailed => "解析响应失败", "Failed to parse response";
+    UnrecognizedRequestLine => "无法识别的请求行", "Unrecognized request line";
+    QueryDslParseFailed => "Query DSL 解析失败", "Failed to parse Query DSL";
+
+    // Redis
+    ParseCommandFailed => "解析命令失败", "Failed to parse command";
+    EmptyCommand => "命令不能为空", "Command cannot be empty";
+    ExecuteCommandFailed => "执行命令失败", "Command failed";
+    ListKeysFailed => "获取键列表失败", "Failed to list keys";
+    GetKeyTypeFailed => "获取键类型失败", "Failed to get key type";
+    GetValueFailed => "获取键值失败", "Failed to read value";
+    SetValueFailed => "设置键值失败", "Failed to set value";
+    DeleteOldValueFailed => "删除旧值失败", "Failed to delete old value";
+    DeleteKeyFailed => "删除键失败", "Failed to delete key";
+    GetTtlFailed => "获取 TTL 失败", "Failed to get TTL";
+    SetTtlFailed => "设置 TTL 失败", "Failed to set TTL";
+    RemoveTtlFailed => "移除 TTL 失败", "Failed to remove TTL";
+    RenameFailed => "重命名失败", "Failed to rename key";
+    NoKeyPrefix => "(无前缀)", "(no prefix)";
+    KeyGroupName => "{} ({} 个键)", "{} ({} keys)";
+    KeyGroupComment => "键前缀分组，共 {} 个键", "Key prefix group, {} keys";
+
+    // 凭据与主密码
+    MasterKeyNotInitialized => "主密钥未初始化，请先调用 initialize_master_key()", "Master key is not initialized, call initialize_master_key() first";
+    MasterPasswordLocked => "主密码已锁定，请先解锁", "Master password is locked, unlock it first";
+    MasterPasswordEmpty => "主密码不能为空", "Master password cannot be empty";
+    MasterPasswordWrong => "主密码错误", "Wrong master password";
+    MasterPasswordDisabled => "未启用主密码", "Master password is not enabled";
+    MasterPasswordEnabled => "已启用主密码", "Master password is already enabled";
+    KeyDerivationFailed => "密钥派生失败", "Key derivation failed";
+    Base64DecodeFailed => "Base64 解码失败", "Base64 decoding failed";
+    EncryptFailed => "加密失败", "Encryption failed";
+    DecryptFailed => "解密失败", "Decryption failed";
+    Utf8ConvertFailed => "UTF-8 转换失败", "UTF-8 conversion failed";
+    InvalidCiphertext => "加密数据格式无效", "Invalid encrypted data";
+    KeyMismatch => "密钥不匹配：该密码由其他密钥加密（机器标识或主密码可能已变化）", "Key mismatch: the password was encrypted with another key (the machine id or master password may have changed)";
+    KeyringUnavailable => "密钥环不可用", "Keyring is unavailable";
+    KeyringOpenFailed => "打开系统密钥环失败", "Failed to open the system keyring";
+    KeyringWriteFailed => "写入系统密钥环失败", "Failed to write to the system keyring";
+    KeyringReadFailed => "读取系统密钥环失败", "Failed to read from the system keyring";
+    KeyringDeleteFailed => "删除系统密钥环凭据失败", "Failed to delete credentials from the system keyring";
+    MigratePasswordFailed => "迁移连接 {} 的密码失败", "Failed to migrate the password of connection {}";
+    MigrateSshPassphraseFailed => "迁移连接 {} 的 SSH 口令失败", "Failed to migrate the SSH passphrase of connection {}";
+}
+
+impl Msg {
+    /// 当前语言的文本
+    pub fn text(self) -> &'static str {
+        self.text_in(locale())
+    }
+
+    /// 依次用参数替换文本中的 `{}`
+    pub fn with(self, args: &[&dyn fmt::Display]) -> String {
+        self.with_in(locale(), args)
+    }
+
+    /// 使用指定语言的文本替换参数
+    pub fn with_in(self, locale: Locale, args: &[&dyn fmt::Display]) -> String {
+        let mut parts = self.text_in(locale).split("{}");
+        let mut result = parts.next().unwrap_or_default().to_string();
+        let mut args = args.iter();
+        for part in parts {
+            if let Some(arg) = args.next() {
+                result.push_str(&arg.to_string());
+            }
+            result.push_str(part);
+        }
+        result
+    }
+}
+
+impl fmt::Display for Msg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text())
+    }
+}
+
+impl From<Msg> for String {
+    fn from(msg: Msg) -> Self {
+        msg.text().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_in() {
+        assert_eq!(Msg::ConnectionNotFound.text_in(Locale::ZhCn), "连接不存在");
+        assert_eq!(Msg::ConnectionNotFound.text_in(Locale::EnUs), "Connection does not exist");
+    }
+
+    #[test]
+    fn test_with_args() {
+        assert_eq!(Msg::StatementFailed.with_in(Locale::ZhCn, &[&3]), "语句 3 执行失败");
+        assert_eq!(
+            Msg::TableStructureUnavailable.with_in(Locale::ZhCn, &[&"db", &"t"]),
+            "无法获取表 db.t 的结构"
+        );
+        // 参数不足时保留剩余文本
+        assert_eq!(
+            Msg::TableStructureUnavailable.with_in(Locale::ZhCn, &[&"db"]),
+            "无法获取表 db. 的结构"
+        );
+        assert_eq!(Msg::StatementFailed.with_in(Locale::EnUs, &[&3]), "Statement 3 failed");
+    }
+
+    #[test]
+    fn test_locale_serde() {
+        assert_eq!(serde_json::to_string(&Locale::EnUs).unwrap(), "\"en-US\"");
+        assert_eq!(serde_json::from_str::<Locale>("\"zh-CN\"").unwrap(), Locale::ZhCn);
+    }
+}
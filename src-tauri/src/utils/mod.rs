@@ -1,3 +1,5 @@
 pub mod crypto;
+pub mod encoding;
+pub mod i18n;
 pub mod sql_formatter;
 
@@ -1,3 +1,7 @@
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::database::registry::{DatabaseDriver, DriverRegistry};
 use crate::database::DatabaseType;
 
 /// SQL 格式化工具，用于适配不同数据库的 SQL 语法
@@ -41,16 +45,16 @@ impl SqlFormatter {
     ) -> String {
         let table_ref = Self::format_table_ref(db_type, database, table, schema);
         let column_ref = Self::quote_identifier(db_type, column);
-        let value_str = if let Some(v) = value {
-            format!("'{}'", v.replace("'", "''"))
-        } else {
-            "NULL".to_string()
+        let value_str = match value {
+            Some(v) => Self::format_string(db_type, v),
+            None => "NULL".to_string(),
         };
-        
-        format!(
-            "UPDATE {} SET {} = {} WHERE {}",
-            table_ref, column_ref, value_str, where_clause
-        )
+        let assignment = format!("{} = {}", column_ref, value_str);
+
+        match Self::driver(db_type) {
+            Some(driver) => driver.update_sql(&table_ref, &assignment, where_clause),
+            None => format!("UPDATE {} SET {} WHERE {}", table_ref, assignment, where_clause),
+        }
     }
     
     /// 格式化 INSERT 语句
@@ -88,65 +92,65 @@ impl SqlFormatter {
         format!("DELETE FROM {} WHERE {}", table_ref, where_clause)
     }
     
-    /// 格式化表引用（database.table 或 table）
-    fn format_table_ref(db_type: &DatabaseType, database: &str, table: &str, schema: Option<&str>) -> String {
-        match db_type {
-            DatabaseType::SQLite => {
-                // SQLite 不使用 database.table 格式，只使用表名
-                Self::quote_identifier(db_type, table)
-            }
-            DatabaseType::MySQL => {
-                // MySQL 使用 `database`.`table`
-                format!(
-                    "{}.{}",
-                    Self::quote_identifier(db_type, database),
-                    Self::quote_identifier(db_type, table)
-                )
-            }
-            DatabaseType::PostgreSQL => {
-                // PostgreSQL 使用 schema.table 格式
-                // 如果提供了 schema 参数，使用 schema.table
-                // 否则默认使用 public schema
-                let schema_name = schema.unwrap_or("public");
-                format!(
-                    "{}.{}",
-                    Self::quote_identifier(db_type, schema_name),
-                    Self::quote_identifier(db_type, table)
-                )
-            }
-            // 为未来的数据库类型预留
-            _ => {
-                // 默认使用 MySQL 风格
-                format!(
-                    "{}.{}",
-                    Self::quote_identifier(db_type, database),
-                    Self::quote_identifier(db_type, table)
-                )
-            }
+    /// 格式化表引用，限定方式由驱动决定（见 `DatabaseDriver::table_ref`）
+    pub fn format_table_ref(db_type: &DatabaseType, database: &str, table: &str, schema: Option<&str>) -> String {
+        match Self::driver(db_type) {
+            Some(driver) => driver.table_ref(database, schema, table),
+            None => format!("{}.{}", Self::quote_standard(database), Self::quote_standard(table)),
         }
     }
     
-    /// 根据数据库类型引用标识符（列名、表名等）
+    /// 根据数据库类型引用标识符（列名、表名等），规则由驱动决定
     pub fn quote_identifier(db_type: &DatabaseType, identifier: &str) -> String {
-        match db_type {
-            DatabaseType::SQLite => {
-                // SQLite 使用双引号
-                format!("\"{}\"", identifier)
-            }
-            DatabaseType::MySQL => {
-                // MySQL 使用反引号
-                format!("`{}`", identifier)
-            }
-            DatabaseType::PostgreSQL => {
-                // PostgreSQL 使用双引号
-                format!("\"{}\"", identifier)
-            }
-            // 为未来的数据库类型预留
-            _ => {
-                // 默认使用反引号
-                format!("`{}`", identifier)
-            }
+        match Self::driver(db_type) {
+            Some(driver) => driver.quote_identifier(identifier),
+            None => Self::quote_standard(identifier),
         }
     }
-}
 
+    /// 标准 SQL 标识符：双引号，内部的双引号写两次
+    pub fn quote_standard(identifier: &str) -> String {
+        format!("\"{}\"", identifier.replace('"', "\"\""))
+    }
+
+    /// 值的 SQL 字面量（字符串按目标数据库的规则转义，JSON 对象和数组写成字符串）
+    pub fn format_literal(db_type: &DatabaseType, value: &Value) -> String {
+        match value {
+            Value::Null => "NULL".to_string(),
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => match Self::driver(db_type) {
+                Some(driver) => driver.bool_literal(*b).to_string(),
+                None => if *b { "TRUE" } else { "FALSE" }.to_string(),
+            },
+            Value::String(s) => Self::format_string(db_type, s),
+            _ => Self::format_string(db_type, &value.to_string()),
+        }
+    }
+
+    /// 字符串字面量
+    pub fn format_string(db_type: &DatabaseType, value: &str) -> String {
+        match Self::driver(db_type) {
+            Some(driver) => driver.string_literal(value),
+            None => Self::string_standard(value),
+        }
+    }
+
+    /// 标准 SQL 字符串字面量：单引号，内部的单引号写两次
+    pub fn string_standard(value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+
+    /// 二进制字面量（十六进制）
+    pub fn format_binary(db_type: &DatabaseType, bytes: &[u8]) -> String {
+        let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+        match Self::driver(db_type) {
+            Some(driver) => driver.binary_literal(&hex),
+            None => format!("X'{}'", hex),
+        }
+    }
+
+    /// 当前编译进来的对应驱动，未启用的数据库类型按标准 SQL 处理
+    fn driver(db_type: &DatabaseType) -> Option<Arc<dyn DatabaseDriver>> {
+        DriverRegistry::builtin().get(db_type).ok()
+    }
+}
@@ -0,0 +1,59 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import type {
+  DumpProgress,
+  DumpSummary,
+  DumpTask,
+  RestoreProgress,
+  RestoreSummary,
+  RestoreTask,
+} from '@/types/database'
+
+export class DumpService {
+  /**
+   * 备份数据库到 SQL 文件，备份过程中回调进度
+   */
+  static async dumpDatabase(
+    task: DumpTask,
+    onProgress?: (progress: DumpProgress) => void
+  ): Promise<DumpSummary> {
+    const unlisten = await listen<DumpProgress>('dump-progress', (event) => {
+      if (event.payload.dump_id === task.dump_id) {
+        onProgress?.(event.payload)
+      }
+    })
+
+    try {
+      return await invoke<DumpSummary>('dump_database', { task })
+    } finally {
+      unlisten()
+    }
+  }
+
+  /**
+   * 从备份文件恢复（支持 .sql.gz），恢复过程中回调进度
+   */
+  static async restoreDatabase(
+    task: RestoreTask,
+    onProgress?: (progress: RestoreProgress) => void
+  ): Promise<RestoreSummary> {
+    const unlisten = await listen<RestoreProgress>('restore-progress', (event) => {
+      if (event.payload.restore_id === task.restore_id) {
+        onProgress?.(event.payload)
+      }
+    })
+
+    try {
+      return await invoke<RestoreSummary>('restore_database', { task })
+    } finally {
+      unlisten()
+    }
+  }
+
+  /**
+   * 取消备份或恢复
+   */
+  static async cancel(id: string): Promise<boolean> {
+    return invoke<boolean>('cancel_export', { exportId: id })
+  }
+}
@@ -0,0 +1,22 @@
+import type { DbError } from '@/types/database'
+
+/**
+ * 是否为后端返回的结构化错误
+ */
+export function isDbError(error: unknown): error is DbError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as DbError).code === 'string' &&
+    typeof (error as DbError).message === 'string'
+  )
+}
+
+/**
+ * 提取错误信息（兼容结构化错误、Error 对象和字符串）
+ */
+export function formatError(error: unknown): string {
+  if (isDbError(error)) return error.message
+  if (error instanceof Error) return error.message
+  return String(error)
+}
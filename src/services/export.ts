@@ -1,8 +1,70 @@
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { save } from '@tauri-apps/plugin-dialog'
-import type { QueryResult } from '@/types/database'
+import type {
+  CopySelection,
+  DatabaseType,
+  ExportFormat,
+  ExportOptions,
+  ExportProgress,
+  QueryResult,
+  SqlExportOptions,
+  StreamExportSummary,
+  StreamExportTask,
+} from '@/types/database'
 
 export class ExportService {
+  /**
+   * 从数据库直接流式导出到文件，导出过程中回调进度
+   */
+  static async exportQueryToFile(
+    task: StreamExportTask,
+    onProgress?: (progress: ExportProgress) => void
+  ): Promise<StreamExportSummary> {
+    const unlisten = await listen<ExportProgress>('export-progress', (event) => {
+      if (event.payload.export_id === task.export_id) {
+        onProgress?.(event.payload)
+      }
+    })
+
+    try {
+      return await invoke<StreamExportSummary>('export_query_to_file', { task })
+    } finally {
+      unlisten()
+    }
+  }
+
+  /**
+   * 取消流式导出
+   */
+  static async cancelExport(exportId: string): Promise<boolean> {
+    return invoke<boolean>('cancel_export', { exportId })
+  }
+
+  /**
+   * 按指定格式导出已加载的查询结果
+   */
+  static async exportResultToFile(
+    data: QueryResult,
+    format: ExportFormat,
+    filePath: string,
+    tableName?: string,
+    options?: ExportOptions
+  ): Promise<boolean> {
+    return invoke<boolean>('export_result_to_file', { data, format, filePath, tableName, options })
+  }
+
+  /**
+   * 按格式把选中的结果复制到剪贴板，SQL 格式按连接的数据库类型（或指定的方言）引用，返回复制的文本
+   */
+  static async copySelection(
+    selection: CopySelection,
+    connectionId?: string,
+    dialect?: DatabaseType
+  ): Promise<string> {
+    return invoke<string>('copy_selection', { selection, connectionId, dialect })
+  }
+
   /**
    * 导出为 CSV
    */
@@ -65,7 +127,8 @@ export class ExportService {
   static async exportToSql(
     data: QueryResult,
     tableName: string,
-    defaultName = 'export.sql'
+    defaultName = 'export.sql',
+    options?: Partial<SqlExportOptions>
   ) {
     const filePath = await save({
       defaultPath: defaultName,
@@ -84,6 +147,7 @@ export class ExportService {
         data,
         tableName,
         filePath,
+        options,
       })
       return true
     } catch (error) {
@@ -0,0 +1,62 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import type {
+  ImportColumn,
+  ImportPreview,
+  ImportProgress,
+  ImportReadOptions,
+  ImportSummary,
+  ImportTask,
+} from '@/types/database'
+
+export class ImportService {
+  /**
+   * 预览导入文件，返回推断的列类型和前若干行
+   */
+  static async previewFile(
+    filePath: string,
+    options: ImportReadOptions,
+    limit?: number
+  ): Promise<ImportPreview> {
+    return invoke<ImportPreview>('preview_import_file', { filePath, options, limit })
+  }
+
+  /**
+   * 按推断的列生成建表语句
+   */
+  static async generateDdl(
+    connectionId: string,
+    table: string,
+    columns: ImportColumn[],
+    database?: string,
+    schema?: string
+  ): Promise<string> {
+    return invoke<string>('generate_import_ddl', {
+      connectionId,
+      database,
+      schema,
+      table,
+      columns,
+    })
+  }
+
+  /**
+   * 在事务中把文件导入到表，导入过程中回调进度
+   */
+  static async importFile(
+    task: ImportTask,
+    onProgress?: (progress: ImportProgress) => void
+  ): Promise<ImportSummary> {
+    const unlisten = await listen<ImportProgress>('import-progress', (event) => {
+      if (event.payload.import_id === task.import_id) {
+        onProgress?.(event.payload)
+      }
+    })
+
+    try {
+      return await invoke<ImportSummary>('import_file_to_table', { task })
+    } finally {
+      unlisten()
+    }
+  }
+}
@@ -0,0 +1,32 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import type { TransferProgress, TransferSummary, TransferTask } from '@/types/database'
+
+export class TransferService {
+  /**
+   * 把表数据复制到另一个连接，传输过程中回调进度
+   */
+  static async transferTable(
+    task: TransferTask,
+    onProgress?: (progress: TransferProgress) => void
+  ): Promise<TransferSummary> {
+    const unlisten = await listen<TransferProgress>('transfer-progress', (event) => {
+      if (event.payload.transfer_id === task.transfer_id) {
+        onProgress?.(event.payload)
+      }
+    })
+
+    try {
+      return await invoke<TransferSummary>('transfer_table', { task })
+    } finally {
+      unlisten()
+    }
+  }
+
+  /**
+   * 取消传输
+   */
+  static async cancelTransfer(transferId: string): Promise<boolean> {
+    return invoke<boolean>('cancel_export', { exportId: transferId })
+  }
+}
@@ -1,8 +1,12 @@
 import { defineStore } from 'pinia'
 import { ref, watch } from 'vue'
+import { invoke } from '@tauri-apps/api/core'
 
 export type Theme = 'light' | 'dark'
 
+// 后端消息（错误提示等）使用的语言
+export type Locale = 'zh-CN' | 'en-US'
+
 const THEME_STORAGE_KEY = 'app-theme'
 
 // 从 localStorage 加载主题
@@ -22,6 +26,9 @@ export const useAppStore = defineStore('app', () => {
   // 主题 - 从 localStorage 初始化
   const theme = ref<Theme>(loadThemeFromStorage())
 
+  // 语言 - 由后端持久化
+  const locale = ref<Locale>('zh-CN')
+
   // 侧边栏折叠状态
   const sidebarCollapsed = ref(false)
 
@@ -44,6 +51,21 @@ export const useAppStore = defineStore('app', () => {
     theme.value = newTheme
   }
 
+  // 从后端加载语言设置
+  async function loadLocale() {
+    try {
+      locale.value = await invoke<Locale>('get_locale')
+    } catch (e) {
+      console.error('加载语言设置失败:', e)
+    }
+  }
+
+  // 设置语言
+  async function setLocale(newLocale: Locale) {
+    await invoke('set_locale', { locale: newLocale })
+    locale.value = newLocale
+  }
+
   // 切换侧边栏
   function toggleSidebar() {
     sidebarCollapsed.value = !sidebarCollapsed.value
@@ -54,6 +76,9 @@ export const useAppStore = defineStore('app', () => {
     sidebarCollapsed,
     toggleTheme,
     setTheme,
+    locale,
+    loadLocale,
+    setLocale,
     toggleSidebar,
   }
 })
@@ -1,7 +1,14 @@
 import { defineStore } from 'pinia'
 import { ref } from 'vue'
-import type { ConnectionConfig, ConnectionStatus } from '@/types/database'
+import type {
+  ConnectionConfig,
+  ConnectionHealth,
+  ConnectionStatus,
+  ConnectionTestResult,
+  DbError,
+} from '@/types/database'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 
 export const useConnectionStore = defineStore('connection', () => {
   // 状态
@@ -9,6 +16,11 @@ export const useConnectionStore = defineStore('connection', () => {
   const activeConnectionId = ref<string | null>(null)
   const connectionStatuses = ref<Map<string, ConnectionStatus>>(new Map())
 
+  // 后端健康检查与自动重连推送的连接状态
+  listen<ConnectionHealth>('connection-status-changed', (event) => {
+    connectionStatuses.value.set(event.payload.connection_id, event.payload.status)
+  })
+
   // 获取所有连接
   async function fetchConnections() {
     try {
@@ -20,7 +32,7 @@ export const useConnectionStore = defineStore('connection', () => {
   }
 
   // 保存连接
-  async function saveConnection(config: ConnectionConfig, password?: string) {
+  async function saveConnection(config: ConnectionConfig, password?: string, sshPassphrase?: string) {
     try {
       // 创建不包含密码的存储对象
       const storedConnection = {
@@ -32,8 +44,14 @@ export const useConnectionStore = defineStore('connection', () => {
         username: config.username,
         database: config.database,
         ssl: config.ssl,
+        trust_server_certificate: config.trust_server_certificate || false,
         connection_timeout: config.connection_timeout,
         pool_size: config.pool_size,
+        credential_storage: config.credential_storage || 'encrypted',
+        init_sql: config.init_sql || null,
+        charset: config.charset || null,
+        time_zone: config.time_zone || null,
+        application_name: config.application_name || null,
         tags: config.tags || [],
         created_at: config.created_at || Date.now(),
         updated_at: Date.now(),
@@ -41,7 +59,8 @@ export const useConnectionStore = defineStore('connection', () => {
       
       const saved = await invoke<any>('save_connection', { 
         connection: storedConnection,
-        password: password || null
+        password: password || null,
+        sshPassphrase: sshPassphrase || null
       })
       
       const index = connections.value.findIndex(c => c.id === saved.id)
@@ -58,7 +77,7 @@ export const useConnectionStore = defineStore('connection', () => {
   }
 
   // 更新连接
-  async function updateConnection(config: ConnectionConfig, password?: string) {
+  async function updateConnection(config: ConnectionConfig, password?: string, sshPassphrase?: string) {
     try {
       // 创建不包含密码的存储对象
       const storedConnection = {
@@ -70,8 +89,14 @@ export const useConnectionStore = defineStore('connection', () => {
         username: config.username,
         database: config.database,
         ssl: config.ssl,
+        trust_server_certificate: config.trust_server_certificate || false,
         connection_timeout: config.connection_timeout,
         pool_size: config.pool_size,
+        credential_storage: config.credential_storage || 'encrypted',
+        init_sql: config.init_sql || null,
+        charset: config.charset || null,
+        time_zone: config.time_zone || null,
+        application_name: config.application_name || null,
         tags: config.tags || [],
         created_at: config.created_at,
         updated_at: Date.now(),
@@ -79,7 +104,8 @@ export const useConnectionStore = defineStore('connection', () => {
       
       const updated = await invoke<any>('update_connection', { 
         connection: storedConnection,
-        password: password || null
+        password: password || null,
+        sshPassphrase: sshPassphrase || null
       })
       
       const index = connections.value.findIndex(c => c.id === config.id)
@@ -111,10 +137,14 @@ export const useConnectionStore = defineStore('connection', () => {
   // 测试连接
   async function testConnection(config: ConnectionConfig) {
     try {
-      const result = await invoke<any>('test_connection', { config })
-      // 检查连接测试结果
+      const result = await invoke<ConnectionTestResult>('test_connection', { config })
+      // 检查连接测试结果，失败时抛出带错误码的结构化错误
       if (!result.success) {
-        throw new Error(result.message || '连接失败')
+        const error: DbError = {
+          code: result.error_code ?? 'connection_failed',
+          message: result.message || '连接失败',
+        }
+        throw error
       }
       return result
     } catch (error) {
@@ -2,11 +2,16 @@
  * 数据库类型定义
  */
 
-export type DatabaseType = 'mysql' | 'postgresql' | 'sqlite' | 'mongodb' | 'redis' | 'elasticsearch'
+export type DatabaseType = 'mysql' | 'postgresql' | 'sqlite' | 'sqlserver' | 'duckdb' | 'clickhouse' | 'mongodb' | 'redis' | 'elasticsearch'
 
 /**
  * 连接配置
  */
+/**
+ * 凭据存储方式
+ */
+export type CredentialStorage = 'encrypted' | 'keyring'
+
 export interface ConnectionConfig {
   id: string
   name: string
@@ -17,8 +22,15 @@ export interface ConnectionConfig {
   password?: string
   database?: string
   ssl: boolean
+  /** 启用 SSL 时信任服务器证书（SQL Server） */
+  trust_server_certificate?: boolean
   connection_timeout: number
   pool_size: number
+  credential_storage?: CredentialStorage
+  init_sql?: string
+  charset?: string
+  time_zone?: string
+  application_name?: string
   group?: string
   color?: string
   tags: string[]
@@ -26,13 +38,248 @@ export interface ConnectionConfig {
   updated_at: number
 }
 
+/**
+ * 服务器产品分支
+ */
+export type ServerFlavor =
+  | 'mysql'
+  | 'mariadb'
+  | 'tidb'
+  | 'postgresql'
+  | 'cockroachdb'
+  | 'sqlite'
+  | 'sqlserver'
+  | 'duckdb'
+  | 'clickhouse'
+  | 'mongodb'
+  | 'redis'
+  | 'valkey'
+  | 'elasticsearch'
+  | 'opensearch'
+
+/**
+ * 服务器特性
+ */
+export interface ServerFeatures {
+  window_functions: boolean
+  cte: boolean
+  json: boolean
+  returning: boolean
+  upsert: boolean
+  sequences: boolean
+  check_constraints: boolean
+  stored_procedures: boolean
+  triggers: boolean
+  events: boolean
+  transactions: boolean
+}
+
+/**
+ * 服务器信息
+ */
+export interface ServerInfo {
+  flavor: ServerFlavor
+  version: string
+  version_string: string
+  current_user?: string
+  time_zone?: string
+  features: ServerFeatures
+}
+
+/**
+ * 数据库对象类型
+ */
+export type ObjectType =
+  | 'table'
+  | 'view'
+  | 'procedure'
+  | 'function'
+  | 'trigger'
+  | 'event'
+  | 'sequence'
+  | 'index'
+  | 'collection'
+  | 'key'
+
+/**
+ * 数据编辑能力
+ */
+export interface EditingCapabilities {
+  insert: boolean
+  update: boolean
+  delete: boolean
+  truncate: boolean
+  create_table: boolean
+  alter_table: boolean
+  drop_table: boolean
+}
+
+/**
+ * 连接能力（驱动能力结合服务器特性）
+ */
+export interface DriverCapabilities {
+  sql: boolean
+  schemas: boolean
+  file_based: boolean
+  key_value: boolean
+  search: boolean
+  object_types: ObjectType[]
+  editing: EditingCapabilities
+  transactions: boolean
+  explain_formats: ('text' | 'json' | 'tree' | 'xml' | 'yaml')[]
+  export_formats: ExportFormat[]
+}
+
+/**
+ * 导出格式
+ */
+export type ExportFormat =
+  | 'csv'
+  | 'json'
+  | 'jsonl'
+  | 'json_columns'
+  | 'xml'
+  | 'markdown'
+  | 'html'
+  | 'sql'
+  | 'ddl'
+  | 'xlsx'
+  | 'parquet'
+  | 'arrow'
+
+/**
+ * CSV 格式选项（导出和导入共用）
+ */
+export interface CsvDialect {
+  delimiter: string
+  quote: string
+  quoting: 'necessary' | 'always' | 'non_numeric' | 'never'
+  /** 是否写入表头（导出） */
+  header: boolean
+  /** NULL 的文本形式 */
+  null_value: string
+  line_ending: 'lf' | 'crlf'
+  /** 是否写入 UTF-8 BOM（导出） */
+  bom: boolean
+  /** 字符编码，如 utf-8、gbk */
+  encoding: string
+}
+
+/**
+ * SQL 导出遇到键冲突时的处理方式
+ */
+export type SqlInsertMode = 'insert' | 'ignore' | 'replace' | 'upsert'
+
+/**
+ * SQL INSERT 导出选项
+ */
+export interface SqlExportOptions {
+  /** 目标数据库类型，默认与来源连接相同 */
+  dialect: DatabaseType | null
+  /** 每条 INSERT 包含的行数 */
+  batch_size: number
+  mode: SqlInsertMode
+  /** 冲突键列（ON CONFLICT） */
+  conflict_columns: string[]
+  /** 是否写入建表语句 */
+  create_table: boolean
+  /** 按二进制字面量写入的列 */
+  binary_columns: string[]
+}
+
+/**
+ * Parquet / Arrow 压缩算法（Arrow 只支持 lz4 和 zstd）
+ */
+export type ColumnarCompression = 'none' | 'snappy' | 'gzip' | 'lz4' | 'zstd'
+
+/**
+ * Parquet 和 Arrow IPC 导出选项
+ */
+export interface ColumnarOptions {
+  compression: ColumnarCompression
+  /** 压缩级别（gzip 0-9，zstd 1-22），null 时使用默认级别 */
+  compression_level: number | null
+  /** Parquet 每个行组的最大行数 */
+  row_group_size: number
+}
+
+/**
+ * 各格式的导出选项
+ */
+export interface ExportOptions {
+  csv?: Partial<CsvDialect>
+  sql?: Partial<SqlExportOptions>
+  columnar?: Partial<ColumnarOptions>
+}
+
+/**
+ * 复制到剪贴板的格式
+ */
+export type CopyFormat = 'tsv' | 'csv' | 'markdown' | 'json' | 'sql_insert' | 'sql_in' | 'sql_where'
+
+/**
+ * 结果中选中的区域（copy_selection）
+ */
+export interface CopySelection {
+  columns: string[]
+  /** 每行的值，顺序与列名一致 */
+  rows: any[][]
+  format: CopyFormat
+  /** 是否包含表头（TSV 和 CSV），默认 true */
+  header?: boolean
+  /** INSERT 的目标表 */
+  table_name?: string
+}
+
+/**
+ * 后端错误码
+ */
+export type ErrorCode =
+  | 'connection_failed'
+  | 'connection_lost'
+  | 'auth_failed'
+  | 'timeout'
+  | 'syntax_error'
+  | 'constraint_violation'
+  | 'permission_denied'
+  | 'not_found'
+  | 'query_failed'
+  | 'unsupported'
+  | 'config_error'
+  | 'other'
+
+/**
+ * 数据库原生错误信息
+ */
+export interface NativeError {
+  /** SQLSTATE */
+  sqlstate?: string
+  /** 数值错误码（MySQL errno、SQL Server 错误号等） */
+  errno?: number
+  /** 错误类别名称（Redis 错误前缀、Elasticsearch error.type 等） */
+  kind?: string
+}
+
+/**
+ * 数据库命令返回的错误
+ */
+export interface DbError {
+  code: ErrorCode
+  message: string
+  native?: NativeError
+}
+
 /**
  * 连接测试结果
  */
 export interface ConnectionTestResult {
   success: boolean
   message: string
+  error_code?: ErrorCode
   version?: string
+  server_info?: ServerInfo
+  /** 读取服务器信息失败时的提示（连接本身已成功） */
+  server_info_error?: string
   ping_time_ms: number
 }
 
@@ -41,6 +288,20 @@ export interface ConnectionTestResult {
  */
 export type ConnectionStatus = 'connected' | 'disconnected' | 'connecting' | 'error'
 
+/**
+ * 连接健康状态（connection-status-changed 事件）
+ */
+export interface ConnectionHealth {
+  connection_id: string
+  status: ConnectionStatus
+  error?: string
+  error_code?: ErrorCode
+  retry_attempts: number
+  next_retry_secs?: number
+  /** 读取服务器信息失败的原因（连接仍可用） */
+  server_info_error?: string
+}
+
 /**
  * 数据库信息
  */
@@ -100,6 +361,297 @@ export interface QueryResult {
   execution_time_ms: number
 }
 
+/**
+ * 流式导出的数据来源
+ */
+export type ExportSource =
+  | { type: 'query'; sql: string }
+  | { type: 'table'; table: string; schema?: string }
+
+/**
+ * 流式导出任务（export_query_to_file）
+ */
+export interface StreamExportTask {
+  export_id: string
+  connection_id: string
+  database?: string
+  source: ExportSource
+  format: ExportFormat
+  file_path: string
+  /** SQL 格式的目标表名 */
+  table_name?: string
+  options?: ExportOptions
+}
+
+/**
+ * 导出进度（export-progress 事件）
+ */
+export interface ExportProgress {
+  export_id: string
+  rows: number
+  elapsed_ms: number
+}
+
+/**
+ * 流式导出结果
+ */
+export interface StreamExportSummary {
+  export_id: string
+  rows: number
+  elapsed_ms: number
+  cancelled: boolean
+}
+
+/**
+ * 导入文件格式
+ */
+export type ImportFileFormat = 'csv' | 'xlsx' | 'xls' | 'ods'
+
+/**
+ * 导入文件读取选项
+ */
+export interface ImportReadOptions {
+  /** 工作表名称（电子表格，默认第一个） */
+  sheet?: string
+  has_header: boolean
+  /** CSV 的分隔符、引号、NULL 文本和编码 */
+  csv?: Partial<CsvDialect>
+}
+
+/**
+ * 推断的列类型
+ */
+export type ImportColumnType = 'integer' | 'float' | 'boolean' | 'date' | 'datetime' | 'text'
+
+/**
+ * 源文件中的列
+ */
+export interface ImportColumn {
+  name: string
+  column_type: ImportColumnType
+  nullable: boolean
+  max_length: number
+}
+
+/**
+ * 导入文件预览
+ */
+export interface ImportPreview {
+  format: ImportFileFormat
+  sheets: string[]
+  sheet?: string
+  columns: ImportColumn[]
+  rows: any[][]
+}
+
+/**
+ * 源列到目标列的映射
+ */
+export interface ImportColumnMapping {
+  /** 源列序号 */
+  source: number
+  target: string
+}
+
+/**
+ * 导入任务（import_file_to_table）
+ */
+export interface ImportTask {
+  import_id: string
+  connection_id: string
+  database?: string
+  schema?: string
+  table: string
+  file_path: string
+  options: ImportReadOptions
+  mappings: ImportColumnMapping[]
+  /** 导入前在同一事务中执行的建表语句 */
+  create_table_sql?: string
+  batch_size?: number
+  max_errors?: number
+}
+
+/**
+ * 被拒绝的行
+ */
+export interface RejectedRow {
+  /** 文件中的行号 */
+  row: number
+  values: any[]
+  error: string
+}
+
+/**
+ * 导入进度（import-progress 事件）
+ */
+export interface ImportProgress {
+  import_id: string
+  rows: number
+  inserted: number
+  rejected: number
+  elapsed_ms: number
+}
+
+/**
+ * 导入结果
+ */
+export interface ImportSummary {
+  import_id: string
+  rows: number
+  inserted: number
+  rejected: RejectedRow[]
+  elapsed_ms: number
+}
+
+/**
+ * 备份选项
+ */
+export interface DumpOptions {
+  schema: boolean
+  data: boolean
+  /** 存储过程、函数和事件 */
+  routines: boolean
+  triggers: boolean
+  /** 创建前先删除已存在的对象 */
+  drop_existing: boolean
+  /** 每条 INSERT 包含的行数 */
+  batch_size: number
+  gzip: boolean
+  /** 只备份这些表（为空时备份全部） */
+  tables: string[]
+}
+
+/**
+ * 备份任务
+ */
+export interface DumpTask {
+  dump_id: string
+  connection_id: string
+  database: string
+  file_path: string
+  options?: Partial<DumpOptions>
+}
+
+export type DumpStage = 'schema' | 'data' | 'routines' | 'triggers'
+
+/**
+ * 备份进度
+ */
+export interface DumpProgress {
+  dump_id: string
+  stage: DumpStage
+  object: string
+  tables_done: number
+  tables_total: number
+  rows: number
+  elapsed_ms: number
+}
+
+/**
+ * 备份结果
+ */
+export interface DumpSummary {
+  dump_id: string
+  tables: number
+  views: number
+  routines: number
+  triggers: number
+  rows: number
+  elapsed_ms: number
+  cancelled: boolean
+}
+
+/**
+ * 恢复任务
+ */
+export interface RestoreTask {
+  restore_id: string
+  connection_id: string
+  database?: string
+  file_path: string
+  continue_on_error?: boolean
+}
+
+/**
+ * 恢复进度
+ */
+export interface RestoreProgress {
+  restore_id: string
+  executed: number
+  total: number
+  failed: number
+  elapsed_ms: number
+}
+
+/**
+ * 恢复结果
+ */
+export interface RestoreSummary {
+  restore_id: string
+  executed: number
+  total: number
+  /** 执行失败的语句（index 从 1 开始） */
+  errors: { index: number; sql: string; error: string }[]
+  elapsed_ms: number
+  cancelled: boolean
+}
+
+/**
+ * 跨连接传输的写入方式
+ */
+export type TransferMode = 'append' | 'truncate' | 'upsert'
+
+/**
+ * 传输选项
+ */
+export interface TransferOptions {
+  mode: TransferMode
+  /** 目标表不存在时按来源表结构创建 */
+  create_table: boolean
+  batch_size: number
+  /** Upsert 的冲突键列（为空时使用目标表主键） */
+  conflict_columns: string[]
+}
+
+/**
+ * 传输任务
+ */
+export interface TransferTask {
+  transfer_id: string
+  source_connection_id: string
+  source_database?: string
+  source_schema?: string
+  source_table: string
+  target_connection_id: string
+  target_database?: string
+  target_schema?: string
+  /** 目标表名（默认与来源表相同） */
+  target_table?: string
+  options?: Partial<TransferOptions>
+}
+
+/**
+ * 传输进度
+ */
+export interface TransferProgress {
+  transfer_id: string
+  rows: number
+  /** 来源表的总行数（统计失败时为空） */
+  total: number | null
+  elapsed_ms: number
+}
+
+/**
+ * 传输结果
+ */
+export interface TransferSummary {
+  transfer_id: string
+  rows: number
+  created_table: boolean
+  elapsed_ms: number
+  cancelled: boolean
+}
+
 /**
  * 单条SQL语句执行结果
  */
@@ -110,6 +662,8 @@ export interface StatementResult {
   success: boolean
   /** 错误信息（如果有） */
   error?: string
+  /** 错误码（如果有） */
+  error_code?: ErrorCode
   /** 影响行数 */
   affected_rows: number
   /** 执行时间（毫秒） */